@@ -10,6 +10,7 @@ use std::cmp::Ordering;
 
 mod balls3;
 mod boxes3;
+mod broad_phase_bvh3;
 mod capsules3;
 mod ccd3;
 mod compound3;
@@ -22,7 +23,9 @@ mod joint_revolute3;
 mod keva3;
 mod pyramid3;
 mod stacks3;
+mod tgs_soft3;
 mod trimesh3;
+mod trimesh_contacts3;
 
 enum Command {
     Run(String),
@@ -58,11 +61,32 @@ pub fn main() {
         ("Stacks", stacks3::init_world),
         ("Pyramid", pyramid3::init_world),
         ("Trimesh", trimesh3::init_world),
+        ("Trimesh contacts", trimesh_contacts3::init_world),
         ("ImpulseJoint ball", joint_ball3::init_world),
         ("ImpulseJoint fixed", joint_fixed3::init_world),
         ("ImpulseJoint revolute", joint_revolute3::init_world),
         ("ImpulseJoint prismatic", joint_prismatic3::init_world),
         ("Keva tower", keva3::init_world),
+        ("Pyramid (TGS-soft)", tgs_soft3::init_world_pyramid),
+        ("Keva tower (TGS-soft)", tgs_soft3::init_world_keva),
+        ("Stacks (TGS-soft)", tgs_soft3::init_world_stacks),
+        ("Balls (BVH)", broad_phase_bvh3::init_world_balls),
+        ("Boxes (BVH)", broad_phase_bvh3::init_world_boxes),
+        ("Capsules (BVH)", broad_phase_bvh3::init_world_capsules),
+        ("CCD (BVH)", broad_phase_bvh3::init_world_ccd),
+        ("Compound (BVH)", broad_phase_bvh3::init_world_compound),
+        (
+            "Convex polyhedron (BVH)",
+            broad_phase_bvh3::init_world_convex_polyhedron,
+        ),
+        (
+            "Heightfield (BVH)",
+            broad_phase_bvh3::init_world_heightfield,
+        ),
+        ("Stacks (BVH)", broad_phase_bvh3::init_world_stacks),
+        ("Pyramid (BVH)", broad_phase_bvh3::init_world_pyramid),
+        ("Trimesh (BVH)", broad_phase_bvh3::init_world_trimesh),
+        ("Keva tower (BVH)", broad_phase_bvh3::init_world_keva),
     ];
 
     // Lexicographic sort, with stress tests moved at the end of the list.
@@ -0,0 +1,63 @@
+use rapier3d::prelude::*;
+use rapier_testbed3d::physics::PhysicsBroadPhase;
+use rapier_testbed3d::Testbed;
+
+// Runs an existing scene with the dynamic AABB tree broad-phase instead of the default one.
+fn use_bvh(testbed: &mut Testbed) {
+    testbed.harness_mut().physics.broad_phase = PhysicsBroadPhase::Bvh(BroadPhaseBvh::new());
+}
+
+pub fn init_world_balls(testbed: &mut Testbed) {
+    crate::balls3::init_world(testbed);
+    use_bvh(testbed);
+}
+
+pub fn init_world_boxes(testbed: &mut Testbed) {
+    crate::boxes3::init_world(testbed);
+    use_bvh(testbed);
+}
+
+pub fn init_world_capsules(testbed: &mut Testbed) {
+    crate::capsules3::init_world(testbed);
+    use_bvh(testbed);
+}
+
+pub fn init_world_ccd(testbed: &mut Testbed) {
+    crate::ccd3::init_world(testbed);
+    use_bvh(testbed);
+}
+
+pub fn init_world_compound(testbed: &mut Testbed) {
+    crate::compound3::init_world(testbed);
+    use_bvh(testbed);
+}
+
+pub fn init_world_convex_polyhedron(testbed: &mut Testbed) {
+    crate::convex_polyhedron3::init_world(testbed);
+    use_bvh(testbed);
+}
+
+pub fn init_world_heightfield(testbed: &mut Testbed) {
+    crate::heightfield3::init_world(testbed);
+    use_bvh(testbed);
+}
+
+pub fn init_world_stacks(testbed: &mut Testbed) {
+    crate::stacks3::init_world(testbed);
+    use_bvh(testbed);
+}
+
+pub fn init_world_pyramid(testbed: &mut Testbed) {
+    crate::pyramid3::init_world(testbed);
+    use_bvh(testbed);
+}
+
+pub fn init_world_trimesh(testbed: &mut Testbed) {
+    crate::trimesh3::init_world(testbed);
+    use_bvh(testbed);
+}
+
+pub fn init_world_keva(testbed: &mut Testbed) {
+    crate::keva3::init_world(testbed);
+    use_bvh(testbed);
+}
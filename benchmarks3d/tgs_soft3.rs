@@ -0,0 +1,25 @@
+use rapier3d::prelude::*;
+use rapier_testbed3d::Testbed;
+
+// The TGS-soft solver reaches a better convergence with fewer iterations per substep.
+fn use_tgs_soft(testbed: &mut Testbed) {
+    let params = testbed.integration_parameters_mut();
+    params.solver_mode = SolverMode::TgsSoft { num_substeps: 4 };
+    params.max_velocity_iterations = 1;
+    params.max_velocity_friction_iterations = 2;
+}
+
+pub fn init_world_pyramid(testbed: &mut Testbed) {
+    crate::pyramid3::init_world(testbed);
+    use_tgs_soft(testbed);
+}
+
+pub fn init_world_keva(testbed: &mut Testbed) {
+    crate::keva3::init_world(testbed);
+    use_tgs_soft(testbed);
+}
+
+pub fn init_world_stacks(testbed: &mut Testbed) {
+    crate::stacks3::init_world(testbed);
+    use_tgs_soft(testbed);
+}
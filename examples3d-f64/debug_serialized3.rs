@@ -1,10 +1,11 @@
 use rapier3d::prelude::*;
+use rapier_testbed3d::physics::PhysicsBroadPhase;
 use rapier_testbed3d::Testbed;
 
 #[derive(serde::Deserialize)]
 struct State {
     pub islands: IslandManager,
-    pub broad_phase: BroadPhase,
+    pub broad_phase: BroadPhaseMultiSap,
     pub narrow_phase: NarrowPhase,
     pub bodies: RigidBodySet,
     pub colliders: ColliderSet,
@@ -27,7 +28,7 @@ pub fn init_world(testbed: &mut Testbed) {
         state.multibody_joints,
     );
     testbed.harness_mut().physics.islands = state.islands;
-    testbed.harness_mut().physics.broad_phase = state.broad_phase;
+    testbed.harness_mut().physics.broad_phase = PhysicsBroadPhase::MultiSap(state.broad_phase);
     testbed.harness_mut().physics.narrow_phase = state.narrow_phase;
     testbed.harness_mut().physics.ccd_solver = state.ccd_solver;
 
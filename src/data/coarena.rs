@@ -66,12 +66,13 @@ impl<T> Coarena<T> {
     /// Inserts an element into this coarena.
     pub fn insert(&mut self, a: Index, value: T)
     where
-        T: Clone + Default,
+        T: Default,
     {
         let (i1, g1) = a.into_raw_parts();
 
         if self.data.len() <= i1 as usize {
-            self.data.resize(i1 as usize + 1, (u32::MAX, T::default()));
+            self.data
+                .resize_with(i1 as usize + 1, || (u32::MAX, T::default()));
         }
 
         self.data[i1 as usize] = (g1, value);
@@ -3,9 +3,11 @@
 pub use self::arena::{Arena, Index};
 pub use self::coarena::Coarena;
 pub use self::component_set::{BundleSet, ComponentSet, ComponentSetMut, ComponentSetOption};
+pub use self::user_data::{ArenaHandle, ContactPairUserDataMap, UserDataMap, UserDataStorage};
 
 pub mod arena;
 mod coarena;
 mod component_set;
 pub(crate) mod graph;
 pub mod pubsub;
+mod user_data;
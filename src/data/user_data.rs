@@ -0,0 +1,646 @@
+//! Typed user data attached to rigid-bodies, colliders, joints, and contact pairs.
+
+use crate::data::{Coarena, Index};
+use crate::geometry::ColliderHandle;
+use parry::utils::hashmap::HashMap;
+use std::any::{Any, TypeId};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// A handle identifying an element stored into an [`Arena`](crate::data::Arena).
+pub trait ArenaHandle: Copy {
+    /// Builds the handle from the arena index it wraps.
+    fn from_arena_index(index: Index) -> Self;
+    /// The arena index wrapped by this handle.
+    fn arena_index(self) -> Index;
+}
+
+impl ArenaHandle for Index {
+    fn from_arena_index(index: Index) -> Self {
+        index
+    }
+
+    fn arena_index(self) -> Index {
+        self
+    }
+}
+
+/// A side-table associating typed user data to handles of rigid-bodies, colliders, or joints.
+///
+/// This is an alternative to the `u128` `user_data` fields of rigid-bodies and colliders when
+/// arbitrary data needs to be attached to them. The data is stored contiguously, indexed by the
+/// handle index, and checked against the handle generation, so data attached to a removed
+/// element is never returned for another element reusing its index.
+///
+/// The maps obtained from the [`UserDataStorage`] of a set (e.g. `RigidBodySet::user_data_mut`)
+/// are updated automatically when elements are removed from this set.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
+pub struct UserDataMap<Handle, T> {
+    data: Coarena<Option<T>>,
+    len: usize,
+    _phantom: PhantomData<Handle>,
+}
+
+impl<Handle, T> Default for UserDataMap<Handle, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Handle, T> UserDataMap<Handle, T> {
+    /// Creates an empty user data map.
+    pub fn new() -> Self {
+        Self {
+            data: Coarena::new(),
+            len: 0,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// The number of elements with user data attached.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Is there no user data attached to any element?
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes all the user data from this map.
+    pub fn clear(&mut self) {
+        self.data = Coarena::new();
+        self.len = 0;
+    }
+}
+
+impl<Handle: ArenaHandle, T> UserDataMap<Handle, T> {
+    /// Attaches user data to the element identified by `handle`.
+    ///
+    /// Returns the user data previously attached to this element, if any.
+    pub fn insert(&mut self, handle: Handle, data: T) -> Option<T> {
+        let index = handle.arena_index();
+        // NOTE: the slot may contain data attached to a removed element with the same index.
+        let slot_occupied = matches!(
+            self.data.get_unknown_gen(index.into_raw_parts().0),
+            Some(Some(_))
+        );
+        let previous = self.data.get_mut(index).and_then(|data| data.take());
+
+        if !slot_occupied {
+            self.len += 1;
+        }
+
+        self.data.insert(index, Some(data));
+        previous
+    }
+
+    /// Detaches and returns the user data attached to the element identified by `handle`.
+    pub fn remove(&mut self, handle: Handle) -> Option<T> {
+        let removed = self.data.remove(handle.arena_index(), None).flatten();
+
+        if removed.is_some() {
+            self.len -= 1;
+        }
+
+        removed
+    }
+
+    /// The user data attached to the element identified by `handle`.
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        self.data.get(handle.arena_index())?.as_ref()
+    }
+
+    /// A mutable reference to the user data attached to the element identified by `handle`.
+    pub fn get_mut(&mut self, handle: Handle) -> Option<&mut T> {
+        self.data.get_mut(handle.arena_index())?.as_mut()
+    }
+
+    /// Is there any user data attached to the element identified by `handle`?
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.get(handle).is_some()
+    }
+
+    /// Iterates through all the user data and the handles of the elements they are attached to.
+    pub fn iter(&self) -> impl Iterator<Item = (Handle, &T)> {
+        self.data
+            .iter()
+            .filter_map(|(index, data)| Some((Handle::from_arena_index(index), data.as_ref()?)))
+    }
+
+    /// Detaches the user data for which `f` returns `false`.
+    pub fn retain(&mut self, mut f: impl FnMut(Handle, &mut T) -> bool) {
+        let to_remove: Vec<_> = self
+            .data
+            .iter()
+            .filter(|(_, data)| data.is_some())
+            .map(|(index, _)| index)
+            .collect();
+
+        for index in to_remove {
+            let handle = Handle::from_arena_index(index);
+            let keep = self
+                .get_mut(handle)
+                .map(|data| f(handle, data))
+                .unwrap_or(true);
+
+            if !keep {
+                let _ = self.remove(handle);
+            }
+        }
+    }
+}
+
+/// A side-table associating typed user data to contact pairs.
+///
+/// The user data of a contact pair is identified by the handles of its two colliders, in any
+/// order.
+///
+/// The maps obtained from the [`UserDataStorage`] of a narrow-phase (see
+/// `NarrowPhase::pair_user_data_mut`) are updated automatically when contact pairs are removed
+/// from this narrow-phase.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
+pub struct ContactPairUserDataMap<T> {
+    data: HashMap<(ColliderHandle, ColliderHandle), T>,
+}
+
+impl<T> Default for ContactPairUserDataMap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> ContactPairUserDataMap<T> {
+    /// Creates an empty contact pair user data map.
+    pub fn new() -> Self {
+        Self {
+            data: HashMap::default(),
+        }
+    }
+
+    fn key(
+        collider1: ColliderHandle,
+        collider2: ColliderHandle,
+    ) -> (ColliderHandle, ColliderHandle) {
+        if collider1.into_raw_parts() <= collider2.into_raw_parts() {
+            (collider1, collider2)
+        } else {
+            (collider2, collider1)
+        }
+    }
+
+    /// The number of contact pairs with user data attached.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Is there no user data attached to any contact pair?
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Removes all the user data from this map.
+    pub fn clear(&mut self) {
+        self.data.clear()
+    }
+
+    /// Attaches user data to the contact pair between `collider1` and `collider2`.
+    ///
+    /// Returns the user data previously attached to this pair, if any.
+    pub fn insert(
+        &mut self,
+        collider1: ColliderHandle,
+        collider2: ColliderHandle,
+        data: T,
+    ) -> Option<T> {
+        self.data.insert(Self::key(collider1, collider2), data)
+    }
+
+    /// Detaches and returns the user data attached to the contact pair between `collider1` and `collider2`.
+    pub fn remove(&mut self, collider1: ColliderHandle, collider2: ColliderHandle) -> Option<T> {
+        self.data.remove(&Self::key(collider1, collider2))
+    }
+
+    /// The user data attached to the contact pair between `collider1` and `collider2`.
+    pub fn get(&self, collider1: ColliderHandle, collider2: ColliderHandle) -> Option<&T> {
+        self.data.get(&Self::key(collider1, collider2))
+    }
+
+    /// A mutable reference to the user data attached to the contact pair between `collider1` and `collider2`.
+    pub fn get_mut(
+        &mut self,
+        collider1: ColliderHandle,
+        collider2: ColliderHandle,
+    ) -> Option<&mut T> {
+        self.data.get_mut(&Self::key(collider1, collider2))
+    }
+
+    /// Iterates through all the user data and the handles of the colliders of their contact pair.
+    pub fn iter(&self) -> impl Iterator<Item = (ColliderHandle, ColliderHandle, &T)> {
+        self.data.iter().map(|(key, data)| (key.0, key.1, data))
+    }
+
+    /// Detaches the user data for which `f` returns `false`.
+    pub fn retain(&mut self, mut f: impl FnMut(ColliderHandle, ColliderHandle, &mut T) -> bool) {
+        self.data.retain(|key, data| f(key.0, key.1, data))
+    }
+}
+
+/// A user data map of any type, as stored by an [`UserDataStorage`].
+trait ErasedUserDataMap<Key>: Send + Sync {
+    fn detach(&mut self, key: Key);
+    fn transfer(&mut self, from: Key, to: Key);
+    fn clone_box(&self) -> Box<dyn ErasedUserDataMap<Key>>;
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<Handle, T> ErasedUserDataMap<Handle> for UserDataMap<Handle, T>
+where
+    Handle: ArenaHandle + Send + Sync + 'static,
+    T: Clone + Send + Sync + 'static,
+{
+    fn detach(&mut self, key: Handle) {
+        let _ = self.remove(key);
+    }
+
+    fn transfer(&mut self, from: Handle, to: Handle) {
+        if let Some(data) = self.remove(from) {
+            let _ = self.insert(to, data);
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn ErasedUserDataMap<Handle>> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl<T> ErasedUserDataMap<(ColliderHandle, ColliderHandle)> for ContactPairUserDataMap<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    fn detach(&mut self, key: (ColliderHandle, ColliderHandle)) {
+        let _ = self.remove(key.0, key.1);
+    }
+
+    fn transfer(
+        &mut self,
+        from: (ColliderHandle, ColliderHandle),
+        to: (ColliderHandle, ColliderHandle),
+    ) {
+        if let Some(data) = self.remove(from.0, from.1) {
+            let _ = self.insert(to.0, to.1, data);
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn ErasedUserDataMap<(ColliderHandle, ColliderHandle)>> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// The typed user data attached to the elements of a set, or to the contact pairs of a
+/// narrow-phase.
+///
+/// It contains one map per user data type, created the first time it is accessed mutably. The
+/// user data attached to an element is detached automatically when this element is removed.
+///
+/// Because the types of its maps are erased, this storage isn't serialized with the set (or the
+/// narrow-phase) owning it. Each user data type must instead be serialized and deserialized
+/// explicitly, with `serialize_map::<T>` and `deserialize_map::<T>` (or `serialize_pair_map::<T>`
+/// and `deserialize_pair_map::<T>` for contact pairs).
+pub struct UserDataStorage<Key> {
+    maps: HashMap<TypeId, Box<dyn ErasedUserDataMap<Key>>>,
+}
+
+impl<Key> Default for UserDataStorage<Key> {
+    fn default() -> Self {
+        Self {
+            maps: HashMap::default(),
+        }
+    }
+}
+
+impl<Key> Clone for UserDataStorage<Key> {
+    fn clone(&self) -> Self {
+        Self {
+            maps: self
+                .maps
+                .iter()
+                .map(|(type_id, map)| (*type_id, map.clone_box()))
+                .collect(),
+        }
+    }
+}
+
+impl<Key> fmt::Debug for UserDataStorage<Key> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("UserDataStorage")
+            .field("num_maps", &self.maps.len())
+            .finish()
+    }
+}
+
+impl<Key: Copy> UserDataStorage<Key> {
+    fn map_as<M: 'static>(&self) -> Option<&M> {
+        self.maps.get(&TypeId::of::<M>())?.as_any().downcast_ref()
+    }
+
+    fn map_as_mut<M: ErasedUserDataMap<Key> + Default + 'static>(&mut self) -> &mut M {
+        self.maps
+            .entry(TypeId::of::<M>())
+            .or_insert_with(|| Box::new(M::default()))
+            .as_any_mut()
+            .downcast_mut()
+            .unwrap()
+    }
+
+    /// Detaches all the user data attached to `key`.
+    pub(crate) fn detach(&mut self, key: Key) {
+        for map in self.maps.values_mut() {
+            map.detach(key);
+        }
+    }
+
+    /// Moves all the user data attached to `from` so it is attached to `to` instead.
+    pub(crate) fn transfer(&mut self, from: Key, to: Key) {
+        for map in self.maps.values_mut() {
+            map.transfer(from, to);
+        }
+    }
+}
+
+impl<Handle: ArenaHandle + Send + Sync + 'static> UserDataStorage<Handle> {
+    /// The user data of type `T`, if any was attached.
+    pub fn map<T: 'static>(&self) -> Option<&UserDataMap<Handle, T>> {
+        self.map_as()
+    }
+
+    /// The user data of type `T`.
+    pub fn map_mut<T: Clone + Send + Sync + 'static>(&mut self) -> &mut UserDataMap<Handle, T> {
+        self.map_as_mut()
+    }
+}
+
+#[cfg(feature = "serde-serialize")]
+impl<Handle: ArenaHandle + Send + Sync + 'static> UserDataStorage<Handle> {
+    /// Serializes the user data of type `T`.
+    ///
+    /// An empty map is serialized if no user data of type `T` was attached.
+    pub fn serialize_map<T, S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: 'static,
+        S: serde::Serializer,
+        UserDataMap<Handle, T>: serde::Serialize,
+    {
+        match self.map::<T>() {
+            Some(map) => serde::Serialize::serialize(map, serializer),
+            None => serde::Serialize::serialize(&UserDataMap::<Handle, T>::new(), serializer),
+        }
+    }
+
+    /// Replaces the user data of type `T` by the one deserialized with `deserializer`.
+    ///
+    /// The handles must still identify the same elements, e.g., because the set owning this
+    /// storage has been deserialized too.
+    pub fn deserialize_map<'de, T, D>(&mut self, deserializer: D) -> Result<(), D::Error>
+    where
+        T: Clone + Send + Sync + 'static,
+        D: serde::Deserializer<'de>,
+        UserDataMap<Handle, T>: serde::Deserialize<'de>,
+    {
+        *self.map_mut::<T>() = serde::Deserialize::deserialize(deserializer)?;
+        Ok(())
+    }
+}
+
+impl UserDataStorage<(ColliderHandle, ColliderHandle)> {
+    /// The contact pair user data of type `T`, if any was attached.
+    pub fn pair_map<T: 'static>(&self) -> Option<&ContactPairUserDataMap<T>> {
+        self.map_as()
+    }
+
+    /// The contact pair user data of type `T`.
+    pub fn pair_map_mut<T: Clone + Send + Sync + 'static>(
+        &mut self,
+    ) -> &mut ContactPairUserDataMap<T> {
+        self.map_as_mut()
+    }
+}
+
+#[cfg(feature = "serde-serialize")]
+impl UserDataStorage<(ColliderHandle, ColliderHandle)> {
+    /// Serializes the contact pair user data of type `T`.
+    ///
+    /// An empty map is serialized if no user data of type `T` was attached.
+    pub fn serialize_pair_map<T, S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: serde::Serialize + 'static,
+        S: serde::Serializer,
+    {
+        match self.pair_map::<T>() {
+            Some(map) => serde::Serialize::serialize(map, serializer),
+            None => serde::Serialize::serialize(&ContactPairUserDataMap::<T>::new(), serializer),
+        }
+    }
+
+    /// Replaces the contact pair user data of type `T` by the one deserialized with `deserializer`.
+    pub fn deserialize_pair_map<'de, T, D>(&mut self, deserializer: D) -> Result<(), D::Error>
+    where
+        T: serde::Deserialize<'de> + Clone + Send + Sync + 'static,
+        D: serde::Deserializer<'de>,
+    {
+        *self.pair_map_mut::<T>() = serde::Deserialize::deserialize(deserializer)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::Vector;
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn typed_user_data() {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+
+        let (ground, ground_collider) = world.insert_body(
+            RigidBodyBuilder::new_static().build(),
+            ColliderBuilder::ball(1.0).build(),
+        );
+        let ball = RigidBodyBuilder::new_dynamic()
+            .translation(Vector::y() * 1.5)
+            .build();
+        let (ball, ball_collider) = world.insert_body(ball, ColliderBuilder::ball(1.0).build());
+
+        let body_names = world.bodies.user_data_mut().map_mut::<String>();
+        body_names.insert(ground, "ground".to_string());
+        body_names.insert(ball, "ball".to_string());
+        let collider_names = world.colliders.user_data_mut().map_mut::<String>();
+        collider_names.insert(ground_collider, "ground collider".to_string());
+        collider_names.insert(ball_collider, "ball collider".to_string());
+
+        world.step();
+
+        let pair_names = world
+            .narrow_phase
+            .pair_user_data_mut()
+            .pair_map_mut::<String>();
+        pair_names.insert(ball_collider, ground_collider, "impact".to_string());
+        assert_eq!(
+            pair_names
+                .get(ground_collider, ball_collider)
+                .map(|s| s.as_str()),
+            Some("impact")
+        );
+
+        let _ = world.remove_body(ball);
+        world.step();
+
+        // The data of the removed elements is detached automatically.
+        let body_names = world.bodies.user_data().map::<String>().unwrap();
+        let collider_names = world.colliders.user_data().map::<String>().unwrap();
+        let pair_names = world.narrow_phase.pair_user_data().pair_map::<String>();
+        assert_eq!(body_names.len(), 1);
+        assert_eq!(collider_names.len(), 1);
+        assert!(pair_names.unwrap().is_empty());
+        assert_eq!(
+            body_names
+                .iter()
+                .map(|(h, s)| (h, s.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(ground, "ground")]
+        );
+        assert!(body_names.get(ball).is_none());
+
+        // The data of the removed body must not be visible through a handle reusing its index.
+        let new_ball = world.bodies.insert(RigidBodyBuilder::new_dynamic().build());
+        assert_eq!(new_ball.into_raw_parts().0, ball.into_raw_parts().0);
+        let body_names = world.bodies.user_data_mut().map_mut::<String>();
+        assert!(body_names.get(new_ball).is_none());
+        body_names.insert(new_ball, "new ball".to_string());
+        assert_eq!(body_names.len(), 2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn user_data_serialization_round_trip() {
+        use super::{ContactPairUserDataMap, UserDataMap};
+
+        let mut world = TestWorld::new(Vector::zeros());
+        let mut body_names = UserDataMap::new();
+        let mut pair_names = ContactPairUserDataMap::new();
+
+        let (body1, collider1) = world.insert_body(
+            RigidBodyBuilder::new_dynamic().build(),
+            ColliderBuilder::ball(1.0).build(),
+        );
+        let (body2, collider2) = world.insert_body(
+            RigidBodyBuilder::new_dynamic().build(),
+            ColliderBuilder::ball(1.0).build(),
+        );
+        body_names.insert(body1, "body1".to_string());
+        body_names.insert(body2, "body2".to_string());
+        pair_names.insert(collider2, collider1, 42u32);
+
+        let body_names: UserDataMap<_, String> =
+            bincode::deserialize(&bincode::serialize(&body_names).unwrap()).unwrap();
+        let pair_names: ContactPairUserDataMap<u32> =
+            bincode::deserialize(&bincode::serialize(&pair_names).unwrap()).unwrap();
+
+        assert_eq!(body_names.len(), 2);
+        assert_eq!(body_names.get(body1).map(|s| s.as_str()), Some("body1"));
+        assert_eq!(body_names.get(body2).map(|s| s.as_str()), Some("body2"));
+        assert_eq!(pair_names.len(), 1);
+        assert_eq!(pair_names.get(collider1, collider2), Some(&42));
+    }
+
+    #[cfg(feature = "serde-serialize")]
+    #[test]
+    fn user_data_storage_serialization_round_trip() {
+        use bincode::{DefaultOptions, Deserializer, Serializer};
+
+        let mut world = TestWorld::new(Vector::zeros());
+        let (body1, collider1) = world.insert_body(
+            RigidBodyBuilder::new_dynamic().build(),
+            ColliderBuilder::ball(1.0).build(),
+        );
+        let (body2, collider2) = world.insert_body(
+            RigidBodyBuilder::new_dynamic().build(),
+            ColliderBuilder::ball(1.0).build(),
+        );
+        let body_names = world.bodies.user_data_mut().map_mut::<String>();
+        body_names.insert(body1, "body1".to_string());
+        body_names.insert(body2, "body2".to_string());
+        world
+            .narrow_phase
+            .pair_user_data_mut()
+            .pair_map_mut::<u32>()
+            .insert(collider1, collider2, 42);
+
+        let bodies_bytes = bincode::serialize(&world.bodies).unwrap();
+        let mut names_bytes = Vec::new();
+        world
+            .bodies
+            .user_data()
+            .serialize_map::<String, _>(&mut Serializer::new(
+                &mut names_bytes,
+                DefaultOptions::new(),
+            ))
+            .unwrap();
+        let mut pairs_bytes = Vec::new();
+        world
+            .narrow_phase
+            .pair_user_data()
+            .serialize_pair_map::<u32, _>(&mut Serializer::new(
+                &mut pairs_bytes,
+                DefaultOptions::new(),
+            ))
+            .unwrap();
+
+        // The storage isn't serialized with its set: the maps are restored explicitly.
+        let mut bodies: crate::dynamics::RigidBodySet =
+            bincode::deserialize(&bodies_bytes).unwrap();
+        assert!(bodies.user_data().map::<String>().is_none());
+        bodies
+            .user_data_mut()
+            .deserialize_map::<String, _>(&mut Deserializer::from_slice(
+                &names_bytes,
+                DefaultOptions::new(),
+            ))
+            .unwrap();
+        let mut pair_user_data = crate::data::UserDataStorage::default();
+        pair_user_data
+            .deserialize_pair_map::<u32, _>(&mut Deserializer::from_slice(
+                &pairs_bytes,
+                DefaultOptions::new(),
+            ))
+            .unwrap();
+
+        let body_names = bodies.user_data().map::<String>().unwrap();
+        assert_eq!(body_names.len(), 2);
+        assert_eq!(body_names.get(body1).map(|s| s.as_str()), Some("body1"));
+        assert_eq!(body_names.get(body2).map(|s| s.as_str()), Some("body2"));
+        let pair_names = pair_user_data.pair_map::<u32>().unwrap();
+        assert_eq!(pair_names.get(collider2, collider1), Some(&42));
+    }
+}
@@ -1,17 +1,19 @@
 use super::TOIEntry;
 use crate::data::{BundleSet, ComponentSet, ComponentSetMut, ComponentSetOption};
-use crate::dynamics::{IslandManager, RigidBodyColliders, RigidBodyForces};
+use crate::dynamics::{IslandManager, KinematicCcd, RigidBodyColliders, RigidBodyForces};
 use crate::dynamics::{
-    RigidBodyCcd, RigidBodyHandle, RigidBodyMassProps, RigidBodyPosition, RigidBodyVelocity,
+    RigidBodyCcd, RigidBodyHandle, RigidBodyMassProps, RigidBodyPosition, RigidBodyType,
+    RigidBodyVelocity,
 };
 use crate::geometry::{
-    ColliderParent, ColliderPosition, ColliderShape, ColliderType, IntersectionEvent, NarrowPhase,
+    ColliderHandle, ColliderParent, ColliderPosition, ColliderShape, ColliderType,
+    IntersectionEvent, NarrowPhase,
 };
 use crate::math::Real;
 use crate::parry::utils::SortedPair;
 use crate::pipeline::{EventHandler, QueryPipeline, QueryPipelineMode};
 use crate::prelude::{ActiveEvents, ColliderFlags};
-use parry::query::{DefaultQueryDispatcher, QueryDispatcher};
+use parry::query::{DefaultQueryDispatcher, NonlinearRigidMotion, QueryDispatcher};
 use parry::utils::hashmap::HashMap;
 use std::collections::BinaryHeap;
 
@@ -90,6 +92,183 @@ impl CCDSolver {
         }
     }
 
+    /// Records the colliders modified or removed since the last call to this method.
+    ///
+    /// This keeps the acceleration structure used by `self.clamp_kinematic_motions` up-to-date
+    /// incrementally, instead of rebuilding it every time a kinematic body with kinematic CCD
+    /// moves.
+    pub(crate) fn update_colliders<Colliders>(
+        &mut self,
+        colliders: &Colliders,
+        modified_colliders: &[ColliderHandle],
+        removed_colliders: &[ColliderHandle],
+    ) where
+        Colliders: ComponentSet<ColliderShape> + ComponentSet<ColliderPosition>,
+    {
+        self.query_pipeline.update_incremental(
+            colliders,
+            modified_colliders,
+            removed_colliders,
+            false,
+        );
+    }
+
+    /// Apply motion-clamping to the kinematic bodies with kinematic CCD enabled.
+    ///
+    /// The motion of each of these kinematic bodies during the next `dt` is stopped at its first
+    /// time of impact with the colliders selected by its `KinematicCcd` mode, by clamping its
+    /// next position. The velocities of the velocity-based kinematic bodies are left untouched.
+    ///
+    /// This must be called after the next positions of the velocity-based kinematic bodies are
+    /// predicted from their velocities, and before the velocities of the position-based kinematic
+    /// bodies are computed from their next positions.
+    pub fn clamp_kinematic_motions<Bodies, Colliders>(
+        &mut self,
+        dt: Real,
+        islands: &IslandManager,
+        bodies: &mut Bodies,
+        colliders: &Colliders,
+    ) where
+        Bodies: ComponentSetMut<RigidBodyPosition>
+            + ComponentSet<RigidBodyVelocity>
+            + ComponentSet<RigidBodyType>
+            + ComponentSet<RigidBodyCcd>
+            + ComponentSet<RigidBodyColliders>
+            + ComponentSet<RigidBodyForces>
+            + ComponentSet<RigidBodyMassProps>,
+        Colliders: ComponentSetOption<ColliderParent>
+            + ComponentSet<ColliderPosition>
+            + ComponentSet<ColliderShape>
+            + ComponentSet<ColliderType>
+            + ComponentSet<ColliderFlags>,
+    {
+        let mut query_pipeline_updated = false;
+
+        for handle in islands.active_kinematic_bodies() {
+            let (rb_type, rb_ccd, rb_pos, rb_vels, rb_mprops, rb_colliders): (
+                &RigidBodyType,
+                &RigidBodyCcd,
+                &RigidBodyPosition,
+                &RigidBodyVelocity,
+                &RigidBodyMassProps,
+                &RigidBodyColliders,
+            ) = bodies.index_bundle(handle.0);
+
+            if rb_ccd.kinematic_ccd == KinematicCcd::Disabled {
+                continue;
+            }
+
+            let rb_type = *rb_type;
+            let kinematic_ccd = rb_ccd.kinematic_ccd;
+            let local_com = rb_mprops.local_mprops.local_com;
+            let vels = if rb_type == RigidBodyType::KinematicPositionBased {
+                rb_pos.interpolate_velocity(crate::utils::inv(dt), &local_com)
+            } else {
+                *rb_vels
+            };
+
+            if vels.is_zero() {
+                continue;
+            }
+
+            if !query_pipeline_updated {
+                // Apply the collider changes recorded by `self.update_colliders`.
+                self.query_pipeline
+                    .update_incremental(colliders, &[], &[], true);
+                query_pipeline_updated = true;
+            }
+
+            let motion1 =
+                NonlinearRigidMotion::new(rb_pos.position, local_com, vels.linvel, vels.angvel);
+            let predicted_body_pos1 = vels.integrate(dt, &rb_pos.position, &local_com);
+            let mut min_toi = dt;
+
+            for ch1 in &rb_colliders.0 {
+                let co_parent1: &ColliderParent = colliders
+                    .get(ch1.0)
+                    .expect("Could not find the ColliderParent component.");
+                let (co_type1, co_shape1, co_flags1): (
+                    &ColliderType,
+                    &ColliderShape,
+                    &ColliderFlags,
+                ) = colliders.index_bundle(ch1.0);
+
+                if co_type1.is_sensor() || !co_flags1.enabled.is_enabled() {
+                    continue; // Ignore sensors and disabled colliders.
+                }
+
+                let collider_pos1 = rb_pos.position * co_parent1.pos_wrt_parent;
+                let predicted_collider_pos1 = predicted_body_pos1 * co_parent1.pos_wrt_parent;
+                let aabb1 = co_shape1.compute_swept_aabb(&collider_pos1, &predicted_collider_pos1);
+                let motion_c1 = motion1.prepend(co_parent1.pos_wrt_parent);
+
+                self.query_pipeline
+                    .colliders_with_aabb_intersecting_aabb(&aabb1, |ch2| {
+                        let (co_type2, co_shape2, co_pos2, co_flags2): (
+                            &ColliderType,
+                            &ColliderShape,
+                            &ColliderPosition,
+                            &ColliderFlags,
+                        ) = colliders.index_bundle(ch2.0);
+                        let co_parent2: Option<&ColliderParent> = colliders.get(ch2.0);
+
+                        // Ignore sensors and disabled colliders, and apply the groups filters.
+                        if co_type2.is_sensor()
+                            || !co_flags2.enabled.is_enabled()
+                            || !co_flags1.collision_groups.test(co_flags2.collision_groups)
+                            || !co_flags1.solver_groups.test(co_flags2.solver_groups)
+                        {
+                            return true;
+                        }
+
+                        // NOTE: this also ignores self-intersections since the
+                        //       parent of `ch1` is kinematic.
+                        let type2 = co_parent2
+                            .map(|p| *bodies.index(p.handle.0))
+                            .unwrap_or(RigidBodyType::Static);
+                        let is_obstacle = match type2 {
+                            RigidBodyType::Static => true,
+                            RigidBodyType::Dynamic => {
+                                kinematic_ccd == KinematicCcd::StaticAndDynamic
+                            }
+                            _ => false,
+                        };
+
+                        if !is_obstacle {
+                            return true;
+                        }
+
+                        let motion_c2 = NonlinearRigidMotion::constant_position(co_pos2.0);
+                        let toi = self
+                            .query_pipeline
+                            .query_dispatcher()
+                            .nonlinear_time_of_impact(
+                                &motion_c1,
+                                co_shape1.as_ref(),
+                                &motion_c2,
+                                co_shape2.as_ref(),
+                                0.0,
+                                min_toi,
+                                false,
+                            );
+
+                        if let Ok(Some(toi)) = toi {
+                            min_toi = min_toi.min(toi.toi);
+                        }
+
+                        true
+                    });
+            }
+
+            if min_toi < dt {
+                let new_pos = vels.integrate(min_toi, &rb_pos.position, &local_com);
+                bodies.map_mut_internal(handle.0, |rb_pos: &mut RigidBodyPosition| {
+                    rb_pos.next_position = new_pos;
+                });
+            }
+        }
+    }
+
     /// Updates the set of bodies that needs CCD to be resolved.
     ///
     /// Returns `true` if any rigid-body must have CCD resolved.
@@ -624,3 +803,71 @@ impl CCDSolver {
         PredictedImpacts::Impacts(frozen)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{KinematicCcd, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn kinematic_ccd() {
+        fn final_position(kinematic_ccd: KinematicCcd) -> Real {
+            let mut world = TestWorld::new(Vector::zeros());
+
+            #[cfg(feature = "dim2")]
+            let wall = ColliderBuilder::cuboid(0.1, 5.0);
+            #[cfg(feature = "dim3")]
+            let wall = ColliderBuilder::cuboid(0.1, 5.0, 5.0);
+            world
+                .colliders
+                .insert(wall.translation(Vector::x() * 5.0).build());
+
+            let obstacle = RigidBodyBuilder::new_dynamic()
+                .translation(Vector::x() * 2.0)
+                .build();
+            world.insert_body(obstacle, ColliderBuilder::ball(0.5).build());
+
+            let body = RigidBodyBuilder::new_kinematic_position_based()
+                .kinematic_ccd(kinematic_ccd)
+                .build();
+            let (body, _) = world.insert_body(body, ColliderBuilder::ball(0.5).build());
+
+            // Teleport the kinematic body behind the wall.
+            world.bodies[body].set_next_kinematic_translation(Vector::x() * 10.0);
+            world.step();
+
+            world.bodies[body].translation().x
+        }
+
+        assert_eq!(final_position(KinematicCcd::Disabled), 10.0);
+        assert!((final_position(KinematicCcd::Static) - 4.4).abs() < 1.0e-2);
+        assert!((final_position(KinematicCcd::StaticAndDynamic) - 1.0).abs() < 1.0e-2);
+    }
+
+    #[test]
+    fn kinematic_ccd_velocity_based() {
+        let mut world = TestWorld::new(Vector::zeros());
+
+        #[cfg(feature = "dim2")]
+        let wall = ColliderBuilder::cuboid(0.1, 5.0);
+        #[cfg(feature = "dim3")]
+        let wall = ColliderBuilder::cuboid(0.1, 5.0, 5.0);
+        world
+            .colliders
+            .insert(wall.translation(Vector::x() * 5.0).build());
+
+        let body = RigidBodyBuilder::new_kinematic_velocity_based()
+            .kinematic_ccd(KinematicCcd::Static)
+            .build();
+        let (body, _) = world.insert_body(body, ColliderBuilder::ball(0.5).build());
+
+        // The wall keeps blocking the body, step after step.
+        for _ in 0..5 {
+            world.bodies[body].set_linvel(Vector::x() * 600.0, true);
+            world.step();
+            assert!((world.bodies[body].translation().x - 4.4).abs() < 1.0e-2);
+        }
+    }
+}
@@ -37,6 +37,13 @@ pub struct IntegrationParameters {
 
     /// Amount of penetration the engine wont attempt to correct (default: `0.001m`).
     pub allowed_linear_error: Real,
+    /// Maximum velocity the solver can apply to push penetrating objects apart with
+    /// `SolverMode::TgsSoft` (default: `10.0m/s`).
+    ///
+    /// Without this limit, deep penetrations (e.g. after a high-speed impact) are corrected
+    /// in a single substep, which can launch the objects away from each other.
+    /// This is ignored by `SolverMode::Pgs`.
+    pub max_corrective_velocity: Real,
     /// The maximal distance separating two objects that will generate predictive contacts (default: `0.002`).
     pub prediction_distance: Real,
     /// Maximum number of iterations performed to solve non-penetration and joint constraints (default: `4`).
@@ -52,9 +59,62 @@ pub struct IntegrationParameters {
     pub min_island_size: usize,
     /// Maximum number of substeps performed by the  solver (default: `1`).
     pub max_ccd_substeps: usize,
+    /// The constraints resolution scheme used by the solver (default: `SolverMode::Pgs`).
+    pub solver_mode: SolverMode,
+}
+
+/// The constraints resolution scheme used by the velocity solver.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub enum SolverMode {
+    /// Projected Gauss-Seidel: the constraints are assembled once and solved over the whole timestep.
+    Pgs,
+    /// Temporal Gauss-Seidel with soft constraints.
+    ///
+    /// The timestep is split into `num_substeps` substeps. The constraints are re-linearized
+    /// at the beginning of each substep, solved with the softness given by
+    /// [`IntegrationParameters::erp`] and [`IntegrationParameters::damping_ratio`], and then
+    /// relaxed (solved without position correction) after the positions are integrated.
+    /// This generally results in stiffer stacks and joint chains than `Pgs`. Because each
+    /// substep runs `max_velocity_iterations` solver iterations, the number of iterations can
+    /// often be reduced to compensate for the additional cost of the substeps.
+    ///
+    /// This mode is not supported by the parallel solver: with the `parallel` feature, the
+    /// islands are then solved sequentially, one after the other.
+    TgsSoft {
+        /// The number of substeps the timestep is subdivided into.
+        num_substeps: usize,
+    },
+}
+
+impl Default for SolverMode {
+    fn default() -> Self {
+        SolverMode::Pgs
+    }
+}
+
+impl SolverMode {
+    /// The number of substeps the timestep is subdivided into by the velocity solver.
+    pub fn num_substeps(&self) -> usize {
+        match self {
+            SolverMode::Pgs => 1,
+            SolverMode::TgsSoft { num_substeps } => (*num_substeps).max(1),
+        }
+    }
 }
 
 impl IntegrationParameters {
+    /// The maximum velocity the contact constraints can apply to correct penetrations.
+    ///
+    /// This is only limited with `SolverMode::TgsSoft`.
+    #[inline]
+    pub(crate) fn contact_max_corrective_velocity(&self) -> Real {
+        match self.solver_mode {
+            SolverMode::Pgs => Real::MAX,
+            SolverMode::TgsSoft { .. } => self.max_corrective_velocity,
+        }
+    }
+
     /// The inverse of the time-stepping length, i.e. the steps per seconds (Hz).
     ///
     /// This is zero if `self.dt` is zero.
@@ -135,6 +195,7 @@ impl Default for IntegrationParameters {
             erp: 0.8,
             damping_ratio: 0.25,
             allowed_linear_error: 0.001, // 0.005
+            max_corrective_velocity: 10.0,
             prediction_distance: 0.002,
             max_velocity_iterations: 4,
             max_velocity_friction_iterations: 8,
@@ -147,6 +208,7 @@ impl Default for IntegrationParameters {
             // tons of islands, reducing SIMD parallelism opportunities.
             min_island_size: 128,
             max_ccd_substeps: 1,
+            solver_mode: SolverMode::Pgs,
         }
     }
 }
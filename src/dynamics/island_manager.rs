@@ -100,7 +100,12 @@ impl IslandManager {
         //       deleting a joint attached to an already-removed body) where we could be
         //       attempting to wake-up a rigid-body that has already been deleted.
         let rb_type: Option<RigidBodyType> = bodies.get(handle.0).copied();
-        if rb_type == Some(RigidBodyType::Dynamic) {
+        let enabled = bodies
+            .get(handle.0)
+            .map(|activation: &RigidBodyActivation| activation.enabled)
+            .unwrap_or(false);
+
+        if rb_type == Some(RigidBodyType::Dynamic) && enabled {
             bodies.map_mut_internal(handle.0, |activation: &mut RigidBodyActivation| {
                 activation.wake_up(strong)
             });
@@ -281,15 +286,19 @@ impl IslandManager {
         let mut island_marker = self.stack.len().max(1) - 1;
 
         while let Some(handle) = self.stack.pop() {
-            let (rb_status, rb_ids, rb_colliders): (
+            let (rb_status, rb_ids, rb_colliders, rb_activation): (
                 &RigidBodyType,
                 &RigidBodyIds,
                 &RigidBodyColliders,
+                &RigidBodyActivation,
             ) = bodies.index_bundle(handle.0);
 
-            if rb_ids.active_set_timestamp == self.active_set_timestamp || !rb_status.is_dynamic() {
+            if rb_ids.active_set_timestamp == self.active_set_timestamp
+                || !rb_status.is_dynamic()
+                || !rb_activation.enabled
+            {
                 // We already visited this body and its neighbors.
-                // Also, we don't propagate awake state through static bodies.
+                // Also, we don't propagate awake state through static or disabled bodies.
                 continue;
             }
 
@@ -313,6 +322,9 @@ impl IslandManager {
                 self.stack.push(other);
             }
 
+            // The bodies attached to coupled joints must be solved together.
+            self.stack.extend(impulse_joints.coupled_bodies(handle));
+
             for other in multibody_joints.attached_bodies(handle) {
                 self.stack.push(other);
             }
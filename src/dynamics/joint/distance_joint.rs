@@ -0,0 +1,139 @@
+use crate::dynamics::joint::{JointAxesMask, JointData};
+use crate::dynamics::{JointAxis, MotorModel};
+use crate::math::{Point, Real};
+
+/// A joint that constrains the distance between two anchors.
+///
+/// This can be used to model ropes (with only a maximum length), rods (with
+/// equal minimum and maximum lengths), or springs between two points.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DistanceJoint {
+    data: JointData,
+}
+
+impl Default for DistanceJoint {
+    fn default() -> Self {
+        DistanceJoint::new()
+    }
+}
+
+impl DistanceJoint {
+    /// Creates a new distance joint with no length limits.
+    pub fn new() -> Self {
+        let data = JointData::default().coupled_axes(JointAxesMask::LIN_AXES);
+        Self { data }
+    }
+
+    /// Creates a new distance joint where the distance between the anchors can't exceed `max_length`.
+    pub fn rope(max_length: Real) -> Self {
+        Self::new().limit_length([0.0, max_length])
+    }
+
+    pub fn data(&self) -> &JointData {
+        &self.data
+    }
+
+    #[must_use]
+    pub fn local_anchor1(mut self, anchor1: Point<Real>) -> Self {
+        self.data = self.data.local_anchor1(anchor1);
+        self
+    }
+
+    #[must_use]
+    pub fn local_anchor2(mut self, anchor2: Point<Real>) -> Self {
+        self.data = self.data.local_anchor2(anchor2);
+        self
+    }
+
+    /// Sets the minimum and maximum distance allowed between the two anchors.
+    #[must_use]
+    pub fn limit_length(mut self, limits: [Real; 2]) -> Self {
+        self.data = self.data.limit_axis(JointAxis::X, limits);
+        self
+    }
+
+    /// Set the spring-like model used by the spring to reach its rest length.
+    #[must_use]
+    pub fn spring_model(mut self, model: MotorModel) -> Self {
+        self.data = self.data.motor_model(JointAxis::X, model);
+        self
+    }
+
+    /// Adds a spring pulling the anchors towards the given rest length.
+    #[must_use]
+    pub fn spring(mut self, rest_length: Real, stiffness: Real, damping: Real) -> Self {
+        self.data = self
+            .data
+            .motor_position(JointAxis::X, rest_length, stiffness, damping);
+        self
+    }
+
+    /// Sets the maximum impulse the spring can apply at each timestep.
+    #[must_use]
+    pub fn spring_max_impulse(mut self, max_impulse: Real) -> Self {
+        self.data = self.data.motor_max_impulse(JointAxis::X, max_impulse);
+        self
+    }
+}
+
+impl Into<JointData> for DistanceJoint {
+    fn into(self) -> JointData {
+        self.data
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{DistanceJoint, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    fn distance_joint_max_length(use_multibody: bool) {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        let max_length: Real = 2.0;
+
+        let ground = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        let body = RigidBodyBuilder::new_dynamic()
+            .translation(Vector::x() * 1.0)
+            .build();
+        let (handle, _) = world.insert_body(body, ColliderBuilder::ball(0.1).build());
+
+        let joint = DistanceJoint::rope(max_length);
+        if use_multibody {
+            world
+                .multibody_joints
+                .insert(ground, handle, joint)
+                .unwrap();
+        } else {
+            world.impulse_joints.insert(ground, handle, joint);
+        }
+
+        world.step_n(200);
+
+        let dist = world.bodies[handle].translation().norm();
+        assert!(
+            dist <= max_length * 1.05,
+            "The rope length was exceeded: {} > {}",
+            dist,
+            max_length
+        );
+        assert!(
+            dist >= max_length * 0.95,
+            "The body should hang at the end of the rope: {} < {}",
+            dist,
+            max_length
+        );
+    }
+
+    #[test]
+    fn impulse_distance_joint_max_length() {
+        distance_joint_max_length(false)
+    }
+
+    #[test]
+    fn multibody_distance_joint_max_length() {
+        distance_joint_max_length(true)
+    }
+}
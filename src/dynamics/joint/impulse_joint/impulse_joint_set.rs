@@ -2,9 +2,13 @@ use super::ImpulseJoint;
 use crate::geometry::{InteractionGraph, RigidBodyGraphIndex, TemporaryInteractionIndex};
 
 use crate::data::arena::Arena;
-use crate::data::{BundleSet, Coarena, ComponentSet, ComponentSetMut};
+use crate::data::{BundleSet, Coarena, ComponentSet, ComponentSetMut, UserDataStorage};
 use crate::dynamics::{IslandManager, RigidBodyActivation, RigidBodyIds, RigidBodyType};
-use crate::dynamics::{JointData, RigidBodyHandle};
+use crate::dynamics::{
+    JointCoupling, JointCouplingError, JointCouplingHandle, JointData, MultibodyJointSet,
+    RigidBodyHandle,
+};
+use crate::math::{Isometry, Real};
 
 /// The unique identifier of a joint added to the joint set.
 /// The unique identifier of a collider added to a collider set.
@@ -33,6 +37,16 @@ impl JointHandle {
     }
 }
 
+impl crate::data::ArenaHandle for JointHandle {
+    fn from_arena_index(index: crate::data::arena::Index) -> Self {
+        Self(index)
+    }
+
+    fn arena_index(self) -> crate::data::arena::Index {
+        self.0
+    }
+}
+
 pub(crate) type JointIndex = usize;
 pub(crate) type JointGraphEdge = crate::data::graph::Edge<ImpulseJoint>;
 
@@ -43,6 +57,14 @@ pub struct ImpulseJointSet {
     rb_graph_ids: Coarena<RigidBodyGraphIndex>,
     joint_ids: Arena<TemporaryInteractionIndex>, // Map joint handles to edge ids on the graph.
     joint_graph: InteractionGraph<RigidBodyHandle, ImpulseJoint>,
+    #[cfg_attr(feature = "serde-serialize", serde(default))]
+    coupling_ids: Arena<usize>, // Map coupling handles to indices in `couplings`.
+    #[cfg_attr(feature = "serde-serialize", serde(default))]
+    couplings: Vec<JointCoupling>,
+    #[cfg_attr(feature = "serde-serialize", serde(default))]
+    joint_couplings: Coarena<Vec<JointCouplingHandle>>, // The couplings involving each joint.
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    user_data: UserDataStorage<JointHandle>,
 }
 
 impl ImpulseJointSet {
@@ -52,9 +74,26 @@ impl ImpulseJointSet {
             rb_graph_ids: Coarena::new(),
             joint_ids: Arena::new(),
             joint_graph: InteractionGraph::new(),
+            coupling_ids: Arena::new(),
+            couplings: Vec::new(),
+            joint_couplings: Coarena::new(),
+            user_data: UserDataStorage::default(),
         }
     }
 
+    /// The typed user data attached to the impulse joints of this set.
+    pub fn user_data(&self) -> &UserDataStorage<JointHandle> {
+        &self.user_data
+    }
+
+    /// The typed user data attached to the impulse joints of this set.
+    ///
+    /// The user data attached to an element is detached when it is removed from this set. It
+    /// isn't serialized with this set: see `UserDataStorage::serialize_map`.
+    pub fn user_data_mut(&mut self) -> &mut UserDataStorage<JointHandle> {
+        &mut self.user_data
+    }
+
     /// The number of impulse_joints on this set.
     pub fn len(&self) -> usize {
         self.joint_graph.graph.edges.len()
@@ -159,9 +198,13 @@ impl ImpulseJointSet {
     //     //     .map(|e| &mut e.weight)
     // }
 
-    #[cfg(not(feature = "parallel"))]
-    pub(crate) fn joints_mut(&mut self) -> &mut [JointGraphEdge] {
-        &mut self.joint_graph.graph.edges[..]
+    pub(crate) fn joints_and_couplings_mut(
+        &mut self,
+    ) -> (&mut [JointGraphEdge], &mut [JointCoupling]) {
+        (
+            &mut self.joint_graph.graph.edges[..],
+            &mut self.couplings[..],
+        )
     }
 
     #[cfg(feature = "parallel")]
@@ -176,18 +219,140 @@ impl ImpulseJointSet {
         body2: RigidBodyHandle,
         data: impl Into<JointData>,
     ) -> JointHandle {
-        let data = data.into();
         let handle = self.joint_ids.insert(0.into());
         let joint = ImpulseJoint {
             body1,
             body2,
-            data,
+            data: data.into(),
             impulses: na::zero(),
             handle: JointHandle(handle),
             #[cfg(feature = "parallel")]
             constraint_index: 0,
         };
 
+        self.joint_ids[handle] = self.add_joint_edge(joint);
+        JointHandle(handle)
+    }
+
+    /// Inserts a new coupling between two joints of this set and retrieve its handle.
+    ///
+    /// The coupling is removed automatically when one of the coupled joints is removed.
+    /// Pulley joints and joints attached to multibody links can't be coupled. If one of
+    /// the bodies attached to a coupled joint becomes a multibody link afterwards, the
+    /// coupling is ignored.
+    ///
+    /// With the `parallel` feature, the islands containing couplings are solved by the
+    /// sequential solver.
+    pub fn insert_coupling(
+        &mut self,
+        mut coupling: JointCoupling,
+        multibody_joints: &MultibodyJointSet,
+    ) -> Result<JointCouplingHandle, JointCouplingError> {
+        for joint_handle in [coupling.joint1, coupling.joint2] {
+            let joint = self
+                .get(joint_handle)
+                .ok_or(JointCouplingError::JointNotFound(joint_handle))?;
+
+            if joint.data.pulley.is_some()
+                || multibody_joints.rigid_body_link(joint.body1).is_some()
+                || multibody_joints.rigid_body_link(joint.body2).is_some()
+            {
+                return Err(JointCouplingError::UnsupportedJoint(joint_handle));
+            }
+        }
+
+        let handle = JointCouplingHandle(self.coupling_ids.insert(self.couplings.len()));
+        coupling.handle = handle;
+        self.couplings.push(coupling);
+
+        for joint_handle in [coupling.joint1, coupling.joint2] {
+            self.joint_couplings
+                .ensure_element_exist(joint_handle.0, vec![])
+                .push(handle);
+        }
+
+        Ok(handle)
+    }
+
+    /// Removes a joint coupling from this set.
+    pub fn remove_coupling(&mut self, handle: JointCouplingHandle) -> Option<JointCoupling> {
+        let id = self.coupling_ids.remove(handle.0)?;
+        let removed = self.couplings.swap_remove(id);
+
+        // Update the id of the coupling which took the place of the removed one.
+        if let Some(moved) = self.couplings.get(id) {
+            self.coupling_ids[moved.handle.0] = id;
+        }
+
+        for joint_handle in [removed.joint1, removed.joint2] {
+            if let Some(couplings) = self.joint_couplings.get_mut(joint_handle.0) {
+                couplings.retain(|h| *h != handle);
+            }
+        }
+
+        Some(removed)
+    }
+
+    /// Gets the joint coupling with the given handle.
+    pub fn coupling(&self, handle: JointCouplingHandle) -> Option<&JointCoupling> {
+        let id = self.coupling_ids.get(handle.0)?;
+        self.couplings.get(*id)
+    }
+
+    /// Gets a mutable reference to the joint coupling with the given handle.
+    pub fn coupling_mut(&mut self, handle: JointCouplingHandle) -> Option<&mut JointCoupling> {
+        let id = self.coupling_ids.get(handle.0)?;
+        self.couplings.get_mut(*id)
+    }
+
+    /// Iterates through all the joint couplings on this set.
+    pub fn couplings(&self) -> impl Iterator<Item = (JointCouplingHandle, &JointCoupling)> {
+        self.couplings.iter().map(|c| (c.handle, c))
+    }
+
+    /// Iterates through all the couplings involving the given joint.
+    pub fn couplings_with(
+        &self,
+        joint: JointHandle,
+    ) -> impl Iterator<Item = (JointCouplingHandle, &JointCoupling)> {
+        self.joint_couplings
+            .get(joint.0)
+            .into_iter()
+            .flatten()
+            .filter_map(move |handle| Some((*handle, self.coupling(*handle)?)))
+    }
+
+    /// Iterates through the bodies attached to joints coupled with the joints of the given body.
+    pub(crate) fn coupled_bodies(
+        &self,
+        body: RigidBodyHandle,
+    ) -> impl Iterator<Item = RigidBodyHandle> + '_ {
+        self.joints_with(body)
+            .flat_map(move |(_, _, joint)| {
+                self.couplings_with(joint.handle).map(move |(_, coupling)| {
+                    if coupling.joint1 == joint.handle {
+                        coupling.joint2
+                    } else {
+                        coupling.joint1
+                    }
+                })
+            })
+            .filter_map(move |other| self.get(other))
+            .flat_map(|other| [other.body1, other.body2])
+    }
+
+    // Removes all the couplings involving the given joint.
+    fn remove_couplings_of_joint(&mut self, joint: JointHandle) {
+        if let Some(couplings) = self.joint_couplings.remove(joint.0, vec![]) {
+            for coupling in couplings {
+                let _ = self.remove_coupling(coupling);
+            }
+        }
+    }
+
+    /// Adds the edge of the given joint to the joint graph, creating the graph nodes of
+    /// its attached bodies if needed.
+    fn add_joint_edge(&mut self, joint: ImpulseJoint) -> TemporaryInteractionIndex {
         let default_id = InteractionGraph::<(), ()>::invalid_graph_index();
         let mut graph_index1 = *self
             .rb_graph_ids
@@ -208,17 +373,121 @@ impl ImpulseJointSet {
             self.rb_graph_ids.insert(joint.body2.0, graph_index2);
         }
 
-        self.joint_ids[handle] = self.joint_graph.add_edge(graph_index1, graph_index2, joint);
-        JointHandle(handle)
+        self.joint_graph.add_edge(graph_index1, graph_index2, joint)
+    }
+
+    /// Re-attaches all the impulse joints attached to the rigid-body `from` to the rigid-body `to`.
+    ///
+    /// The local frames of the transferred joints are expressed in the local-space of `to`
+    /// using `from_wrt_to`, the pose of `from` relative to `to`. Joints that would end up
+    /// attaching `to` to itself are removed. The joint handles are preserved.
+    pub(crate) fn transfer_joints_attached_to_rigid_body<Bodies>(
+        &mut self,
+        from: RigidBodyHandle,
+        to: RigidBodyHandle,
+        from_wrt_to: &Isometry<Real>,
+        islands: &mut IslandManager,
+        bodies: &mut Bodies,
+    ) where
+        Bodies: ComponentSetMut<RigidBodyActivation>
+            + ComponentSet<RigidBodyType>
+            + ComponentSetMut<RigidBodyIds>,
+    {
+        let graph_id = match self.rb_graph_ids.get(from.0) {
+            Some(id) if InteractionGraph::<(), ()>::is_graph_index_valid(*id) => *id,
+            _ => return,
+        };
+
+        let to_transfer: Vec<_> = self
+            .joint_graph
+            .interactions_with(graph_id)
+            .map(|e| e.2.handle)
+            .collect();
+
+        for joint_handle in to_transfer {
+            self.update_joint_bodies(joint_handle, islands, bodies, |joint| {
+                if joint.body1 == from {
+                    joint.body1 = to;
+                    joint.data.local_frame1 = from_wrt_to * joint.data.local_frame1;
+                }
+
+                if joint.body2 == from {
+                    joint.body2 = to;
+                    joint.data.local_frame2 = from_wrt_to * joint.data.local_frame2;
+                }
+            });
+        }
+    }
+
+    /// Re-attaches an impulse joint to the rigid-bodies `body1` and `body2`.
+    ///
+    /// The local frames of the joint are not modified. The joint is removed if `body1` and
+    /// `body2` are the same rigid-body. The joint handle is preserved.
+    pub(crate) fn reattach_joint<Bodies>(
+        &mut self,
+        handle: JointHandle,
+        body1: RigidBodyHandle,
+        body2: RigidBodyHandle,
+        islands: &mut IslandManager,
+        bodies: &mut Bodies,
+    ) where
+        Bodies: ComponentSetMut<RigidBodyActivation>
+            + ComponentSet<RigidBodyType>
+            + ComponentSetMut<RigidBodyIds>,
+    {
+        self.update_joint_bodies(handle, islands, bodies, |joint| {
+            joint.body1 = body1;
+            joint.body2 = body2;
+        });
+    }
+
+    /// Removes the graph edge of a joint, applies `f` to it, and adds it back to the graph.
+    fn update_joint_bodies<Bodies>(
+        &mut self,
+        handle: JointHandle,
+        islands: &mut IslandManager,
+        bodies: &mut Bodies,
+        f: impl FnOnce(&mut ImpulseJoint),
+    ) where
+        Bodies: ComponentSetMut<RigidBodyActivation>
+            + ComponentSet<RigidBodyType>
+            + ComponentSetMut<RigidBodyIds>,
+    {
+        let edge_id = match self.joint_ids.get(handle.0) {
+            Some(edge_id) => *edge_id,
+            None => return,
+        };
+        let mut joint = match self.joint_graph.graph.remove_edge(edge_id) {
+            Some(joint) => joint,
+            None => return,
+        };
+
+        // Update the id of the edge which took the place of the removed one.
+        if let Some(j) = self.joint_graph.graph.edge_weight(edge_id) {
+            self.joint_ids[j.handle.0] = edge_id;
+        }
+
+        f(&mut joint);
+        islands.wake_up(bodies, joint.body1, true);
+        islands.wake_up(bodies, joint.body2, true);
+
+        if joint.body1 == joint.body2 {
+            let _ = self.joint_ids.remove(handle.0);
+            self.remove_couplings_of_joint(handle);
+        } else {
+            self.joint_ids[handle.0] = self.add_joint_edge(joint);
+        }
     }
 
-    /// Retrieve all the impulse_joints happening between two active bodies.
+    /// Retrieve all the impulse_joints happening between two active bodies, and the
+    /// couplings between two of these joints.
     // NOTE: this is very similar to the code from NarrowPhase::select_active_interactions.
     pub(crate) fn select_active_interactions<Bodies>(
-        &self,
+        &mut self,
         islands: &IslandManager,
         bodies: &Bodies,
         out: &mut Vec<Vec<JointIndex>>,
+        out_couplings: &mut [Vec<usize>],
     ) where
         Bodies: ComponentSet<RigidBodyType>
             + ComponentSet<RigidBodyActivation>
@@ -228,34 +497,72 @@ impl ImpulseJointSet {
             out_island.clear();
         }
 
+        for out_island in &mut out_couplings[..islands.num_islands()] {
+            out_island.clear();
+        }
+
         // FIXME: don't iterate through all the interactions.
         for (i, edge) in self.joint_graph.graph.edges.iter().enumerate() {
-            let joint = &edge.weight;
-
-            let (status1, activation1, ids1): (
-                &RigidBodyType,
-                &RigidBodyActivation,
-                &RigidBodyIds,
-            ) = bodies.index_bundle(joint.body1.0);
-            let (status2, activation2, ids2): (
-                &RigidBodyType,
-                &RigidBodyActivation,
-                &RigidBodyIds,
-            ) = bodies.index_bundle(joint.body2.0);
-
-            if (status1.is_dynamic() || status2.is_dynamic())
-                && (!status1.is_dynamic() || !activation1.sleeping)
-                && (!status2.is_dynamic() || !activation2.sleeping)
-            {
-                let island_index = if !status1.is_dynamic() {
-                    ids2.active_island_id
-                } else {
-                    ids1.active_island_id
-                };
-
+            if let Some(island_index) = Self::active_island(&edge.weight, bodies) {
                 out[island_index].push(i);
             }
         }
+
+        for (i, coupling) in self.couplings.iter_mut().enumerate() {
+            // Refresh the edges of the coupled joints since they may have
+            // moved after a removal.
+            coupling.edges = [coupling.joint1, coupling.joint2].map(|h| {
+                self.joint_ids
+                    .get(h.0)
+                    .map(|id| id.index())
+                    .unwrap_or(usize::MAX)
+            });
+
+            let island1 = self
+                .joint_graph
+                .graph
+                .edge_weight(self.joint_ids[coupling.joint1.0])
+                .and_then(|joint| Self::active_island(joint, bodies));
+            let island2 = self
+                .joint_graph
+                .graph
+                .edge_weight(self.joint_ids[coupling.joint2.0])
+                .and_then(|joint| Self::active_island(joint, bodies));
+
+            // Both joints are in the same island since the island manager
+            // traverses the couplings.
+            if let (Some(island_index), Some(_)) = (island1, island2) {
+                out_couplings[island_index].push(i);
+            }
+        }
+    }
+
+    /// The index of the active island of the given joint, if it has to be simulated.
+    fn active_island<Bodies>(joint: &ImpulseJoint, bodies: &Bodies) -> Option<usize>
+    where
+        Bodies: ComponentSet<RigidBodyType>
+            + ComponentSet<RigidBodyActivation>
+            + ComponentSet<RigidBodyIds>,
+    {
+        let (status1, activation1, ids1): (&RigidBodyType, &RigidBodyActivation, &RigidBodyIds) =
+            bodies.index_bundle(joint.body1.0);
+        let (status2, activation2, ids2): (&RigidBodyType, &RigidBodyActivation, &RigidBodyIds) =
+            bodies.index_bundle(joint.body2.0);
+
+        if (status1.is_dynamic() || status2.is_dynamic())
+            && activation1.enabled
+            && activation2.enabled
+            && (!status1.is_dynamic() || !activation1.sleeping)
+            && (!status2.is_dynamic() || !activation2.sleeping)
+        {
+            if !status1.is_dynamic() {
+                Some(ids2.active_island_id)
+            } else {
+                Some(ids1.active_island_id)
+            }
+        } else {
+            None
+        }
     }
 
     /// Removes a joint from this set.
@@ -293,6 +600,8 @@ impl ImpulseJointSet {
             self.joint_ids[edge.handle.0] = id;
         }
 
+        self.remove_couplings_of_joint(handle);
+        self.user_data.detach(handle);
         removed_joint
     }
 
@@ -338,6 +647,9 @@ impl ImpulseJointSet {
                         self.joint_ids[j.handle.0] = to_delete_edge_id;
                     }
 
+                    self.remove_couplings_of_joint(to_delete_handle);
+                    self.user_data.detach(to_delete_handle);
+
                     // Wake up the attached bodies.
                     islands.wake_up(bodies, h1, true);
                     islands.wake_up(bodies, h2, true);
@@ -0,0 +1,299 @@
+use crate::dynamics::{JointAxis, JointHandle};
+use crate::math::Real;
+use std::fmt;
+
+/// The unique identifier of a joint coupling added to an impulse joint set.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[repr(transparent)]
+pub struct JointCouplingHandle(pub crate::data::arena::Index);
+
+impl JointCouplingHandle {
+    /// Converts this handle into its (index, generation) components.
+    pub fn into_raw_parts(self) -> (u32, u32) {
+        self.0.into_raw_parts()
+    }
+
+    /// Reconstructs an handle from its (index, generation) components.
+    pub fn from_raw_parts(id: u32, generation: u32) -> Self {
+        Self(crate::data::arena::Index::from_raw_parts(id, generation))
+    }
+
+    /// An always-invalid joint coupling handle.
+    pub fn invalid() -> Self {
+        Self(crate::data::arena::Index::from_raw_parts(
+            crate::INVALID_U32,
+            crate::INVALID_U32,
+        ))
+    }
+}
+
+/// The reason why a joint coupling could not be inserted into an impulse joint set.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum JointCouplingError {
+    /// The coupled joint is not part of the impulse joint set.
+    JointNotFound(JointHandle),
+    /// The coupled joint is a pulley joint, or is attached to a multibody link.
+    UnsupportedJoint(JointHandle),
+}
+
+impl fmt::Display for JointCouplingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JointCouplingError::JointNotFound(handle) => {
+                write!(f, "the coupled joint {:?} does not exist", handle)
+            }
+            JointCouplingError::UnsupportedJoint(handle) => {
+                write!(f, "the coupled joint {:?} can't be coupled", handle)
+            }
+        }
+    }
+}
+
+impl std::error::Error for JointCouplingError {}
+
+/// A constraint tying the coordinates of two impulse joints together.
+///
+/// The coupling enforces `coord1 + ratio * coord2 = constant`, where `coord1` (resp. `coord2`)
+/// is the coordinate of `joint1` (resp. `joint2`) along `axis1` (resp. `axis2`), and the
+/// constant is the value it had the first time the coupling was simulated.
+///
+/// Only the velocities of the bodies attached to the coupled joints are affected: the joints
+/// themselves must be created separately. The coupling is removed automatically when one of
+/// the coupled joints is removed.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct JointCoupling {
+    /// The first coupled joint.
+    pub joint1: JointHandle,
+    /// The second coupled joint.
+    pub joint2: JointHandle,
+    /// The coordinate of `joint1` that is coupled.
+    pub axis1: JointAxis,
+    /// The coordinate of `joint2` that is coupled.
+    pub axis2: JointAxis,
+    /// The ratio between the two coupled coordinates.
+    pub ratio: Real,
+    /// The amount of free play allowed around the coupled configuration, in units of `axis1`.
+    pub backlash: Real,
+    /// The impulse applied by this coupling during the last timestep.
+    pub impulse: Real,
+    // The joint graph edges of `joint1` and `joint2`, refreshed at each timestep.
+    pub(crate) edges: [usize; 2],
+    // The handle of this coupling, to simplify its removal.
+    pub(crate) handle: JointCouplingHandle,
+    // The unwrapped coordinates of the coupled joints at the last timestep.
+    pub(crate) coords: Option<[Real; 2]>,
+    // The value of `coord1 + ratio * coord2` the coupling maintains.
+    pub(crate) rest_value: Option<Real>,
+}
+
+impl JointCoupling {
+    /// Couples two joints so that `coord1 + ratio * coord2` remains constant.
+    pub fn new(
+        joint1: JointHandle,
+        axis1: JointAxis,
+        joint2: JointHandle,
+        axis2: JointAxis,
+        ratio: Real,
+    ) -> Self {
+        Self {
+            joint1,
+            joint2,
+            axis1,
+            axis2,
+            ratio,
+            backlash: 0.0,
+            impulse: 0.0,
+            edges: [usize::MAX; 2],
+            handle: JointCouplingHandle::invalid(),
+            coords: None,
+            rest_value: None,
+        }
+    }
+
+    /// Couples the rotations of two revolute joints, like two meshing gears.
+    ///
+    /// With `ratio = radius2 / radius1` the two gears rotate in opposite directions
+    /// at the speeds of meshing gears with these radii.
+    pub fn gear(revolute1: JointHandle, revolute2: JointHandle, ratio: Real) -> Self {
+        Self::new(
+            revolute1,
+            JointAxis::AngX,
+            revolute2,
+            JointAxis::AngX,
+            ratio,
+        )
+    }
+
+    /// Couples the rotation of a revolute joint with the translation of a prismatic joint,
+    /// like a pinion of radius `pinion_radius` meshing with a rack.
+    pub fn rack_and_pinion(pinion: JointHandle, rack: JointHandle, pinion_radius: Real) -> Self {
+        Self::new(
+            pinion,
+            JointAxis::AngX,
+            rack,
+            JointAxis::X,
+            1.0 / pinion_radius,
+        )
+    }
+
+    /// Sets the amount of free play allowed around the coupled configuration.
+    #[must_use]
+    pub fn backlash(mut self, backlash: Real) -> Self {
+        self.backlash = backlash;
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{
+        JointCoupling, JointCouplingError, PulleyJoint, RevoluteJoint, RigidBodyBuilder,
+    };
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+    use na::RealField;
+
+    #[test]
+    fn gear_coupling() {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        let ratio = 2.0;
+
+        let ground = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        let mut gears = vec![];
+        let mut revolutes = vec![];
+
+        for i in 0..2 {
+            let position = Vector::x() * (i as Real * 3.0);
+            let body = RigidBodyBuilder::new_dynamic()
+                .translation(position)
+                .build();
+            let (gear, _) = world.insert_body(body, ColliderBuilder::ball(0.5).build());
+
+            #[cfg(feature = "dim2")]
+            let revolute = RevoluteJoint::new();
+            #[cfg(feature = "dim3")]
+            let revolute = RevoluteJoint::new(Vector::z_axis());
+            let revolute = revolute.local_anchor1(Point::from(position));
+            let revolute = if i == 0 {
+                revolute.motor_velocity(5.0, 1.0)
+            } else {
+                revolute
+            };
+
+            gears.push(gear);
+            revolutes.push(world.impulse_joints.insert(ground, gear, revolute));
+        }
+
+        world
+            .impulse_joints
+            .insert_coupling(
+                JointCoupling::gear(revolutes[0], revolutes[1], ratio),
+                &world.multibody_joints,
+            )
+            .unwrap();
+
+        world.step_n(200);
+
+        let bodies = &world.bodies;
+        #[cfg(feature = "dim2")]
+        let (angvel1, angvel2) = (bodies[gears[0]].angvel(), bodies[gears[1]].angvel());
+        #[cfg(feature = "dim3")]
+        let (angvel1, angvel2) = (bodies[gears[0]].angvel().z, bodies[gears[1]].angvel().z);
+
+        #[cfg(feature = "dim2")]
+        let (angle1, angle2) = (
+            bodies[gears[0]].rotation().angle(),
+            bodies[gears[1]].rotation().angle(),
+        );
+        #[cfg(feature = "dim3")]
+        let (angle1, angle2) = (
+            bodies[gears[0]].rotation().scaled_axis().z,
+            bodies[gears[1]].rotation().scaled_axis().z,
+        );
+        let two_pi = Real::two_pi();
+        let angle_err = angle1 + ratio * angle2;
+        let angle_err = angle_err - (angle_err / two_pi).round() * two_pi;
+
+        assert!(angvel1 > 1.0, "The driving gear should rotate: {}", angvel1);
+        assert!(
+            angle_err.abs() < 1.0e-2,
+            "The gears drifted apart: {}",
+            angle_err
+        );
+        assert!(
+            (angvel1 + ratio * angvel2).abs() < 1.0e-2,
+            "The gear ratio is not respected: {} vs. {}",
+            angvel1,
+            angvel2
+        );
+    }
+
+    #[test]
+    fn coupling_removed_with_joint() {
+        let mut world = TestWorld::new(Vector::zeros());
+        let ground = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        let mut revolutes = vec![];
+
+        for i in 0..2 {
+            let position = Vector::x() * (i as Real * 3.0);
+            let body = RigidBodyBuilder::new_dynamic()
+                .translation(position)
+                .build();
+            let (gear, _) = world.insert_body(body, ColliderBuilder::ball(0.5).build());
+
+            #[cfg(feature = "dim2")]
+            let revolute = RevoluteJoint::new();
+            #[cfg(feature = "dim3")]
+            let revolute = RevoluteJoint::new(Vector::z_axis());
+            let revolute = revolute.local_anchor1(Point::from(position));
+            revolutes.push(world.impulse_joints.insert(ground, gear, revolute));
+        }
+
+        let coupling = world
+            .impulse_joints
+            .insert_coupling(
+                JointCoupling::gear(revolutes[0], revolutes[1], 1.0),
+                &world.multibody_joints,
+            )
+            .unwrap();
+
+        // The coupling isn't a joint of its own.
+        assert_eq!(world.impulse_joints.len(), 2);
+        assert_eq!(world.impulse_joints.iter().count(), 2);
+        assert_eq!(world.impulse_joints.couplings_with(revolutes[1]).count(), 1);
+
+        world.step();
+        world
+            .impulse_joints
+            .remove(revolutes[0], &mut world.islands, &mut world.bodies, true);
+
+        assert!(world.impulse_joints.coupling(coupling).is_none());
+        assert_eq!(world.impulse_joints.couplings().count(), 0);
+        assert_eq!(world.impulse_joints.couplings_with(revolutes[1]).count(), 0);
+        world.step();
+
+        // Coupling a removed joint or a pulley is an error.
+        let body1 = world.bodies.insert(RigidBodyBuilder::new_dynamic().build());
+        let body2 = world.bodies.insert(RigidBodyBuilder::new_dynamic().build());
+        let pulley = PulleyJoint::new(Point::origin(), Point::origin(), 1.0);
+        let pulley = world.impulse_joints.insert(body1, body2, pulley);
+
+        assert_eq!(
+            world.impulse_joints.insert_coupling(
+                JointCoupling::gear(revolutes[0], revolutes[1], 1.0),
+                &world.multibody_joints,
+            ),
+            Err(JointCouplingError::JointNotFound(revolutes[0]))
+        );
+        assert_eq!(
+            world.impulse_joints.insert_coupling(
+                JointCoupling::gear(revolutes[1], pulley, 1.0),
+                &world.multibody_joints,
+            ),
+            Err(JointCouplingError::UnsupportedJoint(pulley))
+        );
+    }
+}
@@ -1,8 +1,11 @@
 use crate::dynamics::solver::MotorParameters;
-use crate::dynamics::MotorModel;
+use crate::dynamics::{JointPulley, MotorModel};
 use crate::math::{Isometry, Point, Real, Rotation, UnitVector, SPATIAL_DIM};
 use crate::utils::WBasis;
 
+#[cfg(feature = "dim3")]
+use {crate::math::Vector, na::RealField};
+
 #[cfg(feature = "dim3")]
 bitflags::bitflags! {
     #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
@@ -14,6 +17,8 @@ bitflags::bitflags! {
         const ANG_X = 1 << 3;
         const ANG_Y = 1 << 4;
         const ANG_Z = 1 << 5;
+        const LIN_AXES = Self::X.bits | Self::Y.bits | Self::Z.bits;
+        const ANG_AXES = Self::ANG_X.bits | Self::ANG_Y.bits | Self::ANG_Z.bits;
     }
 }
 
@@ -25,6 +30,8 @@ bitflags::bitflags! {
         const X = 1 << 0;
         const Y = 1 << 1;
         const ANG_X = 1 << 2;
+        const LIN_AXES = Self::X.bits | Self::Y.bits;
+        const ANG_AXES = Self::ANG_X.bits;
     }
 }
 
@@ -66,6 +73,73 @@ impl Default for JointLimits {
     }
 }
 
+/// Cone-twist limits restricting the relative orientation of the two frames of a joint.
+///
+/// The swing is the rotation bringing the X axis of the first frame onto the X axis of the
+/// second frame. It is limited to an elliptical cone around the first frame’s X axis. The
+/// twist is the remaining rotation around the X axis of the second frame.
+#[cfg(feature = "dim3")]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct JointSwingTwistLimits {
+    /// The maximum swing angles around the Y and Z axes of the first frame.
+    pub swing: [Real; 2],
+    /// The minimum and maximum twist angles around the X axis.
+    pub twist: [Real; 2],
+    /// The impulses applied by the swing and twist limits during the last timestep.
+    pub impulses: [Real; 2],
+}
+
+#[cfg(feature = "dim3")]
+impl JointSwingTwistLimits {
+    /// Creates cone-twist limits with the given maximum swing angles and twist range.
+    pub fn new(swing: [Real; 2], twist: [Real; 2]) -> Self {
+        Self {
+            swing,
+            twist,
+            impulses: [0.0; 2],
+        }
+    }
+
+    /// Computes the swing and twist limit constraints for the given rotation of the
+    /// second frame relative to the first frame.
+    ///
+    /// Returns, for the swing and the twist, the rotation axis (expressed in the first frame),
+    /// the current angle around this axis, and the limits of this angle.
+    pub(crate) fn constraints(
+        &self,
+        rel_rot: &Rotation<Real>,
+    ) -> [(Vector<Real>, Real, [Real; 2]); 2] {
+        let x1 = Vector::x();
+        let x2 = rel_rot * x1;
+
+        let swing_angle = x2.x.clamp(-1.0, 1.0).acos();
+        let swing_axis = x1
+            .cross(&x2)
+            .try_normalize(1.0e-6)
+            .unwrap_or_else(Vector::y);
+        let [swing_y, swing_z] = self.swing;
+        let max_swing = swing_y
+            * swing_z
+            * crate::utils::inv(
+                ((swing_axis.y * swing_z).powi(2) + (swing_axis.z * swing_y).powi(2)).sqrt(),
+            );
+
+        let mut twist_angle = 2.0 * rel_rot.i.atan2(rel_rot.w);
+        if twist_angle > Real::pi() {
+            twist_angle -= Real::two_pi();
+        } else if twist_angle < -Real::pi() {
+            twist_angle += Real::two_pi();
+        }
+        let twist_axis = (x1 + x2).try_normalize(1.0e-6).unwrap_or(x1);
+
+        [
+            (swing_axis, swing_angle, [-Real::MAX, max_swing]),
+            (twist_axis, twist_angle, self.twist),
+        ]
+    }
+}
+
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct JointMotor {
@@ -94,17 +168,17 @@ impl Default for JointMotor {
 
 impl JointMotor {
     pub(crate) fn motor_params(&self, dt: Real) -> MotorParameters<Real> {
-        let (stiffness, damping, gamma, _keep_lhs) =
+        let (stiffness, damping, cfm_gain) =
             self.model
                 .combine_coefficients(dt, self.stiffness, self.damping);
         MotorParameters {
             stiffness,
             damping,
-            gamma,
-            // keep_lhs,
+            cfm_gain,
             target_pos: self.target_pos,
             target_vel: self.target_vel,
             max_impulse: self.max_impulse,
+            lookahead: self.model.lookahead(dt),
         }
     }
 }
@@ -117,8 +191,20 @@ pub struct JointData {
     pub locked_axes: JointAxesMask,
     pub limit_axes: JointAxesMask,
     pub motor_axes: JointAxesMask,
+    /// The free linear axes whose coordinates are limited and motorized together.
+    ///
+    /// If any linear axis is coupled, the limits and motor of `JointAxis::X` apply to
+    /// the distance between the two anchors (projected on the coupled axes) instead
+    /// of the individual coordinates. Limits and motors set on the other coupled linear
+    /// axes are ignored.
+    pub coupled_axes: JointAxesMask,
     pub limits: [JointLimits; SPATIAL_DIM],
     pub motors: [JointMotor; SPATIAL_DIM],
+    /// If set, the two bodies are attached to each other through a pulley.
+    pub pulley: Option<JointPulley>,
+    /// If set, the relative orientation of the two frames is restricted to a cone-twist range.
+    #[cfg(feature = "dim3")]
+    pub swing_twist_limits: Option<JointSwingTwistLimits>,
 }
 
 impl Default for JointData {
@@ -129,8 +215,12 @@ impl Default for JointData {
             locked_axes: JointAxesMask::FREE,
             limit_axes: JointAxesMask::FREE,
             motor_axes: JointAxesMask::FREE,
+            coupled_axes: JointAxesMask::FREE,
             limits: [JointLimits::default(); SPATIAL_DIM],
             motors: [JointMotor::default(); SPATIAL_DIM],
+            pulley: None,
+            #[cfg(feature = "dim3")]
+            swing_twist_limits: None,
         }
     }
 }
@@ -143,7 +233,15 @@ impl JointData {
 
     /// Can this joint use SIMD-accelerated constraint formulations?
     pub fn supports_simd_constraints(&self) -> bool {
-        self.limit_axes.is_empty() && self.motor_axes.is_empty()
+        #[cfg(feature = "dim2")]
+        let no_swing_twist = true;
+        #[cfg(feature = "dim3")]
+        let no_swing_twist = self.swing_twist_limits.is_none();
+
+        self.limit_axes.is_empty()
+            && self.motor_axes.is_empty()
+            && self.pulley.is_none()
+            && no_swing_twist
     }
 
     #[must_use]
@@ -152,6 +250,19 @@ impl JointData {
         self
     }
 
+    /// Couples the given linear axes so that the limits and motor of `JointAxis::X`
+    /// act on the distance between the anchors.
+    #[must_use]
+    pub fn coupled_axes(mut self, axes: JointAxesMask) -> Self {
+        self.coupled_axes |= axes & JointAxesMask::LIN_AXES;
+        self
+    }
+
+    /// The linear axes that are coupled and free, as a bit mask.
+    pub(crate) fn coupled_lin_axes_bits(&self) -> u8 {
+        (self.coupled_axes & JointAxesMask::LIN_AXES & !self.locked_axes).bits()
+    }
+
     fn complete_ang_frame(axis: UnitVector<Real>) -> Rotation<Real> {
         let basis = axis.orthonormal_basis();
 
@@ -217,6 +328,15 @@ impl JointData {
         self
     }
 
+    /// Restricts the relative orientation of the two frames to an elliptical swing cone
+    /// of half-angles `swing` (around the Y and Z axes) and to the twist range `twist`.
+    #[cfg(feature = "dim3")]
+    #[must_use]
+    pub fn limit_swing_twist(mut self, swing: [Real; 2], twist: [Real; 2]) -> Self {
+        self.swing_twist_limits = Some(JointSwingTwistLimits::new(swing, twist));
+        self
+    }
+
     /// Set the spring-like model used by the motor to reach the desired target velocity and position.
     #[must_use]
     pub fn motor_model(mut self, axis: JointAxis, model: MotorModel) -> Self {
@@ -14,9 +14,13 @@ pub enum MotorModel {
     /// The solved spring-like equation is:
     /// `acceleration(t + dt) = stiffness * (target_pos - pos(t)) + damping * (target_vel - vel(t))`
     AccelerationBased,
-    // /// The solved spring-like equation is:
-    // /// `force(t + dt) = stiffness * (target_pos - pos(t + dt)) + damping * (target_vel - vel(t + dt))`
-    // ForceBased,
+    /// The solved spring-like equation is:
+    /// `force(t + dt) = stiffness * (target_pos - pos(t + dt)) + damping * (target_vel - vel(t + dt))`
+    ///
+    /// Here the `stiffness` and `damping` are expressed in physical units (N/m and N·s/m for
+    /// linear axes, N·m/rad and N·m·s/rad for angular axes), like the gains of a PD controller.
+    /// The spring is integrated implicitly so it remains stable even with large stiffnesses.
+    ForceBased,
 }
 
 impl Default for MotorModel {
@@ -28,34 +32,161 @@ impl Default for MotorModel {
 impl MotorModel {
     /// Combines the coefficients used for solving the spring equation.
     ///
-    /// Returns the new coefficients (stiffness, damping, gamma, keep_inv_lhs)
+    /// Returns the new coefficients (stiffness, damping, cfm_gain)
     /// coefficients for the equivalent impulse-based equation. These new
     /// coefficients must be used in the following way:
-    /// - `rhs = (stiffness * pos_err + damping * vel_err) / gamma`.
-    /// - `new_inv_lhs = gamma * if keep_inv_lhs { inv_lhs } else { 1.0 }`.
-    /// Note that the returned `gamma` will be zero if both `stiffness` and `damping` are zero.
+    /// - `rhs = stiffness * (pos_err + lookahead * vel) + damping * vel_err`, where
+    ///   `lookahead` is given by [`MotorModel::lookahead`].
+    /// - `new_inv_lhs = 1.0 / (lhs + cfm_gain)`.
+    /// - `delta_impulse = new_inv_lhs * (J * delta_vel + rhs - cfm_gain * impulse)`.
+    ///
+    /// The `cfm_gain` is zero for the models that don't soften the constraint.
     pub fn combine_coefficients(
         self,
         dt: Real,
         stiffness: Real,
         damping: Real,
-    ) -> (Real, Real, Real, bool) {
+    ) -> (Real, Real, Real) {
         match self {
-            MotorModel::VelocityBased => (stiffness * crate::utils::inv(dt), damping, 1.0, true),
+            MotorModel::VelocityBased => (stiffness * crate::utils::inv(dt), damping, 0.0),
             MotorModel::AccelerationBased => {
                 let effective_stiffness = stiffness * dt;
                 let effective_damping = damping * dt;
-                // TODO: Using gamma behaves very badly for some reasons.
-                // Maybe I got the formulation wrong, so let's keep it to 1.0 for now,
-                // and get back to this later.
-                // let gamma = effective_stiffness * dt + effective_damping;
-                (effective_stiffness, effective_damping, 1.0, true)
-            } // MotorModel::ForceBased => {
-              //     let effective_stiffness = stiffness * dt;
-              //     let effective_damping = damping * dt;
-              //     let gamma = effective_stiffness * dt + effective_damping;
-              //     (effective_stiffness, effective_damping, gamma, false)
-              // }
+                (effective_stiffness, effective_damping, 0.0)
+            }
+            MotorModel::ForceBased => {
+                // Implicit integration of the spring-damper: the impulse `f * dt` satisfies
+                // `f * dt = -dt * (stiffness * (pos_err + dt * vel) + damping * vel_err)`,
+                // where `vel` and `vel_err` are taken at the end of the timestep. Dividing
+                // by `dt * (dt * stiffness + damping)` gives all the coefficients.
+                let inv_denom = crate::utils::inv(dt * stiffness + damping);
+                (
+                    stiffness * inv_denom,
+                    damping * inv_denom,
+                    crate::utils::inv(dt) * inv_denom,
+                )
+            }
+        }
+    }
+
+    /// The duration by which the position error is extrapolated with the current
+    /// velocity before applying the stiffness returned by [`MotorModel::combine_coefficients`].
+    ///
+    /// This is `dt` for the `ForceBased` model, which evaluates the spring at the end of
+    /// the timestep, and zero for the other models.
+    pub fn lookahead(self, dt: Real) -> Real {
+        match self {
+            MotorModel::VelocityBased | MotorModel::AccelerationBased => 0.0,
+            MotorModel::ForceBased => dt,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{MotorModel, PrismaticJoint, RigidBodyBuilder};
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+    use na::RealField;
+
+    fn force_based_spring_mass(use_multibody: bool) {
+        let mut world = TestWorld::new(Vector::zeros());
+        let (mass, stiffness, x0): (Real, Real, Real) = (2.0, 8.0, 1.0);
+
+        let ground = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        let body = RigidBodyBuilder::new_dynamic()
+            .translation(Vector::x() * x0)
+            .additional_mass(mass)
+            .build();
+        let handle = world.bodies.insert(body);
+
+        // The joint starts at its zero coordinate, which is `x0` away from the target.
+        let joint = PrismaticJoint::new(Vector::x_axis())
+            .local_anchor1(Point::from(Vector::x() * x0))
+            .motor_model(MotorModel::ForceBased)
+            .motor_position(-x0, stiffness, 0.0);
+        if use_multibody {
+            world
+                .multibody_joints
+                .insert(ground, handle, joint)
+                .unwrap();
+        } else {
+            world.impulse_joints.insert(ground, handle, joint);
         }
+
+        // Compare against `x(t) = x0 * cos(sqrt(k / m) * t)` during half a period. The
+        // tolerance accounts for the numerical damping of the implicit integration.
+        let dt = world.integration_parameters.dt;
+        let omega = (stiffness / mass).sqrt();
+        let half_period = Real::pi() / omega;
+        let num_steps = (half_period / dt) as usize;
+
+        for i in 1..=num_steps {
+            world.step();
+
+            let t = i as Real * dt;
+            let expected = x0 * (omega * t).cos();
+            let x = world.bodies[handle].translation().x;
+            assert!(
+                (x - expected).abs() < 0.075,
+                "Spring-mass mismatch at t = {}: {} != {}",
+                t,
+                x,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn impulse_force_based_motor_oscillation() {
+        force_based_spring_mass(false)
+    }
+
+    #[test]
+    fn multibody_force_based_motor_oscillation() {
+        force_based_spring_mass(true)
+    }
+
+    #[test]
+    fn force_based_motor_one_step() {
+        let mut world = TestWorld::new(Vector::zeros());
+        let (mass, stiffness, damping): (Real, Real, Real) = (2.0, 300.0, 10.0);
+        let (x0, v0, target_pos, target_vel): (Real, Real, Real, Real) = (1.0, 0.5, -0.5, 2.0);
+
+        let ground = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        let body = RigidBodyBuilder::new_dynamic()
+            .translation(Vector::x() * x0)
+            .linvel(Vector::x() * v0)
+            .additional_mass(mass)
+            .build();
+        let handle = world.bodies.insert(body);
+
+        let joint = PrismaticJoint::new(Vector::x_axis())
+            .motor_model(MotorModel::ForceBased)
+            .motor_axis(target_pos, target_vel, stiffness, damping);
+        world.impulse_joints.insert(ground, handle, joint);
+
+        world.step();
+
+        // Implicit Euler step of `m * a = k * (target_pos - x) + c * (target_vel - v)`.
+        let dt = world.integration_parameters.dt;
+        let expected_vel = (mass * v0 - dt * stiffness * (x0 - target_pos)
+            + dt * damping * target_vel)
+            / (mass + dt * dt * stiffness + dt * damping);
+        let expected_pos = x0 + dt * expected_vel;
+
+        let body = &world.bodies[handle];
+        assert!(
+            (body.linvel().x - expected_vel).abs() < 1.0e-4,
+            "Velocity mismatch: {} != {}",
+            body.linvel().x,
+            expected_vel
+        );
+        assert!(
+            (body.translation().x - expected_pos).abs() < 1.0e-5,
+            "Position mismatch: {} != {}",
+            body.translation().x,
+            expected_pos
+        );
     }
 }
@@ -4,7 +4,13 @@ pub use self::multibody::Multibody;
 pub use self::multibody_joint::MultibodyJoint;
 pub use self::multibody_joint_set::{MultibodyIndex, MultibodyJointHandle, MultibodyJointSet};
 pub use self::multibody_link::MultibodyLink;
-pub use self::unit_multibody_joint::{unit_joint_limit_constraint, unit_joint_motor_constraint};
+pub use self::unit_multibody_joint::{
+    coupled_joint_limit_constraint, coupled_joint_motor_constraint, unit_joint_limit_constraint,
+    unit_joint_motor_constraint,
+};
+
+#[cfg(feature = "dim3")]
+pub use self::unit_multibody_joint::swing_twist_limit_constraints;
 
 mod multibody;
 mod multibody_joint_set;
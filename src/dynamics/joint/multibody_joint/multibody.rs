@@ -3,8 +3,8 @@ use super::multibody_workspace::MultibodyWorkspace;
 use crate::data::{BundleSet, ComponentSet, ComponentSetMut};
 use crate::dynamics::solver::AnyJointVelocityConstraint;
 use crate::dynamics::{
-    IntegrationParameters, RigidBodyForces, RigidBodyHandle, RigidBodyMassProps, RigidBodyPosition,
-    RigidBodyType, RigidBodyVelocity,
+    IntegrationParameters, RigidBodyActivation, RigidBodyForces, RigidBodyHandle,
+    RigidBodyMassProps, RigidBodyPosition, RigidBodyType, RigidBodyVelocity,
 };
 #[cfg(feature = "dim3")]
 use crate::math::Matrix;
@@ -785,6 +785,38 @@ impl Multibody {
         }
     }
 
+    /// Translates the world-space positions of the links of this multibody by `-shift`.
+    pub(crate) fn shift_origin(&mut self, shift: &Vector<Real>) {
+        for link in self.links.iter_mut() {
+            link.local_to_world.translation.vector -= shift;
+        }
+
+        // The root joint is expressed in world-space.
+        let root_joint = &mut self.links[0].joint;
+
+        if root_joint.data.locked_axes.is_empty() {
+            let mut root_pos = root_joint.body_to_parent();
+            root_pos.translation.vector -= shift;
+            root_joint.set_free_pos(root_pos);
+        } else {
+            root_joint.data.local_frame1.translation.vector -= shift;
+        }
+    }
+
+    /// Is this multibody enabled?
+    ///
+    /// All the links of a multibody share the same enabled state, so this checks its root.
+    pub(crate) fn is_enabled<Bodies>(&self, bodies: &Bodies) -> bool
+    where
+        Bodies: ComponentSet<RigidBodyActivation>,
+    {
+        let activation: Option<&RigidBodyActivation> = bodies.get(self.links[0].rigid_body.0);
+        match activation {
+            Some(activation) => activation.enabled,
+            None => true,
+        }
+    }
+
     pub fn update_root_type<Bodies>(&mut self, bodies: &mut Bodies)
     where
         Bodies: ComponentSet<RigidBodyType> + ComponentSet<RigidBodyPosition>,
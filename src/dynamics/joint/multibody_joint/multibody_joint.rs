@@ -7,9 +7,13 @@ use crate::math::{
     Isometry, JacobianSliceMut, Real, Rotation, SpacialVector, Translation, Vector, ANG_DIM, DIM,
     SPATIAL_DIM,
 };
+use arrayvec::ArrayVec;
 use na::{DVector, DVectorSliceMut};
 #[cfg(feature = "dim3")]
-use na::{UnitQuaternion, Vector3};
+use {
+    crate::dynamics::{JointAxesMask, JointSwingTwistLimits},
+    na::{UnitQuaternion, Vector3},
+};
 
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug)]
@@ -54,6 +58,16 @@ impl MultibodyJoint {
         DIM - (locked_bits & ((1 << DIM) - 1)).count_ones() as usize
     }
 
+    /// The swing-twist limits of this multibody_joint, if it has any and all its angular dofs are free.
+    #[cfg(feature = "dim3")]
+    fn swing_twist_limits(&self) -> Option<&JointSwingTwistLimits> {
+        if (self.data.locked_axes & JointAxesMask::ANG_AXES).is_empty() {
+            self.data.swing_twist_limits.as_ref()
+        } else {
+            None
+        }
+    }
+
     /// The number of degrees of freedom allowed by the multibody_joint.
     pub fn ndofs(&self) -> usize {
         SPATIAL_DIM - self.data.locked_axes.bits().count_ones() as usize
@@ -227,10 +241,18 @@ impl MultibodyJoint {
     /// Maximum number of velocity constrains that can be generated by this multibody_joint.
     pub fn num_velocity_constraints(&self) -> usize {
         let locked_bits = self.data.locked_axes.bits();
-        let limit_bits = self.data.limit_axes.bits();
-        let motor_bits = self.data.motor_axes.bits();
+        let coupled_bits = self.data.coupled_lin_axes_bits();
+        let limit_bits = self.data.limit_axes.bits() & !coupled_bits;
+        let motor_bits = self.data.motor_axes.bits() & !coupled_bits;
         let mut num_constraints = 0;
 
+        if self.data.limit_axes.bits() & coupled_bits & 1 != 0 {
+            num_constraints += 1;
+        }
+        if self.data.motor_axes.bits() & coupled_bits & 1 != 0 {
+            num_constraints += 1;
+        }
+
         for i in 0..SPATIAL_DIM {
             if (locked_bits & (1 << i)) == 0 {
                 if (limit_bits & (1 << i)) != 0 {
@@ -242,6 +264,11 @@ impl MultibodyJoint {
             }
         }
 
+        #[cfg(feature = "dim3")]
+        if self.swing_twist_limits().is_some() {
+            num_constraints += 2;
+        }
+
         num_constraints
     }
 
@@ -257,12 +284,18 @@ impl MultibodyJoint {
         constraints: &mut Vec<AnyJointVelocityConstraint>,
     ) {
         let locked_bits = self.data.locked_axes.bits();
-        let limit_bits = self.data.limit_axes.bits();
-        let motor_bits = self.data.motor_axes.bits();
+        let coupled_bits = self.data.coupled_lin_axes_bits();
+        let limit_bits = self.data.limit_axes.bits() & !coupled_bits;
+        let motor_bits = self.data.motor_axes.bits() & !coupled_bits;
+        let mut coupled_dofs = ArrayVec::<(usize, Real), DIM>::new();
         let mut curr_free_dof = 0;
 
         for i in 0..DIM {
             if (locked_bits & (1 << i)) == 0 {
+                if (coupled_bits & (1 << i)) != 0 {
+                    coupled_dofs.push((dof_id + curr_free_dof, self.coords[i]));
+                }
+
                 if (limit_bits & (1 << i)) != 0 {
                     joint::unit_joint_limit_constraint(
                         params,
@@ -294,6 +327,34 @@ impl MultibodyJoint {
             }
         }
 
+        if !coupled_dofs.is_empty() {
+            if self.data.limit_axes.bits() & coupled_bits & 1 != 0 {
+                joint::coupled_joint_limit_constraint(
+                    params,
+                    multibody,
+                    link,
+                    [self.data.limits[0].min, self.data.limits[0].max],
+                    &coupled_dofs,
+                    j_id,
+                    jacobians,
+                    constraints,
+                );
+            }
+
+            if self.data.motor_axes.bits() & coupled_bits & 1 != 0 {
+                joint::coupled_joint_motor_constraint(
+                    params,
+                    multibody,
+                    link,
+                    &self.data.motors[0],
+                    &coupled_dofs,
+                    j_id,
+                    jacobians,
+                    constraints,
+                );
+            }
+        }
+
         /*
         let locked_ang_bits = locked_bits >> DIM;
         let num_free_ang_dofs = ANG_DIM - locked_ang_bits.count_ones() as usize;
@@ -340,5 +401,20 @@ impl MultibodyJoint {
                 curr_free_dof += 1;
             }
         }
+
+        #[cfg(feature = "dim3")]
+        if let Some(swing_twist) = self.swing_twist_limits() {
+            joint::swing_twist_limit_constraints(
+                params,
+                multibody,
+                link,
+                swing_twist,
+                &self.joint_rot,
+                dof_id + self.num_free_lin_dofs(),
+                j_id,
+                jacobians,
+                constraints,
+            );
+        }
     }
 }
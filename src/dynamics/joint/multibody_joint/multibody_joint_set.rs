@@ -1,8 +1,8 @@
-use crate::data::{Arena, Coarena, ComponentSet, ComponentSetMut, Index};
+use crate::data::{Arena, Coarena, ComponentSet, ComponentSetMut, Index, UserDataStorage};
 use crate::dynamics::joint::MultibodyLink;
 use crate::dynamics::{
-    IslandManager, JointData, Multibody, MultibodyJoint, RigidBodyActivation, RigidBodyHandle,
-    RigidBodyIds, RigidBodyType,
+    IslandManager, JointData, Multibody, MultibodyJoint, RigidBodyActivation, RigidBodyChanges,
+    RigidBodyHandle, RigidBodyIds, RigidBodyType,
 };
 use crate::geometry::{InteractionGraph, RigidBodyGraphIndex};
 use crate::parry::partitioning::IndexedData;
@@ -54,6 +54,16 @@ impl IndexedData for MultibodyJointHandle {
     }
 }
 
+impl crate::data::ArenaHandle for MultibodyJointHandle {
+    fn from_arena_index(index: Index) -> Self {
+        Self(index)
+    }
+
+    fn arena_index(self) -> Index {
+        self.0
+    }
+}
+
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct MultibodyJointLink {
@@ -85,6 +95,8 @@ pub struct MultibodyJointSet {
     // NOTE: this is mostly for the island extraction. So perhaps we won’t need
     //       that any more in the future when we improve our island builder.
     pub(crate) connectivity_graph: InteractionGraph<RigidBodyHandle, ()>,
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    user_data: UserDataStorage<MultibodyJointHandle>,
 }
 
 impl MultibodyJointSet {
@@ -94,9 +106,23 @@ impl MultibodyJointSet {
             multibodies: Arena::new(),
             rb2mb: Coarena::new(),
             connectivity_graph: InteractionGraph::new(),
+            user_data: UserDataStorage::default(),
         }
     }
 
+    /// The typed user data attached to the multibody joints of this set.
+    pub fn user_data(&self) -> &UserDataStorage<MultibodyJointHandle> {
+        &self.user_data
+    }
+
+    /// The typed user data attached to the multibody joints of this set.
+    ///
+    /// The user data attached to an element is detached when it is removed from this set. It
+    /// isn't serialized with this set: see `UserDataStorage::serialize_map`.
+    pub fn user_data_mut(&mut self) -> &mut UserDataStorage<MultibodyJointHandle> {
+        &mut self.user_data
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = (MultibodyJointHandle, &Multibody, &MultibodyLink)> {
         self.rb2mb
             .iter()
@@ -108,6 +134,9 @@ impl MultibodyJointSet {
     }
 
     /// Inserts a new multibody_joint into this set.
+    ///
+    /// Returns `None` if the joint would introduce an invalid configuration, or if it is a
+    /// pulley joint, which is only supported as an impulse joint.
     pub fn insert(
         &mut self,
         body1: RigidBodyHandle,
@@ -115,6 +144,11 @@ impl MultibodyJointSet {
         data: impl Into<JointData>,
     ) -> Option<MultibodyJointHandle> {
         let data = data.into();
+
+        if data.pulley.is_some() {
+            return None;
+        }
+
         let link1 = self.rb2mb.get(body1.0).copied().unwrap_or_else(|| {
             let mb_handle = self.multibodies.insert(Multibody::with_root(body1));
             MultibodyJointLink {
@@ -175,6 +209,7 @@ impl MultibodyJointSet {
     {
         if let Some(removed) = self.rb2mb.get(handle.0).copied() {
             let multibody = self.multibodies.remove(removed.multibody.0).unwrap();
+            self.user_data.detach(handle);
 
             // Remove the edge from the connectivity graph.
             if let Some(parent_link) = multibody.link(removed.id).unwrap().parent_id() {
@@ -198,8 +233,11 @@ impl MultibodyJointSet {
                 for multibody in multibodies {
                     if multibody.num_links() == 1 {
                         // We don’t have any multibody_joint attached to this body, remove it.
-                        if let Some(other) = self.connectivity_graph.remove_node(removed.graph_id) {
-                            self.rb2mb.get_mut(other.0).unwrap().graph_id = removed.graph_id;
+                        let rb = multibody.links().next().unwrap().rigid_body;
+                        let isolated = self.rb2mb.remove(rb.0, Default::default()).unwrap();
+                        if let Some(other) = self.connectivity_graph.remove_node(isolated.graph_id)
+                        {
+                            self.rb2mb.get_mut(other.0).unwrap().graph_id = isolated.graph_id;
                         }
                     } else {
                         let mb_id = self.multibodies.insert(multibody);
@@ -214,6 +252,48 @@ impl MultibodyJointSet {
         }
     }
 
+    /// Re-attaches the multibody_joint `handle` to the rigid-bodies `body1` and `body2`.
+    ///
+    /// The joint keeps its data and coordinates, but its handle becomes `body2`. Returns `None`
+    /// (and leaves the joint removed) if the new attachment would introduce an invalid
+    /// configuration.
+    pub(crate) fn reattach<Bodies>(
+        &mut self,
+        handle: MultibodyJointHandle,
+        body1: RigidBodyHandle,
+        body2: RigidBodyHandle,
+        islands: &mut IslandManager,
+        bodies: &mut Bodies,
+    ) -> Option<MultibodyJointHandle>
+    where
+        Bodies: ComponentSetMut<RigidBodyActivation>
+            + ComponentSet<RigidBodyType>
+            + ComponentSetMut<RigidBodyIds>,
+    {
+        let (multibody, link_id) = self.get(handle)?;
+        let joint = multibody.link(link_id)?.joint;
+
+        // Take the user data out so it isn't detached by `remove`.
+        let mut user_data = std::mem::take(&mut self.user_data);
+        self.remove(handle, islands, bodies, true);
+        let new_handle = self.insert(body1, body2, joint.data);
+
+        match new_handle {
+            Some(new_handle) => user_data.transfer(handle, new_handle),
+            None => user_data.detach(handle),
+        }
+
+        self.user_data = user_data;
+        let new_handle = new_handle?;
+
+        // Restore the coordinates of the joint.
+        let link = *self.rb2mb.get(body2.0)?;
+        self.multibodies[link.multibody.0].link_mut(link.id)?.joint = joint;
+        islands.wake_up(bodies, body1, true);
+        islands.wake_up(bodies, body2, true);
+        Some(new_handle)
+    }
+
     /// Removes all the multibody_joints from the multibody the given rigid-body is part of.
     pub fn remove_multibody_articulations<Bodies>(
         &mut self,
@@ -231,6 +311,7 @@ impl MultibodyJointSet {
             let multibody = self.multibodies.remove(removed.multibody.0).unwrap();
             for link in multibody.links() {
                 let rb_handle = link.rigid_body;
+                self.user_data.detach(MultibodyJointHandle(rb_handle.0));
 
                 if wake_up {
                     islands.wake_up(bodies, rb_handle, true);
@@ -282,6 +363,71 @@ impl MultibodyJointSet {
         }
     }
 
+    /// Applies the enabled/disabled state of the modified multibody links to their whole multibody.
+    ///
+    /// A multibody can't be simulated partially, so disabling (resp. enabling) one of its links
+    /// disables (resp. enables) all the other links of the same multibody. The other links
+    /// affected are marked as modified and pushed to `modified_bodies`.
+    pub(crate) fn propagate_enabled_state<Bodies>(
+        &self,
+        bodies: &mut Bodies,
+        modified_bodies: &mut Vec<RigidBodyHandle>,
+    ) where
+        Bodies: ComponentSetMut<RigidBodyActivation> + ComponentSetMut<RigidBodyChanges>,
+    {
+        for i in 0..modified_bodies.len() {
+            let handle = modified_bodies[i];
+            let changes: Option<&RigidBodyChanges> = bodies.get(handle.0);
+
+            if !changes
+                .is_some_and(|changes| changes.contains(RigidBodyChanges::ENABLED_OR_DISABLED))
+            {
+                continue;
+            }
+
+            let multibody = match self
+                .rb2mb
+                .get(handle.0)
+                .and_then(|link| self.multibodies.get(link.multibody.0))
+            {
+                Some(multibody) => multibody,
+                None => continue,
+            };
+            let enabled = ComponentSet::<RigidBodyActivation>::index(bodies, handle.0).enabled;
+
+            for link in multibody.links() {
+                let activation: &RigidBodyActivation = bodies.index(link.rigid_body.0);
+
+                if activation.enabled == enabled {
+                    continue;
+                }
+
+                let was_sleeping = activation.sleeping;
+                bodies.map_mut_internal(
+                    link.rigid_body.0,
+                    |activation: &mut RigidBodyActivation| {
+                        activation.enabled = enabled;
+
+                        if enabled {
+                            activation.wake_up(true);
+                        }
+                    },
+                );
+                bodies.map_mut_internal(link.rigid_body.0, |changes: &mut RigidBodyChanges| {
+                    if !changes.contains(RigidBodyChanges::MODIFIED) {
+                        modified_bodies.push(link.rigid_body);
+                    }
+
+                    *changes |= RigidBodyChanges::MODIFIED | RigidBodyChanges::ENABLED_OR_DISABLED;
+
+                    if enabled && was_sleeping {
+                        changes.insert(RigidBodyChanges::SLEEP);
+                    }
+                });
+            }
+        }
+    }
+
     /// Returns the link of this multibody attached to the given rigid-body.
     ///
     /// Returns `None` if `rb` isn’t part of any rigid-body.
@@ -8,6 +8,9 @@ use crate::dynamics::{IntegrationParameters, JointMotor, Multibody};
 use crate::math::Real;
 use na::DVector;
 
+#[cfg(feature = "dim3")]
+use crate::{dynamics::JointSwingTwistLimits, math::Rotation};
+
 /// Initializes and generate the velocity constraints applicable to the multibody links attached
 /// to this multibody_joint.
 pub fn unit_joint_limit_constraint(
@@ -51,6 +54,7 @@ pub fn unit_joint_limit_constraint(
         joint_id: usize::MAX,
         impulse: 0.0,
         impulse_bounds,
+        cfm_gain: 0.0,
         inv_lhs: crate::utils::inv(lhs),
         rhs: rhs_wo_bias + rhs_bias,
         rhs_wo_bias,
@@ -97,9 +101,10 @@ pub fn unit_joint_motor_constraint(
         rhs_wo_bias += (curr_pos - motor_params.target_pos) * motor_params.stiffness;
     }
 
-    if motor_params.damping != 0.0 {
+    if motor_params.damping != 0.0 || motor_params.lookahead != 0.0 {
         let dvel = joint_velocity[dof_id];
         rhs_wo_bias += (dvel - motor_params.target_vel) * motor_params.damping;
+        rhs_wo_bias += dvel * motor_params.lookahead * motor_params.stiffness;
     }
 
     let constraint = JointGenericVelocityGroundConstraint {
@@ -109,7 +114,8 @@ pub fn unit_joint_motor_constraint(
         joint_id: usize::MAX,
         impulse: 0.0,
         impulse_bounds,
-        inv_lhs: crate::utils::inv(lhs),
+        cfm_gain: motor_params.cfm_gain,
+        inv_lhs: crate::utils::inv(lhs + motor_params.cfm_gain),
         rhs: rhs_wo_bias,
         rhs_wo_bias,
         writeback_id: WritebackId::Limit(dof_id),
@@ -120,3 +126,212 @@ pub fn unit_joint_motor_constraint(
     ));
     *j_id += 2 * ndofs;
 }
+
+/// Fills the jacobians of the distance formed by the given coupled degrees of freedom.
+///
+/// Returns the current distance, its time derivative, and the projected mass `J^t * M^-1 J`.
+fn coupled_joint_jacobians(
+    multibody: &Multibody,
+    link: &MultibodyLink,
+    coupled_dofs: &[(usize, Real)],
+    j_id: usize,
+    jacobians: &mut DVector<Real>,
+) -> (Real, Real, Real) {
+    let ndofs = multibody.ndofs();
+    let joint_velocity = multibody.joint_velocity(link);
+
+    let dist = coupled_dofs
+        .iter()
+        .map(|(_, coord)| coord * coord)
+        .sum::<Real>()
+        .sqrt();
+    let inv_dist = crate::utils::inv(dist);
+    let mut dvel = 0.0;
+
+    jacobians.rows_mut(j_id, ndofs * 2).fill(0.0);
+
+    for (dof_id, coord) in coupled_dofs {
+        let coeff = coord * inv_dist;
+        let dof_j_id = j_id + dof_id + link.assembly_id;
+        jacobians[dof_j_id] = coeff;
+        jacobians[dof_j_id + ndofs] = coeff;
+        dvel += joint_velocity[*dof_id] * coeff;
+    }
+
+    multibody
+        .inv_augmented_mass()
+        .solve_mut(&mut jacobians.rows_mut(j_id + ndofs, ndofs));
+
+    let lhs = jacobians
+        .rows(j_id, ndofs)
+        .dot(&jacobians.rows(j_id + ndofs, ndofs)); // = J^t * M^-1 J
+
+    (dist, dvel, lhs)
+}
+
+/// Initializes and generate the velocity constraint limiting the distance formed by
+/// several coupled linear degrees of freedom of this multibody_joint.
+///
+/// Each element of `coupled_dofs` is the index of a coupled degree of freedom and its current
+/// coordinate.
+pub fn coupled_joint_limit_constraint(
+    params: &IntegrationParameters,
+    multibody: &Multibody,
+    link: &MultibodyLink,
+    limits: [Real; 2],
+    coupled_dofs: &[(usize, Real)],
+    j_id: &mut usize,
+    jacobians: &mut DVector<Real>,
+    constraints: &mut Vec<AnyJointVelocityConstraint>,
+) {
+    let ndofs = multibody.ndofs();
+    let (dist, dvel, lhs) =
+        coupled_joint_jacobians(multibody, link, coupled_dofs, *j_id, jacobians);
+
+    let min_enabled = dist < limits[0];
+    let max_enabled = limits[1] < dist;
+    let erp_inv_dt = params.erp_inv_dt();
+    let rhs_bias = ((dist - limits[1]).max(0.0) - (limits[0] - dist).max(0.0)) * erp_inv_dt;
+    let rhs_wo_bias = dvel;
+
+    let impulse_bounds = [
+        min_enabled as u32 as Real * -Real::MAX,
+        max_enabled as u32 as Real * Real::MAX,
+    ];
+
+    let constraint = JointGenericVelocityGroundConstraint {
+        mj_lambda2: multibody.solver_id,
+        ndofs2: ndofs,
+        j_id2: *j_id,
+        joint_id: usize::MAX,
+        impulse: 0.0,
+        impulse_bounds,
+        cfm_gain: 0.0,
+        inv_lhs: crate::utils::inv(lhs),
+        rhs: rhs_wo_bias + rhs_bias,
+        rhs_wo_bias,
+        writeback_id: WritebackId::Limit(coupled_dofs[0].0),
+    };
+
+    constraints.push(AnyJointVelocityConstraint::JointGenericGroundConstraint(
+        constraint,
+    ));
+    *j_id += 2 * ndofs;
+}
+
+/// Initializes and generate the velocity constraint motorizing the distance formed by
+/// several coupled linear degrees of freedom of this multibody_joint.
+///
+/// Each element of `coupled_dofs` is the index of a coupled degree of freedom and its current
+/// coordinate.
+pub fn coupled_joint_motor_constraint(
+    params: &IntegrationParameters,
+    multibody: &Multibody,
+    link: &MultibodyLink,
+    motor: &JointMotor,
+    coupled_dofs: &[(usize, Real)],
+    j_id: &mut usize,
+    jacobians: &mut DVector<Real>,
+    constraints: &mut Vec<AnyJointVelocityConstraint>,
+) {
+    let ndofs = multibody.ndofs();
+    let (dist, dvel, lhs) =
+        coupled_joint_jacobians(multibody, link, coupled_dofs, *j_id, jacobians);
+
+    let motor_params = motor.motor_params(params.dt);
+    let impulse_bounds = [-motor_params.max_impulse, motor_params.max_impulse];
+
+    let mut rhs_wo_bias = 0.0;
+    if motor_params.stiffness != 0.0 {
+        rhs_wo_bias += (dist - motor_params.target_pos) * motor_params.stiffness;
+    }
+
+    if motor_params.damping != 0.0 || motor_params.lookahead != 0.0 {
+        rhs_wo_bias += (dvel - motor_params.target_vel) * motor_params.damping;
+        rhs_wo_bias += dvel * motor_params.lookahead * motor_params.stiffness;
+    }
+
+    let constraint = JointGenericVelocityGroundConstraint {
+        mj_lambda2: multibody.solver_id,
+        ndofs2: ndofs,
+        j_id2: *j_id,
+        joint_id: usize::MAX,
+        impulse: 0.0,
+        impulse_bounds,
+        cfm_gain: motor_params.cfm_gain,
+        inv_lhs: crate::utils::inv(lhs + motor_params.cfm_gain),
+        rhs: rhs_wo_bias,
+        rhs_wo_bias,
+        writeback_id: WritebackId::Motor(coupled_dofs[0].0),
+    };
+
+    constraints.push(AnyJointVelocityConstraint::JointGenericGroundConstraint(
+        constraint,
+    ));
+    *j_id += 2 * ndofs;
+}
+
+/// Initializes and generate the velocity constraints enforcing the swing and twist limits
+/// of a multibody_joint with three free angular degrees of freedom.
+///
+/// `ang_dof_id` is the index of the first angular degree of freedom of this multibody_joint.
+#[cfg(feature = "dim3")]
+pub fn swing_twist_limit_constraints(
+    params: &IntegrationParameters,
+    multibody: &Multibody,
+    link: &MultibodyLink,
+    swing_twist: &JointSwingTwistLimits,
+    joint_rot: &Rotation<Real>,
+    ang_dof_id: usize,
+    j_id: &mut usize,
+    jacobians: &mut DVector<Real>,
+    constraints: &mut Vec<AnyJointVelocityConstraint>,
+) {
+    let ndofs = multibody.ndofs();
+    let joint_velocity = multibody.joint_velocity(link);
+    let erp_inv_dt = params.erp_inv_dt();
+
+    for (i, (axis, angle, limits)) in swing_twist.constraints(joint_rot).into_iter().enumerate() {
+        let min_enabled = angle < limits[0];
+        let max_enabled = limits[1] < angle;
+        let rhs_bias = ((angle - limits[1]).max(0.0) - (limits[0] - angle).max(0.0)) * erp_inv_dt;
+        let rhs_wo_bias = joint_velocity.fixed_rows::<3>(ang_dof_id).dot(&axis);
+
+        let dof_j_id = *j_id + ang_dof_id + link.assembly_id;
+        jacobians.rows_mut(*j_id, ndofs * 2).fill(0.0);
+        jacobians.fixed_rows_mut::<3>(dof_j_id).copy_from(&axis);
+        jacobians
+            .fixed_rows_mut::<3>(dof_j_id + ndofs)
+            .copy_from(&axis);
+        multibody
+            .inv_augmented_mass()
+            .solve_mut(&mut jacobians.rows_mut(*j_id + ndofs, ndofs));
+
+        let lhs = jacobians
+            .rows(*j_id, ndofs)
+            .dot(&jacobians.rows(*j_id + ndofs, ndofs)); // = J^t * M^-1 J
+        let impulse_bounds = [
+            min_enabled as u32 as Real * -Real::MAX,
+            max_enabled as u32 as Real * Real::MAX,
+        ];
+
+        let constraint = JointGenericVelocityGroundConstraint {
+            mj_lambda2: multibody.solver_id,
+            ndofs2: ndofs,
+            j_id2: *j_id,
+            joint_id: usize::MAX,
+            impulse: 0.0,
+            impulse_bounds,
+            cfm_gain: 0.0,
+            inv_lhs: crate::utils::inv(lhs),
+            rhs: rhs_wo_bias + rhs_bias,
+            rhs_wo_bias,
+            writeback_id: WritebackId::SwingTwist(i),
+        };
+
+        constraints.push(AnyJointVelocityConstraint::JointGenericGroundConstraint(
+            constraint,
+        ));
+        *j_id += 2 * ndofs;
+    }
+}
@@ -0,0 +1,171 @@
+use crate::dynamics::joint::JointData;
+use crate::math::{Point, Real};
+
+/// The pulley parameters of a joint.
+///
+/// The rope goes from the anchor of the first body to `ground_anchor1`, then from
+/// `ground_anchor2` to the anchor of the second body. Its lengths are constrained
+/// such that `len1 + ratio * len2 <= length`: the rope can only pull the bodies.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct JointPulley {
+    /// The fixed world-space anchor the first body hangs from.
+    pub ground_anchor1: Point<Real>,
+    /// The fixed world-space anchor the second body hangs from.
+    pub ground_anchor2: Point<Real>,
+    /// The ratio applied to the length on the side of the second body.
+    pub ratio: Real,
+    /// The constant value of `len1 + ratio * len2`.
+    pub length: Real,
+    /// The impulse applied by the pulley during the last timestep.
+    pub impulse: Real,
+}
+
+/// A joint connecting two bodies through a pulley with two fixed world-space anchors.
+///
+/// This is only supported as an impulse joint: `MultibodyJointSet::insert` rejects it.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PulleyJoint {
+    data: JointData,
+}
+
+impl PulleyJoint {
+    /// Creates a new pulley joint such that `len1 + len2 <= length`, where `len1` (resp. `len2`)
+    /// is the distance between the first (resp. second) body anchor and `ground_anchor1`
+    /// (resp. `ground_anchor2`).
+    pub fn new(ground_anchor1: Point<Real>, ground_anchor2: Point<Real>, length: Real) -> Self {
+        let data = JointData {
+            pulley: Some(JointPulley {
+                ground_anchor1,
+                ground_anchor2,
+                ratio: 1.0,
+                length,
+                impulse: 0.0,
+            }),
+            ..JointData::default()
+        };
+        Self { data }
+    }
+
+    pub fn data(&self) -> &JointData {
+        &self.data
+    }
+
+    #[must_use]
+    pub fn local_anchor1(mut self, anchor1: Point<Real>) -> Self {
+        self.data = self.data.local_anchor1(anchor1);
+        self
+    }
+
+    #[must_use]
+    pub fn local_anchor2(mut self, anchor2: Point<Real>) -> Self {
+        self.data = self.data.local_anchor2(anchor2);
+        self
+    }
+
+    /// Sets the ratio such that `len1 + ratio * len2 = length`.
+    #[must_use]
+    pub fn ratio(mut self, ratio: Real) -> Self {
+        if let Some(pulley) = &mut self.data.pulley {
+            pulley.ratio = ratio;
+        }
+        self
+    }
+}
+
+impl Into<JointData> for PulleyJoint {
+    fn into(self) -> JointData {
+        self.data
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{PulleyJoint, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn pulley_joint() {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        let ratio = 2.0;
+        let length = 9.0;
+        let ground_anchors = [Point::from(-Vector::x()), Point::from(Vector::x())];
+        let mut handles = vec![];
+
+        for (i, ground_anchor) in ground_anchors.iter().enumerate() {
+            let body = RigidBodyBuilder::new_dynamic()
+                .translation(ground_anchor.coords - Vector::y() * 3.0)
+                .build();
+            // The second body is light enough to be lifted despite the ratio.
+            let density = if i == 0 { 4.0 } else { 1.0 };
+            let collider = ColliderBuilder::ball(0.3).density(density).build();
+            handles.push(world.insert_body(body, collider).0);
+        }
+
+        let joint = PulleyJoint::new(ground_anchors[0], ground_anchors[1], length).ratio(ratio);
+        // Pulleys are only supported as impulse joints.
+        assert!(world
+            .multibody_joints
+            .insert(handles[0], handles[1], joint)
+            .is_none());
+        world.impulse_joints.insert(handles[0], handles[1], joint);
+
+        world.step_n(60);
+
+        let bodies = &world.bodies;
+        let len1 = (bodies[handles[0]].translation() - ground_anchors[0].coords).norm();
+        let len2 = (bodies[handles[1]].translation() - ground_anchors[1].coords).norm();
+
+        assert!(len1 > 4.0, "The heavier body should go down: {}", len1);
+        assert!(
+            (len1 + ratio * len2 - length).abs() < 0.05,
+            "The pulley length is not respected: {} + {} * {} != {}",
+            len1,
+            ratio,
+            len2,
+            length
+        );
+    }
+
+    #[test]
+    fn pulley_rope_does_not_push() {
+        let mut world = TestWorld::new(Vector::zeros());
+        let mut handles = vec![];
+
+        // The bodies move toward each other and toward the shared ground anchor.
+        for sign in [-1.0, 1.0] {
+            let body = RigidBodyBuilder::new_dynamic()
+                .translation(Vector::x() * sign * 3.0)
+                .linvel(Vector::x() * -sign)
+                .build();
+            let collider = ColliderBuilder::ball(0.3).build();
+            handles.push(world.insert_body(body, collider).0);
+        }
+
+        let joint = PulleyJoint::new(Point::origin(), Point::origin(), 6.0);
+        let joint = world.impulse_joints.insert(handles[0], handles[1], joint);
+
+        world.step_n(30);
+
+        let pulley = world
+            .impulse_joints
+            .get(joint)
+            .unwrap()
+            .data
+            .pulley
+            .unwrap();
+        assert_eq!(pulley.impulse, 0.0);
+
+        for (handle, sign) in handles.iter().zip([-1.0, 1.0]) {
+            let linvel = *world.bodies[*handle].linvel();
+            assert!(
+                (linvel - Vector::x() * -sign).norm() < 1.0e-5,
+                "The slack rope applied a force: {:?}",
+                linvel
+            );
+        }
+    }
+}
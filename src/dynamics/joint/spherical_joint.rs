@@ -1,6 +1,6 @@
 use crate::dynamics::joint::{JointAxesMask, JointData};
 use crate::dynamics::{JointAxis, MotorModel};
-use crate::math::{Point, Real};
+use crate::math::{Isometry, Point, Real};
 
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -25,6 +25,22 @@ impl SphericalJoint {
         &self.data
     }
 
+    /// Sets the joint’s frame, expressed in the first rigid-body’s local-space.
+    ///
+    /// The X axis of this frame is the axis of the swing cone set by [`Self::limit_swing_twist`].
+    #[must_use]
+    pub fn local_frame1(mut self, local_frame: Isometry<Real>) -> Self {
+        self.data = self.data.local_frame1(local_frame);
+        self
+    }
+
+    /// Sets the joint’s frame, expressed in the second rigid-body’s local-space.
+    #[must_use]
+    pub fn local_frame2(mut self, local_frame: Isometry<Real>) -> Self {
+        self.data = self.data.local_frame2(local_frame);
+        self
+    }
+
     #[must_use]
     pub fn local_anchor1(mut self, anchor1: Point<Real>) -> Self {
         self.data = self.data.local_anchor1(anchor1);
@@ -88,6 +104,15 @@ impl SphericalJoint {
         self.data = self.data.limit_axis(axis, limits);
         self
     }
+
+    /// Restricts the relative orientation of the two bodies to an elliptical swing cone
+    /// of half-angles `swing` (around the local Y and Z axes) and to the twist range `twist`
+    /// (around the local X axis).
+    #[must_use]
+    pub fn limit_swing_twist(mut self, swing: [Real; 2], twist: [Real; 2]) -> Self {
+        self.data = self.data.limit_swing_twist(swing, twist);
+        self
+    }
 }
 
 impl Into<JointData> for SphericalJoint {
@@ -95,3 +120,88 @@ impl Into<JointData> for SphericalJoint {
         self.data
     }
 }
+
+#[cfg(all(test, feature = "dim3"))]
+mod test {
+    use crate::dynamics::{RigidBodyBuilder, SphericalJoint};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+    use na::RealField;
+
+    fn spherical_joint_swing_twist_limits(use_multibody: bool) {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        let max_swing: Real = 0.5;
+        let max_twist: Real = 0.2;
+
+        // The body starts horizontally, along the swing cone’s axis, and spins around it.
+        let ground = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        let body = RigidBodyBuilder::new_dynamic()
+            .translation(Vector::x())
+            .angvel(Vector::x() * 5.0)
+            .build();
+        let (handle, _) = world.insert_body(body, ColliderBuilder::ball(0.1).build());
+
+        let joint = SphericalJoint::new()
+            .local_anchor2(Point::new(-1.0, 0.0, 0.0))
+            .limit_swing_twist([0.3, max_swing], [-max_twist, max_twist]);
+        if use_multibody {
+            world
+                .multibody_joints
+                .insert(ground, handle, joint)
+                .unwrap();
+        } else {
+            world.impulse_joints.insert(ground, handle, joint);
+        }
+
+        let mut largest_swing: Real = 0.0;
+        let mut largest_twist: Real = 0.0;
+
+        for _ in 0..200 {
+            world.step();
+
+            let rot = world.bodies[handle].rotation();
+            let x2 = rot * Vector::x();
+            let swing = x2.x.clamp(-1.0, 1.0).acos();
+            let twist = 2.0 * rot.i.atan2(rot.w);
+            let twist = if twist > Real::pi() {
+                twist - Real::two_pi()
+            } else if twist < -Real::pi() {
+                twist + Real::two_pi()
+            } else {
+                twist
+            };
+            largest_swing = largest_swing.max(swing);
+            largest_twist = largest_twist.max(twist.abs());
+        }
+
+        assert!(
+            largest_swing <= max_swing + 0.05,
+            "The swing limit was exceeded: {} > {}",
+            largest_swing,
+            max_swing
+        );
+        assert!(
+            largest_swing >= max_swing - 0.05,
+            "The body should rest against the swing limit: {} < {}",
+            largest_swing,
+            max_swing
+        );
+        assert!(
+            largest_twist <= max_twist + 0.05,
+            "The twist limit was exceeded: {} > {}",
+            largest_twist,
+            max_twist
+        );
+    }
+
+    #[test]
+    fn impulse_spherical_joint_swing_twist_limits() {
+        spherical_joint_swing_twist_limits(false)
+    }
+
+    #[test]
+    fn multibody_spherical_joint_swing_twist_limits() {
+        spherical_joint_swing_twist_limits(true)
+    }
+}
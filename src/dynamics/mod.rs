@@ -2,13 +2,12 @@
 
 pub use self::ccd::CCDSolver;
 pub use self::coefficient_combine_rule::CoefficientCombineRule;
-pub use self::integration_parameters::IntegrationParameters;
+pub use self::integration_parameters::{IntegrationParameters, SolverMode};
 pub use self::island_manager::IslandManager;
 pub(crate) use self::joint::JointGraphEdge;
 pub(crate) use self::joint::JointIndex;
 pub use self::joint::*;
 pub use self::rigid_body_components::*;
-#[cfg(not(feature = "parallel"))]
 pub(crate) use self::solver::IslandSolver;
 #[cfg(feature = "parallel")]
 pub(crate) use self::solver::ParallelIslandSolver;
@@ -17,7 +16,7 @@ pub use parry::mass_properties::MassProperties;
 #[cfg(feature = "default-sets")]
 pub use self::rigid_body::{RigidBody, RigidBodyBuilder};
 #[cfg(feature = "default-sets")]
-pub use self::rigid_body_set::{BodyPair, RigidBodySet};
+pub use self::rigid_body_set::{BodyPair, RigidBodyMergeError, RigidBodySet};
 
 mod ccd;
 mod coefficient_combine_rule;
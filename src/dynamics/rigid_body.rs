@@ -1,7 +1,8 @@
 use crate::dynamics::{
-    MassProperties, RigidBodyActivation, RigidBodyCcd, RigidBodyChanges, RigidBodyColliders,
-    RigidBodyDamping, RigidBodyDominance, RigidBodyForces, RigidBodyIds, RigidBodyMassProps,
-    RigidBodyMassPropsFlags, RigidBodyPosition, RigidBodyType, RigidBodyVelocity,
+    KinematicCcd, MassProperties, RigidBodyActivation, RigidBodyCcd, RigidBodyChanges,
+    RigidBodyColliders, RigidBodyDamping, RigidBodyDominance, RigidBodyForces, RigidBodyIds,
+    RigidBodyMassProps, RigidBodyMassPropsFlags, RigidBodyPosition, RigidBodyType,
+    RigidBodyVelocity,
 };
 use crate::geometry::{
     Collider, ColliderHandle, ColliderMassProps, ColliderParent, ColliderPosition, ColliderShape,
@@ -33,6 +34,8 @@ pub struct RigidBody {
     /// The dominance group this rigid-body is part of.
     pub(crate) rb_dominance: RigidBodyDominance,
     /// User-defined data associated to this rigid-body.
+    ///
+    /// Use a [`UserDataMap`](crate::data::UserDataMap) to attach typed data instead.
     pub user_data: u128,
 }
 
@@ -77,6 +80,32 @@ impl RigidBody {
         &mut self.rb_activation
     }
 
+    /// Is this rigid-body enabled?
+    pub fn is_enabled(&self) -> bool {
+        self.rb_activation.enabled
+    }
+
+    /// Enables or disables this rigid-body.
+    ///
+    /// A disabled rigid-body keeps its handle, its colliders, and its joints, but it is no longer
+    /// simulated, and its colliders no longer participate to collision detection and scene
+    /// queries. A rigid-body is woken up when it is enabled again. Impulse joints attached to a
+    /// disabled rigid-body are ignored by the solver.
+    ///
+    /// A multibody is enabled or disabled as a whole: enabling or disabling one of its links
+    /// enables or disables all its other links at the next call to `PhysicsPipeline::step`. A
+    /// disabled multibody is frozen and resumes with its previous joint coordinates and velocities.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if enabled != self.rb_activation.enabled {
+            self.rb_activation.enabled = enabled;
+            self.changes.insert(RigidBodyChanges::ENABLED_OR_DISABLED);
+
+            if enabled {
+                self.wake_up(true);
+            }
+        }
+    }
+
     /// The linear damping coefficient of this rigid-body.
     #[inline]
     pub fn linear_damping(&self) -> Real {
@@ -279,6 +308,21 @@ impl RigidBody {
         self.rb_ccd.ccd_enabled
     }
 
+    /// Sets the colliders this kinematic rigid-body performs motion-clamping against.
+    ///
+    /// With kinematic CCD enabled, the motion of this kinematic rigid-body during a timestep
+    /// (including the one set by `set_next_kinematic_position`) stops at its first time of
+    /// impact with the selected colliders, preventing it from passing through them.
+    /// This is ignored if the rigid-body isn't kinematic.
+    pub fn set_kinematic_ccd(&mut self, kinematic_ccd: KinematicCcd) {
+        self.rb_ccd.kinematic_ccd = kinematic_ccd;
+    }
+
+    /// The colliders this kinematic rigid-body performs motion-clamping against.
+    pub fn kinematic_ccd(&self) -> KinematicCcd {
+        self.rb_ccd.kinematic_ccd
+    }
+
     // This is different from `is_ccd_enabled`. This checks that CCD
     // is active for this rigid-body, i.e., if it was seen to move fast
     // enough to justify a CCD run.
@@ -365,6 +409,19 @@ impl RigidBody {
         self.rb_forces.gravity_scale = scale;
     }
 
+    /// Enables or disables the integration of the gyroscopic torque `ω × Iω` for this rigid-body.
+    ///
+    /// The gyroscopic torque makes spinning bodies with non-uniform angular inertia precess or flip,
+    /// and is needed for their angular momentum to be conserved. This only has an effect in 3D.
+    pub fn enable_gyroscopic_forces(&mut self, enabled: bool) {
+        self.rb_forces.gyroscopic_forces_enabled = enabled;
+    }
+
+    /// Is the gyroscopic torque `ω × Iω` integrated for this rigid-body?
+    pub fn is_gyroscopic_forces_enabled(&self) -> bool {
+        self.rb_forces.gyroscopic_forces_enabled
+    }
+
     /// The dominance group of this rigid-body.
     pub fn dominance_group(&self) -> i8 {
         self.rb_dominance.0
@@ -378,6 +435,55 @@ impl RigidBody {
         }
     }
 
+    /// The factor applied to the inverse mass of this rigid-body by the contact and joint constraints.
+    pub fn inv_mass_scale(&self) -> Real {
+        self.rb_mprops.inv_mass_scale
+    }
+
+    /// Sets the factor applied to the inverse mass of this rigid-body by the contact and joint
+    /// constraints only.
+    ///
+    /// This is a softer alternative to dominance groups. A factor smaller than `1.0` makes this
+    /// rigid-body react to contacts and joints as if it was heavier, without affecting its response
+    /// to gravity and other forces. For example, a character with a small inverse mass scale can
+    /// push crates without being pushed back as much by them. A factor of `0.0` prevents contacts
+    /// and joints from changing the linear velocity of this rigid-body.
+    pub fn set_inv_mass_scale(&mut self, scale: Real, wake_up: bool) {
+        assert!(scale >= 0.0, "The inverse mass scale cannot be negative.");
+
+        if wake_up && self.rb_activation.sleeping {
+            self.changes.insert(RigidBodyChanges::SLEEP);
+            self.rb_activation.sleeping = false;
+        }
+
+        self.rb_mprops.inv_mass_scale = scale;
+    }
+
+    /// The factor applied to the inverse angular inertia of this rigid-body by the contact and
+    /// joint constraints.
+    pub fn inv_inertia_scale(&self) -> Real {
+        self.rb_mprops.inv_inertia_scale
+    }
+
+    /// Sets the factor applied to the inverse angular inertia of this rigid-body by the contact
+    /// and joint constraints only.
+    ///
+    /// This is the angular counterpart of [`Self::set_inv_mass_scale`]. A factor of `0.0` prevents
+    /// contacts and joints from changing the angular velocity of this rigid-body.
+    pub fn set_inv_inertia_scale(&mut self, scale: Real, wake_up: bool) {
+        assert!(
+            scale >= 0.0,
+            "The inverse inertia scale cannot be negative."
+        );
+
+        if wake_up && self.rb_activation.sleeping {
+            self.changes.insert(RigidBodyChanges::SLEEP);
+            self.rb_activation.sleeping = false;
+        }
+
+        self.rb_mprops.inv_inertia_scale = scale;
+    }
+
     /// Adds a collider to this rigid-body.
     // TODO ECS: we keep this public for now just to simply our experiments on bevy_rapier.
     pub fn add_collider(
@@ -586,6 +692,9 @@ impl RigidBody {
     }
 
     /// If this rigid body is kinematic, sets its future position after the next timestep integration.
+    ///
+    /// If kinematic CCD is enabled (see `Self::set_kinematic_ccd`), the actual position reached after
+    /// the next timestep may be clamped so the rigid-body doesn't pass through other colliders.
     pub fn set_next_kinematic_position(&mut self, pos: Isometry<Real>) {
         if self.is_kinematic() {
             self.rb_pos.next_position = pos;
@@ -767,6 +876,8 @@ pub struct RigidBodyBuilder {
     pub angvel: AngVector<Real>,
     /// The scale factor applied to the gravity affecting the rigid-body to be built, `1.0` by default.
     pub gravity_scale: Real,
+    /// Whether the gyroscopic torque is integrated for the rigid-body to be built, `false` by default.
+    pub gyroscopic_forces_enabled: bool,
     /// Damping factor for gradually slowing down the translational motion of the rigid-body, `0.0` by default.
     pub linear_damping: Real,
     /// Damping factor for gradually slowing down the angular motion of the rigid-body, `0.0` by default.
@@ -779,12 +890,20 @@ pub struct RigidBodyBuilder {
     pub can_sleep: bool,
     /// Whether or not the rigid-body is to be created asleep.
     pub sleeping: bool,
+    /// Whether or not the rigid-body is to be created enabled.
+    pub enabled: bool,
     /// Whether continuous collision-detection is enabled for the rigid-body to be built.
     ///
     /// CCD prevents tunneling, but may still allow limited interpenetration of colliders.
     pub ccd_enabled: bool,
+    /// The colliders the kinematic rigid-body to be built performs motion-clamping against.
+    pub kinematic_ccd: KinematicCcd,
     /// The dominance group of the rigid-body to be built.
     pub dominance_group: i8,
+    /// The factor applied to the inverse mass of the rigid-body to be built by contacts and joints.
+    pub inv_mass_scale: Real,
+    /// The factor applied to the inverse angular inertia of the rigid-body to be built by contacts and joints.
+    pub inv_inertia_scale: Real,
     /// An arbitrary user-defined 128-bit integer associated to the rigid-bodies built by this builder.
     pub user_data: u128,
 }
@@ -797,6 +916,7 @@ impl RigidBodyBuilder {
             linvel: Vector::zeros(),
             angvel: na::zero(),
             gravity_scale: 1.0,
+            gyroscopic_forces_enabled: false,
             linear_damping: 0.0,
             angular_damping: 0.0,
             rb_type,
@@ -804,8 +924,12 @@ impl RigidBodyBuilder {
             mass_properties: MassProperties::zero(),
             can_sleep: true,
             sleeping: false,
+            enabled: true,
             ccd_enabled: false,
+            kinematic_ccd: KinematicCcd::Disabled,
             dominance_group: 0,
+            inv_mass_scale: 1.0,
+            inv_inertia_scale: 1.0,
             user_data: 0,
         }
     }
@@ -836,12 +960,38 @@ impl RigidBodyBuilder {
         self
     }
 
+    /// Sets whether the gyroscopic torque `ω × Iω` is integrated for the rigid-body to be created.
+    ///
+    /// This only has an effect in 3D.
+    pub fn gyroscopic_forces_enabled(mut self, enabled: bool) -> Self {
+        self.gyroscopic_forces_enabled = enabled;
+        self
+    }
+
     /// Sets the dominance group of this rigid-body.
     pub fn dominance_group(mut self, group: i8) -> Self {
         self.dominance_group = group;
         self
     }
 
+    /// Sets the factor applied to the inverse mass of the rigid-body to be built by the contact
+    /// and joint constraints only.
+    ///
+    /// See [`RigidBody::set_inv_mass_scale`] for details.
+    pub fn inv_mass_scale(mut self, scale: Real) -> Self {
+        self.inv_mass_scale = scale;
+        self
+    }
+
+    /// Sets the factor applied to the inverse angular inertia of the rigid-body to be built by
+    /// the contact and joint constraints only.
+    ///
+    /// See [`RigidBody::set_inv_inertia_scale`] for details.
+    pub fn inv_inertia_scale(mut self, scale: Real) -> Self {
+        self.inv_inertia_scale = scale;
+        self
+    }
+
     /// Sets the initial translation of the rigid-body to be created.
     pub fn translation(mut self, translation: Vector<Real>) -> Self {
         self.position.translation.vector = translation;
@@ -1061,12 +1211,27 @@ impl RigidBodyBuilder {
         self
     }
 
+    /// Sets the colliders the kinematic rigid-body to be built performs motion-clamping against.
+    ///
+    /// This prevents the kinematic rigid-body from passing through these colliders.
+    /// This is ignored if the rigid-body isn't kinematic.
+    pub fn kinematic_ccd(mut self, kinematic_ccd: KinematicCcd) -> Self {
+        self.kinematic_ccd = kinematic_ccd;
+        self
+    }
+
     /// Sets whether or not the rigid-body is to be created asleep.
     pub fn sleeping(mut self, sleeping: bool) -> Self {
         self.sleeping = sleeping;
         self
     }
 
+    /// Sets whether or not the rigid-body is to be created enabled.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
     /// Build a new rigid-body with the parameters configured with this builder.
     pub fn build(&self) -> RigidBody {
         let mut rb = RigidBody::new();
@@ -1081,8 +1246,13 @@ impl RigidBodyBuilder {
         rb.rb_damping.linear_damping = self.linear_damping;
         rb.rb_damping.angular_damping = self.angular_damping;
         rb.rb_forces.gravity_scale = self.gravity_scale;
+        rb.rb_forces.gyroscopic_forces_enabled = self.gyroscopic_forces_enabled;
         rb.rb_dominance = RigidBodyDominance(self.dominance_group);
+        rb.set_inv_mass_scale(self.inv_mass_scale, false);
+        rb.set_inv_inertia_scale(self.inv_inertia_scale, false);
+        rb.rb_activation.enabled = self.enabled;
         rb.enable_ccd(self.ccd_enabled);
+        rb.set_kinematic_ccd(self.kinematic_ccd);
 
         if self.can_sleep && self.sleeping {
             rb.sleep();
@@ -1096,3 +1266,183 @@ impl RigidBodyBuilder {
         rb
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{FixedJoint, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn rigid_body_and_collider_enabling() {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+
+        #[cfg(feature = "dim2")]
+        let ground_collider = ColliderBuilder::cuboid(10.0, 0.5).build();
+        #[cfg(feature = "dim3")]
+        let ground_collider = ColliderBuilder::cuboid(10.0, 0.5, 10.0).build();
+        let ground_collider = world.colliders.insert(ground_collider);
+
+        let body = RigidBodyBuilder::new_dynamic()
+            .translation(Vector::y() * 0.95)
+            .build();
+        let (body, collider) = world.insert_body(body, ColliderBuilder::ball(0.5).build());
+
+        world.step();
+        assert!(world
+            .narrow_phase
+            .contact_pair(ground_collider, collider)
+            .is_some());
+
+        // A disabled rigid-body is no longer simulated and its colliders are disabled.
+        world.bodies[body].set_enabled(false);
+        let pos = *world.bodies[body].position();
+        world.step_n(10);
+        assert!(!world.colliders[collider].is_enabled());
+        assert_eq!(*world.bodies[body].position(), pos);
+        assert!(world
+            .narrow_phase
+            .contact_pair(ground_collider, collider)
+            .is_none());
+
+        // A re-enabled rigid-body is simulated again.
+        world.bodies[body].set_enabled(true);
+        world.step();
+        assert!(world.colliders[collider].is_enabled());
+        assert!(world
+            .narrow_phase
+            .contact_pair(ground_collider, collider)
+            .is_some());
+
+        // A disabled collider doesn't collide anymore.
+        world.colliders[ground_collider].set_enabled(false);
+        world.step_n(10);
+        assert!(world
+            .narrow_phase
+            .contact_pair(ground_collider, collider)
+            .is_none());
+        assert!(world.bodies[body].position().translation.y < pos.translation.y - 0.1);
+    }
+
+    #[test]
+    fn rigid_body_disable_multibody_link() {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        let (body1, _) = world.insert_body(
+            RigidBodyBuilder::new_dynamic().build(),
+            ColliderBuilder::ball(0.5).build(),
+        );
+        let (body2, _) = world.insert_body(
+            RigidBodyBuilder::new_dynamic()
+                .translation(Vector::x() * 2.0)
+                .build(),
+            ColliderBuilder::ball(0.5).build(),
+        );
+        world
+            .multibody_joints
+            .insert(
+                body1,
+                body2,
+                FixedJoint::new().local_anchor1(Point::from(Vector::x() * 2.0)),
+            )
+            .unwrap();
+
+        world.step();
+        world.bodies[body2].set_enabled(false);
+        world.step();
+
+        // The whole multibody is disabled and frozen.
+        assert!(!world.bodies[body1].is_enabled());
+        let pos1 = *world.bodies[body1].translation();
+        let pos2 = *world.bodies[body2].translation();
+        world.step_n(10);
+        assert_eq!(*world.bodies[body1].translation(), pos1);
+        assert_eq!(*world.bodies[body2].translation(), pos2);
+
+        // Re-enabling the link resumes the whole multibody, with its joint.
+        world.bodies[body2].set_enabled(true);
+        world.step_n(10);
+        assert!(world.bodies[body1].is_enabled());
+        assert!(world.bodies[body1].translation().y < pos1.y);
+        assert!(world.bodies[body2].translation().y < pos2.y);
+        assert!(world.multibody_joints.rigid_body_link(body2).is_some());
+        let offset = world.bodies[body2].translation() - world.bodies[body1].translation();
+        assert!((offset - Vector::x() * 2.0).norm() < 1.0e-3);
+    }
+
+    fn mass_scaled_collision(inv_mass_scale: Real, use_joint: bool) -> (Real, Real) {
+        let mut world = TestWorld::new(Vector::zeros());
+
+        // The "character" moving toward the "crate".
+        let character = RigidBodyBuilder::new_dynamic()
+            .linvel(Vector::x())
+            .inv_mass_scale(inv_mass_scale)
+            .build();
+        let (character, _) = world.insert_body(character, ColliderBuilder::ball(0.5).build());
+
+        let crate_x = if use_joint { 2.0 } else { 1.05 };
+        let crate_body = RigidBodyBuilder::new_dynamic()
+            .translation(Vector::x() * crate_x)
+            .build();
+        let (crate_body, _) = world.insert_body(crate_body, ColliderBuilder::ball(0.5).build());
+
+        if use_joint {
+            let joint = FixedJoint::new().local_anchor1(Point::from(Vector::x() * crate_x));
+            world.impulse_joints.insert(character, crate_body, joint);
+        }
+
+        world.step_n(60);
+
+        (
+            world.bodies[character].linvel().x,
+            world.bodies[crate_body].linvel().x,
+        )
+    }
+
+    #[test]
+    fn inv_mass_scale() {
+        for use_joint in [false, true] {
+            // With the default scale, both bodies have the same mass.
+            let (vel1, vel2) = mass_scaled_collision(1.0, use_joint);
+            assert!((vel1 - 0.5).abs() < 1.0e-2, "{} != 0.5", vel1);
+            assert!((vel2 - 0.5).abs() < 1.0e-2, "{} != 0.5", vel2);
+
+            // The character is seen ten times heavier by the constraints.
+            let (vel1, vel2) = mass_scaled_collision(0.1, use_joint);
+            let expected = 1.0 / 1.1;
+            assert!((vel1 - expected).abs() < 1.0e-2, "{} != {}", vel1, expected);
+            assert!((vel2 - expected).abs() < 1.0e-2, "{} != {}", vel2, expected);
+        }
+    }
+
+    #[test]
+    fn zero_inv_inertia_scale() {
+        let mut world = TestWorld::new(Vector::zeros());
+
+        let body1 = RigidBodyBuilder::new_dynamic().build();
+        let (body1, _) = world.insert_body(body1, ColliderBuilder::ball(0.5).build());
+        let body2 = RigidBodyBuilder::new_dynamic()
+            .translation(Vector::x() * 2.0)
+            .build();
+        let (body2, _) = world.insert_body(body2, ColliderBuilder::ball(0.5).build());
+
+        world.bodies[body2].sleep();
+        world.bodies[body2].set_inv_inertia_scale(0.0, true);
+        assert!(!world.bodies[body2].is_sleeping());
+
+        // The torques still affect a rigid-body with a zero inverse inertia scale.
+        #[cfg(feature = "dim2")]
+        let torque = 1.0;
+        #[cfg(feature = "dim3")]
+        let torque = Vector::z();
+        world.bodies[body1].apply_torque(torque, true);
+        world.bodies[body2].apply_torque(torque, true);
+        world.step();
+
+        let (angvel1, angvel2) = (world.bodies[body1].angvel(), world.bodies[body2].angvel());
+        #[cfg(feature = "dim2")]
+        assert!(angvel1 != 0.0 && (angvel1 - angvel2).abs() < 1.0e-5);
+        #[cfg(feature = "dim3")]
+        assert!(angvel1.norm() != 0.0 && (angvel1 - angvel2).norm() < 1.0e-5);
+    }
+}
@@ -8,6 +8,8 @@ use crate::math::{
     AngVector, AngularInertia, Isometry, Point, Real, Rotation, Translation, Vector,
 };
 use crate::parry::partitioning::IndexedData;
+#[cfg(feature = "dim3")]
+use crate::utils::WCrossMatrix;
 use crate::utils::{WAngularInertia, WCross, WDot};
 use num::Zero;
 
@@ -47,6 +49,16 @@ impl IndexedData for RigidBodyHandle {
     }
 }
 
+impl crate::data::ArenaHandle for RigidBodyHandle {
+    fn from_arena_index(index: crate::data::arena::Index) -> Self {
+        Self(index)
+    }
+
+    fn arena_index(self) -> crate::data::arena::Index {
+        self.0
+    }
+}
+
 /// The type of a body, governing the way it is affected by external forces.
 #[deprecated(note = "renamed as RigidBodyType")]
 pub type BodyStatus = RigidBodyType;
@@ -73,7 +85,6 @@ pub enum RigidBodyType {
     /// cannot be pushed by anything. In other words, the trajectory of a kinematic body can only be
     /// modified by the user and is independent from any contact or joint it is involved in.
     KinematicVelocityBased = 3,
-    // Semikinematic, // A kinematic that performs automatic CCD with the static environment to avoid traversing it?
     // Disabled,
 }
 
@@ -111,6 +122,8 @@ bitflags::bitflags! {
         const TYPE        = 1 << 4;
         /// Flag indicating that the `RigidBodyDominance` component of this rigid-body has been modified.
         const DOMINANCE   = 1 << 5;
+        /// Flag indicating that the rigid-body has been enabled or disabled.
+        const ENABLED_OR_DISABLED = 1 << 6;
     }
 }
 
@@ -238,6 +251,19 @@ pub struct RigidBodyMassProps {
     /// The square-root of the world-space inverse angular inertia tensor of the rigid-body,
     /// taking into account rotation locking.
     pub effective_world_inv_inertia_sqrt: AngularInertia<Real>,
+    /// Factor applied to the inverse mass of the rigid-body by the contact and joint
+    /// constraints only (default: `1.0`).
+    ///
+    /// This does not affect the response of the rigid-body to forces and impulses
+    /// applied by the user.
+    pub inv_mass_scale: Real,
+    /// Factor applied to the inverse angular inertia of the rigid-body by the contact and
+    /// joint constraints only (default: `1.0`). Must be non-negative; zero makes the body
+    /// rotationally immovable by constraints.
+    ///
+    /// This does not affect the response of the rigid-body to forces and impulses
+    /// applied by the user.
+    pub inv_inertia_scale: Real,
 }
 
 impl Default for RigidBodyMassProps {
@@ -248,6 +274,8 @@ impl Default for RigidBodyMassProps {
             world_com: Point::origin(),
             effective_inv_mass: Vector::zero(),
             effective_world_inv_inertia_sqrt: AngularInertia::zero(),
+            inv_mass_scale: 1.0,
+            inv_inertia_scale: 1.0,
         }
     }
 }
@@ -291,6 +319,42 @@ impl RigidBodyMassProps {
         self.effective_world_inv_inertia_sqrt.squared().inverse()
     }
 
+    /// The inverse mass used by the contact and joint constraints, i.e., the effective
+    /// inverse mass multiplied by `self.inv_mass_scale`.
+    #[must_use]
+    pub fn constraint_inv_mass(&self) -> Vector<Real> {
+        self.effective_inv_mass * self.inv_mass_scale
+    }
+
+    /// The square-root of the world-space inverse angular inertia tensor used by the contact
+    /// and joint constraints, i.e., taking `self.inv_inertia_scale` into account.
+    #[must_use]
+    pub fn constraint_world_inv_inertia_sqrt(&self) -> AngularInertia<Real> {
+        self.effective_world_inv_inertia_sqrt * self.inv_inertia_scale.sqrt()
+    }
+
+    /// The angular velocity change computed by the velocity solver, given the angular part of
+    /// its delta-velocity and the `torque` applied during `dt`.
+    ///
+    /// The torque can't be stored in the delta-velocity if `self.inv_inertia_scale` is zero,
+    /// so it is only taken into account in that case.
+    #[must_use]
+    pub(crate) fn solver_angvel_change(
+        &self,
+        dvel_angular: AngVector<Real>,
+        torque: AngVector<Real>,
+        dt: Real,
+    ) -> AngVector<Real> {
+        if self.inv_inertia_scale == 0.0 {
+            self.effective_world_inv_inertia_sqrt
+                .squared()
+                .transform_vector(torque * dt)
+        } else {
+            self.constraint_world_inv_inertia_sqrt()
+                .transform_vector(dvel_angular)
+        }
+    }
+
     /// Update the world-space mass properties of `self`, taking into account the new position.
     pub fn update_world_mass_properties(&mut self, position: &Isometry<Real>) {
         self.world_com = self.local_mprops.world_com(&position);
@@ -525,6 +589,58 @@ impl RigidBodyVelocity {
         result
     }
 
+    /// Integrates implicitly the gyroscopic torque `ω × Iω` during `dt` to compute a new angular velocity.
+    ///
+    /// The gyroscopic torque is what makes a body spinning around an axis with non-uniform inertia
+    /// precess or flip. The implicit integration is stable even for fast-spinning bodies and doesn't
+    /// dissipate energy. This returns `self` unchanged if the rigid-body has any rotation locked, or
+    /// any infinite principal angular inertia.
+    #[cfg(feature = "dim3")]
+    #[must_use]
+    pub fn integrate_gyroscopic_forces(
+        &self,
+        dt: Real,
+        rotation: &Rotation<Real>,
+        mprops: &RigidBodyMassProps,
+    ) -> RigidBodyVelocity {
+        const NUM_ITERATIONS: usize = 2;
+        let inv_inertia_sqrt = mprops.local_mprops.inv_principal_inertia_sqrt;
+
+        if mprops
+            .flags
+            .intersects(RigidBodyMassPropsFlags::ROTATION_LOCKED)
+            || inv_inertia_sqrt.iter().any(|e| *e == 0.0)
+        {
+            return *self;
+        }
+
+        // Solve the implicit midpoint rule `I (ω' - ω) + dt ωm × I ωm = 0` where `ωm = (ω + ω') / 2`
+        // with Newton iterations, expressed in the principal inertia frame where `I` is diagonal.
+        // This keeps both the norm of the angular momentum and the kinetic energy unchanged.
+        let inertia = na::Matrix3::from_diagonal(&inv_inertia_sqrt.map(|e| 1.0 / (e * e)));
+        let frame = rotation * mprops.local_mprops.principal_inertia_local_frame;
+        let angvel = frame.inverse_transform_vector(&self.angvel);
+        let mut new_angvel = angvel;
+
+        for _ in 0..NUM_ITERATIONS {
+            let mid_angvel = (angvel + new_angvel) / 2.0;
+            let mid_angmom = inertia * mid_angvel;
+            let residual = inertia * (new_angvel - angvel) + mid_angvel.cross(&mid_angmom) * dt;
+            let jacobian = inertia
+                + (mid_angvel.gcross_matrix() * inertia - mid_angmom.gcross_matrix()) * (dt / 2.0);
+
+            match jacobian.try_inverse() {
+                Some(inv_jacobian) => new_angvel -= inv_jacobian * residual,
+                None => break,
+            }
+        }
+
+        RigidBodyVelocity {
+            linvel: self.linvel,
+            angvel: frame * new_angvel,
+        }
+    }
+
     /// Are these velocities exactly equal to zero?
     #[must_use]
     pub fn is_zero(&self) -> bool {
@@ -659,6 +775,10 @@ pub struct RigidBodyForces {
     /// Gravity is multiplied by this scaling factor before it's
     /// applied to this rigid-body.
     pub gravity_scale: Real,
+    /// Is the gyroscopic torque `ω × Iω` integrated for this rigid-body?
+    ///
+    /// This only has an effect in 3D.
+    pub gyroscopic_forces_enabled: bool,
 }
 
 impl Default for RigidBodyForces {
@@ -667,6 +787,7 @@ impl Default for RigidBodyForces {
             force: na::zero(),
             torque: na::zero(),
             gravity_scale: 1.0,
+            gyroscopic_forces_enabled: false,
         }
     }
 }
@@ -708,6 +829,33 @@ impl RigidBodyForces {
     }
 }
 
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Hash)]
+/// The colliders a kinematic rigid-body performs Continuous-Collision-Detection against.
+///
+/// When enabled, the motion of a kinematic rigid-body (either set with
+/// `RigidBody::set_next_kinematic_position` or integrated from its velocity) is clamped
+/// at its first time of impact with the selected colliders, preventing it from
+/// passing through them.
+pub enum KinematicCcd {
+    /// The kinematic rigid-body is moved without any motion-clamping.
+    Disabled,
+    /// The kinematic rigid-body stops at its first impact with a collider attached to
+    /// a static rigid-body or not attached to any rigid-body.
+    Static,
+    /// The kinematic rigid-body stops at its first impact with a collider attached to
+    /// a static or dynamic rigid-body, or not attached to any rigid-body.
+    ///
+    /// Dynamic rigid-bodies are considered at their position at the beginning of the timestep.
+    StaticAndDynamic,
+}
+
+impl Default for KinematicCcd {
+    fn default() -> Self {
+        KinematicCcd::Disabled
+    }
+}
+
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug, Copy, PartialEq)]
 /// Information used for Continuous-Collision-Detection.
@@ -726,6 +874,10 @@ pub struct RigidBodyCcd {
     pub ccd_active: bool,
     /// Is CCD enabled for this rigid-body?
     pub ccd_enabled: bool,
+    /// The colliders a kinematic rigid-body performs motion-clamping against.
+    ///
+    /// This is ignored for non-kinematic rigid-bodies.
+    pub kinematic_ccd: KinematicCcd,
 }
 
 impl Default for RigidBodyCcd {
@@ -735,6 +887,7 @@ impl Default for RigidBodyCcd {
             ccd_max_dist: 0.0,
             ccd_active: false,
             ccd_enabled: false,
+            kinematic_ccd: KinematicCcd::Disabled,
         }
     }
 }
@@ -935,6 +1088,12 @@ pub struct RigidBodyActivation {
     pub time_since_can_sleep: Real,
     /// Is this body sleeping?
     pub sleeping: bool,
+    /// Is this body enabled?
+    ///
+    /// A disabled body is not simulated and its colliders don't participate to collision
+    /// detection. Use [`RigidBody::set_enabled`](crate::dynamics::RigidBody::set_enabled)
+    /// to modify it so that the physics pipeline is notified of the change.
+    pub enabled: bool,
 }
 
 impl Default for RigidBodyActivation {
@@ -967,6 +1126,7 @@ impl RigidBodyActivation {
             angular_threshold: Self::default_angular_threshold(),
             time_since_can_sleep: 0.0,
             sleeping: false,
+            enabled: true,
         }
     }
 
@@ -977,6 +1137,7 @@ impl RigidBodyActivation {
             angular_threshold: Self::default_angular_threshold(),
             sleeping: true,
             time_since_can_sleep: Self::default_time_until_sleep(),
+            enabled: true,
         }
     }
 
@@ -1011,3 +1172,83 @@ impl RigidBodyActivation {
         self.time_since_can_sleep = Self::default_time_until_sleep();
     }
 }
+
+#[cfg(all(test, feature = "dim3"))]
+mod test {
+    use crate::dynamics::{RigidBody, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    fn simulate_spinning_cuboid(
+        angvel: Vector<Real>,
+        gyroscopic_forces_enabled: bool,
+        mut callback: impl FnMut(&RigidBody),
+    ) {
+        let mut world = TestWorld::new(Vector::zeros());
+
+        let body = RigidBodyBuilder::new_dynamic()
+            .angvel(angvel)
+            .gyroscopic_forces_enabled(gyroscopic_forces_enabled)
+            .can_sleep(false)
+            .build();
+        let collider = ColliderBuilder::cuboid(0.1, 0.5, 1.0).build();
+        let (body, _) = world.insert_body(body, collider);
+
+        for _ in 0..600 {
+            world.step();
+            callback(&world.bodies[body]);
+        }
+    }
+
+    #[test]
+    fn gyroscopic_dzhanibekov_effect() {
+        // The `Y` axis is the intermediate principal axis of the cuboid, so a rotation around
+        // it is unstable: the body periodically flips while spinning.
+        let angvel = Vector::new(0.01, 10.0, 0.0);
+        let mut min_alignment = Real::MAX;
+        simulate_spinning_cuboid(angvel, true, |rb| {
+            let alignment = (rb.position().rotation * Vector::y()).dot(&Vector::y());
+            min_alignment = min_alignment.min(alignment);
+        });
+        assert!(min_alignment < -0.9);
+
+        // Without gyroscopic torque, the body keeps spinning around the same axis.
+        let mut min_alignment = Real::MAX;
+        simulate_spinning_cuboid(angvel, false, |rb| {
+            let alignment = (rb.position().rotation * Vector::y()).dot(&Vector::y());
+            min_alignment = min_alignment.min(alignment);
+        });
+        assert!(min_alignment > 0.99);
+    }
+
+    #[test]
+    fn gyroscopic_angular_momentum_conservation() {
+        fn angular_momentum(rb: &RigidBody) -> Vector<Real> {
+            let mprops = rb.mass_properties();
+            let frame = rb.position().rotation * mprops.principal_inertia_local_frame;
+            let local_angvel = frame.inverse_transform_vector(rb.angvel());
+            let inertia = mprops.inv_principal_inertia_sqrt.map(|e| 1.0 / (e * e));
+            frame * inertia.component_mul(&local_angvel)
+        }
+
+        let angvel = Vector::new(0.5, 1.0, 1.5);
+
+        for gyroscopic_forces_enabled in [true, false] {
+            let mut initial_momentum = None;
+            let mut max_error: Real = 0.0;
+            simulate_spinning_cuboid(angvel, gyroscopic_forces_enabled, |rb| {
+                let momentum = angular_momentum(rb);
+                let initial_momentum = *initial_momentum.get_or_insert(momentum);
+                max_error =
+                    max_error.max((momentum - initial_momentum).norm() / initial_momentum.norm());
+            });
+
+            if gyroscopic_forces_enabled {
+                assert!(max_error < 0.05, "{}", max_error);
+            } else {
+                assert!(max_error > 0.1, "{}", max_error);
+            }
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::data::{Arena, ComponentSet, ComponentSetMut, ComponentSetOption};
+use crate::data::{Arena, ComponentSet, ComponentSetMut, ComponentSetOption, UserDataStorage};
 use crate::dynamics::{
     ImpulseJointSet, RigidBody, RigidBodyCcd, RigidBodyChanges, RigidBodyDamping, RigidBodyForces,
     RigidBodyIds, RigidBodyMassProps, RigidBodyPosition, RigidBodyVelocity,
@@ -7,7 +7,12 @@ use crate::dynamics::{
     IslandManager, MultibodyJointSet, RigidBodyActivation, RigidBodyColliders, RigidBodyDominance,
     RigidBodyHandle, RigidBodyType,
 };
-use crate::geometry::ColliderSet;
+use crate::geometry::{ColliderHandle, ColliderSet};
+use crate::math::{AngVector, Point, Real, Vector};
+use crate::parry::mass_properties::MassProperties;
+use crate::utils::{WAngularInertia, WCross};
+use num::Zero;
+use std::fmt;
 use std::ops::{Index, IndexMut};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -20,6 +25,30 @@ pub struct BodyPair {
     pub body2: RigidBodyHandle,
 }
 
+/// The reason why rigid-bodies could not be merged.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RigidBodyMergeError {
+    /// The set of rigid-bodies to merge is empty, or its first element doesn't exist.
+    TargetNotFound,
+    /// One of the rigid-bodies to merge is a multibody link.
+    MultibodyLink(RigidBodyHandle),
+}
+
+impl fmt::Display for RigidBodyMergeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RigidBodyMergeError::TargetNotFound => {
+                write!(f, "the rigid-body to merge into does not exist")
+            }
+            RigidBodyMergeError::MultibodyLink(handle) => {
+                write!(f, "the rigid-body {:?} is a multibody link", handle)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RigidBodyMergeError {}
+
 impl BodyPair {
     /// Builds a new pair of rigid-body handles.
     pub fn new(body1: RigidBodyHandle, body2: RigidBodyHandle) -> Self {
@@ -37,6 +66,8 @@ pub struct RigidBodySet {
     // Could we avoid this?
     pub(crate) bodies: Arena<RigidBody>,
     pub(crate) modified_bodies: Vec<RigidBodyHandle>,
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    user_data: UserDataStorage<RigidBodyHandle>,
 }
 
 macro_rules! impl_field_component_set(
@@ -98,9 +129,23 @@ impl RigidBodySet {
         RigidBodySet {
             bodies: Arena::new(),
             modified_bodies: Vec::new(),
+            user_data: UserDataStorage::default(),
         }
     }
 
+    /// The typed user data attached to the rigid-bodies of this set.
+    pub fn user_data(&self) -> &UserDataStorage<RigidBodyHandle> {
+        &self.user_data
+    }
+
+    /// The typed user data attached to the rigid-bodies of this set.
+    ///
+    /// The user data attached to an element is detached when it is removed from this set. It
+    /// isn't serialized with this set: see `UserDataStorage::serialize_map`.
+    pub fn user_data_mut(&mut self) -> &mut UserDataStorage<RigidBodyHandle> {
+        &mut self.user_data
+    }
+
     pub(crate) fn take_modified(&mut self) -> Vec<RigidBodyHandle> {
         std::mem::replace(&mut self.modified_bodies, vec![])
     }
@@ -142,6 +187,8 @@ impl RigidBodySet {
         multibody_joints: &mut MultibodyJointSet,
     ) -> Option<RigidBody> {
         let rb = self.bodies.remove(handle.0)?;
+        self.user_data.detach(handle);
+
         /*
          * Update active sets.
          */
@@ -163,6 +210,284 @@ impl RigidBodySet {
         Some(rb)
     }
 
+    /// Merges a set of rigid-bodies into a single one.
+    ///
+    /// All the rigid-bodies identified by `handles` are merged into the first one, which keeps
+    /// its handle and pose. The colliders, additional mass properties, and impulse joints of the
+    /// other rigid-bodies are moved to the merged rigid-body, and its velocity is set so that
+    /// the total linear and angular momentum of the merged rigid-bodies are preserved.
+    /// The other rigid-bodies are then removed from this set.
+    ///
+    /// Returns the handle of the merged rigid-body. Nothing is merged if `handles` is empty, if
+    /// its first element doesn't identify a rigid-body of this set, or if one of the rigid-bodies
+    /// is a multibody link.
+    pub fn merge(
+        &mut self,
+        handles: &[RigidBodyHandle],
+        islands: &mut IslandManager,
+        colliders: &mut ColliderSet,
+        impulse_joints: &mut ImpulseJointSet,
+        multibody_joints: &mut MultibodyJointSet,
+    ) -> Result<RigidBodyHandle, RigidBodyMergeError> {
+        let target = *handles.first().ok_or(RigidBodyMergeError::TargetNotFound)?;
+        let target_pos = *self
+            .get(target)
+            .ok_or(RigidBodyMergeError::TargetNotFound)?
+            .position();
+
+        if let Some(link) = handles
+            .iter()
+            .find(|handle| multibody_joints.rigid_body_link(**handle).is_some())
+        {
+            return Err(RigidBodyMergeError::MultibodyLink(*link));
+        }
+
+        let mut linear_momentum = Vector::zeros();
+        let mut angular_momentum = na::zero::<AngVector<Real>>();
+        let mut merged = vec![];
+
+        for handle in handles {
+            if merged.contains(handle) {
+                continue;
+            }
+
+            let rb = match self.get(*handle) {
+                Some(rb) => rb,
+                None => continue,
+            };
+            merged.push(*handle);
+
+            let (linear, angular) = Self::momentum(rb);
+            linear_momentum += linear;
+            angular_momentum += angular;
+
+            if *handle == target {
+                continue;
+            }
+
+            let rb_pos = *rb.position();
+            let rb_colliders = rb.colliders().to_vec();
+            let pos_wrt_target = target_pos.inv_mul(&rb_pos);
+
+            for collider in rb_colliders {
+                colliders.set_parent(collider, Some(target), self);
+            }
+
+            // Once all its colliders are detached, the remaining mass properties of
+            // the rigid-body are its additional mass properties.
+            let additional_mprops = self.bodies[handle.0].rb_mprops.local_mprops;
+            let target_rb = self
+                .get_mut_internal_with_modification_tracking(target)
+                .ok_or(RigidBodyMergeError::TargetNotFound)?;
+            target_rb.rb_mprops.local_mprops += additional_mprops.transform_by(&pos_wrt_target);
+            target_rb.update_world_mass_properties();
+
+            impulse_joints.transfer_joints_attached_to_rigid_body(
+                *handle,
+                target,
+                &pos_wrt_target,
+                islands,
+                self,
+            );
+            let _ = self.remove(
+                *handle,
+                islands,
+                colliders,
+                impulse_joints,
+                multibody_joints,
+            );
+        }
+
+        let target_rb = self
+            .get_mut_internal_with_modification_tracking(target)
+            .ok_or(RigidBodyMergeError::TargetNotFound)?;
+
+        if target_rb.is_dynamic() {
+            let mprops = &target_rb.rb_mprops;
+            let linvel = linear_momentum.component_mul(&mprops.effective_inv_mass);
+            let angvel = mprops
+                .effective_world_inv_inertia_sqrt
+                .squared()
+                .transform_vector(
+                    angular_momentum - mprops.world_com.coords.gcross(linear_momentum),
+                );
+            target_rb.set_linvel(linvel, true);
+            target_rb.set_angvel(angvel, true);
+        }
+
+        Ok(target)
+    }
+
+    /// Splits the given rigid-body into several ones.
+    ///
+    /// Each element of `parts` is a set of colliders attached to the rigid-body identified by
+    /// `handle`. For each part, a new rigid-body is created with the same pose and properties
+    /// as the original rigid-body (except for its additional mass properties) and the colliders
+    /// of this part are moved to it. The velocity of each new rigid-body, and of the original
+    /// rigid-body, is set to the velocity of the original rigid-body at its center-of-mass.
+    /// Colliders that are not attached to the original rigid-body are ignored.
+    ///
+    /// Each joint attached to the original rigid-body follows the collider closest to its anchor:
+    /// if this collider moved to a new rigid-body, the joint is re-attached to that rigid-body.
+    /// The handle of a re-attached impulse joint is preserved, while the handle of a re-attached
+    /// multibody joint changes to match the handle of its new second rigid-body.
+    ///
+    /// Returns the handles of the new rigid-bodies, in the same order as `parts`.
+    pub fn split<Part: AsRef<[ColliderHandle]>>(
+        &mut self,
+        handle: RigidBodyHandle,
+        parts: &[Part],
+        islands: &mut IslandManager,
+        colliders: &mut ColliderSet,
+        impulse_joints: &mut ImpulseJointSet,
+        multibody_joints: &mut MultibodyJointSet,
+    ) -> Vec<RigidBodyHandle> {
+        let mut result = vec![];
+        let source = match self.get(handle) {
+            Some(rb) => rb.clone(),
+            None => return result,
+        };
+
+        for part in parts {
+            let mut new_rb = source.clone();
+            new_rb.rb_mprops.local_mprops = MassProperties::zero();
+            new_rb.update_world_mass_properties();
+            let new_handle = self.insert(new_rb);
+
+            for collider in part.as_ref() {
+                if source.colliders().contains(collider) {
+                    colliders.set_parent(*collider, Some(new_handle), self);
+                }
+            }
+
+            let new_rb = &mut self.bodies[new_handle.0];
+            let linvel = source.velocity_at_point(&new_rb.rb_mprops.world_com);
+            new_rb.set_linvel(linvel, true);
+            new_rb.set_angvel(source.rb_vels.angvel, true);
+            result.push(new_handle);
+        }
+
+        // The center-of-mass of the original rigid-body moved after its colliders were detached.
+        if let Some(rb) = self.get_mut_internal_with_modification_tracking(handle) {
+            let linvel = source.velocity_at_point(&rb.rb_mprops.world_com);
+            rb.set_linvel(linvel, true);
+        }
+
+        islands.wake_up(self, handle, true);
+        self.split_joints(
+            handle,
+            &source,
+            islands,
+            colliders,
+            impulse_joints,
+            multibody_joints,
+        );
+        result
+    }
+
+    /// Re-attaches the joints of the split rigid-body `handle` to the new parent of the
+    /// collider closest to their anchor.
+    fn split_joints(
+        &mut self,
+        handle: RigidBodyHandle,
+        source: &RigidBody,
+        islands: &mut IslandManager,
+        colliders: &ColliderSet,
+        impulse_joints: &mut ImpulseJointSet,
+        multibody_joints: &mut MultibodyJointSet,
+    ) {
+        // NOTE: the new rigid-bodies have the same pose as the original one, so the
+        //       local frames of the joints remain valid after the split.
+        let new_parent = |local_anchor: Point<Real>| {
+            let anchor = source.position() * local_anchor;
+            let distance = |collider: &ColliderHandle| {
+                let co = &colliders[*collider];
+                co.shape().distance_to_point(co.position(), &anchor, true)
+            };
+            let closest = source
+                .colliders()
+                .iter()
+                .map(|collider| (distance(collider), collider))
+                .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal))?;
+            colliders[*closest.1]
+                .parent()
+                .filter(|parent| *parent != handle)
+        };
+
+        let mut to_reattach = vec![];
+
+        for (_, _, joint) in impulse_joints.joints_with(handle) {
+            if joint.body1 == handle {
+                if let Some(parent) = new_parent(joint.data.local_frame1.translation.vector.into())
+                {
+                    to_reattach.push((joint.handle, parent, joint.body2));
+                }
+            } else if let Some(parent) =
+                new_parent(joint.data.local_frame2.translation.vector.into())
+            {
+                to_reattach.push((joint.handle, joint.body1, parent));
+            }
+        }
+
+        for (joint, body1, body2) in to_reattach {
+            impulse_joints.reattach_joint(joint, body1, body2, islands, self);
+        }
+
+        let mut to_reattach = vec![];
+
+        for (joint_handle, multibody, link) in multibody_joints.iter() {
+            let body2 = link.rigid_body;
+            let body1 = match link.parent_id().and_then(|id| multibody.link(id)) {
+                Some(parent) => parent.rigid_body,
+                None => continue,
+            };
+            let data = &link.joint.data;
+
+            if body1 == handle {
+                if let Some(parent) = new_parent(data.local_frame1.translation.vector.into()) {
+                    to_reattach.push((joint_handle, parent, body2));
+                }
+            } else if body2 == handle {
+                if let Some(parent) = new_parent(data.local_frame2.translation.vector.into()) {
+                    to_reattach.push((joint_handle, body1, parent));
+                }
+            }
+        }
+
+        for (joint, body1, body2) in to_reattach {
+            let _ = multibody_joints.reattach(joint, body1, body2, islands, self);
+        }
+    }
+
+    /// The linear momentum of the given rigid-body, and its angular momentum wrt. the origin.
+    fn momentum(rb: &RigidBody) -> (Vector<Real>, AngVector<Real>) {
+        let mprops = &rb.rb_mprops;
+        let linear = *rb.linvel() * mprops.mass();
+
+        #[cfg(feature = "dim2")]
+        let angular = if mprops.effective_world_inv_inertia_sqrt.is_zero() {
+            0.0
+        } else {
+            rb.rb_vels.angvel / mprops.effective_world_inv_inertia_sqrt.squared()
+        };
+
+        #[cfg(feature = "dim3")]
+        let angular = {
+            // NOTE: the inverse angular inertia is singular if some rotations are locked, so
+            //       we use its pseudo-inverse to only account for the free rotational axes.
+            let m = &mprops.effective_world_inv_inertia_sqrt;
+            let inv_inertia_sqrt = na::Matrix3::new(
+                m.m11, m.m12, m.m13, m.m12, m.m22, m.m23, m.m13, m.m23, m.m33,
+            );
+            let inertia_sqrt = inv_inertia_sqrt
+                .pseudo_inverse(crate::math::DEFAULT_EPSILON)
+                .unwrap_or_else(|_| na::Matrix3::zeros());
+            inertia_sqrt * (inertia_sqrt * rb.rb_vels.angvel)
+        };
+
+        (linear, angular + mprops.world_com.coords.gcross(linear))
+    }
+
     /// Gets the rigid-body with the given handle without a known generation.
     ///
     /// This is useful when you know you want the rigid-body at position `i` but
@@ -275,3 +600,262 @@ impl IndexMut<RigidBodyHandle> for RigidBodySet {
         rb
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{
+        DistanceJoint, FixedJoint, RigidBodyBuilder, RigidBodyHandle, RigidBodyMergeError,
+        RigidBodySet,
+    };
+    use crate::geometry::{ColliderBuilder, ColliderHandle};
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    fn merge(
+        world: &mut TestWorld,
+        handles: &[RigidBodyHandle],
+    ) -> Result<RigidBodyHandle, RigidBodyMergeError> {
+        world.bodies.merge(
+            handles,
+            &mut world.islands,
+            &mut world.colliders,
+            &mut world.impulse_joints,
+            &mut world.multibody_joints,
+        )
+    }
+
+    fn split(
+        world: &mut TestWorld,
+        handle: RigidBodyHandle,
+        part: &[ColliderHandle],
+    ) -> Vec<RigidBodyHandle> {
+        world.bodies.split(
+            handle,
+            &[part],
+            &mut world.islands,
+            &mut world.colliders,
+            &mut world.impulse_joints,
+            &mut world.multibody_joints,
+        )
+    }
+
+    // Linear momentum, and angular momentum wrt. the origin, of a set of rigid-bodies.
+    // In 2D, the angular momentum is stored in the first component of a vector.
+    fn momentum(
+        bodies: &RigidBodySet,
+        handles: &[RigidBodyHandle],
+    ) -> (Vector<Real>, Vector<Real>) {
+        let mut linear = Vector::zeros();
+        let mut angular = Vector::<Real>::zeros();
+
+        for handle in handles {
+            let rb = &bodies[*handle];
+            let mprops = rb.mass_properties().transform_by(rb.position());
+            let com = mprops.local_com.coords;
+            linear += rb.linvel() * rb.mass();
+
+            #[cfg(feature = "dim2")]
+            {
+                angular.x += rb.angvel() / mprops.inv_principal_inertia_sqrt.powi(2)
+                    + com.perp(&(rb.linvel() * rb.mass()));
+            }
+            #[cfg(feature = "dim3")]
+            {
+                angular += mprops.reconstruct_inertia_matrix() * rb.angvel()
+                    + com.cross(&(rb.linvel() * rb.mass()));
+            }
+        }
+
+        (linear, angular)
+    }
+
+    #[test]
+    fn rigid_body_merge_and_split() {
+        let mut world = TestWorld::new(Vector::zeros());
+
+        let body1 = RigidBodyBuilder::new_dynamic().linvel(Vector::x()).build();
+        let (body1, collider1) = world.insert_body(body1, ColliderBuilder::ball(0.5).build());
+
+        let body2 = RigidBodyBuilder::new_dynamic()
+            .translation(Vector::x() * 2.0)
+            .linvel(Vector::y())
+            .build();
+        #[cfg(feature = "dim2")]
+        let collider2 = ColliderBuilder::cuboid(0.5, 0.5).build();
+        #[cfg(feature = "dim3")]
+        let collider2 = ColliderBuilder::cuboid(0.5, 0.5, 0.5).build();
+        let (body2, collider2) = world.insert_body(body2, collider2);
+
+        let anchor = RigidBodyBuilder::new_static()
+            .translation(Vector::x() * 4.0)
+            .build();
+        let anchor = world.bodies.insert(anchor);
+        let joint = DistanceJoint::new().local_anchor2(Point::origin());
+        let joint = world.impulse_joints.insert(body2, anchor, joint);
+
+        let mass = world.bodies[body1].mass() + world.bodies[body2].mass();
+        let (linear, angular) = momentum(&world.bodies, &[body1, body2]);
+
+        let merged = merge(&mut world, &[body1, body2]).unwrap();
+        let (bodies, colliders) = (&world.bodies, &world.colliders);
+        assert_eq!(merged, body1);
+        assert!(bodies.get(body2).is_none());
+        assert_eq!(colliders[collider2].parent(), Some(body1));
+        assert!((bodies[body1].mass() - mass).abs() < 1.0e-5);
+        assert_eq!(world.impulse_joints.get(joint).unwrap().body1, body1);
+
+        let (merged_linear, merged_angular) = momentum(bodies, &[body1]);
+        assert!((merged_linear - linear).norm() < 1.0e-5);
+        assert!((merged_angular - angular).norm() < 1.0e-5);
+
+        let (linear, angular) = momentum(bodies, &[body1]);
+        let parts = split(&mut world, body1, &[collider2]);
+        assert_eq!(parts.len(), 1);
+        assert_eq!(world.colliders[collider1].parent(), Some(body1));
+        assert_eq!(world.colliders[collider2].parent(), Some(parts[0]));
+        // The joint is anchored at the center of the second collider.
+        assert_eq!(world.impulse_joints.get(joint).unwrap().body1, parts[0]);
+
+        let (split_linear, split_angular) = momentum(&world.bodies, &[body1, parts[0]]);
+        assert!((split_linear - linear).norm() < 1.0e-5);
+        assert!((split_angular - angular).norm() < 1.0e-5);
+    }
+
+    #[cfg(feature = "dim3")]
+    #[test]
+    fn rigid_body_merge_with_locked_rotations() {
+        let mut world = TestWorld::new(Vector::zeros());
+
+        // Only the rotations around the `Y` axis are allowed.
+        let body1 = RigidBodyBuilder::new_dynamic()
+            .restrict_rotations(false, true, false)
+            .angvel(Vector::y())
+            .build();
+        let (body1, _) = world.insert_body(body1, ColliderBuilder::ball(0.5).build());
+
+        let body2 = RigidBodyBuilder::new_dynamic()
+            .translation(Vector::x() * 2.0)
+            .linvel(Vector::y())
+            .angvel(Vector::repeat(1.0))
+            .build();
+        let (body2, _) = world.insert_body(body2, ColliderBuilder::ball(0.5).build());
+
+        let (linear, angular) = momentum(&world.bodies, &[body1, body2]);
+        let merged = merge(&mut world, &[body1, body2]).unwrap();
+        let (merged_linear, merged_angular) = momentum(&world.bodies, &[merged]);
+        let angvel = world.bodies[merged].angvel();
+
+        // The angular momentum is only preserved along the free rotation axis.
+        assert!((merged_linear - linear).norm() < 1.0e-5);
+        assert_eq!((angvel.x, angvel.z), (0.0, 0.0));
+        assert!((merged_angular.y - angular.y).abs() < 1.0e-5);
+    }
+
+    #[test]
+    fn rigid_body_merge_multibody_link() {
+        let mut world = TestWorld::new(Vector::zeros());
+
+        let body1 = RigidBodyBuilder::new_dynamic().build();
+        let (body1, _) = world.insert_body(body1, ColliderBuilder::ball(0.5).build());
+
+        let body2 = RigidBodyBuilder::new_dynamic()
+            .translation(Vector::x() * 2.0)
+            .build();
+        let (body2, collider2) = world.insert_body(body2, ColliderBuilder::ball(0.5).build());
+
+        let link = RigidBodyBuilder::new_dynamic()
+            .translation(Vector::x() * 4.0)
+            .build();
+        let (link, _) = world.insert_body(link, ColliderBuilder::ball(0.5).build());
+        let joint = FixedJoint::new().local_anchor1(Point::from(Vector::x() * 2.0));
+        let joint = world.multibody_joints.insert(body2, link, joint).unwrap();
+
+        assert_eq!(
+            merge(&mut world, &[body1, body2]),
+            Err(RigidBodyMergeError::MultibodyLink(body2))
+        );
+        assert_eq!(
+            merge(&mut world, &[]),
+            Err(RigidBodyMergeError::TargetNotFound)
+        );
+
+        // Nothing was merged, and the multibody joint is still there.
+        assert!(world.bodies.get(body2).is_some());
+        assert_eq!(world.colliders[collider2].parent(), Some(body2));
+        assert!(world.multibody_joints.get(joint).is_some());
+    }
+
+    #[test]
+    fn rigid_body_split_joints() {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+
+        // A rigid-body with two colliders, each held by a fixed joint to the ground.
+        let body = world.bodies.insert(RigidBodyBuilder::new_dynamic().build());
+        let colliders: Vec<_> = [-1.0, 1.0]
+            .iter()
+            .map(|x| {
+                let collider = ColliderBuilder::ball(0.5)
+                    .translation(Vector::x() * *x)
+                    .build();
+                world
+                    .colliders
+                    .insert_with_parent(collider, body, &mut world.bodies)
+            })
+            .collect();
+
+        let ground1 = RigidBodyBuilder::new_static()
+            .translation(-Vector::x() * 3.0)
+            .build();
+        let ground1 = world.bodies.insert(ground1);
+        let joint = FixedJoint::new()
+            .local_anchor1(Point::from(Vector::x() * 1.5))
+            .local_anchor2(Point::from(-Vector::x() * 1.5));
+        let impulse_joint = world.impulse_joints.insert(ground1, body, joint);
+
+        let ground2 = RigidBodyBuilder::new_static()
+            .translation(Vector::x() * 3.0)
+            .build();
+        let ground2 = world.bodies.insert(ground2);
+        let joint = FixedJoint::new()
+            .local_anchor1(Point::from(-Vector::x() * 1.5))
+            .local_anchor2(Point::from(Vector::x() * 1.5));
+        world.multibody_joints.insert(ground2, body, joint).unwrap();
+
+        // A multibody link hanging below the second collider.
+        let child_pos = Vector::x() - Vector::y() * 2.0;
+        let child = RigidBodyBuilder::new_dynamic()
+            .translation(child_pos)
+            .build();
+        let (child, _) = world.insert_body(child, ColliderBuilder::ball(0.5).build());
+        let joint = FixedJoint::new().local_anchor1(Point::from(child_pos));
+        world.multibody_joints.insert(body, child, joint).unwrap();
+
+        let part = split(&mut world, body, &[colliders[1]])[0];
+
+        // Each joint follows the collider closest to its anchor.
+        let impulse_joint = world.impulse_joints.get(impulse_joint).unwrap();
+        assert_eq!((impulse_joint.body1, impulse_joint.body2), (ground1, body));
+        let multibody = |rb| {
+            world
+                .multibody_joints
+                .rigid_body_link(rb)
+                .map(|link| link.multibody)
+        };
+        assert!(multibody(ground2).is_some());
+        assert_eq!(multibody(part), multibody(ground2));
+        assert_eq!(multibody(child), multibody(ground2));
+
+        // The joints still hold both halves of the split rigid-body.
+        let handles = [body, part, child];
+        let positions: Vec<_> = handles
+            .iter()
+            .map(|h| *world.bodies[*h].translation())
+            .collect();
+        world.step_n(60);
+
+        for (handle, pos) in handles.iter().zip(positions) {
+            let drift = (world.bodies[*handle].translation() - pos).norm();
+            assert!(drift < 0.05, "{}", drift);
+        }
+    }
+}
@@ -167,12 +167,12 @@ impl GenericVelocityConstraint {
                 tangent1: tangents1[0],
                 elements: [VelocityConstraintElement::zero(); MAX_MANIFOLD_POINTS],
                 im1: if rb_type1.is_dynamic() {
-                    rb_mprops1.effective_inv_mass
+                    rb_mprops1.constraint_inv_mass()
                 } else {
                     na::zero()
                 },
                 im2: if rb_type2.is_dynamic() {
-                    rb_mprops2.effective_inv_mass
+                    rb_mprops2.constraint_inv_mass()
                 } else {
                     na::zero()
                 },
@@ -202,14 +202,14 @@ impl GenericVelocityConstraint {
 
                     let gcross1 = if rb_type1.is_dynamic() {
                         rb_mprops1
-                            .effective_world_inv_inertia_sqrt
+                            .constraint_world_inv_inertia_sqrt()
                             .transform_vector(torque_dir1)
                     } else {
                         na::zero()
                     };
                     let gcross2 = if rb_type2.is_dynamic() {
                         rb_mprops2
-                            .effective_world_inv_inertia_sqrt
+                            .constraint_world_inv_inertia_sqrt()
                             .transform_vector(torque_dir2)
                     } else {
                         na::zero()
@@ -228,7 +228,7 @@ impl GenericVelocityConstraint {
                         )
                         .0
                     } else if rb_type1.is_dynamic() {
-                        force_dir1.dot(&rb_mprops1.effective_inv_mass.component_mul(&force_dir1))
+                        force_dir1.dot(&rb_mprops1.constraint_inv_mass().component_mul(&force_dir1))
                             + gcross1.gdot(gcross1)
                     } else {
                         0.0
@@ -247,7 +247,7 @@ impl GenericVelocityConstraint {
                         )
                         .0
                     } else if rb_type2.is_dynamic() {
-                        force_dir1.dot(&rb_mprops2.effective_inv_mass.component_mul(&force_dir1))
+                        force_dir1.dot(&rb_mprops2.constraint_inv_mass().component_mul(&force_dir1))
                             + gcross2.gdot(gcross2)
                     } else {
                         0.0
@@ -268,7 +268,7 @@ impl GenericVelocityConstraint {
                     constraint.elements[k].normal_part = VelocityConstraintNormalPart {
                         gcross1,
                         gcross2,
-                        rhs: rhs_wo_bias + rhs_bias,
+                        rhs: rhs_wo_bias + rhs_bias.max(-params.contact_max_corrective_velocity()),
                         rhs_wo_bias,
                         impulse: na::zero(),
                         r,
@@ -283,7 +283,7 @@ impl GenericVelocityConstraint {
                         let torque_dir1 = dp1.gcross(tangents1[j]);
                         let gcross1 = if rb_type1.is_dynamic() {
                             rb_mprops1
-                                .effective_world_inv_inertia_sqrt
+                                .constraint_world_inv_inertia_sqrt()
                                 .transform_vector(torque_dir1)
                         } else {
                             na::zero()
@@ -293,7 +293,7 @@ impl GenericVelocityConstraint {
                         let torque_dir2 = dp2.gcross(-tangents1[j]);
                         let gcross2 = if rb_type2.is_dynamic() {
                             rb_mprops2
-                                .effective_world_inv_inertia_sqrt
+                                .constraint_world_inv_inertia_sqrt()
                                 .transform_vector(torque_dir2)
                         } else {
                             na::zero()
@@ -314,7 +314,7 @@ impl GenericVelocityConstraint {
                             .0
                         } else if rb_type1.is_dynamic() {
                             force_dir1
-                                .dot(&rb_mprops1.effective_inv_mass.component_mul(&force_dir1))
+                                .dot(&rb_mprops1.constraint_inv_mass().component_mul(&force_dir1))
                                 + gcross1.gdot(gcross1)
                         } else {
                             0.0
@@ -334,7 +334,7 @@ impl GenericVelocityConstraint {
                             .0
                         } else if rb_type2.is_dynamic() {
                             force_dir1
-                                .dot(&rb_mprops2.effective_inv_mass.component_mul(&force_dir1))
+                                .dot(&rb_mprops2.constraint_inv_mass().component_mul(&force_dir1))
                                 + gcross2.gdot(gcross2)
                         } else {
                             0.0
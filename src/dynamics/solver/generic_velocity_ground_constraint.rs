@@ -102,7 +102,7 @@ impl GenericVelocityGroundConstraint {
                 #[cfg(feature = "dim3")]
                 tangent1: tangents1[0],
                 elements: [VelocityGroundConstraintElement::zero(); MAX_MANIFOLD_POINTS],
-                im2: rb_mprops2.effective_inv_mass,
+                im2: rb_mprops2.constraint_inv_mass(),
                 limit: 0.0,
                 mj_lambda2,
                 manifold_id,
@@ -151,7 +151,7 @@ impl GenericVelocityGroundConstraint {
 
                     constraint.elements[k].normal_part = VelocityGroundConstraintNormalPart {
                         gcross2: na::zero(), // Unused for generic constraints.
-                        rhs: rhs_wo_bias + rhs_bias,
+                        rhs: rhs_wo_bias + rhs_bias.max(-params.contact_max_corrective_velocity()),
                         rhs_wo_bias,
                         impulse: na::zero(),
                         r,
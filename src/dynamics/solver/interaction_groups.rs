@@ -178,7 +178,6 @@ impl InteractionGroups {
         }
     }
 
-    #[cfg(not(feature = "parallel"))]
     pub fn clear(&mut self) {
         #[cfg(feature = "simd-is-enabled")]
         {
@@ -5,18 +5,26 @@ use crate::dynamics::solver::{
     AnyGenericVelocityConstraint, AnyJointVelocityConstraint, AnyVelocityConstraint,
     SolverConstraints,
 };
+use crate::dynamics::{ImpulseJoint, JointCoupling, RigidBodyHandle};
 use crate::dynamics::{
     IntegrationParameters, JointGraphEdge, JointIndex, RigidBodyDamping, RigidBodyForces,
     RigidBodyIds, RigidBodyMassProps, RigidBodyPosition, RigidBodyType,
 };
 use crate::dynamics::{IslandManager, RigidBodyVelocity};
-use crate::geometry::{ContactManifold, ContactManifoldIndex};
+use crate::geometry::{ContactData, ContactManifold, ContactManifoldIndex};
+use crate::math::{Isometry, Point, Real, SpacialVector, SPATIAL_DIM};
 use crate::prelude::{MultibodyJointSet, RigidBodyActivation};
 
 pub struct IslandSolver {
     contact_constraints: SolverConstraints<AnyVelocityConstraint, AnyGenericVelocityConstraint>,
     joint_constraints: SolverConstraints<AnyJointVelocityConstraint, ()>,
     velocity_solver: VelocitySolver,
+    // Workspaces used by the TGS-soft solver.
+    saved_bodies: Vec<(RigidBodyPosition, RigidBodyMassProps)>,
+    saved_contacts: Vec<(Point<Real>, Real)>,
+    contact_impulses: Vec<ContactData>,
+    joint_impulses: Vec<JointImpulses>,
+    coupling_impulses: Vec<Real>,
 }
 
 impl Default for IslandSolver {
@@ -31,6 +39,11 @@ impl IslandSolver {
             contact_constraints: SolverConstraints::new(),
             joint_constraints: SolverConstraints::new(),
             velocity_solver: VelocitySolver::new(),
+            saved_bodies: Vec::new(),
+            saved_contacts: Vec::new(),
+            contact_impulses: Vec::new(),
+            joint_impulses: Vec::new(),
+            coupling_impulses: Vec::new(),
         }
     }
 
@@ -45,6 +58,8 @@ impl IslandSolver {
         manifold_indices: &[ContactManifoldIndex],
         impulse_joints: &mut [JointGraphEdge],
         joint_indices: &[JointIndex],
+        couplings: &mut [JointCoupling],
+        coupling_indices: &[usize],
         multibody_joints: &mut MultibodyJointSet,
     ) where
         Bodies: ComponentSet<RigidBodyForces>
@@ -56,7 +71,8 @@ impl IslandSolver {
             + ComponentSet<RigidBodyIds>
             + ComponentSet<RigidBodyType>,
     {
-        let mut has_constraints = manifold_indices.len() != 0 || joint_indices.len() != 0;
+        let mut has_constraints =
+            manifold_indices.len() != 0 || joint_indices.len() != 0 || coupling_indices.len() != 0;
         if !has_constraints {
             // Check if the multibody_joints have internal constraints.
             for handle in islands.active_island(island_id) {
@@ -82,43 +98,40 @@ impl IslandSolver {
                 solver_id += multibody.ndofs();
             }
 
-            counters.solver.velocity_assembly_time.resume();
-            self.contact_constraints.init(
-                island_id,
-                params,
-                islands,
-                bodies,
-                multibody_joints,
-                manifolds,
-                manifold_indices,
-            );
-            self.joint_constraints.init(
-                island_id,
-                params,
-                islands,
-                bodies,
-                multibody_joints,
-                impulse_joints,
-                joint_indices,
-            );
-            counters.solver.velocity_assembly_time.pause();
+            let num_substeps = params.solver_mode.num_substeps();
 
-            counters.solver.velocity_resolution_time.resume();
-            self.velocity_solver.solve(
-                island_id,
-                params,
-                islands,
-                bodies,
-                multibody_joints,
-                manifolds,
-                impulse_joints,
-                &mut self.contact_constraints.velocity_constraints,
-                &mut self.contact_constraints.generic_velocity_constraints,
-                &self.contact_constraints.generic_jacobians,
-                &mut self.joint_constraints.velocity_constraints,
-                &self.joint_constraints.generic_jacobians,
-            );
-            counters.solver.velocity_resolution_time.pause();
+            if num_substeps > 1 {
+                self.solve_substeps(
+                    num_substeps,
+                    island_id,
+                    counters,
+                    params,
+                    islands,
+                    bodies,
+                    manifolds,
+                    manifold_indices,
+                    impulse_joints,
+                    joint_indices,
+                    couplings,
+                    coupling_indices,
+                    multibody_joints,
+                );
+            } else {
+                self.init_and_solve_constraints(
+                    island_id,
+                    counters,
+                    params,
+                    islands,
+                    bodies,
+                    manifolds,
+                    manifold_indices,
+                    impulse_joints,
+                    joint_indices,
+                    couplings,
+                    coupling_indices,
+                    multibody_joints,
+                );
+            }
         } else {
             self.contact_constraints.clear();
             self.joint_constraints.clear();
@@ -150,8 +163,11 @@ impl IslandSolver {
                     let new_vels = forces
                         .integrate(params.dt, vels, mprops)
                         .apply_damping(params.dt, &damping);
-                    new_poss.next_position =
-                        new_vels.integrate(params.dt, &poss.position, &mprops.local_mprops.local_com);
+                    new_poss.next_position = new_vels.integrate(
+                        params.dt,
+                        &poss.position,
+                        &mprops.local_mprops.local_com,
+                    );
 
                     bodies.set_internal(handle.0, new_vels);
                     bodies.set_internal(handle.0, new_poss);
@@ -160,4 +176,704 @@ impl IslandSolver {
             counters.solver.velocity_update_time.pause();
         }
     }
+
+    fn init_and_solve_constraints<Bodies>(
+        &mut self,
+        island_id: usize,
+        counters: &mut Counters,
+        params: &IntegrationParameters,
+        islands: &IslandManager,
+        bodies: &mut Bodies,
+        manifolds: &mut [&mut ContactManifold],
+        manifold_indices: &[ContactManifoldIndex],
+        impulse_joints: &mut [JointGraphEdge],
+        joint_indices: &[JointIndex],
+        couplings: &mut [JointCoupling],
+        coupling_indices: &[usize],
+        multibody_joints: &mut MultibodyJointSet,
+    ) where
+        Bodies: ComponentSet<RigidBodyForces>
+            + ComponentSetMut<RigidBodyPosition>
+            + ComponentSetMut<RigidBodyVelocity>
+            + ComponentSetMut<RigidBodyMassProps>
+            + ComponentSetMut<RigidBodyActivation>
+            + ComponentSet<RigidBodyDamping>
+            + ComponentSet<RigidBodyIds>
+            + ComponentSet<RigidBodyType>,
+    {
+        counters.solver.velocity_assembly_time.resume();
+        self.contact_constraints.init(
+            island_id,
+            params,
+            islands,
+            bodies,
+            multibody_joints,
+            manifolds,
+            manifold_indices,
+        );
+        self.joint_constraints.init(
+            island_id,
+            params,
+            islands,
+            bodies,
+            multibody_joints,
+            impulse_joints,
+            joint_indices,
+            couplings,
+            coupling_indices,
+        );
+        counters.solver.velocity_assembly_time.pause();
+
+        counters.solver.velocity_resolution_time.resume();
+        self.velocity_solver.solve(
+            island_id,
+            params,
+            islands,
+            bodies,
+            multibody_joints,
+            manifolds,
+            impulse_joints,
+            couplings,
+            &mut self.contact_constraints.velocity_constraints,
+            &mut self.contact_constraints.generic_velocity_constraints,
+            &self.contact_constraints.generic_jacobians,
+            &mut self.joint_constraints.velocity_constraints,
+            &self.joint_constraints.generic_jacobians,
+        );
+        counters.solver.velocity_resolution_time.pause();
+    }
+
+    /// Solves the island with the TGS-soft scheme.
+    ///
+    /// The timestep is split into `num_substeps` substeps. Before each substep (except the
+    /// first one), the bodies are moved to the pose computed by the previous substep, and
+    /// the contacts and joints are re-linearized at that pose. The velocity solver then runs
+    /// as usual (biased solve, position integration, unbiased relaxation) with the substep length.
+    ///
+    /// Once all the substeps are done, the current positions, world-space mass properties and
+    /// solver contacts are restored to their values at the beginning of the timestep, so that
+    /// the rest of the pipeline sees the same state as with the PGS solver. The impulses written
+    /// back to the contacts and joints are the sum of the impulses of all the substeps.
+    ///
+    /// There is no warm-starting: like with the PGS solver, the constraints of each substep
+    /// start from zero impulses. The impulses are reset before each substep only so that the
+    /// impulses applied by that substep can be accumulated.
+    fn solve_substeps<Bodies>(
+        &mut self,
+        num_substeps: usize,
+        island_id: usize,
+        counters: &mut Counters,
+        params: &IntegrationParameters,
+        islands: &IslandManager,
+        bodies: &mut Bodies,
+        manifolds: &mut [&mut ContactManifold],
+        manifold_indices: &[ContactManifoldIndex],
+        impulse_joints: &mut [JointGraphEdge],
+        joint_indices: &[JointIndex],
+        couplings: &mut [JointCoupling],
+        coupling_indices: &[usize],
+        multibody_joints: &mut MultibodyJointSet,
+    ) where
+        Bodies: ComponentSet<RigidBodyForces>
+            + ComponentSetMut<RigidBodyPosition>
+            + ComponentSetMut<RigidBodyVelocity>
+            + ComponentSetMut<RigidBodyMassProps>
+            + ComponentSetMut<RigidBodyActivation>
+            + ComponentSet<RigidBodyDamping>
+            + ComponentSet<RigidBodyIds>
+            + ComponentSet<RigidBodyType>,
+    {
+        let mut substep_params = *params;
+        substep_params.dt = params.dt / num_substeps as Real;
+        let h = substep_params.dt;
+
+        /*
+         * Save the state that will be modified by the substeps.
+         */
+        self.saved_bodies.clear();
+        for handle in islands.active_island(island_id) {
+            let (poss, mprops): (&RigidBodyPosition, &RigidBodyMassProps) =
+                bodies.index_bundle(handle.0);
+            self.saved_bodies.push((*poss, *mprops));
+        }
+
+        self.saved_contacts.clear();
+        for manifold_id in manifold_indices {
+            let manifold = &manifolds[*manifold_id];
+            self.saved_contacts.extend(
+                manifold
+                    .data
+                    .solver_contacts
+                    .iter()
+                    .map(|c| (c.point, c.dist)),
+            );
+        }
+
+        self.contact_impulses.clear();
+        self.contact_impulses
+            .resize(self.saved_contacts.len(), ContactData::default());
+        self.joint_impulses.clear();
+        self.joint_impulses
+            .resize(joint_indices.len(), JointImpulses::default());
+        self.coupling_impulses.clear();
+        self.coupling_impulses.resize(coupling_indices.len(), 0.0);
+
+        for substep in 0..num_substeps {
+            if substep > 0 {
+                // Move the bodies to the pose reached at the end of the previous substep.
+                for handle in islands.active_island(island_id) {
+                    let (poss, mprops): (&RigidBodyPosition, &RigidBodyMassProps) =
+                        bodies.index_bundle(handle.0);
+                    let mut new_poss = *poss;
+                    let mut new_mprops = *mprops;
+                    new_poss.position = new_poss.next_position;
+                    new_mprops.update_world_mass_properties(&new_poss.position);
+                    bodies.set_internal(handle.0, new_poss);
+                    bodies.set_internal(handle.0, new_mprops);
+                }
+
+                // The multibody coordinates have already been integrated by the velocity
+                // solver, so we only need to update their dynamics.
+                for handle in islands.active_island(island_id) {
+                    if let Some(link) = multibody_joints.rigid_body_link(*handle).copied() {
+                        let multibody = multibody_joints
+                            .get_multibody_mut_internal(link.multibody)
+                            .unwrap();
+
+                        if link.id == 0 || link.id == 1 && !multibody.root_is_dynamic {
+                            multibody.update_dynamics(h, bodies);
+                            multibody.update_acceleration(bodies);
+                        }
+                    }
+                }
+
+                // Re-linearize the contacts at the new poses. The contact normal is
+                // kept constant for the whole timestep.
+                let elapsed = h * substep as Real;
+                let mut contact_id = 0;
+                for manifold_id in manifold_indices {
+                    let manifold = &mut manifolds[*manifold_id];
+                    let motion1 = self.substep_displacement(
+                        manifold.data.rigid_body1,
+                        elapsed,
+                        island_id,
+                        islands,
+                        bodies,
+                    );
+                    let motion2 = self.substep_displacement(
+                        manifold.data.rigid_body2,
+                        elapsed,
+                        island_id,
+                        islands,
+                        bodies,
+                    );
+                    let normal = manifold.data.normal;
+
+                    for solver_contact in &mut manifold.data.solver_contacts {
+                        let (point, dist) = self.saved_contacts[contact_id];
+                        let point1 = motion1 * point;
+                        let point2 = motion2 * point;
+                        solver_contact.point = na::center(&point1, &point2);
+                        solver_contact.dist = dist + (point2 - point1).dot(&normal);
+                        contact_id += 1;
+                    }
+                }
+            }
+
+            // Reset the impulses so we only accumulate the ones applied during this substep.
+            // They are not used for warm-starting, so this doesn't affect the resolution.
+            for manifold_id in manifold_indices {
+                let manifold = &mut manifolds[*manifold_id];
+                for solver_contact in &manifold.data.solver_contacts {
+                    manifold.points[solver_contact.contact_id as usize].data =
+                        ContactData::default();
+                }
+            }
+
+            for joint_id in joint_indices {
+                JointImpulses::default().write(&mut impulse_joints[*joint_id].weight);
+            }
+
+            for coupling_id in coupling_indices {
+                couplings[*coupling_id].impulse = 0.0;
+            }
+
+            self.init_and_solve_constraints(
+                island_id,
+                counters,
+                &substep_params,
+                islands,
+                bodies,
+                manifolds,
+                manifold_indices,
+                impulse_joints,
+                joint_indices,
+                couplings,
+                coupling_indices,
+                multibody_joints,
+            );
+
+            // Accumulate the impulses applied during this substep.
+            let mut contact_id = 0;
+            for manifold_id in manifold_indices {
+                let manifold = &manifolds[*manifold_id];
+                for solver_contact in &manifold.data.solver_contacts {
+                    let data = &manifold.points[solver_contact.contact_id as usize].data;
+                    let total = &mut self.contact_impulses[contact_id];
+                    total.impulse += data.impulse;
+                    total.tangent_impulse += data.tangent_impulse;
+                    contact_id += 1;
+                }
+            }
+
+            for (joint_id, total) in joint_indices.iter().zip(self.joint_impulses.iter_mut()) {
+                total.accumulate(&impulse_joints[*joint_id].weight);
+            }
+
+            for (coupling_id, total) in coupling_indices
+                .iter()
+                .zip(self.coupling_impulses.iter_mut())
+            {
+                *total += couplings[*coupling_id].impulse;
+            }
+        }
+
+        /*
+         * Restore the state at the beginning of the timestep, and write the total impulses back.
+         */
+        for (handle, (poss, mprops)) in islands
+            .active_island(island_id)
+            .iter()
+            .zip(self.saved_bodies.iter())
+        {
+            let next_position =
+                ComponentSet::<RigidBodyPosition>::index(bodies, handle.0).next_position;
+            let mut poss = *poss;
+            poss.next_position = next_position;
+            bodies.set_internal(handle.0, poss);
+            bodies.set_internal(handle.0, *mprops);
+        }
+
+        let mut contact_id = 0;
+        for manifold_id in manifold_indices {
+            let manifold = &mut manifolds[*manifold_id];
+            for solver_contact in &mut manifold.data.solver_contacts {
+                let (point, dist) = self.saved_contacts[contact_id];
+                solver_contact.point = point;
+                solver_contact.dist = dist;
+                manifold.points[solver_contact.contact_id as usize].data =
+                    self.contact_impulses[contact_id];
+                contact_id += 1;
+            }
+        }
+
+        for (joint_id, total) in joint_indices.iter().zip(self.joint_impulses.iter()) {
+            total.write(&mut impulse_joints[*joint_id].weight);
+        }
+
+        for (coupling_id, total) in coupling_indices.iter().zip(self.coupling_impulses.iter()) {
+            couplings[*coupling_id].impulse = *total;
+        }
+    }
+
+    /// The rigid motion of the given body between the beginning of the timestep and
+    /// the beginning of the current substep.
+    fn substep_displacement<Bodies>(
+        &self,
+        handle: Option<RigidBodyHandle>,
+        elapsed: Real,
+        island_id: usize,
+        islands: &IslandManager,
+        bodies: &Bodies,
+    ) -> Isometry<Real>
+    where
+        Bodies: ComponentSet<RigidBodyPosition>
+            + ComponentSet<RigidBodyVelocity>
+            + ComponentSet<RigidBodyMassProps>
+            + ComponentSet<RigidBodyIds>
+            + ComponentSet<RigidBodyType>,
+    {
+        let handle = match handle {
+            Some(handle) => handle,
+            None => return Isometry::identity(),
+        };
+
+        let (poss, vels, mprops, ids, status): (
+            &RigidBodyPosition,
+            &RigidBodyVelocity,
+            &RigidBodyMassProps,
+            &RigidBodyIds,
+            &RigidBodyType,
+        ) = bodies.index_bundle(handle.0);
+
+        if status.is_dynamic() {
+            let island = islands.active_island(island_id);
+
+            if ids.active_island_id == island_id
+                && island.get(ids.active_set_offset) == Some(&handle)
+            {
+                let saved_position = &self.saved_bodies[ids.active_set_offset].0.position;
+                poss.position * saved_position.inverse()
+            } else {
+                Isometry::identity()
+            }
+        } else {
+            // Kinematic bodies are not moved by the substeps, so we compute their pose
+            // from their velocity.
+            let position = vels.integrate(elapsed, &poss.position, &mprops.local_mprops.local_com);
+            position * poss.position.inverse()
+        }
+    }
+}
+
+/// The impulses applied by a joint, accumulated over the substeps of a timestep.
+#[derive(Copy, Clone, Default)]
+struct JointImpulses {
+    dofs: SpacialVector<Real>,
+    limits: [Real; SPATIAL_DIM],
+    motors: [Real; SPATIAL_DIM],
+    #[cfg(feature = "dim3")]
+    swing_twist: [Real; 2],
+    pulley: Real,
+}
+
+impl JointImpulses {
+    fn accumulate(&mut self, joint: &ImpulseJoint) {
+        self.dofs += joint.impulses;
+
+        for i in 0..SPATIAL_DIM {
+            self.limits[i] += joint.data.limits[i].impulse;
+            self.motors[i] += joint.data.motors[i].impulse;
+        }
+
+        #[cfg(feature = "dim3")]
+        if let Some(limits) = &joint.data.swing_twist_limits {
+            self.swing_twist[0] += limits.impulses[0];
+            self.swing_twist[1] += limits.impulses[1];
+        }
+
+        if let Some(pulley) = &joint.data.pulley {
+            self.pulley += pulley.impulse;
+        }
+    }
+
+    fn write(&self, joint: &mut ImpulseJoint) {
+        joint.impulses = self.dofs;
+
+        for i in 0..SPATIAL_DIM {
+            joint.data.limits[i].impulse = self.limits[i];
+            joint.data.motors[i].impulse = self.motors[i];
+        }
+
+        #[cfg(feature = "dim3")]
+        if let Some(limits) = &mut joint.data.swing_twist_limits {
+            limits.impulses = self.swing_twist;
+        }
+
+        if let Some(pulley) = &mut joint.data.pulley {
+            pulley.impulse = self.pulley;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "dim2")]
+    use crate::dynamics::RevoluteJoint;
+    #[cfg(feature = "dim3")]
+    use crate::dynamics::SphericalJoint;
+    use crate::dynamics::{RigidBodyBuilder, SolverMode};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    const TGS_SOFT: SolverMode = SolverMode::TgsSoft { num_substeps: 4 };
+
+    fn test_world(solver_mode: SolverMode) -> TestWorld {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        world.integration_parameters.solver_mode = solver_mode;
+        world
+    }
+
+    fn box_stack_drift(solver_mode: SolverMode) -> (Real, Real) {
+        let mut world = test_world(solver_mode);
+
+        #[cfg(feature = "dim2")]
+        let ground_shape = ColliderBuilder::cuboid(10.0, 0.1);
+        #[cfg(feature = "dim3")]
+        let ground_shape = ColliderBuilder::cuboid(10.0, 0.1, 10.0);
+        let (_, ground_collider) =
+            world.insert_body(RigidBodyBuilder::new_static().build(), ground_shape.build());
+
+        let num_boxes = 20;
+        let mut handles = vec![];
+
+        for i in 0..num_boxes {
+            let y = 0.1 + 0.5 + i as Real;
+            let body = RigidBodyBuilder::new_dynamic()
+                .translation(Vector::y() * y)
+                .build();
+            #[cfg(feature = "dim2")]
+            let shape = ColliderBuilder::cuboid(0.5, 0.5);
+            #[cfg(feature = "dim3")]
+            let shape = ColliderBuilder::cuboid(0.5, 0.5, 0.5);
+            handles.push(world.insert_body(body, shape.build()).0);
+        }
+
+        world.step_n(300);
+
+        let top = handles[num_boxes - 1];
+        let expected = 0.1 + 0.5 + (num_boxes - 1) as Real;
+        let drift = (world.bodies[top].translation() - Vector::y() * expected).norm();
+        let ground_impulse: Real = world
+            .narrow_phase
+            .contacts_with(ground_collider)
+            .flat_map(|pair| pair.manifolds.iter())
+            .flat_map(|manifold| manifold.points.iter())
+            .map(|point| point.data.impulse)
+            .sum();
+
+        (drift, ground_impulse)
+    }
+
+    #[test]
+    fn tgs_soft_box_stack() {
+        let (pgs_drift, _) = box_stack_drift(SolverMode::Pgs);
+        let (tgs_drift, tgs_impulse) = box_stack_drift(TGS_SOFT);
+        assert!(
+            tgs_drift < 0.3 && tgs_drift < pgs_drift,
+            "The TGS-soft stack drifted too much: {} (PGS: {})",
+            tgs_drift,
+            pgs_drift
+        );
+
+        // The impulses of all the substeps must add up to the weight of the stack.
+        let weight_impulse = 20.0 * 9.81 / 60.0;
+        assert!(
+            (tgs_impulse - weight_impulse).abs() < weight_impulse * 0.05,
+            "Unexpected ground impulse: {} != {}",
+            tgs_impulse,
+            weight_impulse
+        );
+    }
+
+    fn joint_chain_errors(solver_mode: SolverMode, use_multibody: bool) -> (Real, Real) {
+        let mut world = test_world(solver_mode);
+
+        #[cfg(feature = "dim2")]
+        let ground_shape = ColliderBuilder::cuboid(20.0, 0.1);
+        #[cfg(feature = "dim3")]
+        let ground_shape = ColliderBuilder::cuboid(20.0, 0.1, 20.0);
+        let ground = RigidBodyBuilder::new_static()
+            .translation(Vector::y() * -3.0)
+            .build();
+        world.insert_body(ground, ground_shape.build());
+
+        let num_links = 10;
+        let mut parent = world.bodies.insert(RigidBodyBuilder::new_static().build());
+        let mut handles = vec![];
+
+        for i in 0..num_links {
+            // The last link is much heavier than the others.
+            let density = if i == num_links - 1 { 100.0 } else { 1.0 };
+            let body = RigidBodyBuilder::new_dynamic()
+                .translation(Vector::x() * (i + 1) as Real)
+                .build();
+            let collider = ColliderBuilder::ball(0.1).density(density).build();
+            let (handle, _) = world.insert_body(body, collider);
+
+            #[cfg(feature = "dim2")]
+            let joint = RevoluteJoint::new();
+            #[cfg(feature = "dim3")]
+            let joint = SphericalJoint::new();
+            let joint = joint.local_anchor2(Point::from(-Vector::x()));
+            if use_multibody {
+                world
+                    .multibody_joints
+                    .insert(parent, handle, joint)
+                    .unwrap();
+            } else {
+                world.impulse_joints.insert(parent, handle, joint);
+            }
+
+            parent = handle;
+            handles.push(handle);
+        }
+
+        let mut max_stretch: Real = 0.0;
+
+        for _ in 0..200 {
+            world.step();
+
+            let mut prev = Vector::zeros();
+            for handle in &handles {
+                let curr = *world.bodies[*handle].translation();
+                max_stretch = max_stretch.max((curr - prev).norm() - 1.0);
+                prev = curr;
+            }
+        }
+
+        let max_penetration = handles
+            .iter()
+            .map(|h| -2.8 - world.bodies[*h].translation().y)
+            .fold(0.0, Real::max);
+
+        (max_stretch, max_penetration)
+    }
+
+    #[test]
+    fn tgs_soft_joint_chain() {
+        for use_multibody in [false, true] {
+            let pgs = joint_chain_errors(SolverMode::Pgs, use_multibody);
+            let tgs = joint_chain_errors(TGS_SOFT, use_multibody);
+            // Multibody joints can't stretch since they use reduced coordinates.
+            assert!(
+                tgs.0 < 0.02 && (use_multibody || tgs.0 < pgs.0),
+                "The TGS-soft chain stretched too much: {} (PGS: {})",
+                tgs.0,
+                pgs.0
+            );
+            assert!(
+                tgs.1 < 0.005,
+                "The TGS-soft chain penetrates the ground: {}",
+                tgs.1
+            );
+        }
+    }
+
+    fn insert_ground(world: &mut TestWorld) {
+        #[cfg(feature = "dim2")]
+        let ground_shape = ColliderBuilder::cuboid(10.0, 0.1);
+        #[cfg(feature = "dim3")]
+        let ground_shape = ColliderBuilder::cuboid(10.0, 0.1, 10.0);
+        let ground = RigidBodyBuilder::new_static()
+            .translation(Vector::y() * -0.1)
+            .build();
+        world.insert_body(ground, ground_shape.build());
+    }
+
+    fn insert_box(world: &mut TestWorld, pos: Vector<Real>, half_extents: Vector<Real>) {
+        let body = RigidBodyBuilder::new_dynamic().translation(pos).build();
+        #[cfg(feature = "dim2")]
+        let shape = ColliderBuilder::cuboid(half_extents.x, half_extents.y);
+        #[cfg(feature = "dim3")]
+        let shape = ColliderBuilder::cuboid(half_extents.x, half_extents.y, half_extents.z);
+        world.insert_body(body, shape.build());
+    }
+
+    /// Runs a scene for `settle_steps`, and returns the largest displacement of its
+    /// bodies during the `num_steps` that follow.
+    fn scene_drift(
+        solver_mode: SolverMode,
+        settle_steps: usize,
+        num_steps: usize,
+        build: impl FnOnce(&mut TestWorld),
+    ) -> Real {
+        let mut world = test_world(solver_mode);
+        build(&mut world);
+        world.step_n(settle_steps);
+
+        let initial: Vec<_> = world
+            .bodies
+            .iter()
+            .filter(|(_, rb)| rb.is_dynamic())
+            .map(|(handle, rb)| (handle, *rb.translation()))
+            .collect();
+        world.step_n(num_steps);
+
+        initial
+            .iter()
+            .map(|(handle, pos)| (world.bodies[*handle].translation() - pos).norm())
+            .fold(0.0, Real::max)
+    }
+
+    #[test]
+    fn tgs_soft_pyramid() {
+        // A pyramid with a base of 4 boxes.
+        let build = |world: &mut TestWorld| {
+            insert_ground(world);
+
+            for row in 0..4 {
+                for i in 0..4 - row {
+                    let x = i as Real * 1.1 + row as Real * 0.55;
+                    let y = row as Real + 0.5;
+                    insert_box(
+                        world,
+                        Vector::x() * x + Vector::y() * y,
+                        Vector::repeat(0.5),
+                    );
+                }
+            }
+        };
+
+        for solver_mode in [SolverMode::Pgs, TGS_SOFT] {
+            let drift = scene_drift(solver_mode, 60, 60, build);
+            assert!(
+                drift < 0.01,
+                "The pyramid drifted too much with {:?}: {}",
+                solver_mode,
+                drift
+            );
+        }
+    }
+
+    #[cfg(feature = "dim3")]
+    #[test]
+    fn tgs_soft_planks() {
+        // A tower of thin planks, alternately laid along the `X` and `Z` axes.
+        let build = |world: &mut TestWorld| {
+            insert_ground(world);
+            let half_extents = Vector::new(1.0, 0.1, 0.1);
+
+            for layer in 0..6 {
+                let y = 0.1 + layer as Real * 0.2;
+
+                for side in [-0.8, 0.8] {
+                    if layer % 2 == 0 {
+                        insert_box(world, Vector::new(0.0, y, side), half_extents);
+                    } else {
+                        insert_box(world, Vector::new(side, y, 0.0), half_extents.zyx());
+                    }
+                }
+            }
+        };
+
+        let pgs_drift = scene_drift(SolverMode::Pgs, 30, 60, build);
+        let tgs_drift = scene_drift(TGS_SOFT, 30, 60, build);
+        assert!(
+            tgs_drift < pgs_drift,
+            "The TGS-soft plank tower drifted too much: {} (PGS: {})",
+            tgs_drift,
+            pgs_drift
+        );
+    }
+
+    #[test]
+    fn tgs_soft_deep_penetration() {
+        let mut world = test_world(TGS_SOFT);
+        insert_ground(&mut world);
+        // The box starts half-way into the ground.
+        insert_box(&mut world, Vector::zeros(), Vector::repeat(0.5));
+
+        // The penetration must not launch the box up.
+        let mut max_height: Real = 0.0;
+        for _ in 0..60 {
+            world.step();
+            for (_, rb) in world.bodies.iter() {
+                max_height = max_height.max(rb.translation().y);
+            }
+        }
+
+        let max_speed = world
+            .bodies
+            .iter()
+            .map(|(_, rb)| rb.linvel().norm())
+            .fold(0.0, Real::max);
+        assert!(
+            max_height < 1.0 && max_speed < 0.01,
+            "The box didn't settle: max height {}, max speed {}",
+            max_height,
+            max_speed
+        );
+    }
 }
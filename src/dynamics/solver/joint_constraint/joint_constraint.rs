@@ -1,4 +1,5 @@
 use crate::data::{BundleSet, ComponentSet};
+use crate::dynamics::solver::joint_constraint::joint_coupling_velocity_constraint::JointCouplingVelocityConstraint;
 use crate::dynamics::solver::joint_constraint::joint_generic_velocity_constraint::{
     JointGenericVelocityConstraint, JointGenericVelocityGroundConstraint,
 };
@@ -7,7 +8,7 @@ use crate::dynamics::solver::joint_constraint::joint_velocity_constraint::{
 };
 use crate::dynamics::solver::DeltaVel;
 use crate::dynamics::{
-    ImpulseJoint, IntegrationParameters, JointGraphEdge, JointIndex, RigidBodyIds,
+    ImpulseJoint, IntegrationParameters, JointCoupling, JointGraphEdge, JointIndex, RigidBodyIds,
     RigidBodyMassProps, RigidBodyPosition, RigidBodyType, RigidBodyVelocity,
 };
 #[cfg(feature = "simd-is-enabled")]
@@ -21,6 +22,7 @@ pub enum AnyJointVelocityConstraint {
     JointGroundConstraint(JointVelocityGroundConstraint<Real, 1>),
     JointGenericConstraint(JointGenericVelocityConstraint),
     JointGenericGroundConstraint(JointGenericVelocityGroundConstraint),
+    JointCouplingConstraint(JointCouplingVelocityConstraint),
     #[cfg(feature = "simd-is-enabled")]
     JointConstraintSimd(JointVelocityConstraint<SimdReal, SIMD_WIDTH>),
     #[cfg(feature = "simd-is-enabled")]
@@ -47,8 +49,24 @@ impl AnyJointVelocityConstraint {
         Bodies: ComponentSet<RigidBodyPosition>
             + ComponentSet<RigidBodyVelocity>
             + ComponentSet<RigidBodyMassProps>
+            + ComponentSet<RigidBodyType>
             + ComponentSet<RigidBodyIds>,
     {
+        if let Some(pulley) = &joint.data.pulley {
+            out.extend(
+                JointCouplingVelocityConstraint::from_pulley(
+                    params,
+                    joint_id,
+                    joint,
+                    pulley,
+                    bodies,
+                    multibodies,
+                )
+                .map(AnyJointVelocityConstraint::JointCouplingConstraint),
+            );
+            return;
+        }
+
         let local_frame1 = joint.data.local_frame1;
         let local_frame2 = joint.data.local_frame2;
         let rb1: (
@@ -72,16 +90,16 @@ impl AnyJointVelocityConstraint {
         let body1 = SolverBody {
             linvel: rb_vel1.linvel,
             angvel: rb_vel1.angvel,
-            im: rb_mprops1.effective_inv_mass,
-            sqrt_ii: rb_mprops1.effective_world_inv_inertia_sqrt,
+            im: rb_mprops1.constraint_inv_mass(),
+            sqrt_ii: rb_mprops1.constraint_world_inv_inertia_sqrt(),
             world_com: rb_mprops1.world_com,
             mj_lambda: [rb_ids1.active_set_offset],
         };
         let body2 = SolverBody {
             linvel: rb_vel2.linvel,
             angvel: rb_vel2.angvel,
-            im: rb_mprops2.effective_inv_mass,
-            sqrt_ii: rb_mprops2.effective_world_inv_inertia_sqrt,
+            im: rb_mprops2.constraint_inv_mass(),
+            sqrt_ii: rb_mprops2.constraint_world_inv_inertia_sqrt(),
             world_com: rb_mprops2.world_com,
             mj_lambda: [rb_ids2.active_set_offset],
         };
@@ -205,16 +223,16 @@ impl AnyJointVelocityConstraint {
         let body1: SolverBody<SimdReal, SIMD_WIDTH> = SolverBody {
             linvel: gather![|ii| rb_vel1[ii].linvel].into(),
             angvel: gather![|ii| rb_vel1[ii].angvel].into(),
-            im: gather![|ii| rb_mprops1[ii].effective_inv_mass].into(),
-            sqrt_ii: gather![|ii| rb_mprops1[ii].effective_world_inv_inertia_sqrt].into(),
+            im: gather![|ii| rb_mprops1[ii].constraint_inv_mass()].into(),
+            sqrt_ii: gather![|ii| rb_mprops1[ii].constraint_world_inv_inertia_sqrt()].into(),
             world_com: gather![|ii| rb_mprops1[ii].world_com].into(),
             mj_lambda: gather![|ii| rb_ids1[ii].active_set_offset],
         };
         let body2: SolverBody<SimdReal, SIMD_WIDTH> = SolverBody {
             linvel: gather![|ii| rb_vel2[ii].linvel].into(),
             angvel: gather![|ii| rb_vel2[ii].angvel].into(),
-            im: gather![|ii| rb_mprops2[ii].effective_inv_mass].into(),
-            sqrt_ii: gather![|ii| rb_mprops2[ii].effective_world_inv_inertia_sqrt].into(),
+            im: gather![|ii| rb_mprops2[ii].constraint_inv_mass()].into(),
+            sqrt_ii: gather![|ii| rb_mprops2[ii].constraint_world_inv_inertia_sqrt()].into(),
             world_com: gather![|ii| rb_mprops2[ii].world_com].into(),
             mj_lambda: gather![|ii| rb_ids2[ii].active_set_offset],
         };
@@ -253,6 +271,21 @@ impl AnyJointVelocityConstraint {
             + ComponentSet<RigidBodyMassProps>
             + ComponentSet<RigidBodyIds>,
     {
+        if let Some(pulley) = &joint.data.pulley {
+            out.extend(
+                JointCouplingVelocityConstraint::from_pulley(
+                    params,
+                    joint_id,
+                    joint,
+                    pulley,
+                    bodies,
+                    multibodies,
+                )
+                .map(AnyJointVelocityConstraint::JointCouplingConstraint),
+            );
+            return;
+        }
+
         let mut handle1 = joint.body1;
         let mut handle2 = joint.body2;
         let status2: &RigidBodyType = bodies.index(handle2.0);
@@ -282,16 +315,16 @@ impl AnyJointVelocityConstraint {
         let body1 = SolverBody {
             linvel: rb_vel1.linvel,
             angvel: rb_vel1.angvel,
-            im: rb_mprops1.effective_inv_mass,
-            sqrt_ii: rb_mprops1.effective_world_inv_inertia_sqrt,
+            im: rb_mprops1.constraint_inv_mass(),
+            sqrt_ii: rb_mprops1.constraint_world_inv_inertia_sqrt(),
             world_com: rb_mprops1.world_com,
             mj_lambda: [crate::INVALID_USIZE],
         };
         let body2 = SolverBody {
             linvel: rb_vel2.linvel,
             angvel: rb_vel2.angvel,
-            im: rb_mprops2.effective_inv_mass,
-            sqrt_ii: rb_mprops2.effective_world_inv_inertia_sqrt,
+            im: rb_mprops2.constraint_inv_mass(),
+            sqrt_ii: rb_mprops2.constraint_world_inv_inertia_sqrt(),
             world_com: rb_mprops2.world_com,
             mj_lambda: [rb_ids2.active_set_offset],
         };
@@ -428,16 +461,16 @@ impl AnyJointVelocityConstraint {
         let body1: SolverBody<SimdReal, SIMD_WIDTH> = SolverBody {
             linvel: gather![|ii| rb_vel1[ii].linvel].into(),
             angvel: gather![|ii| rb_vel1[ii].angvel].into(),
-            im: gather![|ii| rb_mprops1[ii].effective_inv_mass].into(),
-            sqrt_ii: gather![|ii| rb_mprops1[ii].effective_world_inv_inertia_sqrt].into(),
+            im: gather![|ii| rb_mprops1[ii].constraint_inv_mass()].into(),
+            sqrt_ii: gather![|ii| rb_mprops1[ii].constraint_world_inv_inertia_sqrt()].into(),
             world_com: gather![|ii| rb_mprops1[ii].world_com].into(),
             mj_lambda: [crate::INVALID_USIZE; SIMD_WIDTH],
         };
         let body2: SolverBody<SimdReal, SIMD_WIDTH> = SolverBody {
             linvel: gather![|ii| rb_vel2[ii].linvel].into(),
             angvel: gather![|ii| rb_vel2[ii].angvel].into(),
-            im: gather![|ii| rb_mprops2[ii].effective_inv_mass].into(),
-            sqrt_ii: gather![|ii| rb_mprops2[ii].effective_world_inv_inertia_sqrt].into(),
+            im: gather![|ii| rb_mprops2[ii].constraint_inv_mass()].into(),
+            sqrt_ii: gather![|ii| rb_mprops2[ii].constraint_world_inv_inertia_sqrt()].into(),
             world_com: gather![|ii| rb_mprops2[ii].world_com].into(),
             mj_lambda: gather![|ii| rb_ids2[ii].active_set_offset],
         };
@@ -470,6 +503,7 @@ impl AnyJointVelocityConstraint {
             AnyJointVelocityConstraint::JointGroundConstraintSimd(c) => c.remove_bias_from_rhs(),
             AnyJointVelocityConstraint::JointGenericConstraint(c) => c.remove_bias_from_rhs(),
             AnyJointVelocityConstraint::JointGenericGroundConstraint(c) => c.remove_bias_from_rhs(),
+            AnyJointVelocityConstraint::JointCouplingConstraint(c) => c.remove_bias_from_rhs(),
             AnyJointVelocityConstraint::Empty => unreachable!(),
         }
     }
@@ -493,11 +527,16 @@ impl AnyJointVelocityConstraint {
             AnyJointVelocityConstraint::JointGenericGroundConstraint(c) => {
                 c.solve(jacobians, mj_lambdas, generic_mj_lambdas)
             }
+            AnyJointVelocityConstraint::JointCouplingConstraint(c) => c.solve(mj_lambdas),
             AnyJointVelocityConstraint::Empty => unreachable!(),
         }
     }
 
-    pub fn writeback_impulses(&self, joints_all: &mut [JointGraphEdge]) {
+    pub fn writeback_impulses(
+        &self,
+        joints_all: &mut [JointGraphEdge],
+        couplings_all: &mut [JointCoupling],
+    ) {
         match self {
             AnyJointVelocityConstraint::JointConstraint(c) => c.writeback_impulses(joints_all),
             AnyJointVelocityConstraint::JointGroundConstraint(c) => {
@@ -515,6 +554,9 @@ impl AnyJointVelocityConstraint {
             AnyJointVelocityConstraint::JointGenericGroundConstraint(c) => {
                 c.writeback_impulses(joints_all)
             }
+            AnyJointVelocityConstraint::JointCouplingConstraint(c) => {
+                c.writeback_impulses(joints_all, couplings_all)
+            }
             AnyJointVelocityConstraint::Empty => unreachable!(),
         }
     }
@@ -0,0 +1,395 @@
+use crate::data::{BundleSet, ComponentSet};
+use crate::dynamics::solver::DeltaVel;
+use crate::dynamics::{
+    ImpulseJoint, IntegrationParameters, JointAxis, JointCoupling, JointGraphEdge, JointIndex,
+    JointPulley, MultibodyJointSet, RigidBodyHandle, RigidBodyIds, RigidBodyMassProps,
+    RigidBodyPosition, RigidBodyType, RigidBodyVelocity,
+};
+use crate::math::{AngVector, Point, Real, Vector, DEFAULT_EPSILON, DIM};
+use crate::utils::{WCross, WDot};
+use na::RealField;
+
+#[derive(Copy, Clone, Debug)]
+struct CoupledBody {
+    mj_lambda: usize,
+    im: Vector<Real>,
+    lin_jac: Vector<Real>,
+    // Angular jacobian, pre-multiplied by the square root of the inverse inertia.
+    ang_jac: AngVector<Real>,
+}
+
+// The element the impulse of a `JointCouplingVelocityConstraint` is written back to.
+#[derive(Copy, Clone, Debug)]
+enum CouplingWritebackId {
+    Coupling(usize),
+    Pulley(JointIndex),
+}
+
+/// The velocity constraint of a coupling between two joints, or of a pulley.
+///
+/// Unlike other joint constraints, it may involve up to four bodies.
+#[derive(Debug)]
+pub struct JointCouplingVelocityConstraint {
+    writeback_id: CouplingWritebackId,
+    bodies: [CoupledBody; 4],
+    num_bodies: usize,
+    impulse: Real,
+    impulse_bounds: [Real; 2],
+    inv_lhs: Real,
+    rhs: Real,
+    rhs_wo_bias: Real,
+    coords: [Real; 2],
+    rest_value: Real,
+}
+
+impl JointCouplingVelocityConstraint {
+    pub fn from_coupling<Bodies>(
+        params: &IntegrationParameters,
+        coupling_id: usize,
+        coupling: &JointCoupling,
+        joints_all: &[JointGraphEdge],
+        bodies: &Bodies,
+        multibodies: &MultibodyJointSet,
+    ) -> Option<Self>
+    where
+        Bodies: ComponentSet<RigidBodyPosition>
+            + ComponentSet<RigidBodyVelocity>
+            + ComponentSet<RigidBodyMassProps>
+            + ComponentSet<RigidBodyType>
+            + ComponentSet<RigidBodyIds>,
+    {
+        let joint1 = &joints_all.get(coupling.edges[0])?.weight;
+        let joint2 = &joints_all.get(coupling.edges[1])?.weight;
+
+        if [joint1.body1, joint1.body2, joint2.body1, joint2.body2]
+            .iter()
+            .any(|h| multibodies.rigid_body_link(*h).is_some())
+        {
+            // Couplings between multibody links are not supported.
+            return None;
+        }
+
+        let mut constraint = Self::new(CouplingWritebackId::Coupling(coupling_id));
+        let (raw1, dvel1) = constraint.add_joint(joint1, coupling.axis1, 1.0, bodies);
+        let (raw2, dvel2) = constraint.add_joint(joint2, coupling.axis2, coupling.ratio, bodies);
+
+        if constraint.num_bodies == 0 {
+            return None;
+        }
+
+        // Unwrap the angles so the coupling keeps working past a full revolution.
+        let prev_coords = coupling.coords.unwrap_or([raw1, raw2]);
+        constraint.coords = [
+            unwrap_coordinate(coupling.axis1, raw1, prev_coords[0]),
+            unwrap_coordinate(coupling.axis2, raw2, prev_coords[1]),
+        ];
+
+        let value = constraint.coords[0] + coupling.ratio * constraint.coords[1];
+        constraint.rest_value = coupling.rest_value.unwrap_or(value);
+        constraint.finalize(
+            params,
+            value - constraint.rest_value,
+            coupling.backlash.max(0.0) * 0.5,
+            dvel1 + dvel2,
+        );
+
+        Some(constraint)
+    }
+
+    pub fn from_pulley<Bodies>(
+        params: &IntegrationParameters,
+        joint_id: JointIndex,
+        joint: &ImpulseJoint,
+        pulley: &JointPulley,
+        bodies: &Bodies,
+        multibodies: &MultibodyJointSet,
+    ) -> Option<Self>
+    where
+        Bodies: ComponentSet<RigidBodyPosition>
+            + ComponentSet<RigidBodyVelocity>
+            + ComponentSet<RigidBodyMassProps>
+            + ComponentSet<RigidBodyType>
+            + ComponentSet<RigidBodyIds>,
+    {
+        if multibodies.rigid_body_link(joint.body1).is_some()
+            || multibodies.rigid_body_link(joint.body2).is_some()
+        {
+            // Pulleys attached to multibody links are not supported.
+            return None;
+        }
+
+        let mut constraint = Self::new(CouplingWritebackId::Pulley(joint_id));
+        let mut value = 0.0;
+        let mut dvel = 0.0;
+        let sides = [
+            (
+                joint.body1,
+                joint.data.local_frame1,
+                pulley.ground_anchor1,
+                1.0,
+            ),
+            (
+                joint.body2,
+                joint.data.local_frame2,
+                pulley.ground_anchor2,
+                pulley.ratio,
+            ),
+        ];
+
+        for (handle, local_frame, ground_anchor, factor) in sides {
+            let (rb_pos, rb_mprops): (&RigidBodyPosition, &RigidBodyMassProps) =
+                bodies.index_bundle(handle.0);
+            let anchor = rb_pos.position * Point::from(local_frame.translation.vector);
+            let rope = anchor - ground_anchor;
+            let length = rope.norm();
+            let dir = if length > DEFAULT_EPSILON {
+                rope / length
+            } else {
+                na::zero()
+            };
+
+            value += length * factor;
+            dvel += constraint.add_body(
+                handle,
+                dir * factor,
+                (anchor - rb_mprops.world_com).gcross(dir) * factor,
+                bodies,
+            );
+        }
+
+        if constraint.num_bodies == 0 {
+            return None;
+        }
+
+        let error = value - pulley.length;
+        constraint.finalize(params, error.max(0.0), 0.0, dvel);
+
+        // The rope can only pull the bodies toward the ground anchors. While it is slack,
+        // the bodies may move freely until it becomes taut.
+        constraint.impulse_bounds = [0.0, Real::INFINITY];
+        if error < 0.0 {
+            constraint.rhs_wo_bias += error * params.inv_dt();
+            constraint.rhs = constraint.rhs_wo_bias;
+        }
+
+        Some(constraint)
+    }
+
+    fn new(writeback_id: CouplingWritebackId) -> Self {
+        JointCouplingVelocityConstraint {
+            writeback_id,
+            bodies: [CoupledBody {
+                mj_lambda: usize::MAX,
+                im: na::zero(),
+                lin_jac: na::zero(),
+                ang_jac: na::zero(),
+            }; 4],
+            num_bodies: 0,
+            impulse: 0.0,
+            impulse_bounds: [-Real::INFINITY, Real::INFINITY],
+            inv_lhs: 0.0,
+            rhs: 0.0,
+            rhs_wo_bias: 0.0,
+            coords: [0.0; 2],
+            rest_value: 0.0,
+        }
+    }
+
+    // Sets up the impulse bounds, rhs and inverse lhs, once all the jacobians have been added.
+    fn finalize(
+        &mut self,
+        params: &IntegrationParameters,
+        error: Real,
+        half_backlash: Real,
+        dvel: Real,
+    ) {
+        if half_backlash > 0.0 {
+            let min_enabled = error < -half_backlash;
+            let max_enabled = error > half_backlash;
+            self.impulse_bounds = [
+                if min_enabled { -Real::INFINITY } else { 0.0 },
+                if max_enabled { Real::INFINITY } else { 0.0 },
+            ];
+        }
+
+        let erp_inv_dt = params.erp_inv_dt();
+        let rhs_bias =
+            ((error - half_backlash).max(0.0) - (-half_backlash - error).max(0.0)) * erp_inv_dt;
+        self.rhs_wo_bias = dvel * params.velocity_solve_fraction;
+        self.rhs = self.rhs_wo_bias + rhs_bias;
+
+        let lhs: Real = self.bodies[..self.num_bodies]
+            .iter()
+            .map(|b| b.lin_jac.component_mul(&b.im).dot(&b.lin_jac) + b.ang_jac.gdot(b.ang_jac))
+            .sum();
+        self.inv_lhs = crate::utils::inv(lhs);
+    }
+
+    // Adds the jacobians of the given joint coordinate, scaled by `factor`, and returns
+    // the coordinate value with its current time derivative.
+    fn add_joint<Bodies>(
+        &mut self,
+        joint: &ImpulseJoint,
+        axis: JointAxis,
+        factor: Real,
+        bodies: &Bodies,
+    ) -> (Real, Real)
+    where
+        Bodies: ComponentSet<RigidBodyPosition>
+            + ComponentSet<RigidBodyVelocity>
+            + ComponentSet<RigidBodyMassProps>
+            + ComponentSet<RigidBodyType>
+            + ComponentSet<RigidBodyIds>,
+    {
+        let rb_pos1: &RigidBodyPosition = bodies.index(joint.body1.0);
+        let rb_pos2: &RigidBodyPosition = bodies.index(joint.body2.0);
+        let frame1 = rb_pos1.position * joint.data.local_frame1;
+        let frame2 = rb_pos2.position * joint.data.local_frame2;
+        let axis_id = axis as usize;
+
+        let (coord, lin_jac, ang_jac1, ang_jac2);
+
+        if axis_id < DIM {
+            let lin_axis = frame1.rotation * Vector::ith(axis_id, 1.0);
+            let anchor = frame2.translation.vector;
+            let com1: &RigidBodyMassProps = bodies.index(joint.body1.0);
+            let com2: &RigidBodyMassProps = bodies.index(joint.body2.0);
+
+            coord = (frame2.translation.vector - frame1.translation.vector).dot(&lin_axis);
+            lin_jac = lin_axis;
+            ang_jac1 = (anchor - com1.world_com.coords).gcross(lin_axis);
+            ang_jac2 = (anchor - com2.world_com.coords).gcross(lin_axis);
+        } else {
+            let rel_rot = frame1.rotation.inverse() * frame2.rotation;
+
+            #[cfg(feature = "dim2")]
+            {
+                coord = rel_rot.angle();
+                ang_jac1 = 1.0;
+            }
+            #[cfg(feature = "dim3")]
+            {
+                // Twist angle of the relative rotation around the coupled axis.
+                let local_axis = Vector::ith(axis_id - DIM, 1.0);
+                coord = wrap_angle(2.0 * rel_rot.imag().dot(&local_axis).atan2(rel_rot.w));
+                ang_jac1 = frame1.rotation * local_axis;
+            }
+
+            lin_jac = na::zero();
+            ang_jac2 = ang_jac1;
+        }
+
+        let dvel2 = self.add_body(joint.body2, lin_jac * factor, ang_jac2 * factor, bodies);
+        let dvel1 = self.add_body(joint.body1, -lin_jac * factor, -ang_jac1 * factor, bodies);
+
+        (coord, dvel1 + dvel2)
+    }
+
+    // Adds the jacobians of one body, merging them with a previously added body if
+    // necessary, and returns the contribution of its velocity to the constraint.
+    fn add_body<Bodies>(
+        &mut self,
+        handle: RigidBodyHandle,
+        lin_jac: Vector<Real>,
+        ang_jac: AngVector<Real>,
+        bodies: &Bodies,
+    ) -> Real
+    where
+        Bodies: ComponentSet<RigidBodyVelocity>
+            + ComponentSet<RigidBodyMassProps>
+            + ComponentSet<RigidBodyType>
+            + ComponentSet<RigidBodyIds>,
+    {
+        let (rb_vel, rb_mprops, rb_type, rb_ids): (
+            &RigidBodyVelocity,
+            &RigidBodyMassProps,
+            &RigidBodyType,
+            &RigidBodyIds,
+        ) = bodies.index_bundle(handle.0);
+        let dvel = lin_jac.dot(&rb_vel.linvel) + ang_jac.gdot(rb_vel.angvel);
+
+        if rb_type.is_dynamic() {
+            let mj_lambda = rb_ids.active_set_offset;
+            let ang_jac = rb_mprops.constraint_world_inv_inertia_sqrt() * ang_jac;
+
+            if let Some(body) = self.bodies[..self.num_bodies]
+                .iter_mut()
+                .find(|b| b.mj_lambda == mj_lambda)
+            {
+                body.lin_jac += lin_jac;
+                body.ang_jac += ang_jac;
+            } else {
+                self.bodies[self.num_bodies] = CoupledBody {
+                    mj_lambda,
+                    im: rb_mprops.constraint_inv_mass(),
+                    lin_jac,
+                    ang_jac,
+                };
+                self.num_bodies += 1;
+            }
+        }
+
+        dvel
+    }
+
+    pub fn solve(&mut self, mj_lambdas: &mut [DeltaVel<Real>]) {
+        let bodies = &self.bodies[..self.num_bodies];
+        let dvel: Real = bodies
+            .iter()
+            .map(|b| {
+                let mj_lambda = &mj_lambdas[b.mj_lambda];
+                b.lin_jac.dot(&mj_lambda.linear) + b.ang_jac.gdot(mj_lambda.angular)
+            })
+            .sum();
+
+        let total_impulse = (self.impulse + self.inv_lhs * (dvel + self.rhs))
+            .max(self.impulse_bounds[0])
+            .min(self.impulse_bounds[1]);
+        let delta_impulse = total_impulse - self.impulse;
+        self.impulse = total_impulse;
+
+        for b in bodies {
+            let mj_lambda = &mut mj_lambdas[b.mj_lambda];
+            mj_lambda.linear -= b.lin_jac.component_mul(&b.im) * delta_impulse;
+            mj_lambda.angular -= b.ang_jac * delta_impulse;
+        }
+    }
+
+    pub fn writeback_impulses(
+        &self,
+        joints_all: &mut [JointGraphEdge],
+        couplings_all: &mut [JointCoupling],
+    ) {
+        match self.writeback_id {
+            CouplingWritebackId::Coupling(coupling_id) => {
+                let coupling = &mut couplings_all[coupling_id];
+                coupling.impulse = self.impulse;
+                coupling.coords = Some(self.coords);
+                coupling.rest_value = Some(self.rest_value);
+            }
+            CouplingWritebackId::Pulley(joint_id) => {
+                if let Some(pulley) = &mut joints_all[joint_id].weight.data.pulley {
+                    pulley.impulse = self.impulse;
+                }
+            }
+        }
+    }
+
+    pub fn remove_bias_from_rhs(&mut self) {
+        self.rhs = self.rhs_wo_bias;
+    }
+}
+
+// Wraps the given angle into the range `[-pi, pi)`.
+fn wrap_angle(angle: Real) -> Real {
+    let two_pi = Real::two_pi();
+    angle - two_pi * ((angle + Real::pi()) / two_pi).floor()
+}
+
+fn unwrap_coordinate(axis: JointAxis, raw: Real, prev: Real) -> Real {
+    if (axis as usize) < DIM {
+        raw
+    } else {
+        prev + wrap_angle(raw - prev)
+    }
+}
@@ -23,6 +23,7 @@ pub struct JointGenericVelocityConstraint {
     pub impulse: Real,
     pub impulse_bounds: [Real; 2],
     pub inv_lhs: Real,
+    pub cfm_gain: Real,
     pub rhs: Real,
     pub rhs_wo_bias: Real,
 
@@ -49,6 +50,7 @@ impl JointGenericVelocityConstraint {
             joint_id: 0,
             impulse: 0.0,
             impulse_bounds: [-Real::MAX, Real::MAX],
+            cfm_gain: 0.0,
             inv_lhs: 0.0,
             rhs: 0.0,
             rhs_wo_bias: 0.0,
@@ -72,8 +74,11 @@ impl JointGenericVelocityConstraint {
     ) -> usize {
         let mut len = 0;
         let locked_axes = joint.locked_axes.bits();
-        let motor_axes = joint.motor_axes.bits();
-        let limit_axes = joint.limit_axes.bits();
+        let coupled_lin_axes = joint.coupled_lin_axes_bits();
+        let motor_axes = joint.motor_axes.bits() & !coupled_lin_axes;
+        let limit_axes = joint.limit_axes.bits() & !coupled_lin_axes;
+        let coupled_motor = joint.motor_axes.bits() & coupled_lin_axes & 1 != 0;
+        let coupled_limit = joint.limit_axes.bits() & coupled_lin_axes & 1 != 0;
 
         let builder = JointVelocityConstraintBuilder::new(
             frame1,
@@ -118,6 +123,39 @@ impl JointGenericVelocityConstraint {
             }
         }
 
+        if coupled_motor {
+            out[len] = builder.motor_linear_coupled_generic(
+                params,
+                jacobians,
+                j_id,
+                joint_id,
+                body1,
+                body2,
+                mb1,
+                mb2,
+                coupled_lin_axes,
+                &joint.motors[0].motor_params(params.dt),
+                WritebackId::Motor(0),
+            );
+            len += 1;
+        }
+        if coupled_limit {
+            out[len] = builder.limit_linear_coupled_generic(
+                params,
+                jacobians,
+                j_id,
+                joint_id,
+                body1,
+                body2,
+                mb1,
+                mb2,
+                coupled_lin_axes,
+                [joint.limits[0].min, joint.limits[0].max],
+                WritebackId::Limit(0),
+            );
+            len += 1;
+        }
+
         for i in 0..DIM {
             if motor_axes & (1 << i) != 0 {
                 out[len] = builder.motor_linear_generic(
@@ -195,6 +233,28 @@ impl JointGenericVelocityConstraint {
             }
         }
 
+        #[cfg(feature = "dim3")]
+        if let Some(swing_twist) = &joint.swing_twist_limits {
+            let constraints = builder.swing_twist_limits(swing_twist);
+            for (i, (ang_jac, angle, limits)) in constraints.into_iter().enumerate() {
+                out[len] = builder.limit_angular_axis_generic(
+                    params,
+                    jacobians,
+                    j_id,
+                    joint_id,
+                    body1,
+                    body2,
+                    mb1,
+                    mb2,
+                    ang_jac,
+                    angle,
+                    limits,
+                    WritebackId::SwingTwist(i),
+                );
+                len += 1;
+            }
+        }
+
         JointVelocityConstraintBuilder::finalize_generic_constraints(jacobians, &mut out[..len]);
         len
     }
@@ -271,7 +331,7 @@ impl JointGenericVelocityConstraint {
         let j2 = DVectorSlice::from_slice(&jacobians[self.j_id2..], self.ndofs2);
         let vel2 = j2.dot(&mj_lambda2);
 
-        let dvel = self.rhs + (vel2 - vel1);
+        let dvel = self.rhs + (vel2 - vel1) - self.cfm_gain * self.impulse;
         let total_impulse = na::clamp(
             self.impulse + self.inv_lhs * dvel,
             self.impulse_bounds[0],
@@ -295,6 +355,12 @@ impl JointGenericVelocityConstraint {
             WritebackId::Dof(i) => joint.impulses[i] = self.impulse,
             WritebackId::Limit(i) => joint.data.limits[i].impulse = self.impulse,
             WritebackId::Motor(i) => joint.data.motors[i].impulse = self.impulse,
+            #[cfg(feature = "dim3")]
+            WritebackId::SwingTwist(i) => {
+                if let Some(limits) = &mut joint.data.swing_twist_limits {
+                    limits.impulses[i] = self.impulse;
+                }
+            }
         }
     }
 
@@ -314,6 +380,7 @@ pub struct JointGenericVelocityGroundConstraint {
     pub impulse: Real,
     pub impulse_bounds: [Real; 2],
     pub inv_lhs: Real,
+    pub cfm_gain: Real,
     pub rhs: Real,
     pub rhs_wo_bias: Real,
 
@@ -335,6 +402,7 @@ impl JointGenericVelocityGroundConstraint {
             joint_id: 0,
             impulse: 0.0,
             impulse_bounds: [-Real::MAX, Real::MAX],
+            cfm_gain: 0.0,
             inv_lhs: 0.0,
             rhs: 0.0,
             rhs_wo_bias: 0.0,
@@ -357,8 +425,11 @@ impl JointGenericVelocityGroundConstraint {
     ) -> usize {
         let mut len = 0;
         let locked_axes = joint.locked_axes.bits();
-        let motor_axes = joint.motor_axes.bits();
-        let limit_axes = joint.limit_axes.bits();
+        let coupled_lin_axes = joint.coupled_lin_axes_bits();
+        let motor_axes = joint.motor_axes.bits() & !coupled_lin_axes;
+        let limit_axes = joint.limit_axes.bits() & !coupled_lin_axes;
+        let coupled_motor = joint.motor_axes.bits() & coupled_lin_axes & 1 != 0;
+        let coupled_limit = joint.limit_axes.bits() & coupled_lin_axes & 1 != 0;
 
         let builder = JointVelocityConstraintBuilder::new(
             frame1,
@@ -399,6 +470,36 @@ impl JointGenericVelocityGroundConstraint {
             }
         }
 
+        if coupled_motor {
+            out[len] = builder.motor_linear_coupled_generic_ground(
+                params,
+                jacobians,
+                j_id,
+                joint_id,
+                body1,
+                body2,
+                mb2,
+                coupled_lin_axes,
+                &joint.motors[0].motor_params(params.dt),
+                WritebackId::Motor(0),
+            );
+            len += 1;
+        }
+        if coupled_limit {
+            out[len] = builder.limit_linear_coupled_generic_ground(
+                params,
+                jacobians,
+                j_id,
+                joint_id,
+                body1,
+                mb2,
+                coupled_lin_axes,
+                [joint.limits[0].min, joint.limits[0].max],
+                WritebackId::Limit(0),
+            );
+            len += 1;
+        }
+
         for i in 0..DIM {
             if motor_axes & (1 << i) != 0 {
                 out[len] = builder.motor_linear_generic_ground(
@@ -470,6 +571,26 @@ impl JointGenericVelocityGroundConstraint {
             }
         }
 
+        #[cfg(feature = "dim3")]
+        if let Some(swing_twist) = &joint.swing_twist_limits {
+            let constraints = builder.swing_twist_limits(swing_twist);
+            for (i, (ang_jac, angle, limits)) in constraints.into_iter().enumerate() {
+                out[len] = builder.limit_angular_axis_generic_ground(
+                    params,
+                    jacobians,
+                    j_id,
+                    joint_id,
+                    body1,
+                    mb2,
+                    ang_jac,
+                    angle,
+                    limits,
+                    WritebackId::SwingTwist(i),
+                );
+                len += 1;
+            }
+        }
+
         JointVelocityConstraintBuilder::finalize_generic_constraints_ground(
             jacobians,
             &mut out[..len],
@@ -509,7 +630,7 @@ impl JointGenericVelocityGroundConstraint {
         let j2 = DVectorSlice::from_slice(&jacobians[self.j_id2..], self.ndofs2);
         let vel2 = j2.dot(&mj_lambda2);
 
-        let dvel = self.rhs + vel2;
+        let dvel = self.rhs + vel2 - self.cfm_gain * self.impulse;
         let total_impulse = na::clamp(
             self.impulse + self.inv_lhs * dvel,
             self.impulse_bounds[0],
@@ -531,6 +652,12 @@ impl JointGenericVelocityGroundConstraint {
                 WritebackId::Dof(i) => joint.impulses[i] = self.impulse,
                 WritebackId::Limit(i) => joint.data.limits[i].impulse = self.impulse,
                 WritebackId::Motor(i) => joint.data.motors[i].impulse = self.impulse,
+                #[cfg(feature = "dim3")]
+                WritebackId::SwingTwist(i) => {
+                    if let Some(limits) = &mut joint.data.swing_twist_limits {
+                        limits.impulses[i] = self.impulse;
+                    }
+                }
             }
         }
     }
@@ -130,6 +130,7 @@ impl JointVelocityConstraintBuilder<Real> {
             joint_id,
             impulse: 0.0,
             impulse_bounds: [-Real::MAX, Real::MAX],
+            cfm_gain: 0.0,
             inv_lhs: 0.0,
             rhs: rhs_wo_bias,
             rhs_wo_bias,
@@ -270,13 +271,112 @@ impl JointVelocityConstraintBuilder<Real> {
             rhs_wo_bias += (dist - motor_params.target_pos) * motor_params.stiffness;
         }
 
-        if motor_params.damping != 0.0 {
+        if motor_params.damping != 0.0 || motor_params.lookahead != 0.0 {
             let dvel = lin_jac.dot(&(body2.linvel - body1.linvel))
                 + (ang_jac2.gdot(body2.angvel) - ang_jac1.gdot(body1.angvel));
             rhs_wo_bias += (dvel - motor_params.target_vel) * motor_params.damping;
+            rhs_wo_bias += dvel * motor_params.lookahead * motor_params.stiffness;
         }
 
         constraint.impulse_bounds = [-motor_params.max_impulse, motor_params.max_impulse];
+
+        constraint.cfm_gain = motor_params.cfm_gain;
+        constraint.rhs = rhs_wo_bias;
+        constraint.rhs_wo_bias = rhs_wo_bias;
+        constraint
+    }
+
+    pub fn limit_linear_coupled_generic(
+        &self,
+        params: &IntegrationParameters,
+        jacobians: &mut DVector<Real>,
+        j_id: &mut usize,
+        joint_id: JointIndex,
+        body1: &SolverBody<Real, 1>,
+        body2: &SolverBody<Real, 1>,
+        mb1: Option<(&Multibody, usize)>,
+        mb2: Option<(&Multibody, usize)>,
+        coupled_axes: u8,
+        limits: [Real; 2],
+        writeback_id: WritebackId,
+    ) -> JointGenericVelocityConstraint {
+        let (dist, lin_jac, ang_jac1, ang_jac2) = self.coupled_linear_jacobians(coupled_axes);
+
+        let mut constraint = self.lock_jacobians_generic(
+            params,
+            jacobians,
+            j_id,
+            joint_id,
+            body1,
+            body2,
+            mb1,
+            mb2,
+            writeback_id,
+            lin_jac,
+            ang_jac1,
+            ang_jac2,
+        );
+
+        let min_enabled = dist < limits[0];
+        let max_enabled = limits[1] < dist;
+
+        let erp_inv_dt = params.erp_inv_dt();
+        let rhs_bias = ((dist - limits[1]).max(0.0) - (limits[0] - dist).max(0.0)) * erp_inv_dt;
+        constraint.rhs += rhs_bias;
+        constraint.impulse_bounds = [
+            min_enabled as u32 as Real * -Real::MAX,
+            max_enabled as u32 as Real * Real::MAX,
+        ];
+
+        constraint
+    }
+
+    pub fn motor_linear_coupled_generic(
+        &self,
+        params: &IntegrationParameters,
+        jacobians: &mut DVector<Real>,
+        j_id: &mut usize,
+        joint_id: JointIndex,
+        body1: &SolverBody<Real, 1>,
+        body2: &SolverBody<Real, 1>,
+        mb1: Option<(&Multibody, usize)>,
+        mb2: Option<(&Multibody, usize)>,
+        coupled_axes: u8,
+        motor_params: &MotorParameters<Real>,
+        writeback_id: WritebackId,
+    ) -> JointGenericVelocityConstraint {
+        let (dist, lin_jac, ang_jac1, ang_jac2) = self.coupled_linear_jacobians(coupled_axes);
+
+        let mut constraint = self.lock_jacobians_generic(
+            params,
+            jacobians,
+            j_id,
+            joint_id,
+            body1,
+            body2,
+            mb1,
+            mb2,
+            writeback_id,
+            lin_jac,
+            ang_jac1,
+            ang_jac2,
+        );
+
+        let mut rhs_wo_bias = 0.0;
+        if motor_params.stiffness != 0.0 {
+            rhs_wo_bias += (dist - motor_params.target_pos) * motor_params.stiffness;
+        }
+
+        if motor_params.damping != 0.0 || motor_params.lookahead != 0.0 {
+            let dvel = lin_jac.dot(&(body2.linvel - body1.linvel))
+                + (ang_jac2.gdot(body2.angvel) - ang_jac1.gdot(body1.angvel));
+            rhs_wo_bias += (dvel - motor_params.target_vel) * motor_params.damping;
+            rhs_wo_bias += dvel * motor_params.lookahead * motor_params.stiffness;
+        }
+
+        constraint.impulse_bounds = [-motor_params.max_impulse, motor_params.max_impulse];
+
+        constraint.cfm_gain = motor_params.cfm_gain;
         constraint.rhs = rhs_wo_bias;
         constraint.rhs_wo_bias = rhs_wo_bias;
         constraint
@@ -373,6 +473,52 @@ impl JointVelocityConstraintBuilder<Real> {
         constraint
     }
 
+    #[cfg(feature = "dim3")]
+    pub fn limit_angular_axis_generic(
+        &self,
+        params: &IntegrationParameters,
+        jacobians: &mut DVector<Real>,
+        j_id: &mut usize,
+        joint_id: JointIndex,
+        body1: &SolverBody<Real, 1>,
+        body2: &SolverBody<Real, 1>,
+        mb1: Option<(&Multibody, usize)>,
+        mb2: Option<(&Multibody, usize)>,
+        ang_jac: Vector<Real>,
+        angle: Real,
+        limits: [Real; 2],
+        writeback_id: WritebackId,
+    ) -> JointGenericVelocityConstraint {
+        let mut constraint = self.lock_jacobians_generic(
+            params,
+            jacobians,
+            j_id,
+            joint_id,
+            body1,
+            body2,
+            mb1,
+            mb2,
+            writeback_id,
+            na::zero(),
+            ang_jac,
+            ang_jac,
+        );
+
+        let min_enabled = angle < limits[0];
+        let max_enabled = limits[1] < angle;
+        let impulse_bounds = [
+            min_enabled as u32 as Real * -Real::MAX,
+            max_enabled as u32 as Real * Real::MAX,
+        ];
+
+        let erp_inv_dt = params.erp_inv_dt();
+        let rhs_bias = ((angle - limits[1]).max(0.0) - (limits[0] - angle).max(0.0)) * erp_inv_dt;
+
+        constraint.rhs += rhs_bias;
+        constraint.impulse_bounds = impulse_bounds;
+        constraint
+    }
+
     pub fn motor_angular_generic(
         &self,
         params: &IntegrationParameters,
@@ -418,14 +564,16 @@ impl JointVelocityConstraintBuilder<Real> {
             rhs_wo_bias += (s_ang_dist - s_target_ang) * motor_params.stiffness;
         }
 
-        if motor_params.damping != 0.0 {
+        if motor_params.damping != 0.0 || motor_params.lookahead != 0.0 {
             let dvel = ang_jac.gdot(body2.angvel) - ang_jac.gdot(body1.angvel);
             rhs_wo_bias += (dvel - motor_params.target_vel * ang_jac.norm()) * motor_params.damping;
+            rhs_wo_bias += dvel * motor_params.lookahead * motor_params.stiffness;
         }
 
         constraint.rhs_wo_bias = rhs_wo_bias;
         constraint.rhs = rhs_wo_bias;
         constraint.impulse_bounds = [-motor_params.max_impulse, motor_params.max_impulse];
+        constraint.cfm_gain = motor_params.cfm_gain;
         constraint
     }
 
@@ -436,6 +584,11 @@ impl JointVelocityConstraintBuilder<Real> {
         // TODO: orthogonalization doesn’t seem to give good results for multibodies?
         const ORTHOGONALIZE: bool = false;
         let len = constraints.len();
+
+        if len == 0 {
+            return;
+        }
+
         let ndofs1 = constraints[0].ndofs1;
         let ndofs2 = constraints[0].ndofs2;
 
@@ -450,9 +603,10 @@ impl JointVelocityConstraintBuilder<Real> {
 
             let dot_jj = jac_j1.dot(&w_jac_j1) + jac_j2.dot(&w_jac_j2);
             let inv_dot_jj = crate::utils::inv(dot_jj);
-            c_j.inv_lhs = inv_dot_jj; // Don’t forget to update the inv_lhs.
+            // Don’t forget to update the inv_lhs.
+            c_j.inv_lhs = crate::utils::inv(dot_jj + c_j.cfm_gain);
 
-            if c_j.impulse_bounds != [-Real::MAX, Real::MAX] {
+            if c_j.impulse_bounds != [-Real::MAX, Real::MAX] || c_j.cfm_gain != 0.0 {
                 // Don't remove constraints with limited forces from the others
                 // because they may not deliver the necessary forces to fulfill
                 // the removed parts of other constraints.
@@ -521,6 +675,7 @@ impl JointVelocityConstraintBuilder<Real> {
             joint_id,
             impulse: 0.0,
             impulse_bounds: [-Real::MAX, Real::MAX],
+            cfm_gain: 0.0,
             inv_lhs: 0.0,
             rhs: rhs_wo_bias,
             rhs_wo_bias,
@@ -650,13 +805,105 @@ impl JointVelocityConstraintBuilder<Real> {
             rhs_wo_bias += (dist - motor_params.target_pos) * motor_params.stiffness;
         }
 
-        if motor_params.damping != 0.0 {
+        if motor_params.damping != 0.0 || motor_params.lookahead != 0.0 {
+            let dvel = lin_jac.dot(&(body2.linvel - body1.linvel))
+                + (ang_jac2.gdot(body2.angvel) - ang_jac1.gdot(body1.angvel));
+            rhs_wo_bias += (dvel - motor_params.target_vel) * motor_params.damping;
+            rhs_wo_bias += dvel * motor_params.lookahead * motor_params.stiffness;
+        }
+
+        constraint.impulse_bounds = [-motor_params.max_impulse, motor_params.max_impulse];
+
+        constraint.cfm_gain = motor_params.cfm_gain;
+        constraint.rhs = rhs_wo_bias;
+        constraint.rhs_wo_bias = rhs_wo_bias;
+        constraint
+    }
+
+    pub fn limit_linear_coupled_generic_ground(
+        &self,
+        params: &IntegrationParameters,
+        jacobians: &mut DVector<Real>,
+        j_id: &mut usize,
+        joint_id: JointIndex,
+        body1: &SolverBody<Real, 1>,
+        mb2: (&Multibody, usize),
+        coupled_axes: u8,
+        limits: [Real; 2],
+        writeback_id: WritebackId,
+    ) -> JointGenericVelocityGroundConstraint {
+        let (dist, lin_jac, ang_jac1, ang_jac2) = self.coupled_linear_jacobians(coupled_axes);
+
+        let mut constraint = self.lock_jacobians_generic_ground(
+            params,
+            jacobians,
+            j_id,
+            joint_id,
+            body1,
+            mb2,
+            writeback_id,
+            lin_jac,
+            ang_jac1,
+            ang_jac2,
+        );
+
+        let min_enabled = dist < limits[0];
+        let max_enabled = limits[1] < dist;
+
+        let erp_inv_dt = params.erp_inv_dt();
+        let rhs_bias = ((dist - limits[1]).max(0.0) - (limits[0] - dist).max(0.0)) * erp_inv_dt;
+        constraint.rhs += rhs_bias;
+        constraint.impulse_bounds = [
+            min_enabled as u32 as Real * -Real::MAX,
+            max_enabled as u32 as Real * Real::MAX,
+        ];
+
+        constraint
+    }
+
+    pub fn motor_linear_coupled_generic_ground(
+        &self,
+        params: &IntegrationParameters,
+        jacobians: &mut DVector<Real>,
+        j_id: &mut usize,
+        joint_id: JointIndex,
+        body1: &SolverBody<Real, 1>,
+        body2: &SolverBody<Real, 1>, // TODO: we shouldn’t need this.
+        mb2: (&Multibody, usize),
+        coupled_axes: u8,
+        motor_params: &MotorParameters<Real>,
+        writeback_id: WritebackId,
+    ) -> JointGenericVelocityGroundConstraint {
+        let (dist, lin_jac, ang_jac1, ang_jac2) = self.coupled_linear_jacobians(coupled_axes);
+
+        let mut constraint = self.lock_jacobians_generic_ground(
+            params,
+            jacobians,
+            j_id,
+            joint_id,
+            body1,
+            mb2,
+            writeback_id,
+            lin_jac,
+            ang_jac1,
+            ang_jac2,
+        );
+
+        let mut rhs_wo_bias = 0.0;
+        if motor_params.stiffness != 0.0 {
+            rhs_wo_bias += (dist - motor_params.target_pos) * motor_params.stiffness;
+        }
+
+        if motor_params.damping != 0.0 || motor_params.lookahead != 0.0 {
             let dvel = lin_jac.dot(&(body2.linvel - body1.linvel))
                 + (ang_jac2.gdot(body2.angvel) - ang_jac1.gdot(body1.angvel));
             rhs_wo_bias += (dvel - motor_params.target_vel) * motor_params.damping;
+            rhs_wo_bias += dvel * motor_params.lookahead * motor_params.stiffness;
         }
 
         constraint.impulse_bounds = [-motor_params.max_impulse, motor_params.max_impulse];
+
+        constraint.cfm_gain = motor_params.cfm_gain;
         constraint.rhs = rhs_wo_bias;
         constraint.rhs_wo_bias = rhs_wo_bias;
         constraint
@@ -745,6 +992,48 @@ impl JointVelocityConstraintBuilder<Real> {
         constraint
     }
 
+    #[cfg(feature = "dim3")]
+    pub fn limit_angular_axis_generic_ground(
+        &self,
+        params: &IntegrationParameters,
+        jacobians: &mut DVector<Real>,
+        j_id: &mut usize,
+        joint_id: JointIndex,
+        body1: &SolverBody<Real, 1>,
+        mb2: (&Multibody, usize),
+        ang_jac: Vector<Real>,
+        angle: Real,
+        limits: [Real; 2],
+        writeback_id: WritebackId,
+    ) -> JointGenericVelocityGroundConstraint {
+        let mut constraint = self.lock_jacobians_generic_ground(
+            params,
+            jacobians,
+            j_id,
+            joint_id,
+            body1,
+            mb2,
+            writeback_id,
+            na::zero(),
+            ang_jac,
+            ang_jac,
+        );
+
+        let min_enabled = angle < limits[0];
+        let max_enabled = limits[1] < angle;
+        let impulse_bounds = [
+            min_enabled as u32 as Real * -Real::MAX,
+            max_enabled as u32 as Real * Real::MAX,
+        ];
+
+        let erp_inv_dt = params.erp_inv_dt();
+        let rhs_bias = ((angle - limits[1]).max(0.0) - (limits[0] - angle).max(0.0)) * erp_inv_dt;
+
+        constraint.rhs += rhs_bias;
+        constraint.impulse_bounds = impulse_bounds;
+        constraint
+    }
+
     pub fn motor_angular_generic_ground(
         &self,
         params: &IntegrationParameters,
@@ -787,14 +1076,16 @@ impl JointVelocityConstraintBuilder<Real> {
             rhs += (s_ang_dist - s_target_ang) * motor_params.stiffness;
         }
 
-        if motor_params.damping != 0.0 {
+        if motor_params.damping != 0.0 || motor_params.lookahead != 0.0 {
             let dvel = ang_jac.gdot(body2.angvel) - ang_jac.gdot(body1.angvel);
             rhs += (dvel - motor_params.target_vel * ang_jac.norm()) * motor_params.damping;
+            rhs += dvel * motor_params.lookahead * motor_params.stiffness;
         }
 
         constraint.rhs_wo_bias = rhs;
         constraint.rhs = rhs;
         constraint.impulse_bounds = [-motor_params.max_impulse, motor_params.max_impulse];
+        constraint.cfm_gain = motor_params.cfm_gain;
         constraint
     }
 
@@ -805,6 +1096,11 @@ impl JointVelocityConstraintBuilder<Real> {
         // TODO: orthogonalization doesn’t seem to give good results for multibodies?
         const ORTHOGONALIZE: bool = false;
         let len = constraints.len();
+
+        if len == 0 {
+            return;
+        }
+
         let ndofs2 = constraints[0].ndofs2;
 
         // Use the modified Gramm-Schmidt orthogonalization.
@@ -816,9 +1112,10 @@ impl JointVelocityConstraintBuilder<Real> {
 
             let dot_jj = jac_j2.dot(&w_jac_j2);
             let inv_dot_jj = crate::utils::inv(dot_jj);
-            c_j.inv_lhs = inv_dot_jj; // Don’t forget to update the inv_lhs.
+            // Don’t forget to update the inv_lhs.
+            c_j.inv_lhs = crate::utils::inv(dot_jj + c_j.cfm_gain);
 
-            if c_j.impulse_bounds != [-Real::MAX, Real::MAX] {
+            if c_j.impulse_bounds != [-Real::MAX, Real::MAX] || c_j.cfm_gain != 0.0 {
                 // Don't remove constraints with limited forces from the others
                 // because they may not deliver the necessary forces to fulfill
                 // the removed parts of other constraints.
@@ -15,11 +15,13 @@ use {
 pub struct MotorParameters<N: SimdRealField> {
     pub stiffness: N,
     pub damping: N,
-    pub gamma: N,
-    // pub keep_lhs: bool,
+    pub cfm_gain: N,
     pub target_pos: N,
     pub target_vel: N,
     pub max_impulse: N,
+    /// The duration by which the position error is extrapolated with the current
+    /// velocity before applying the stiffness.
+    pub lookahead: N,
 }
 
 impl<N: SimdRealField> Default for MotorParameters<N> {
@@ -27,11 +29,11 @@ impl<N: SimdRealField> Default for MotorParameters<N> {
         Self {
             stiffness: N::zero(),
             damping: N::zero(),
-            gamma: N::zero(),
-            // keep_lhs: true,
+            cfm_gain: N::zero(),
             target_pos: N::zero(),
             target_vel: N::zero(),
             max_impulse: N::zero(),
+            lookahead: N::zero(),
         }
     }
 }
@@ -41,6 +43,8 @@ pub enum WritebackId {
     Dof(usize),
     Limit(usize),
     Motor(usize),
+    #[cfg(feature = "dim3")]
+    SwingTwist(usize),
 }
 
 // TODO: right now we only use this for impulse_joints.
@@ -71,6 +75,7 @@ pub struct JointVelocityConstraint<N: SimdRealField, const LANES: usize> {
     pub ang_jac2: AngVector<N>,
 
     pub inv_lhs: N,
+    pub cfm_gain: N,
     pub rhs: N,
     pub rhs_wo_bias: N,
 
@@ -91,6 +96,7 @@ impl<N: WReal, const LANES: usize> JointVelocityConstraint<N, LANES> {
             lin_jac: Vector::zeros(),
             ang_jac1: na::zero(),
             ang_jac2: na::zero(),
+            cfm_gain: N::zero(),
             inv_lhs: N::zero(),
             rhs: N::zero(),
             rhs_wo_bias: N::zero(),
@@ -105,7 +111,7 @@ impl<N: WReal, const LANES: usize> JointVelocityConstraint<N, LANES> {
         let dangvel =
             self.ang_jac2.gdot(mj_lambda2.angular) - self.ang_jac1.gdot(mj_lambda1.angular);
 
-        let rhs = dlinvel + dangvel + self.rhs;
+        let rhs = dlinvel + dangvel + self.rhs - self.cfm_gain * self.impulse;
         let total_impulse = (self.impulse + self.inv_lhs * rhs)
             .simd_clamp(self.impulse_bounds[0], self.impulse_bounds[1]);
         let delta_impulse = total_impulse - self.impulse;
@@ -139,8 +145,11 @@ impl JointVelocityConstraint<Real, 1> {
     ) -> usize {
         let mut len = 0;
         let locked_axes = joint.locked_axes.bits();
-        let motor_axes = joint.motor_axes.bits();
-        let limit_axes = joint.limit_axes.bits();
+        let coupled_lin_axes = joint.coupled_lin_axes_bits();
+        let motor_axes = joint.motor_axes.bits() & !coupled_lin_axes;
+        let limit_axes = joint.limit_axes.bits() & !coupled_lin_axes;
+        let coupled_motor = joint.motor_axes.bits() & coupled_lin_axes & 1 != 0;
+        let coupled_limit = joint.limit_axes.bits() & coupled_lin_axes & 1 != 0;
 
         let builder = JointVelocityConstraintBuilder::new(
             frame1,
@@ -171,6 +180,30 @@ impl JointVelocityConstraint<Real, 1> {
             }
         }
 
+        if coupled_motor {
+            out[len] = builder.motor_linear_coupled(
+                [joint_id],
+                body1,
+                body2,
+                coupled_lin_axes,
+                &joint.motors[0].motor_params(params.dt),
+                WritebackId::Motor(0),
+            );
+            len += 1;
+        }
+        if coupled_limit {
+            out[len] = builder.limit_linear_coupled(
+                params,
+                [joint_id],
+                body1,
+                body2,
+                coupled_lin_axes,
+                [joint.limits[0].min, joint.limits[0].max],
+                WritebackId::Limit(0),
+            );
+            len += 1;
+        }
+
         for i in 0..DIM {
             if motor_axes & (1 << i) != 0 {
                 out[len] = builder.motor_linear(
@@ -229,6 +262,24 @@ impl JointVelocityConstraint<Real, 1> {
             }
         }
 
+        #[cfg(feature = "dim3")]
+        if let Some(swing_twist) = &joint.swing_twist_limits {
+            let constraints = builder.swing_twist_limits(swing_twist);
+            for (i, (ang_jac, angle, limits)) in constraints.into_iter().enumerate() {
+                out[len] = builder.limit_angular_axis(
+                    params,
+                    [joint_id],
+                    body1,
+                    body2,
+                    ang_jac,
+                    angle,
+                    limits,
+                    WritebackId::SwingTwist(i),
+                );
+                len += 1;
+            }
+        }
+
         JointVelocityConstraintBuilder::finalize_constraints(&mut out[..len]);
         len
     }
@@ -249,6 +300,12 @@ impl JointVelocityConstraint<Real, 1> {
             WritebackId::Dof(i) => joint.impulses[i] = self.impulse,
             WritebackId::Limit(i) => joint.data.limits[i].impulse = self.impulse,
             WritebackId::Motor(i) => joint.data.motors[i].impulse = self.impulse,
+            #[cfg(feature = "dim3")]
+            WritebackId::SwingTwist(i) => {
+                if let Some(limits) = &mut joint.data.swing_twist_limits {
+                    limits.impulses[i] = self.impulse;
+                }
+            }
         }
     }
 }
@@ -333,6 +390,12 @@ impl JointVelocityConstraint<SimdReal, SIMD_WIDTH> {
                 WritebackId::Dof(i) => joint.impulses[i] = impulses[ii],
                 WritebackId::Limit(i) => joint.data.limits[i].impulse = impulses[ii],
                 WritebackId::Motor(i) => joint.data.motors[i].impulse = impulses[ii],
+                #[cfg(feature = "dim3")]
+                WritebackId::SwingTwist(i) => {
+                    if let Some(limits) = &mut joint.data.swing_twist_limits {
+                        limits.impulses[i] = impulses[ii];
+                    }
+                }
             }
         }
     }
@@ -350,6 +413,7 @@ pub struct JointVelocityGroundConstraint<N: SimdRealField, const LANES: usize> {
     pub ang_jac2: AngVector<N>,
 
     pub inv_lhs: N,
+    pub cfm_gain: N,
     pub rhs: N,
     pub rhs_wo_bias: N,
 
@@ -367,6 +431,7 @@ impl<N: WReal, const LANES: usize> JointVelocityGroundConstraint<N, LANES> {
             impulse_bounds: [N::zero(), N::zero()],
             lin_jac: Vector::zeros(),
             ang_jac2: na::zero(),
+            cfm_gain: N::zero(),
             inv_lhs: N::zero(),
             rhs: N::zero(),
             rhs_wo_bias: N::zero(),
@@ -379,7 +444,8 @@ impl<N: WReal, const LANES: usize> JointVelocityGroundConstraint<N, LANES> {
         let dlinvel = mj_lambda2.linear;
         let dangvel = mj_lambda2.angular;
 
-        let dvel = self.lin_jac.dot(&dlinvel) + self.ang_jac2.gdot(dangvel) + self.rhs;
+        let dvel = self.lin_jac.dot(&dlinvel) + self.ang_jac2.gdot(dangvel) + self.rhs
+            - self.cfm_gain * self.impulse;
         let total_impulse = (self.impulse + self.inv_lhs * dvel)
             .simd_clamp(self.impulse_bounds[0], self.impulse_bounds[1]);
         let delta_impulse = total_impulse - self.impulse;
@@ -410,8 +476,11 @@ impl JointVelocityGroundConstraint<Real, 1> {
     ) -> usize {
         let mut len = 0;
         let locked_axes = joint.locked_axes.bits() as u8;
-        let motor_axes = joint.motor_axes.bits() as u8;
-        let limit_axes = joint.limit_axes.bits() as u8;
+        let coupled_lin_axes = joint.coupled_lin_axes_bits();
+        let motor_axes = joint.motor_axes.bits() as u8 & !coupled_lin_axes;
+        let limit_axes = joint.limit_axes.bits() as u8 & !coupled_lin_axes;
+        let coupled_motor = joint.motor_axes.bits() & coupled_lin_axes & 1 != 0;
+        let coupled_limit = joint.limit_axes.bits() & coupled_lin_axes & 1 != 0;
 
         let builder = JointVelocityConstraintBuilder::new(
             frame1,
@@ -448,6 +517,30 @@ impl JointVelocityGroundConstraint<Real, 1> {
             }
         }
 
+        if coupled_motor {
+            out[len] = builder.motor_linear_coupled_ground(
+                [joint_id],
+                body1,
+                body2,
+                coupled_lin_axes,
+                &joint.motors[0].motor_params(params.dt),
+                WritebackId::Motor(0),
+            );
+            len += 1;
+        }
+        if coupled_limit {
+            out[len] = builder.limit_linear_coupled_ground(
+                params,
+                [joint_id],
+                body1,
+                body2,
+                coupled_lin_axes,
+                [joint.limits[0].min, joint.limits[0].max],
+                WritebackId::Limit(0),
+            );
+            len += 1;
+        }
+
         for i in 0..DIM {
             if motor_axes & (1 << i) != 0 {
                 out[len] = builder.motor_linear_ground(
@@ -504,6 +597,24 @@ impl JointVelocityGroundConstraint<Real, 1> {
             }
         }
 
+        #[cfg(feature = "dim3")]
+        if let Some(swing_twist) = &joint.swing_twist_limits {
+            let constraints = builder.swing_twist_limits(swing_twist);
+            for (i, (ang_jac, angle, limits)) in constraints.into_iter().enumerate() {
+                out[len] = builder.limit_angular_axis_ground(
+                    params,
+                    [joint_id],
+                    body1,
+                    body2,
+                    ang_jac,
+                    angle,
+                    limits,
+                    WritebackId::SwingTwist(i),
+                );
+                len += 1;
+            }
+        }
+
         JointVelocityConstraintBuilder::finalize_ground_constraints(&mut out[..len]);
         len
     }
@@ -520,6 +631,12 @@ impl JointVelocityGroundConstraint<Real, 1> {
             WritebackId::Dof(i) => joint.impulses[i] = self.impulse,
             WritebackId::Limit(i) => joint.data.limits[i].impulse = self.impulse,
             WritebackId::Motor(i) => joint.data.motors[i].impulse = self.impulse,
+            #[cfg(feature = "dim3")]
+            WritebackId::SwingTwist(i) => {
+                if let Some(limits) = &mut joint.data.swing_twist_limits {
+                    limits.impulses[i] = self.impulse;
+                }
+            }
         }
     }
 }
@@ -602,6 +719,12 @@ impl JointVelocityGroundConstraint<SimdReal, SIMD_WIDTH> {
                 WritebackId::Dof(i) => joint.impulses[i] = impulses[ii],
                 WritebackId::Limit(i) => joint.data.limits[i].impulse = impulses[ii],
                 WritebackId::Motor(i) => joint.data.motors[i].impulse = impulses[ii],
+                #[cfg(feature = "dim3")]
+                WritebackId::SwingTwist(i) => {
+                    if let Some(limits) = &mut joint.data.swing_twist_limits {
+                        limits.impulses[i] = impulses[ii];
+                    }
+                }
             }
         }
     }
@@ -3,10 +3,12 @@ use crate::dynamics::solver::joint_constraint::joint_velocity_constraint::{
 };
 use crate::dynamics::solver::joint_constraint::SolverBody;
 use crate::dynamics::solver::MotorParameters;
+#[cfg(feature = "dim3")]
+use crate::dynamics::JointSwingTwistLimits;
 use crate::dynamics::{IntegrationParameters, JointIndex};
 use crate::math::{Isometry, Matrix, Point, Real, Rotation, Vector, ANG_DIM, DIM};
 use crate::utils::{IndexMut2, WCrossMatrix, WDot, WQuat, WReal};
-use na::SMatrix;
+use na::{SMatrix, SVector};
 use simba::simd::SimdRealField;
 
 #[derive(Debug, Copy, Clone)]
@@ -132,18 +134,140 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             rhs_wo_bias += (dist - motor_params.target_pos) * motor_params.stiffness;
         }
 
-        if motor_params.damping != N::zero() {
+        if motor_params.damping != N::zero() || motor_params.lookahead != N::zero() {
             let dvel = constraint.lin_jac.dot(&(body2.linvel - body1.linvel))
                 + (constraint.ang_jac2.gdot(body2.angvel) - constraint.ang_jac1.gdot(body1.angvel));
             rhs_wo_bias += (dvel - motor_params.target_vel) * motor_params.damping;
+            rhs_wo_bias += dvel * motor_params.lookahead * motor_params.stiffness;
         }
 
         constraint.impulse_bounds = [-motor_params.max_impulse, motor_params.max_impulse];
+
+        constraint.cfm_gain = motor_params.cfm_gain;
         constraint.rhs = rhs_wo_bias;
         constraint.rhs_wo_bias = rhs_wo_bias;
         constraint
     }
 
+    /// The distance between the anchors projected on the coupled linear axes, and the
+    /// linear and angular jacobians of this distance.
+    pub fn coupled_linear_jacobians(
+        &self,
+        coupled_axes: u8,
+    ) -> (N, Vector<N>, SVector<N, ANG_DIM>, SVector<N, ANG_DIM>) {
+        let mut coeffs = Vector::zeros();
+
+        for i in 0..DIM {
+            if coupled_axes & (1 << i) != 0 {
+                coeffs[i] = self.lin_err.dot(&self.basis.column(i));
+            }
+        }
+
+        let dist = coeffs.norm();
+        let inv_dist = crate::utils::simd_inv(dist);
+        coeffs *= inv_dist;
+
+        let lin_jac = self.basis * coeffs;
+        let ang_jac1 = self.cmat1_basis * coeffs;
+        let ang_jac2 = self.cmat2_basis * coeffs;
+
+        (dist, lin_jac, ang_jac1, ang_jac2)
+    }
+
+    pub fn limit_linear_coupled<const LANES: usize>(
+        &self,
+        params: &IntegrationParameters,
+        joint_id: [JointIndex; LANES],
+        body1: &SolverBody<N, LANES>,
+        body2: &SolverBody<N, LANES>,
+        coupled_axes: u8,
+        limits: [N; 2],
+        writeback_id: WritebackId,
+    ) -> JointVelocityConstraint<N, LANES> {
+        let zero = N::zero();
+        let (dist, lin_jac, ang_jac1, ang_jac2) = self.coupled_linear_jacobians(coupled_axes);
+        #[cfg(feature = "dim2")]
+        let (ang_jac1, ang_jac2) = (ang_jac1[0], ang_jac2[0]);
+
+        let min_enabled = dist.simd_lt(limits[0]);
+        let max_enabled = limits[1].simd_lt(dist);
+
+        let impulse_bounds = [
+            N::splat(-Real::INFINITY).select(min_enabled, zero),
+            N::splat(Real::INFINITY).select(max_enabled, zero),
+        ];
+
+        let dvel = lin_jac.dot(&(body2.linvel - body1.linvel))
+            + (ang_jac2.gdot(body2.angvel) - ang_jac1.gdot(body1.angvel));
+        let rhs_wo_bias = dvel * N::splat(params.velocity_solve_fraction);
+
+        let erp_inv_dt = params.erp_inv_dt();
+        let rhs_bias = ((dist - limits[1]).simd_max(zero) - (limits[0] - dist).simd_max(zero))
+            * N::splat(erp_inv_dt);
+
+        JointVelocityConstraint {
+            joint_id,
+            mj_lambda1: body1.mj_lambda,
+            mj_lambda2: body2.mj_lambda,
+            im1: body1.im,
+            im2: body2.im,
+            impulse: zero,
+            impulse_bounds,
+            lin_jac,
+            ang_jac1: body1.sqrt_ii * ang_jac1,
+            ang_jac2: body2.sqrt_ii * ang_jac2,
+            cfm_gain: zero,
+            inv_lhs: zero, // Will be set during ortogonalization.
+            rhs: rhs_wo_bias + rhs_bias,
+            rhs_wo_bias,
+            writeback_id,
+        }
+    }
+
+    pub fn motor_linear_coupled<const LANES: usize>(
+        &self,
+        joint_id: [JointIndex; LANES],
+        body1: &SolverBody<N, LANES>,
+        body2: &SolverBody<N, LANES>,
+        coupled_axes: u8,
+        motor_params: &MotorParameters<N>,
+        writeback_id: WritebackId,
+    ) -> JointVelocityConstraint<N, LANES> {
+        let (dist, lin_jac, ang_jac1, ang_jac2) = self.coupled_linear_jacobians(coupled_axes);
+        #[cfg(feature = "dim2")]
+        let (ang_jac1, ang_jac2) = (ang_jac1[0], ang_jac2[0]);
+
+        let mut rhs_wo_bias = N::zero();
+        if motor_params.stiffness != N::zero() {
+            rhs_wo_bias += (dist - motor_params.target_pos) * motor_params.stiffness;
+        }
+
+        if motor_params.damping != N::zero() || motor_params.lookahead != N::zero() {
+            let dvel = lin_jac.dot(&(body2.linvel - body1.linvel))
+                + (ang_jac2.gdot(body2.angvel) - ang_jac1.gdot(body1.angvel));
+            rhs_wo_bias += (dvel - motor_params.target_vel) * motor_params.damping;
+            rhs_wo_bias += dvel * motor_params.lookahead * motor_params.stiffness;
+        }
+
+        JointVelocityConstraint {
+            joint_id,
+            mj_lambda1: body1.mj_lambda,
+            mj_lambda2: body2.mj_lambda,
+            im1: body1.im,
+            im2: body2.im,
+            impulse: N::zero(),
+            impulse_bounds: [-motor_params.max_impulse, motor_params.max_impulse],
+            lin_jac,
+            ang_jac1: body1.sqrt_ii * ang_jac1,
+            ang_jac2: body2.sqrt_ii * ang_jac2,
+            cfm_gain: motor_params.cfm_gain,
+            inv_lhs: N::zero(), // Will be set during ortogonalization.
+            rhs: rhs_wo_bias,
+            rhs_wo_bias,
+            writeback_id,
+        }
+    }
+
     pub fn lock_linear<const LANES: usize>(
         &self,
         params: &IntegrationParameters,
@@ -184,6 +308,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             lin_jac,
             ang_jac1,
             ang_jac2,
+            cfm_gain: N::zero(),
             inv_lhs: N::zero(), // Will be set during ortogonalization.
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
@@ -242,6 +367,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             lin_jac: na::zero(),
             ang_jac1,
             ang_jac2,
+            cfm_gain: zero,
             inv_lhs: N::zero(), // Will be set during ortogonalization.
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
@@ -274,10 +400,11 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             rhs_wo_bias += (s_ang_dist - s_target_ang) * motor_params.stiffness;
         }
 
-        if motor_params.damping != N::zero() {
+        if motor_params.damping != N::zero() || motor_params.lookahead != N::zero() {
             let dvel = ang_jac.gdot(body2.angvel) - ang_jac.gdot(body1.angvel);
             rhs_wo_bias +=
                 (dvel - motor_params.target_vel/* * ang_jac.norm() */) * motor_params.damping;
+            rhs_wo_bias += dvel * motor_params.lookahead * motor_params.stiffness;
         }
 
         let ang_jac1 = body1.sqrt_ii * ang_jac;
@@ -294,6 +421,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             lin_jac: na::zero(),
             ang_jac1,
             ang_jac2,
+            cfm_gain: motor_params.cfm_gain,
             inv_lhs: N::zero(), // Will be set during ortogonalization.
             rhs: rhs_wo_bias,
             rhs_wo_bias,
@@ -338,6 +466,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             lin_jac: na::zero(),
             ang_jac1,
             ang_jac2,
+            cfm_gain: N::zero(),
             inv_lhs: N::zero(), // Will be set during ortogonalization.
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
@@ -350,6 +479,11 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
         constraints: &mut [JointVelocityConstraint<N, LANES>],
     ) {
         let len = constraints.len();
+
+        if len == 0 {
+            return;
+        }
+
         let imsum = constraints[0].im1 + constraints[0].im2;
 
         // Use the modified Gram-Schmidt orthogonalization.
@@ -359,9 +493,12 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
                 + c_j.ang_jac1.gdot(c_j.ang_jac1)
                 + c_j.ang_jac2.gdot(c_j.ang_jac2);
             let inv_dot_jj = crate::utils::simd_inv(dot_jj);
-            c_j.inv_lhs = inv_dot_jj; // Don’t forget to update the inv_lhs.
+            // Don’t forget to update the inv_lhs.
+            c_j.inv_lhs = crate::utils::simd_inv(dot_jj + c_j.cfm_gain);
 
-            if c_j.impulse_bounds != [-N::splat(Real::MAX), N::splat(Real::MAX)] {
+            if c_j.impulse_bounds != [-N::splat(Real::MAX), N::splat(Real::MAX)]
+                || c_j.cfm_gain != N::zero()
+            {
                 // Don't remove constraints with limited forces from the others
                 // because they may not deliver the necessary forces to fulfill
                 // the removed parts of other constraints.
@@ -429,6 +566,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             impulse_bounds,
             lin_jac,
             ang_jac2,
+            cfm_gain: zero,
             inv_lhs: zero, // Will be set during ortogonalization.
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
@@ -458,10 +596,11 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             rhs_wo_bias += (dist - motor_params.target_pos) * motor_params.stiffness;
         }
 
-        if motor_params.damping != N::zero() {
+        if motor_params.damping != N::zero() || motor_params.lookahead != N::zero() {
             let dvel = lin_jac.dot(&(body2.linvel - body1.linvel))
                 + (ang_jac2.gdot(body2.angvel) - ang_jac1.gdot(body1.angvel));
             rhs_wo_bias += (dvel - motor_params.target_vel) * motor_params.damping;
+            rhs_wo_bias += dvel * motor_params.lookahead * motor_params.stiffness;
         }
 
         ang_jac2 = body2.sqrt_ii * ang_jac2;
@@ -474,6 +613,95 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             impulse_bounds: [-motor_params.max_impulse, motor_params.max_impulse],
             lin_jac,
             ang_jac2,
+            cfm_gain: motor_params.cfm_gain,
+            inv_lhs: N::zero(), // Will be set during ortogonalization.
+            rhs: rhs_wo_bias,
+            rhs_wo_bias,
+            writeback_id,
+        }
+    }
+
+    pub fn limit_linear_coupled_ground<const LANES: usize>(
+        &self,
+        params: &IntegrationParameters,
+        joint_id: [JointIndex; LANES],
+        body1: &SolverBody<N, LANES>,
+        body2: &SolverBody<N, LANES>,
+        coupled_axes: u8,
+        limits: [N; 2],
+        writeback_id: WritebackId,
+    ) -> JointVelocityGroundConstraint<N, LANES> {
+        let zero = N::zero();
+        let (dist, lin_jac, ang_jac1, ang_jac2) = self.coupled_linear_jacobians(coupled_axes);
+        #[cfg(feature = "dim2")]
+        let (ang_jac1, ang_jac2) = (ang_jac1[0], ang_jac2[0]);
+
+        let min_enabled = dist.simd_lt(limits[0]);
+        let max_enabled = limits[1].simd_lt(dist);
+
+        let impulse_bounds = [
+            N::splat(-Real::INFINITY).select(min_enabled, zero),
+            N::splat(Real::INFINITY).select(max_enabled, zero),
+        ];
+
+        let dvel = lin_jac.dot(&(body2.linvel - body1.linvel))
+            + (ang_jac2.gdot(body2.angvel) - ang_jac1.gdot(body1.angvel));
+        let rhs_wo_bias = dvel * N::splat(params.velocity_solve_fraction);
+
+        let erp_inv_dt = params.erp_inv_dt();
+        let rhs_bias = ((dist - limits[1]).simd_max(zero) - (limits[0] - dist).simd_max(zero))
+            * N::splat(erp_inv_dt);
+
+        JointVelocityGroundConstraint {
+            joint_id,
+            mj_lambda2: body2.mj_lambda,
+            im2: body2.im,
+            impulse: zero,
+            impulse_bounds,
+            lin_jac,
+            ang_jac2: body2.sqrt_ii * ang_jac2,
+            cfm_gain: zero,
+            inv_lhs: zero, // Will be set during ortogonalization.
+            rhs: rhs_wo_bias + rhs_bias,
+            rhs_wo_bias,
+            writeback_id,
+        }
+    }
+
+    pub fn motor_linear_coupled_ground<const LANES: usize>(
+        &self,
+        joint_id: [JointIndex; LANES],
+        body1: &SolverBody<N, LANES>,
+        body2: &SolverBody<N, LANES>,
+        coupled_axes: u8,
+        motor_params: &MotorParameters<N>,
+        writeback_id: WritebackId,
+    ) -> JointVelocityGroundConstraint<N, LANES> {
+        let (dist, lin_jac, ang_jac1, ang_jac2) = self.coupled_linear_jacobians(coupled_axes);
+        #[cfg(feature = "dim2")]
+        let (ang_jac1, ang_jac2) = (ang_jac1[0], ang_jac2[0]);
+
+        let mut rhs_wo_bias = N::zero();
+        if motor_params.stiffness != N::zero() {
+            rhs_wo_bias += (dist - motor_params.target_pos) * motor_params.stiffness;
+        }
+
+        if motor_params.damping != N::zero() || motor_params.lookahead != N::zero() {
+            let dvel = lin_jac.dot(&(body2.linvel - body1.linvel))
+                + (ang_jac2.gdot(body2.angvel) - ang_jac1.gdot(body1.angvel));
+            rhs_wo_bias += (dvel - motor_params.target_vel) * motor_params.damping;
+            rhs_wo_bias += dvel * motor_params.lookahead * motor_params.stiffness;
+        }
+
+        JointVelocityGroundConstraint {
+            joint_id,
+            mj_lambda2: body2.mj_lambda,
+            im2: body2.im,
+            impulse: N::zero(),
+            impulse_bounds: [-motor_params.max_impulse, motor_params.max_impulse],
+            lin_jac,
+            ang_jac2: body2.sqrt_ii * ang_jac2,
+            cfm_gain: motor_params.cfm_gain,
             inv_lhs: N::zero(), // Will be set during ortogonalization.
             rhs: rhs_wo_bias,
             rhs_wo_bias,
@@ -514,6 +742,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             impulse_bounds: [-N::splat(Real::MAX), N::splat(Real::MAX)],
             lin_jac,
             ang_jac2,
+            cfm_gain: N::zero(),
             inv_lhs: N::zero(), // Will be set during ortogonalization.
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
@@ -546,10 +775,11 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             rhs_wo_bias += (s_ang_dist - s_target_ang) * motor_params.stiffness;
         }
 
-        if motor_params.damping != N::zero() {
+        if motor_params.damping != N::zero() || motor_params.lookahead != N::zero() {
             let dvel = ang_jac.gdot(body2.angvel) - ang_jac.gdot(body1.angvel);
             rhs_wo_bias +=
                 (dvel - motor_params.target_vel/* * ang_jac.norm() */) * motor_params.damping;
+            rhs_wo_bias += dvel * motor_params.lookahead * motor_params.stiffness;
         }
 
         let ang_jac2 = body2.sqrt_ii * ang_jac;
@@ -562,6 +792,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             impulse_bounds: [-motor_params.max_impulse, motor_params.max_impulse],
             lin_jac: na::zero(),
             ang_jac2,
+            cfm_gain: motor_params.cfm_gain,
             inv_lhs: N::zero(), // Will be set during ortogonalization.
             rhs: rhs_wo_bias,
             rhs_wo_bias,
@@ -616,6 +847,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             impulse_bounds,
             lin_jac: na::zero(),
             ang_jac2,
+            cfm_gain: zero,
             inv_lhs: zero, // Will be set during ortogonalization.
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
@@ -655,6 +887,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             impulse_bounds: [-N::splat(Real::MAX), N::splat(Real::MAX)],
             lin_jac: na::zero(),
             ang_jac2,
+            cfm_gain: N::zero(),
             inv_lhs: N::zero(), // Will be set during ortogonalization.
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
@@ -667,6 +900,11 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
         constraints: &mut [JointVelocityGroundConstraint<N, LANES>],
     ) {
         let len = constraints.len();
+
+        if len == 0 {
+            return;
+        }
+
         let imsum = constraints[0].im2;
 
         // Use the modified Gram-Schmidt orthogonalization.
@@ -675,9 +913,12 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             let dot_jj = c_j.lin_jac.dot(&imsum.component_mul(&c_j.lin_jac))
                 + c_j.ang_jac2.gdot(c_j.ang_jac2);
             let inv_dot_jj = crate::utils::simd_inv(dot_jj);
-            c_j.inv_lhs = inv_dot_jj; // Don’t forget to update the inv_lhs.
+            // Don’t forget to update the inv_lhs.
+            c_j.inv_lhs = crate::utils::simd_inv(dot_jj + c_j.cfm_gain);
 
-            if c_j.impulse_bounds != [-N::splat(Real::MAX), N::splat(Real::MAX)] {
+            if c_j.impulse_bounds != [-N::splat(Real::MAX), N::splat(Real::MAX)]
+                || c_j.cfm_gain != N::zero()
+            {
                 // Don't remove constraints with limited forces from the others
                 // because they may not deliver the necessary forces to fulfill
                 // the removed parts of other constraints.
@@ -698,3 +939,104 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
         }
     }
 }
+
+#[cfg(feature = "dim3")]
+impl JointVelocityConstraintBuilder<Real> {
+    /// The swing and twist limit constraints of a joint, with their rotation axes in world-space.
+    pub fn swing_twist_limits(
+        &self,
+        limits: &JointSwingTwistLimits,
+    ) -> [(Vector<Real>, Real, [Real; 2]); 2] {
+        limits
+            .constraints(&self.ang_err)
+            .map(|(axis, angle, limits)| (self.basis * axis, angle, limits))
+    }
+
+    pub fn limit_angular_axis(
+        &self,
+        params: &IntegrationParameters,
+        joint_id: [JointIndex; 1],
+        body1: &SolverBody<Real, 1>,
+        body2: &SolverBody<Real, 1>,
+        ang_jac: Vector<Real>,
+        angle: Real,
+        limits: [Real; 2],
+        writeback_id: WritebackId,
+    ) -> JointVelocityConstraint<Real, 1> {
+        let min_enabled = angle < limits[0];
+        let max_enabled = limits[1] < angle;
+        let impulse_bounds = [
+            min_enabled as u32 as Real * -Real::MAX,
+            max_enabled as u32 as Real * Real::MAX,
+        ];
+
+        let dvel = ang_jac.dot(&body2.angvel) - ang_jac.dot(&body1.angvel);
+        let rhs_wo_bias = dvel * params.velocity_solve_fraction;
+
+        let erp_inv_dt = params.erp_inv_dt();
+        let rhs_bias = ((angle - limits[1]).max(0.0) - (limits[0] - angle).max(0.0)) * erp_inv_dt;
+
+        let ang_jac1 = body1.sqrt_ii * ang_jac;
+        let ang_jac2 = body2.sqrt_ii * ang_jac;
+
+        JointVelocityConstraint {
+            joint_id,
+            mj_lambda1: body1.mj_lambda,
+            mj_lambda2: body2.mj_lambda,
+            im1: body1.im,
+            im2: body2.im,
+            impulse: 0.0,
+            impulse_bounds,
+            lin_jac: na::zero(),
+            ang_jac1,
+            ang_jac2,
+            cfm_gain: 0.0,
+            inv_lhs: 0.0, // Will be set during ortogonalization.
+            rhs: rhs_wo_bias + rhs_bias,
+            rhs_wo_bias,
+            writeback_id,
+        }
+    }
+
+    pub fn limit_angular_axis_ground(
+        &self,
+        params: &IntegrationParameters,
+        joint_id: [JointIndex; 1],
+        body1: &SolverBody<Real, 1>,
+        body2: &SolverBody<Real, 1>,
+        ang_jac: Vector<Real>,
+        angle: Real,
+        limits: [Real; 2],
+        writeback_id: WritebackId,
+    ) -> JointVelocityGroundConstraint<Real, 1> {
+        let min_enabled = angle < limits[0];
+        let max_enabled = limits[1] < angle;
+        let impulse_bounds = [
+            min_enabled as u32 as Real * -Real::MAX,
+            max_enabled as u32 as Real * Real::MAX,
+        ];
+
+        let dvel = ang_jac.dot(&body2.angvel) - ang_jac.dot(&body1.angvel);
+        let rhs_wo_bias = dvel * params.velocity_solve_fraction;
+
+        let erp_inv_dt = params.erp_inv_dt();
+        let rhs_bias = ((angle - limits[1]).max(0.0) - (limits[0] - angle).max(0.0)) * erp_inv_dt;
+
+        let ang_jac2 = body2.sqrt_ii * ang_jac;
+
+        JointVelocityGroundConstraint {
+            joint_id,
+            mj_lambda2: body2.mj_lambda,
+            im2: body2.im,
+            impulse: 0.0,
+            impulse_bounds,
+            lin_jac: na::zero(),
+            ang_jac2,
+            cfm_gain: 0.0,
+            inv_lhs: 0.0, // Will be set during ortogonalization.
+            rhs: rhs_wo_bias + rhs_bias,
+            rhs_wo_bias,
+            writeback_id,
+        }
+    }
+}
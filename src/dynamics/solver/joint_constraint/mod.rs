@@ -1,12 +1,14 @@
 pub use joint_velocity_constraint::{MotorParameters, SolverBody, WritebackId};
 
 pub use joint_constraint::AnyJointVelocityConstraint;
+pub use joint_coupling_velocity_constraint::JointCouplingVelocityConstraint;
 pub use joint_generic_velocity_constraint::{
     JointGenericVelocityConstraint, JointGenericVelocityGroundConstraint,
 };
 pub use joint_velocity_constraint_builder::JointVelocityConstraintBuilder;
 
 mod joint_constraint;
+mod joint_coupling_velocity_constraint;
 mod joint_generic_velocity_constraint;
 mod joint_generic_velocity_constraint_builder;
 mod joint_velocity_constraint;
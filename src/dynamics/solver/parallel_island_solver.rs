@@ -14,7 +14,6 @@ use crate::dynamics::{
 };
 use crate::geometry::{ContactManifold, ContactManifoldIndex};
 use crate::math::{Isometry, Real};
-use crate::utils::WAngularInertia;
 
 use super::{DeltaVel, ParallelInteractionGroups, ParallelVelocitySolver};
 
@@ -245,8 +244,11 @@ impl ParallelIslandSolver {
                             let dvel = &mut velocity_solver.mj_lambdas[rb_ids.active_set_offset];
 
                             // NOTE: `dvel.angular` is actually storing angular velocity delta multiplied
-                            //       by the square root of the inertia tensor:
-                            dvel.angular += rb_mass_props.effective_world_inv_inertia_sqrt * rb_forces.torque * params.dt;
+                            //       by the square root of the inertia tensor used by the constraints. If that
+                            //       inertia is infinite, the torque is applied when updating the velocities.
+                            if rb_mass_props.inv_inertia_scale != 0.0 {
+                                dvel.angular += rb_mass_props.effective_world_inv_inertia_sqrt * rb_forces.torque * params.dt / rb_mass_props.inv_inertia_scale.sqrt();
+                            }
                             dvel.linear += rb_forces.force.component_mul(&rb_mass_props.effective_inv_mass) * params.dt;
                         }
                     }
@@ -284,12 +286,13 @@ impl ParallelIslandSolver {
                 concurrent_loop! {
                     let batch_size = thread.batch_size;
                     for handle in active_bodies[thread.body_integration_index, thread.num_integrated_bodies] {
-                        let (rb_ids, rb_pos, rb_vels, rb_damping, rb_mprops): (
+                        let (rb_ids, rb_pos, rb_vels, rb_damping, rb_mprops, rb_forces): (
                             &RigidBodyIds,
                             &RigidBodyPosition,
                             &RigidBodyVelocity,
                             &RigidBodyDamping,
                             &RigidBodyMassProps,
+                            &RigidBodyForces,
                         ) = bodies.index_bundle(handle.0);
 
                         let mut new_rb_pos = *rb_pos;
@@ -297,7 +300,7 @@ impl ParallelIslandSolver {
 
                         let dvels = velocity_solver.mj_lambdas[rb_ids.active_set_offset];
                         new_rb_vels.linvel += dvels.linear;
-                        new_rb_vels.angvel += rb_mprops.effective_world_inv_inertia_sqrt.transform_vector(dvels.angular);
+                        new_rb_vels.angvel += rb_mprops.solver_angvel_change(dvels.angular, rb_forces.torque, params.dt);
 
                         let new_rb_vels = new_rb_vels.apply_damping(params.dt, rb_damping);
                         new_rb_pos.next_position =
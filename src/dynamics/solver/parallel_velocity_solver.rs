@@ -138,7 +138,8 @@ impl ParallelVelocitySolver {
         crate::concurrent_loop! {
              let batch_size = thread.batch_size;
              for constraint in joint_constraints[thread.joint_writeback_index] {
-                 constraint.writeback_impulses(joints_all);
+                 // NOTE: the islands with joint couplings are solved by the sequential solver.
+                 constraint.writeback_impulses(joints_all, &mut []);
              }
         }
         crate::concurrent_loop! {
@@ -1,5 +1,6 @@
 use super::{
-    AnyJointVelocityConstraint, InteractionGroups, VelocityConstraint, VelocityGroundConstraint,
+    AnyJointVelocityConstraint, InteractionGroups, JointCouplingVelocityConstraint,
+    VelocityConstraint, VelocityGroundConstraint,
 };
 #[cfg(feature = "simd-is-enabled")]
 use super::{WVelocityConstraint, WVelocityGroundConstraint};
@@ -9,8 +10,9 @@ use crate::dynamics::solver::generic_velocity_ground_constraint::GenericVelocity
 use crate::dynamics::solver::AnyGenericVelocityConstraint;
 use crate::dynamics::solver::GenericVelocityConstraint;
 use crate::dynamics::{
-    solver::AnyVelocityConstraint, IntegrationParameters, JointGraphEdge, JointIndex,
-    MultibodyJointSet, RigidBodyIds, RigidBodyMassProps, RigidBodyPosition, RigidBodyType,
+    solver::AnyVelocityConstraint, IntegrationParameters, JointCoupling, JointGraphEdge,
+    JointIndex, MultibodyJointSet, RigidBodyIds, RigidBodyMassProps, RigidBodyPosition,
+    RigidBodyType,
 };
 use crate::dynamics::{IslandManager, RigidBodyVelocity};
 use crate::geometry::{ContactManifold, ContactManifoldIndex};
@@ -350,6 +352,8 @@ impl SolverConstraints<AnyJointVelocityConstraint, ()> {
         multibody_joints: &MultibodyJointSet,
         impulse_joints: &[JointGraphEdge],
         joint_constraint_indices: &[JointIndex],
+        couplings: &[JointCoupling],
+        coupling_constraint_indices: &[usize],
     ) where
         Bodies: ComponentSet<RigidBodyType>
             + ComponentSet<RigidBodyIds>
@@ -446,6 +450,20 @@ impl SolverConstraints<AnyJointVelocityConstraint, ()> {
             multibody_joints,
             &mut j_id,
         );
+
+        for coupling_i in coupling_constraint_indices {
+            self.velocity_constraints.extend(
+                JointCouplingVelocityConstraint::from_coupling(
+                    params,
+                    *coupling_i,
+                    &couplings[*coupling_i],
+                    impulse_joints,
+                    bodies,
+                    multibody_joints,
+                )
+                .map(AnyJointVelocityConstraint::JointCouplingConstraint),
+            );
+        }
     }
 
     fn compute_articulation_constraints(
@@ -541,6 +559,7 @@ impl SolverConstraints<AnyJointVelocityConstraint, ()> {
         Bodies: ComponentSet<RigidBodyPosition>
             + ComponentSet<RigidBodyVelocity>
             + ComponentSet<RigidBodyMassProps>
+            + ComponentSet<RigidBodyType>
             + ComponentSet<RigidBodyIds>,
     {
         for joint_i in &self.interaction_groups.nongrouped_interactions {
@@ -569,6 +588,7 @@ impl SolverConstraints<AnyJointVelocityConstraint, ()> {
         Bodies: ComponentSet<RigidBodyPosition>
             + ComponentSet<RigidBodyVelocity>
             + ComponentSet<RigidBodyMassProps>
+            + ComponentSet<RigidBodyType>
             + ComponentSet<RigidBodyIds>,
     {
         for joint_i in &self.generic_not_ground_interactions {
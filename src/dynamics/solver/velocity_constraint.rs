@@ -161,8 +161,8 @@ impl VelocityConstraint {
                 #[cfg(feature = "dim3")]
                 tangent1: tangents1[0],
                 elements: [VelocityConstraintElement::zero(); MAX_MANIFOLD_POINTS],
-                im1: mprops1.effective_inv_mass,
-                im2: mprops2.effective_inv_mass,
+                im1: mprops1.constraint_inv_mass(),
+                im2: mprops2.constraint_inv_mass(),
                 limit: 0.0,
                 mj_lambda1,
                 mj_lambda2,
@@ -208,8 +208,8 @@ impl VelocityConstraint {
                 {
                     constraint.tangent1 = tangents1[0];
                 }
-                constraint.im1 = mprops1.effective_inv_mass;
-                constraint.im2 = mprops2.effective_inv_mass;
+                constraint.im1 = mprops1.constraint_inv_mass();
+                constraint.im2 = mprops2.constraint_inv_mass();
                 constraint.limit = 0.0;
                 constraint.mj_lambda1 = mj_lambda1;
                 constraint.mj_lambda2 = mj_lambda2;
@@ -232,13 +232,13 @@ impl VelocityConstraint {
                 // Normal part.
                 {
                     let gcross1 = mprops1
-                        .effective_world_inv_inertia_sqrt
+                        .constraint_world_inv_inertia_sqrt()
                         .transform_vector(dp1.gcross(force_dir1));
                     let gcross2 = mprops2
-                        .effective_world_inv_inertia_sqrt
+                        .constraint_world_inv_inertia_sqrt()
                         .transform_vector(dp2.gcross(-force_dir1));
 
-                    let imsum = mprops1.effective_inv_mass + mprops2.effective_inv_mass;
+                    let imsum = mprops1.constraint_inv_mass() + mprops2.constraint_inv_mass();
                     let projected_mass = 1.0
                         / (force_dir1.dot(&imsum.component_mul(&force_dir1))
                             + gcross1.gdot(gcross1)
@@ -257,7 +257,7 @@ impl VelocityConstraint {
                     constraint.elements[k].normal_part = VelocityConstraintNormalPart {
                         gcross1,
                         gcross2,
-                        rhs: rhs_wo_bias + rhs_bias,
+                        rhs: rhs_wo_bias + rhs_bias.max(-params.contact_max_corrective_velocity()),
                         rhs_wo_bias,
                         impulse: na::zero(),
                         r: projected_mass,
@@ -270,12 +270,12 @@ impl VelocityConstraint {
 
                     for j in 0..DIM - 1 {
                         let gcross1 = mprops1
-                            .effective_world_inv_inertia_sqrt
+                            .constraint_world_inv_inertia_sqrt()
                             .transform_vector(dp1.gcross(tangents1[j]));
                         let gcross2 = mprops2
-                            .effective_world_inv_inertia_sqrt
+                            .constraint_world_inv_inertia_sqrt()
                             .transform_vector(dp2.gcross(-tangents1[j]));
-                        let imsum = mprops1.effective_inv_mass + mprops2.effective_inv_mass;
+                        let imsum = mprops1.constraint_inv_mass() + mprops2.constraint_inv_mass();
                         let r = tangents1[j].dot(&imsum.component_mul(&tangents1[j]))
                             + gcross1.gdot(gcross1)
                             + gcross2.gdot(gcross2);
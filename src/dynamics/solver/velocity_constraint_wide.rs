@@ -50,6 +50,7 @@ impl WVelocityConstraint {
         let velocity_solve_fraction = SimdReal::splat(params.velocity_solve_fraction);
         let allowed_lin_err = SimdReal::splat(params.allowed_linear_error);
         let erp_inv_dt = SimdReal::splat(params.erp_inv_dt());
+        let max_corrective_velocity = SimdReal::splat(params.contact_max_corrective_velocity());
 
         let handles1 = gather![|ii| manifolds[ii].data.rigid_body1.unwrap()];
         let handles2 = gather![|ii| manifolds[ii].data.rigid_body2.unwrap()];
@@ -62,17 +63,17 @@ impl WVelocityConstraint {
         let mprops2: [&RigidBodyMassProps; SIMD_WIDTH] = gather![|ii| bodies.index(handles2[ii].0)];
 
         let world_com1 = Point::from(gather![|ii| mprops1[ii].world_com]);
-        let im1 = Vector::from(gather![|ii| mprops1[ii].effective_inv_mass]);
+        let im1 = Vector::from(gather![|ii| mprops1[ii].constraint_inv_mass()]);
         let ii1: AngularInertia<SimdReal> =
-            AngularInertia::from(gather![|ii| mprops1[ii].effective_world_inv_inertia_sqrt]);
+            AngularInertia::from(gather![|ii| mprops1[ii].constraint_world_inv_inertia_sqrt()]);
 
         let linvel1 = Vector::from(gather![|ii| vels1[ii].linvel]);
         let angvel1 = AngVector::<SimdReal>::from(gather![|ii| vels1[ii].angvel]);
 
         let world_com2 = Point::from(gather![|ii| mprops2[ii].world_com]);
-        let im2 = Vector::from(gather![|ii| mprops2[ii].effective_inv_mass]);
+        let im2 = Vector::from(gather![|ii| mprops2[ii].constraint_inv_mass()]);
         let ii2: AngularInertia<SimdReal> =
-            AngularInertia::from(gather![|ii| mprops2[ii].effective_world_inv_inertia_sqrt]);
+            AngularInertia::from(gather![|ii| mprops2[ii].constraint_world_inv_inertia_sqrt()]);
 
         let linvel2 = Vector::from(gather![|ii| vels2[ii].linvel]);
         let angvel2 = AngVector::<SimdReal>::from(gather![|ii| vels2[ii].angvel]);
@@ -151,7 +152,7 @@ impl WVelocityConstraint {
                     constraint.elements[k].normal_part = VelocityConstraintNormalPart {
                         gcross1,
                         gcross2,
-                        rhs: rhs_wo_bias + rhs_bias,
+                        rhs: rhs_wo_bias + rhs_bias.simd_max(-max_corrective_velocity),
                         rhs_wo_bias,
                         impulse: SimdReal::splat(0.0),
                         r: projected_mass,
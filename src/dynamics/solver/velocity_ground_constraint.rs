@@ -84,7 +84,7 @@ impl VelocityGroundConstraint {
                 #[cfg(feature = "dim3")]
                 tangent1: tangents1[0],
                 elements: [VelocityGroundConstraintElement::zero(); MAX_MANIFOLD_POINTS],
-                im2: mprops2.effective_inv_mass,
+                im2: mprops2.constraint_inv_mass(),
                 limit: 0.0,
                 mj_lambda2,
                 manifold_id,
@@ -129,7 +129,7 @@ impl VelocityGroundConstraint {
                 {
                     constraint.tangent1 = tangents1[0];
                 }
-                constraint.im2 = mprops2.effective_inv_mass;
+                constraint.im2 = mprops2.constraint_inv_mass();
                 constraint.limit = 0.0;
                 constraint.mj_lambda2 = mj_lambda2;
                 constraint.manifold_id = manifold_id;
@@ -150,11 +150,12 @@ impl VelocityGroundConstraint {
                 // Normal part.
                 {
                     let gcross2 = mprops2
-                        .effective_world_inv_inertia_sqrt
+                        .constraint_world_inv_inertia_sqrt()
                         .transform_vector(dp2.gcross(-force_dir1));
 
                     let projected_mass = 1.0
-                        / (force_dir1.dot(&mprops2.effective_inv_mass.component_mul(&force_dir1))
+                        / (force_dir1
+                            .dot(&mprops2.constraint_inv_mass().component_mul(&force_dir1))
                             + gcross2.gdot(gcross2));
 
                     let is_bouncy = manifold_point.is_bouncy() as u32 as Real;
@@ -170,7 +171,7 @@ impl VelocityGroundConstraint {
 
                     constraint.elements[k].normal_part = VelocityGroundConstraintNormalPart {
                         gcross2,
-                        rhs: rhs_wo_bias + rhs_bias,
+                        rhs: rhs_wo_bias + rhs_bias.max(-params.contact_max_corrective_velocity()),
                         rhs_wo_bias,
                         impulse: na::zero(),
                         r: projected_mass,
@@ -183,10 +184,10 @@ impl VelocityGroundConstraint {
 
                     for j in 0..DIM - 1 {
                         let gcross2 = mprops2
-                            .effective_world_inv_inertia_sqrt
+                            .constraint_world_inv_inertia_sqrt()
                             .transform_vector(dp2.gcross(-tangents1[j]));
                         let r = tangents1[j]
-                            .dot(&mprops2.effective_inv_mass.component_mul(&tangents1[j]))
+                            .dot(&mprops2.constraint_inv_mass().component_mul(&tangents1[j]))
                             + gcross2.gdot(gcross2);
                         let rhs = (vel1 - vel2
                             + flipped_multiplier * manifold_point.tangent_velocity)
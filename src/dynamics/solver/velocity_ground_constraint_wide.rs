@@ -45,6 +45,7 @@ impl WVelocityGroundConstraint {
         let velocity_solve_fraction = SimdReal::splat(params.velocity_solve_fraction);
         let allowed_lin_err = SimdReal::splat(params.allowed_linear_error);
         let erp_inv_dt = SimdReal::splat(params.erp_inv_dt());
+        let max_corrective_velocity = SimdReal::splat(params.contact_max_corrective_velocity());
 
         let mut handles1 = gather![|ii| manifolds[ii].data.rigid_body1];
         let mut handles2 = gather![|ii| manifolds[ii].data.rigid_body2];
@@ -76,9 +77,9 @@ impl WVelocityGroundConstraint {
 
         let flipped_sign = SimdReal::from(flipped);
 
-        let im2 = Vector::from(gather![|ii| mprops2[ii].effective_inv_mass]);
+        let im2 = Vector::from(gather![|ii| mprops2[ii].constraint_inv_mass()]);
         let ii2: AngularInertia<SimdReal> =
-            AngularInertia::from(gather![|ii| mprops2[ii].effective_world_inv_inertia_sqrt]);
+            AngularInertia::from(gather![|ii| mprops2[ii].constraint_world_inv_inertia_sqrt()]);
 
         let linvel1 = Vector::from(gather![|ii| vels1[ii].linvel]);
         let angvel1 = AngVector::<SimdReal>::from(gather![|ii| vels1[ii].angvel]);
@@ -155,7 +156,7 @@ impl WVelocityGroundConstraint {
 
                     constraint.elements[k].normal_part = VelocityGroundConstraintNormalPart {
                         gcross2,
-                        rhs: rhs_wo_bias + rhs_bias,
+                        rhs: rhs_wo_bias + rhs_bias.simd_max(-max_corrective_velocity),
                         rhs_wo_bias,
                         impulse: na::zero(),
                         r: projected_mass,
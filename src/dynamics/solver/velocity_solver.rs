@@ -3,14 +3,13 @@ use crate::data::{BundleSet, ComponentSet, ComponentSetMut};
 use crate::dynamics::solver::AnyGenericVelocityConstraint;
 use crate::dynamics::{
     solver::{AnyVelocityConstraint, DeltaVel},
-    IntegrationParameters, JointGraphEdge, MultibodyJointSet, RigidBodyForces, RigidBodyType,
-    RigidBodyVelocity,
+    IntegrationParameters, JointCoupling, JointGraphEdge, MultibodyJointSet, RigidBodyForces,
+    RigidBodyType, RigidBodyVelocity,
 };
 use crate::dynamics::{IslandManager, RigidBodyIds, RigidBodyMassProps};
 use crate::geometry::ContactManifold;
 use crate::math::Real;
 use crate::prelude::{RigidBodyActivation, RigidBodyDamping, RigidBodyPosition};
-use crate::utils::WAngularInertia;
 use na::DVector;
 
 pub(crate) struct VelocitySolver {
@@ -35,6 +34,7 @@ impl VelocitySolver {
         multibodies: &mut MultibodyJointSet,
         manifolds_all: &mut [&mut ContactManifold],
         joints_all: &mut [JointGraphEdge],
+        couplings_all: &mut [JointCoupling],
         contact_constraints: &mut [AnyVelocityConstraint],
         generic_contact_constraints: &mut [AnyGenericVelocityConstraint],
         generic_contact_jacobians: &DVector<Real>,
@@ -66,8 +66,12 @@ impl VelocitySolver {
             let dvel = &mut self.mj_lambdas[ids.active_set_offset];
 
             // NOTE: `dvel.angular` is actually storing angular velocity delta multiplied
-            //       by the square root of the inertia tensor:
-            dvel.angular += mprops.effective_world_inv_inertia_sqrt * forces.torque * params.dt;
+            //       by the square root of the inertia tensor used by the constraints. If that
+            //       inertia is infinite, the torque is applied when updating the velocities.
+            if mprops.inv_inertia_scale != 0.0 {
+                dvel.angular += mprops.effective_world_inv_inertia_sqrt * forces.torque * params.dt
+                    / mprops.inv_inertia_scale.sqrt();
+            }
             dvel.linear += forces.force.component_mul(&mprops.effective_inv_mass) * params.dt;
         }
 
@@ -170,13 +174,11 @@ impl VelocitySolver {
                     multibody.velocities = prev_vels;
                 }
             } else {
-                let (ids, mprops): (&RigidBodyIds, &RigidBodyMassProps) =
+                let (ids, mprops, forces): (&RigidBodyIds, &RigidBodyMassProps, &RigidBodyForces) =
                     bodies.index_bundle(handle.0);
 
                 let dvel = self.mj_lambdas[ids.active_set_offset];
-                let dangvel = mprops
-                    .effective_world_inv_inertia_sqrt
-                    .transform_vector(dvel.angular);
+                let dangvel = mprops.solver_angvel_change(dvel.angular, forces.torque, params.dt);
 
                 // Update positions.
                 let (poss, vels, damping, mprops): (
@@ -261,16 +263,15 @@ impl VelocitySolver {
                     multibody.velocities += mj_lambdas;
                 }
             } else {
-                let (ids, damping, mprops): (
+                let (ids, damping, mprops, forces): (
                     &RigidBodyIds,
                     &RigidBodyDamping,
                     &RigidBodyMassProps,
+                    &RigidBodyForces,
                 ) = bodies.index_bundle(handle.0);
 
                 let dvel = self.mj_lambdas[ids.active_set_offset];
-                let dangvel = mprops
-                    .effective_world_inv_inertia_sqrt
-                    .transform_vector(dvel.angular);
+                let dangvel = mprops.solver_angvel_change(dvel.angular, forces.torque, params.dt);
                 let damping = *damping; // To avoid borrow issues.
 
                 bodies.map_mut_internal(handle.0, |vels: &mut RigidBodyVelocity| {
@@ -283,7 +284,7 @@ impl VelocitySolver {
 
         // Write impulses back into the manifold structures.
         for constraint in &*joint_constraints {
-            constraint.writeback_impulses(joints_all);
+            constraint.writeback_impulses(joints_all, couplings_all);
         }
 
         for constraint in &*contact_constraints {
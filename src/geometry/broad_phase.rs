@@ -0,0 +1,68 @@
+use crate::data::{ComponentSet, ComponentSetMut};
+use crate::geometry::{
+    BroadPhaseMultiSap, BroadPhasePairEvent, ColliderBroadPhaseData, ColliderChanges,
+    ColliderFlags, ColliderHandle, ColliderPair, ColliderPosition, ColliderShape, AABB,
+};
+use crate::math::{Real, Vector};
+
+/// The broad-phase used by default: the Hierarchical-SAP [`BroadPhaseMultiSap`].
+pub type DefaultBroadPhase = BroadPhaseMultiSap;
+
+/// A broad-phase algorithm, responsible for finding the pairs of colliders with
+/// intersecting AABBs.
+///
+/// The broad-phase reports the beginning and the end of these potential interactions
+/// as [`BroadPhasePairEvent`]s which are then processed by the narrow-phase. The
+/// physics and collision pipelines accept any type implementing this trait.
+///
+/// A given collider set must always be used with the same broad-phase instance
+/// because the broad-phase stores its own proxy index into each collider's
+/// [`ColliderBroadPhaseData`].
+pub trait BroadPhase {
+    /// Updates the broad-phase, taking into account the new collider positions.
+    ///
+    /// The AABBs of the colliders are enlarged by half the `prediction_distance`.
+    /// Every new pair of intersecting AABBs must be reported with a
+    /// `BroadPhasePairEvent::AddPair`, and every pair that stopped intersecting, or
+    /// that involves a removed or disabled collider, with a `BroadPhasePairEvent::DeletePair`.
+    fn update<Colliders>(
+        &mut self,
+        prediction_distance: Real,
+        colliders: &mut Colliders,
+        modified_colliders: &[ColliderHandle],
+        removed_colliders: &[ColliderHandle],
+        events: &mut Vec<BroadPhasePairEvent>,
+    ) where
+        Colliders: ComponentSetMut<ColliderBroadPhaseData>
+            + ComponentSet<ColliderChanges>
+            + ComponentSet<ColliderPosition>
+            + ComponentSet<ColliderShape>
+            + ComponentSet<ColliderFlags>;
+
+    /// Moves the origin of the broad-phase to the point `shift`, i.e., translates all
+    /// its proxies by `-shift`.
+    ///
+    /// This must be called after the positions of all the colliders have been translated
+    /// by `-shift`. Because all the proxies are translated by the same amount, the pairs
+    /// of intersecting AABBs are preserved. Every pair starting or stopping to intersect
+    /// because of rounding errors must be reported into `events`.
+    fn shift_origin(&mut self, shift: &Vector<Real>, events: &mut Vec<BroadPhasePairEvent>);
+
+    /// Calls `callback` on each pair of colliders with intersecting AABBs, as of the last update.
+    ///
+    /// These are the pairs reported by a `BroadPhasePairEvent::AddPair` and not deleted since,
+    /// i.e., the pairs given to the narrow-phase. Their AABBs may be enlarged by the prediction
+    /// distance, and by broad-phase specific margins. This can be used for cheap proximity
+    /// tests without computing any contact. The iteration stops as soon as `callback` returns `false`.
+    fn for_each_pair(&self, callback: impl FnMut(&ColliderPair) -> bool);
+
+    /// Calls `callback` on each collider with an AABB intersecting `aabb`, as of the last update.
+    ///
+    /// The AABBs of the colliders are enlarged by half the prediction distance given to the last
+    /// update. The iteration stops as soon as `callback` returns `false`.
+    fn colliders_with_aabb_intersecting_aabb(
+        &self,
+        aabb: &AABB,
+        callback: impl FnMut(&ColliderHandle) -> bool,
+    );
+}
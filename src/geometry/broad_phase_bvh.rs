@@ -0,0 +1,821 @@
+use crate::data::{BundleSet, ComponentSet, ComponentSetMut};
+use crate::geometry::{
+    BroadPhase, BroadPhasePairEvent, ColliderBroadPhaseData, ColliderChanges, ColliderFlags,
+    ColliderHandle, ColliderPair, ColliderPosition, ColliderShape,
+};
+use crate::math::{Point, Real, Vector, DIM};
+use parry::bounding_volume::{BoundingVolume, AABB};
+use parry::utils::hashmap::{Entry, HashMap};
+
+/// Bounds applied to the AABBs inserted into the tree, so that infinite shapes
+/// (like half-spaces) don't result in infinite or NaN insertion costs.
+const MAX_AABB_EXTENT: Real = 1.0e10;
+/// Factor applied to the displacement of a collider since the last update, to
+/// enlarge its fat AABB toward the direction of its motion.
+const DISPLACEMENT_MULTIPLIER: Real = 2.0;
+
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug)]
+struct BvhNode {
+    // The fat AABB of a leaf, or the AABB enclosing both children of an internal node.
+    aabb: AABB,
+    // The AABB of the collider, as of the last update. Only meaningful for leaves.
+    collider_aabb: AABB,
+    parent: u32,
+    // Both children are `crate::INVALID_U32` for leaves.
+    children: [u32; 2],
+    height: u32,
+    collider: ColliderHandle,
+    moved: bool,
+}
+
+impl BvhNode {
+    fn leaf(collider: ColliderHandle, aabb: AABB, collider_aabb: AABB) -> Self {
+        Self {
+            aabb,
+            collider_aabb,
+            parent: crate::INVALID_U32,
+            children: [crate::INVALID_U32; 2],
+            height: 0,
+            collider,
+            moved: true,
+        }
+    }
+
+    fn internal(aabb: AABB, parent: u32, children: [u32; 2], height: u32) -> Self {
+        Self {
+            aabb,
+            collider_aabb: aabb,
+            parent,
+            children,
+            height,
+            collider: ColliderHandle::invalid(),
+            moved: false,
+        }
+    }
+
+    fn is_leaf(&self) -> bool {
+        self.children[0] == crate::INVALID_U32
+    }
+}
+
+/// A broad-phase based on an incremental dynamic AABB tree (a Bounding Volume Hierarchy).
+///
+/// Each collider is represented by a leaf of a binary tree kept balanced with tree
+/// rotations. The AABB stored in each leaf is a "fat" AABB: the collider AABB enlarged by
+/// a margin, and extended toward the direction of the collider motion. A leaf is only
+/// removed and reinserted into the tree when the collider AABB leaves its fat AABB, so
+/// moving colliders don't need to update the tree at each timestep.
+///
+/// Unlike the Hierarchical-SAP [`BroadPhaseMultiSap`](crate::geometry::BroadPhaseMultiSap),
+/// this does not depend on any spatial discretization, and its cost is only proportional
+/// to the number of colliders that moved outside of their fat AABB. This makes it well-suited
+/// for scenes with lots of small fast-moving objects.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct BroadPhaseBvh {
+    margin: Real,
+    nodes: Vec<BvhNode>,
+    free_nodes: Vec<u32>,
+    root: u32,
+    // NOTE: we maintain this hashmap to find the leaf of removed colliders
+    //       because their ColliderBroadPhaseData component is no longer
+    //       accessible after their removal.
+    colliders_leaf_ids: HashMap<ColliderHandle, u32>,
+    // The pairs of leaves with intersecting fat AABBs, indexed by their sorted leaf ids.
+    pairs: HashMap<(u32, u32), ColliderPair>,
+    // For each node, the other leaves it forms a pair with. Empty for internal nodes.
+    leaf_pairs: Vec<Vec<u32>>,
+    moved_leaves: Vec<u32>,   // Workspace
+    removed_leaves: Vec<u32>, // Workspace
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    stack: Vec<u32>, // Workspace
+}
+
+impl Default for BroadPhaseBvh {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BroadPhaseBvh {
+    /// Create a new empty broad-phase with a default fat AABB margin of `0.1`.
+    pub fn new() -> Self {
+        Self::with_margin(0.1)
+    }
+
+    /// Create a new empty broad-phase with the given fat AABB margin.
+    ///
+    /// A larger margin reduces the number of tree updates for moving colliders, but increases
+    /// the number of pairs reported to the narrow-phase.
+    pub fn with_margin(margin: Real) -> Self {
+        assert!(margin >= 0.0, "The fat AABB margin must be non-negative.");
+        BroadPhaseBvh {
+            margin,
+            nodes: Vec::new(),
+            free_nodes: Vec::new(),
+            root: crate::INVALID_U32,
+            colliders_leaf_ids: HashMap::default(),
+            pairs: HashMap::default(),
+            leaf_pairs: Vec::new(),
+            moved_leaves: Vec::new(),
+            removed_leaves: Vec::new(),
+            stack: Vec::new(),
+        }
+    }
+
+    /// The margin by which the collider AABBs are enlarged before being inserted into the tree.
+    pub fn margin(&self) -> Real {
+        self.margin
+    }
+
+    /// The number of pairs of colliders with intersecting fat AABBs.
+    pub fn num_pairs(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// The height of the tree, i.e., the length of its longest root-to-leaf path.
+    pub fn height(&self) -> u32 {
+        if self.root == crate::INVALID_U32 {
+            0
+        } else {
+            self.nodes[self.root as usize].height
+        }
+    }
+
+    fn allocate_node(&mut self, node: BvhNode) -> u32 {
+        if let Some(id) = self.free_nodes.pop() {
+            self.nodes[id as usize] = node;
+            id
+        } else {
+            self.nodes.push(node);
+            self.leaf_pairs.push(Vec::new());
+            self.nodes.len() as u32 - 1
+        }
+    }
+
+    fn replace_child(&mut self, parent: u32, old_child: u32, new_child: u32) {
+        if parent == crate::INVALID_U32 {
+            self.root = new_child;
+        } else {
+            let children = &mut self.nodes[parent as usize].children;
+            let i = (children[1] == old_child) as usize;
+            children[i] = new_child;
+        }
+    }
+
+    /// Recomputes the AABB and height of an internal node from those of its children.
+    fn refit(&mut self, id: u32) {
+        let [child1, child2] = self.nodes[id as usize].children;
+        let (node1, node2) = (self.nodes[child1 as usize], self.nodes[child2 as usize]);
+        let node = &mut self.nodes[id as usize];
+        node.aabb = node1.aabb.merged(&node2.aabb);
+        node.height = 1 + node1.height.max(node2.height);
+    }
+
+    /// Refits and rebalances every ancestor of a node, starting with `id`.
+    fn refit_ancestors(&mut self, mut id: u32) {
+        while id != crate::INVALID_U32 {
+            id = self.balance(id);
+            self.refit(id);
+            id = self.nodes[id as usize].parent;
+        }
+    }
+
+    fn insert_leaf(&mut self, leaf: u32) {
+        if self.root == crate::INVALID_U32 {
+            self.root = leaf;
+            self.nodes[leaf as usize].parent = crate::INVALID_U32;
+            return;
+        }
+
+        // Find the best sibling for the new leaf, using the surface area heuristic.
+        let leaf_aabb = self.nodes[leaf as usize].aabb;
+        let mut sibling = self.root;
+
+        while !self.nodes[sibling as usize].is_leaf() {
+            let node = &self.nodes[sibling as usize];
+            let area = cost(&node.aabb);
+            let combined_area = cost(&node.aabb.merged(&leaf_aabb));
+
+            // Cost of creating a new parent for this node and the new leaf.
+            let sibling_cost = 2.0 * combined_area;
+            // Minimum cost of pushing the leaf further down the tree.
+            let inheritance_cost = 2.0 * (combined_area - area);
+
+            let child_cost = |child: u32| {
+                let child = &self.nodes[child as usize];
+                let merged_area = cost(&child.aabb.merged(&leaf_aabb));
+
+                if child.is_leaf() {
+                    merged_area + inheritance_cost
+                } else {
+                    merged_area - cost(&child.aabb) + inheritance_cost
+                }
+            };
+
+            let [child1, child2] = node.children;
+            let (cost1, cost2) = (child_cost(child1), child_cost(child2));
+
+            if sibling_cost < cost1 && sibling_cost < cost2 {
+                break;
+            }
+
+            sibling = if cost1 < cost2 { child1 } else { child2 };
+        }
+
+        // Create a new parent for the sibling and the leaf.
+        let old_parent = self.nodes[sibling as usize].parent;
+        let sibling_node = self.nodes[sibling as usize];
+        let new_parent = self.allocate_node(BvhNode::internal(
+            sibling_node.aabb.merged(&leaf_aabb),
+            old_parent,
+            [sibling, leaf],
+            sibling_node.height + 1,
+        ));
+
+        self.replace_child(old_parent, sibling, new_parent);
+        self.nodes[sibling as usize].parent = new_parent;
+        self.nodes[leaf as usize].parent = new_parent;
+
+        self.refit_ancestors(old_parent);
+    }
+
+    fn remove_leaf(&mut self, leaf: u32) {
+        if leaf == self.root {
+            self.root = crate::INVALID_U32;
+            return;
+        }
+
+        // Replace the parent of the leaf by the leaf sibling.
+        let parent = self.nodes[leaf as usize].parent;
+        let grand_parent = self.nodes[parent as usize].parent;
+        let children = self.nodes[parent as usize].children;
+        let sibling = if children[0] == leaf {
+            children[1]
+        } else {
+            children[0]
+        };
+
+        self.replace_child(grand_parent, parent, sibling);
+        self.nodes[sibling as usize].parent = grand_parent;
+        self.free_nodes.push(parent);
+
+        self.refit_ancestors(grand_parent);
+    }
+
+    /// Performs a tree rotation at the node `a` if it is imbalanced.
+    ///
+    /// Returns the node that replaced `a` in the tree.
+    fn balance(&mut self, a: u32) -> u32 {
+        let node_a = self.nodes[a as usize];
+
+        if node_a.is_leaf() || node_a.height < 2 {
+            return a;
+        }
+
+        let [b, c] = node_a.children;
+        let height_b = self.nodes[b as usize].height as i64;
+        let height_c = self.nodes[c as usize].height as i64;
+
+        if height_c - height_b > 1 {
+            self.rotate_up(a, 1)
+        } else if height_b - height_c > 1 {
+            self.rotate_up(a, 0)
+        } else {
+            a
+        }
+    }
+
+    /// Rotates the child `i` of the node `a` up, so it becomes the parent of `a`.
+    fn rotate_up(&mut self, a: u32, i: usize) -> u32 {
+        let up = self.nodes[a as usize].children[i];
+        let other = self.nodes[a as usize].children[1 - i];
+        let [f, g] = self.nodes[up as usize].children;
+
+        // Make `a` a child of `up`.
+        let a_parent = self.nodes[a as usize].parent;
+        self.nodes[up as usize].children[0] = a;
+        self.nodes[up as usize].parent = a_parent;
+        self.nodes[a as usize].parent = up;
+        self.replace_child(a_parent, a, up);
+
+        // Keep the highest grandchild under `up`, and give the other one to `a`.
+        let (kept, given) = if self.nodes[f as usize].height > self.nodes[g as usize].height {
+            (f, g)
+        } else {
+            (g, f)
+        };
+
+        self.nodes[up as usize].children[1] = kept;
+        self.nodes[a as usize].children[i] = given;
+        self.nodes[given as usize].parent = a;
+
+        let other = self.nodes[other as usize];
+        let given = self.nodes[given as usize];
+        let node_a = &mut self.nodes[a as usize];
+        node_a.aabb = other.aabb.merged(&given.aabb);
+        node_a.height = 1 + other.height.max(given.height);
+        let node_a = *node_a;
+
+        let kept = self.nodes[kept as usize];
+        let node_up = &mut self.nodes[up as usize];
+        node_up.aabb = node_a.aabb.merged(&kept.aabb);
+        node_up.height = 1 + node_a.height.max(kept.height);
+
+        up
+    }
+
+    fn fat_aabb(&self, aabb: &AABB, prev_aabb: Option<&AABB>) -> AABB {
+        let mut fat_aabb = aabb.loosened(self.margin);
+
+        if let Some(prev_aabb) = prev_aabb {
+            // Extend the fat AABB toward the direction of motion so fast-moving
+            // colliders don't need to be reinserted at each update.
+            let displacement = (aabb.center() - prev_aabb.center()) * DISPLACEMENT_MULTIPLIER;
+
+            for k in 0..DIM {
+                if displacement[k] < 0.0 {
+                    fat_aabb.mins[k] += displacement[k];
+                } else {
+                    fat_aabb.maxs[k] += displacement[k];
+                }
+            }
+        }
+
+        fat_aabb.mins = clamp_point(fat_aabb.mins);
+        fat_aabb.maxs = clamp_point(fat_aabb.maxs);
+        fat_aabb
+    }
+
+    fn mark_moved(&mut self, leaf: u32) {
+        let node = &mut self.nodes[leaf as usize];
+
+        if !node.moved {
+            node.moved = true;
+            self.moved_leaves.push(leaf);
+        }
+    }
+
+    fn handle_modified_collider(
+        &mut self,
+        prediction_distance: Real,
+        handle: ColliderHandle,
+        leaf: &mut u32,
+        collider: (&ColliderPosition, &ColliderShape, &ColliderChanges),
+    ) {
+        let (co_pos, co_shape, co_changes) = collider;
+        let aabb = co_shape
+            .compute_aabb(co_pos)
+            .loosened(prediction_distance / 2.0);
+
+        if let Some(node) = self.nodes.get(*leaf as usize).copied() {
+            if co_changes.contains(ColliderChanges::SHAPE) || !node.aabb.contains(&aabb) {
+                let prev_aabb = if co_changes.contains(ColliderChanges::SHAPE) {
+                    None
+                } else {
+                    Some(&node.collider_aabb)
+                };
+
+                self.remove_leaf(*leaf);
+                self.nodes[*leaf as usize].aabb = self.fat_aabb(&aabb, prev_aabb);
+                self.insert_leaf(*leaf);
+                self.mark_moved(*leaf);
+            }
+
+            self.nodes[*leaf as usize].collider_aabb = aabb;
+        } else {
+            let fat_aabb = self.fat_aabb(&aabb, None);
+            *leaf = self.allocate_node(BvhNode::leaf(handle, fat_aabb, aabb));
+            self.insert_leaf(*leaf);
+            self.moved_leaves.push(*leaf);
+        }
+    }
+
+    /// Removes a leaf from the tree, and marks it as moved with an invalid AABB so all its
+    /// pairs are deleted by the next call to `find_pairs`.
+    fn predelete_leaf(&mut self, leaf: u32) {
+        self.remove_leaf(leaf);
+        self.nodes[leaf as usize].aabb = AABB::new_invalid();
+        self.mark_moved(leaf);
+        self.removed_leaves.push(leaf);
+    }
+
+    fn find_pairs(&mut self, events: &mut Vec<BroadPhasePairEvent>) {
+        // Delete the pairs of moved leaves that no longer intersect.
+        // This includes all the pairs involving removed leaves.
+        for &leaf in &self.moved_leaves {
+            let mut others = std::mem::take(&mut self.leaf_pairs[leaf as usize]);
+            let leaf_aabb = self.nodes[leaf as usize].aabb;
+
+            others.retain(|&other| {
+                if leaf_aabb.intersects(&self.nodes[other as usize].aabb) {
+                    return true;
+                }
+
+                let key = if other < leaf {
+                    (other, leaf)
+                } else {
+                    (leaf, other)
+                };
+                if let Some(pair) = self.pairs.remove(&key) {
+                    events.push(BroadPhasePairEvent::DeletePair(pair));
+                }
+
+                let other_pairs = &mut self.leaf_pairs[other as usize];
+                if let Some(i) = other_pairs.iter().position(|l| *l == leaf) {
+                    let _ = other_pairs.swap_remove(i);
+                }
+
+                false
+            });
+
+            self.leaf_pairs[leaf as usize] = others;
+        }
+
+        // Find the new pairs involving moved leaves.
+        // NOTE: the tree is empty if all the leaves have been removed.
+        let num_leaves_to_test = if self.root == crate::INVALID_U32 {
+            0
+        } else {
+            self.moved_leaves.len()
+        };
+
+        for i in 0..num_leaves_to_test {
+            let leaf = self.moved_leaves[i];
+            let leaf_node = self.nodes[leaf as usize];
+
+            self.stack.clear();
+            self.stack.push(self.root);
+
+            while let Some(id) = self.stack.pop() {
+                let node = &self.nodes[id as usize];
+
+                if !node.aabb.intersects(&leaf_node.aabb) {
+                    continue;
+                }
+
+                if !node.is_leaf() {
+                    self.stack.extend_from_slice(&node.children);
+                } else if id != leaf && (!node.moved || id < leaf) {
+                    // NOTE: if both leaves moved, the pair is only reported while
+                    //       traversing the tree for the leaf with the largest id.
+                    let key = if id < leaf { (id, leaf) } else { (leaf, id) };
+
+                    if let Entry::Vacant(entry) = self.pairs.entry(key) {
+                        let pair = ColliderPair::new(leaf_node.collider, node.collider);
+                        let _ = entry.insert(pair);
+                        self.leaf_pairs[leaf as usize].push(id);
+                        self.leaf_pairs[id as usize].push(leaf);
+                        events.push(BroadPhasePairEvent::AddPair(pair));
+                    }
+                }
+            }
+        }
+
+        for leaf in self.moved_leaves.drain(..) {
+            self.nodes[leaf as usize].moved = false;
+        }
+    }
+}
+
+impl BroadPhase for BroadPhaseBvh {
+    fn update<Colliders>(
+        &mut self,
+        prediction_distance: Real,
+        colliders: &mut Colliders,
+        modified_colliders: &[ColliderHandle],
+        removed_colliders: &[ColliderHandle],
+        events: &mut Vec<BroadPhasePairEvent>,
+    ) where
+        Colliders: ComponentSetMut<ColliderBroadPhaseData>
+            + ComponentSet<ColliderChanges>
+            + ComponentSet<ColliderPosition>
+            + ComponentSet<ColliderShape>
+            + ComponentSet<ColliderFlags>,
+    {
+        // Phase 1: remove the leaves of the removed colliders from the tree.
+        //          Their nodes are only freed once their pairs have been deleted.
+        for handle in removed_colliders {
+            if let Some(leaf) = self.colliders_leaf_ids.remove(handle) {
+                self.predelete_leaf(leaf);
+            }
+        }
+
+        // Phase 2: update the leaves of the modified colliders.
+        for handle in modified_colliders {
+            // NOTE: we use `get` because the collider may no longer
+            //       exist if it has been removed.
+            let co_changes: Option<&ColliderChanges> = colliders.get(handle.0);
+
+            if let Some(co_changes) = co_changes {
+                let (co_bf_data, co_pos, co_shape, co_flags): (
+                    &ColliderBroadPhaseData,
+                    &ColliderPosition,
+                    &ColliderShape,
+                    &ColliderFlags,
+                ) = colliders.index_bundle(handle.0);
+
+                if !co_changes.needs_broad_phase_update() {
+                    continue;
+                }
+
+                if !co_flags.enabled.is_enabled() {
+                    // Disabled colliders are removed from the broad-phase until they are enabled again.
+                    if co_bf_data.proxy_index != crate::INVALID_U32 {
+                        let _ = self.colliders_leaf_ids.remove(handle);
+                        self.predelete_leaf(co_bf_data.proxy_index);
+                        colliders.set_internal(handle.0, ColliderBroadPhaseData::default());
+                    }
+                    continue;
+                }
+
+                let mut new_leaf_id = co_bf_data.proxy_index;
+
+                self.handle_modified_collider(
+                    prediction_distance,
+                    *handle,
+                    &mut new_leaf_id,
+                    (co_pos, co_shape, co_changes),
+                );
+
+                if co_bf_data.proxy_index != new_leaf_id {
+                    let _ = self.colliders_leaf_ids.insert(*handle, new_leaf_id);
+
+                    // Make sure we have the new leaf index in case
+                    // the collider was added for the first time.
+                    colliders.set_internal(
+                        handle.0,
+                        ColliderBroadPhaseData {
+                            proxy_index: new_leaf_id,
+                        },
+                    );
+                }
+            }
+        }
+
+        // Phase 3: report the pairs that started or stopped intersecting.
+        self.find_pairs(events);
+
+        // Phase 4: free the nodes of the removed leaves.
+        self.free_nodes.append(&mut self.removed_leaves);
+    }
+
+    fn for_each_pair(&self, mut callback: impl FnMut(&ColliderPair) -> bool) {
+        for pair in self.pairs.values() {
+            if !callback(pair) {
+                return;
+            }
+        }
+    }
+
+    fn colliders_with_aabb_intersecting_aabb(
+        &self,
+        aabb: &AABB,
+        mut callback: impl FnMut(&ColliderHandle) -> bool,
+    ) {
+        if self.root == crate::INVALID_U32 {
+            return;
+        }
+
+        let mut stack = vec![self.root];
+
+        while let Some(id) = stack.pop() {
+            let node = &self.nodes[id as usize];
+
+            if !node.aabb.intersects(aabb) {
+                continue;
+            }
+
+            if !node.is_leaf() {
+                stack.extend_from_slice(&node.children);
+            } else if node.collider_aabb.intersects(aabb) && !callback(&node.collider) {
+                return;
+            }
+        }
+    }
+
+    fn shift_origin(&mut self, shift: &Vector<Real>, _events: &mut Vec<BroadPhasePairEvent>) {
+        if self.root == crate::INVALID_U32 {
+            return;
+        }
+
+        // NOTE: translating all the AABBs by the same amount preserves their intersections,
+        //       so the set of pairs doesn't change.
+        self.stack.clear();
+        self.stack.push(self.root);
+
+        while let Some(id) = self.stack.pop() {
+            let node = &mut self.nodes[id as usize];
+            node.aabb = AABB::new(
+                clamp_point(node.aabb.mins - shift),
+                clamp_point(node.aabb.maxs - shift),
+            );
+            node.collider_aabb = AABB::new(
+                node.collider_aabb.mins - shift,
+                node.collider_aabb.maxs - shift,
+            );
+
+            if !node.is_leaf() {
+                self.stack.extend_from_slice(&node.children);
+            }
+        }
+    }
+}
+
+/// The cost of a tree node, i.e., the surface area of its AABB.
+fn cost(aabb: &AABB) -> Real {
+    let extents = aabb.extents();
+
+    #[cfg(feature = "dim2")]
+    return 2.0 * (extents.x + extents.y);
+    #[cfg(feature = "dim3")]
+    return 2.0 * (extents.x * extents.y + extents.y * extents.z + extents.z * extents.x);
+}
+
+fn clamp_point(point: Point<Real>) -> Point<Real> {
+    point.map(|e| na::clamp(e, -MAX_AABB_EXTENT, MAX_AABB_EXTENT))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{IslandManager, RigidBodyBuilder, RigidBodySet};
+    use crate::geometry::{
+        BroadPhase, BroadPhaseBvh, BroadPhasePairEvent, ColliderBuilder, ColliderHandle,
+        ColliderSet, DefaultBroadPhase,
+    };
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn test_bvh_pairs() {
+        let mut broad_phase = BroadPhaseBvh::new();
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut islands = IslandManager::new();
+        let mut events = Vec::new();
+
+        // A row of balls, each touching only its direct neighbors.
+        let handles: Vec<_> = (0..20)
+            .map(|i| {
+                let co = ColliderBuilder::ball(0.5)
+                    .translation(Vector::x() * i as Real * 0.95)
+                    .build();
+                colliders.insert(co)
+            })
+            .collect();
+
+        broad_phase.update(0.0, &mut colliders, &handles, &[], &mut events);
+        assert_eq!(events.len(), 19);
+        assert_eq!(broad_phase.num_pairs(), 19);
+        assert!(broad_phase.height() < 10);
+
+        // Moving the last ball far away deletes its only pair.
+        events.clear();
+        colliders[handles[19]].set_translation(Vector::x() * 100.0);
+        let modified = colliders.take_modified();
+        broad_phase.update(0.0, &mut colliders, &modified, &[], &mut events);
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], BroadPhasePairEvent::DeletePair(_)));
+
+        // Removing a ball deletes its two pairs.
+        events.clear();
+        let _ = colliders.remove(handles[10], &mut islands, &mut bodies, false);
+        let removed = colliders.take_removed();
+        broad_phase.update(0.0, &mut colliders, &[], &removed, &mut events);
+        assert_eq!(events.len(), 2);
+        assert_eq!(broad_phase.num_pairs(), 16);
+
+        // Its node is recycled by a new ball at the same place.
+        events.clear();
+        let co = ColliderBuilder::ball(0.5)
+            .translation(Vector::x() * 9.5)
+            .build();
+        let handle = colliders.insert(co);
+        broad_phase.update(0.0, &mut colliders, &[handle], &[], &mut events);
+        assert_eq!(events.len(), 2);
+        assert!(matches!(events[0], BroadPhasePairEvent::AddPair(_)));
+        assert_eq!(broad_phase.num_pairs(), 18);
+    }
+
+    #[test]
+    fn test_bvh_empty_tree() {
+        let mut broad_phase = BroadPhaseBvh::new();
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut islands = IslandManager::new();
+        let mut events = Vec::new();
+
+        let handles: Vec<_> = (0..3)
+            .map(|i| {
+                let co = ColliderBuilder::ball(0.5)
+                    .translation(Vector::x() * i as Real * 0.95)
+                    .build();
+                colliders.insert(co)
+            })
+            .collect();
+
+        broad_phase.update(0.0, &mut colliders, &handles, &[], &mut events);
+        assert_eq!(broad_phase.num_pairs(), 2);
+
+        // Disabling every collider empties the tree.
+        events.clear();
+        for handle in &handles {
+            colliders[*handle].set_enabled(false);
+        }
+        broad_phase.update(0.0, &mut colliders, &handles, &[], &mut events);
+        assert_eq!(events.len(), 2);
+        assert_eq!(broad_phase.num_pairs(), 0);
+
+        // Updating an empty tree again doesn't do anything.
+        events.clear();
+        broad_phase.update(0.0, &mut colliders, &[], &[], &mut events);
+        assert!(events.is_empty());
+
+        for handle in &handles {
+            colliders[*handle].set_enabled(true);
+        }
+        broad_phase.update(0.0, &mut colliders, &handles, &[], &mut events);
+        assert_eq!(broad_phase.num_pairs(), 2);
+
+        // Removing every collider empties the tree too.
+        events.clear();
+        for handle in &handles {
+            let _ = colliders.remove(*handle, &mut islands, &mut bodies, false);
+        }
+        let removed = colliders.take_removed();
+        broad_phase.update(0.0, &mut colliders, &[], &removed, &mut events);
+        assert_eq!(events.len(), 2);
+        broad_phase.update(0.0, &mut colliders, &[], &[], &mut events);
+        assert_eq!(broad_phase.height(), 0);
+
+        // The tree can be filled again afterwards.
+        events.clear();
+        let handle1 = colliders.insert(ColliderBuilder::ball(0.5).build());
+        let handle2 = colliders.insert(ColliderBuilder::ball(0.5).build());
+        broad_phase.update(0.0, &mut colliders, &[handle1, handle2], &[], &mut events);
+        assert_eq!(events.len(), 1);
+        assert_eq!(broad_phase.num_pairs(), 1);
+    }
+
+    // Drops a grid of balls on the ground, then removes every other ball.
+    // Returns the colliders in contact with the ground after each phase.
+    fn falling_balls(broad_phase: impl BroadPhase) -> (Vec<ColliderHandle>, Vec<ColliderHandle>) {
+        let mut world = TestWorld::with_broad_phase(Vector::y() * -9.81, broad_phase);
+
+        #[cfg(feature = "dim3")]
+        let ground = ColliderBuilder::cuboid(20.0, 0.5, 20.0).build();
+        #[cfg(feature = "dim2")]
+        let ground = ColliderBuilder::cuboid(20.0, 0.5).build();
+        let ground = world.colliders.insert(ground);
+
+        let balls: Vec<_> = (0..16)
+            .map(|i| {
+                let rb = RigidBodyBuilder::new_dynamic()
+                    .translation(
+                        Vector::x() * (i as Real * 1.2 - 9.0) + Vector::y() * (1.0 + i as Real),
+                    )
+                    .build();
+                world.insert_body(rb, ColliderBuilder::ball(0.5).build()).0
+            })
+            .collect();
+
+        let touching = |world: &TestWorld<_>| {
+            let mut touching: Vec<_> = world
+                .narrow_phase
+                .contacts_with(ground)
+                .filter(|pair| pair.has_any_active_contact)
+                .map(|pair| {
+                    if pair.collider1 == ground {
+                        pair.collider2
+                    } else {
+                        pair.collider1
+                    }
+                })
+                .collect();
+            touching.sort_by_key(|h| h.into_raw_parts());
+            touching
+        };
+
+        world.step_n(200);
+        let resting = touching(&world);
+
+        for ball in balls.iter().step_by(2) {
+            let _ = world.remove_body(*ball);
+        }
+
+        world.step_n(200);
+        let remaining = touching(&world);
+        (resting, remaining)
+    }
+
+    #[test]
+    fn test_bvh_same_contacts_as_sap() {
+        let (sap_resting, sap_remaining) = falling_balls(DefaultBroadPhase::new());
+        let (bvh_resting, bvh_remaining) = falling_balls(BroadPhaseBvh::new());
+        assert_eq!(sap_resting.len(), 16);
+        assert_eq!(sap_remaining.len(), 8);
+        assert_eq!(sap_resting, bvh_resting);
+        assert_eq!(sap_remaining, bvh_remaining);
+    }
+}
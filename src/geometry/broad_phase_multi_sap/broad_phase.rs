@@ -3,11 +3,13 @@ use super::{
 };
 use crate::geometry::broad_phase_multi_sap::SAPProxyIndex;
 use crate::geometry::{
-    ColliderBroadPhaseData, ColliderChanges, ColliderHandle, ColliderPosition, ColliderShape,
+    BroadPhase, ColliderBroadPhaseData, ColliderChanges, ColliderFlags, ColliderHandle,
+    ColliderPosition, ColliderShape,
 };
-use crate::math::Real;
+use crate::math::{Real, Vector};
 use crate::utils::IndexMut2;
-use parry::bounding_volume::BoundingVolume;
+use bit_vec::BitVec;
+use parry::bounding_volume::{BoundingVolume, AABB};
 use parry::utils::hashmap::HashMap;
 
 use crate::data::{BundleSet, ComponentSet, ComponentSetMut};
@@ -75,7 +77,11 @@ use crate::data::{BundleSet, ComponentSet, ComponentSetMut};
 ///   broad-phase, as well as the AABBs of all the regions part of this broad-phase.
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Clone)]
-pub struct BroadPhase {
+pub struct BroadPhaseMultiSap {
+    // NOTE: broad-phases serialized before the parameters were configurable
+    //       get the default parameters, i.e., the layout they were built with.
+    #[cfg_attr(feature = "serde-serialize", serde(default))]
+    parameters: BroadPhaseMultiSapParameters,
     proxies: SAPProxies,
     layers: Vec<SAPLayer>,
     smallest_layer: u8,
@@ -113,18 +119,111 @@ pub struct BroadPhase {
         )
     )]
     reporting: HashMap<(u32, u32), bool>, // Workspace
+    // The pairs of colliders with intersecting AABBs, indexed by their sorted proxy ids.
+    pairs: HashMap<(SAPProxyIndex, SAPProxyIndex), ColliderPair>,
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    num_added_pairs: usize,
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    num_removed_pairs: usize,
 }
 
-impl Default for BroadPhase {
+/// The layout of the regions of the hierarchical grid of a [`BroadPhaseMultiSap`].
+///
+/// The regions of the layer at depth `n` are cubes (squares in 2D) with a width equal to
+/// `region_width_base * region_width_power_basis^n`. Each collider is inserted into the layer
+/// with regions about ten times as wide as its AABB. The default layout is suited to scenes
+/// where one unit is one meter. For other scales, e.g., pixels, `region_width_base` should be
+/// multiplied by the number of units per meter.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BroadPhaseMultiSapParameters {
+    /// The width of the regions of the layer at depth 0 (default: `1.0`).
+    pub region_width_base: Real,
+    /// The ratio between the region widths of two consecutive layers (default: `5.0`).
+    ///
+    /// Must be greater than 1.
+    pub region_width_power_basis: Real,
+}
+
+impl Default for BroadPhaseMultiSapParameters {
+    fn default() -> Self {
+        Self {
+            region_width_base: super::DEFAULT_REGION_WIDTH_BASE,
+            region_width_power_basis: super::DEFAULT_REGION_WIDTH_POWER_BASIS,
+        }
+    }
+}
+
+/// Statistics about the internal state of a [`BroadPhaseMultiSap`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BroadPhaseMultiSapStats {
+    /// The statistics of each layer, from the smallest to the largest.
+    pub layers: Vec<BroadPhaseMultiSapLayerStats>,
+    /// The number of `BroadPhasePairEvent::AddPair` generated by the last update.
+    pub num_added_pairs: usize,
+    /// The number of `BroadPhasePairEvent::DeletePair` generated by the last update.
+    pub num_removed_pairs: usize,
+}
+
+impl BroadPhaseMultiSapStats {
+    /// The number of layers of the hierarchical grid.
+    pub fn num_layers(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// The total number of regions, on all the layers.
+    pub fn num_regions(&self) -> usize {
+        self.layers.iter().map(|layer| layer.num_regions()).sum()
+    }
+}
+
+/// Statistics about one layer of the hierarchical grid of a [`BroadPhaseMultiSap`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BroadPhaseMultiSapLayerStats {
+    /// The depth of this layer.
+    pub depth: i8,
+    /// The width of the regions of this layer.
+    pub region_width: Real,
+    /// For each region of this layer, in no particular order, the number of collider
+    /// proxies it contains.
+    ///
+    /// This includes the proxies of the larger colliders from larger layers
+    /// intersecting this region.
+    pub num_proxies_per_region: Vec<usize>,
+}
+
+impl BroadPhaseMultiSapLayerStats {
+    /// The number of regions of this layer.
+    pub fn num_regions(&self) -> usize {
+        self.num_proxies_per_region.len()
+    }
+}
+
+impl Default for BroadPhaseMultiSap {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl BroadPhase {
+impl BroadPhaseMultiSap {
     /// Create a new empty broad-phase.
     pub fn new() -> Self {
-        BroadPhase {
+        Self::with_parameters(BroadPhaseMultiSapParameters::default())
+    }
+
+    /// Create a new empty broad-phase with the given region layout.
+    pub fn with_parameters(parameters: BroadPhaseMultiSapParameters) -> Self {
+        assert!(
+            parameters.region_width_base > 0.0,
+            "The region width base must be positive."
+        );
+        assert!(
+            parameters.region_width_power_basis > 1.0,
+            "The region width power basis must be greater than 1."
+        );
+
+        BroadPhaseMultiSap {
+            parameters,
             proxies: SAPProxies::new(),
             layers: Vec::new(),
             smallest_layer: 0,
@@ -132,6 +231,41 @@ impl BroadPhase {
             region_pool: Vec::new(),
             reporting: HashMap::default(),
             colliders_proxy_ids: HashMap::default(),
+            pairs: HashMap::default(),
+            num_added_pairs: 0,
+            num_removed_pairs: 0,
+        }
+    }
+
+    /// The number of pairs of colliders with intersecting AABBs.
+    pub fn num_pairs(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// The region layout of this broad-phase.
+    pub fn parameters(&self) -> &BroadPhaseMultiSapParameters {
+        &self.parameters
+    }
+
+    /// Computes statistics about the layers and regions of this broad-phase, and
+    /// about the pair events generated by its last update.
+    pub fn stats(&self) -> BroadPhaseMultiSapStats {
+        let mut layers = Vec::with_capacity(self.layers.len());
+
+        if !self.layers.is_empty() {
+            let mut curr_layer = Some(self.smallest_layer);
+
+            while let Some(curr_layer_id) = curr_layer {
+                let layer = &self.layers[curr_layer_id as usize];
+                layers.push(layer.stats(&self.proxies));
+                curr_layer = layer.larger_layer;
+            }
+        }
+
+        BroadPhaseMultiSapStats {
+            layers,
+            num_added_pairs: self.num_added_pairs,
+            num_removed_pairs: self.num_removed_pairs,
         }
     }
 
@@ -139,7 +273,7 @@ impl BroadPhase {
     ///
     /// For each colliders marked as removed, we make their containing layer mark
     /// its proxy as pre-deleted. The actual proxy removal will happen at the end
-    /// of the `BroadPhase::update`.
+    /// of the `BroadPhaseMultiSap::update`.
     fn handle_removed_colliders(&mut self, removed_colliders: &[ColliderHandle]) {
         // For each removed collider, remove the corresponding proxy.
         for removed in removed_colliders {
@@ -219,13 +353,38 @@ impl BroadPhase {
         /*
          * Actually remove the colliders proxies.
          */
+        let mut removed_proxies = vec![];
+
         for removed in removed_colliders {
             if let Some(proxy_id) = self.colliders_proxy_ids.remove(removed) {
                 if proxy_id != crate::INVALID_U32 {
-                    self.proxies.remove(proxy_id);
+                    removed_proxies.push(proxy_id);
                 }
             }
         }
+
+        self.remove_proxies(&removed_proxies);
+    }
+
+    /// Removes the given collider proxies, and all their pairs.
+    ///
+    /// The proxies must have been removed from all the layers already.
+    fn remove_proxies(&mut self, proxy_ids: &[SAPProxyIndex]) {
+        if proxy_ids.is_empty() {
+            return;
+        }
+
+        // NOTE: the deletion of some of these pairs may not have been reported
+        //       by the layers, so we need to remove them explicitly.
+        let mut removed = BitVec::from_elem(self.proxies.elements.len(), false);
+
+        for proxy_id in proxy_ids {
+            removed.set(*proxy_id as usize, true);
+            self.proxies.remove(*proxy_id);
+        }
+
+        self.pairs
+            .retain(|(id1, id2), _| !removed[*id1 as usize] && !removed[*id2 as usize]);
     }
 
     /// Finalize the insertion of the layer identified by `layer_id`.
@@ -272,8 +431,9 @@ impl BroadPhase {
         // Special case: we don't have any layers yet.
         if self.layers.is_empty() {
             let layer_id = self.layers.len() as u8; // TODO: check overflow.
+            let region_width = super::region_width(new_depth, &self.parameters);
             self.layers
-                .push(SAPLayer::new(new_depth, layer_id, None, None));
+                .push(SAPLayer::new(new_depth, layer_id, None, None, region_width));
             return 0;
         }
 
@@ -301,6 +461,7 @@ impl BroadPhase {
                     new_layer_id,
                     Some(self.largest_layer),
                     None,
+                    super::region_width(new_depth, &self.parameters),
                 ));
                 self.largest_layer = new_layer_id;
                 self.finalize_layer_insertion(new_layer_id);
@@ -331,6 +492,7 @@ impl BroadPhase {
                         new_layer_id,
                         smaller_layer_id,
                         Some(larger_layer_id),
+                        super::region_width(new_depth, &self.parameters),
                     ));
                     self.finalize_layer_insertion(new_layer_id);
 
@@ -367,7 +529,7 @@ impl BroadPhase {
                 // migrated to a larger layer. Indeed, if the shape was replaced by
                 // a much larger shape, we need to promote the proxy to a bigger layer
                 // to avoid the O(n²) discretization problem.
-                let new_layer_depth = super::layer_containing_aabb(&aabb);
+                let new_layer_depth = super::layer_containing_aabb(&aabb, &self.parameters);
                 if new_layer_depth > proxy.layer_depth {
                     self.layers[proxy.layer_id as usize]
                         .proper_proxy_moved_to_bigger_layer(&mut self.proxies, *proxy_index);
@@ -381,7 +543,7 @@ impl BroadPhase {
 
             layer_id
         } else {
-            let layer_depth = super::layer_containing_aabb(&aabb);
+            let layer_depth = super::layer_containing_aabb(&aabb, &self.parameters);
             let layer_id = self.ensure_layer_exists(layer_depth);
 
             // Create the proxy.
@@ -391,6 +553,20 @@ impl BroadPhase {
             layer_id
         };
 
+        self.preupdate_proxy(layer_id, *proxy_index, &prev_aabb, &aabb)
+    }
+
+    /// Pre-updates the given collider proxy in its layer, after its AABB changed from
+    /// `prev_aabb` to `aabb`.
+    ///
+    /// Returns `true` if new regions have been created and need to be propagated.
+    fn preupdate_proxy(
+        &mut self,
+        layer_id: u8,
+        proxy_index: SAPProxyIndex,
+        prev_aabb: &AABB,
+        aabb: &AABB,
+    ) -> bool {
         let layer = &mut self.layers[layer_id as usize];
 
         // Preupdate the collider in the layer.
@@ -401,29 +577,29 @@ impl BroadPhase {
         // than the new AABB, we just merge them to save some computation times (to avoid
         // discretizing twice the area at their intersection. If it’s bigger than 25% then
         // we discretize both aabbs individually.
-        let merged_aabbs = prev_aabb.merged(&aabb);
+        let merged_aabbs = prev_aabb.merged(aabb);
 
         if merged_aabbs.volume() > aabb.volume() * 1.25 {
             layer.preupdate_collider(
-                *proxy_index,
-                &aabb,
+                proxy_index,
+                aabb,
                 None,
                 &mut self.proxies,
                 &mut self.region_pool,
             );
 
             layer.preupdate_collider(
-                *proxy_index,
-                &prev_aabb,
-                Some(&aabb),
+                proxy_index,
+                prev_aabb,
+                Some(aabb),
                 &mut self.proxies,
                 &mut self.region_pool,
             );
         } else {
             layer.preupdate_collider(
-                *proxy_index,
+                proxy_index,
                 &merged_aabbs,
-                Some(&aabb),
+                Some(aabb),
                 &mut self.proxies,
                 &mut self.region_pool,
             );
@@ -434,80 +610,6 @@ impl BroadPhase {
         need_region_propagation
     }
 
-    /// Updates the broad-phase, taking into account the new collider positions.
-    pub fn update<Colliders>(
-        &mut self,
-        prediction_distance: Real,
-        colliders: &mut Colliders,
-        modified_colliders: &[ColliderHandle],
-        removed_colliders: &[ColliderHandle],
-        events: &mut Vec<BroadPhasePairEvent>,
-    ) where
-        Colliders: ComponentSetMut<ColliderBroadPhaseData>
-            + ComponentSet<ColliderChanges>
-            + ComponentSet<ColliderPosition>
-            + ComponentSet<ColliderShape>,
-    {
-        // Phase 1: pre-delete the collisions that have been deleted.
-        self.handle_removed_colliders(removed_colliders);
-
-        let mut need_region_propagation = false;
-
-        // Phase 2: pre-delete the collisions that have been deleted.
-        for handle in modified_colliders {
-            // NOTE: we use `get` because the collider may no longer
-            //       exist if it has been removed.
-            let co_changes: Option<&ColliderChanges> = colliders.get(handle.0);
-
-            if let Some(co_changes) = co_changes {
-                let (co_bf_data, co_pos, co_shape): (
-                    &ColliderBroadPhaseData,
-                    &ColliderPosition,
-                    &ColliderShape,
-                ) = colliders.index_bundle(handle.0);
-
-                if !co_changes.needs_broad_phase_update() {
-                    return;
-                }
-                let mut new_proxy_id = co_bf_data.proxy_index;
-
-                if self.handle_modified_collider(
-                    prediction_distance,
-                    *handle,
-                    &mut new_proxy_id,
-                    (co_pos, co_shape, co_changes),
-                ) {
-                    need_region_propagation = true;
-                }
-
-                if co_bf_data.proxy_index != new_proxy_id {
-                    self.colliders_proxy_ids.insert(*handle, new_proxy_id);
-
-                    // Make sure we have the new proxy index in case
-                    // the collider was added for the first time.
-                    colliders.set_internal(
-                        handle.0,
-                        ColliderBroadPhaseData {
-                            proxy_index: new_proxy_id,
-                        },
-                    );
-                }
-            }
-        }
-
-        // Phase 3: bottom-up pass to propagate new regions from smaller layers to larger layers.
-        if need_region_propagation {
-            self.propagate_created_regions();
-        }
-
-        // Phase 4: top-down pass to propagate proxies from larger layers to smaller layers.
-        self.update_layers_and_find_pairs(events);
-
-        // Phase 5: bottom-up pass to remove proxies, and propagate region removed from smaller
-        // layers to possible remove regions from larger layers that would become empty that way.
-        self.complete_removals(removed_colliders);
-    }
-
     /// Propagate regions from the smallest layers up to the larger layers.
     ///
     /// Whenever a region is created on a layer `n`, then its AABB must be
@@ -578,14 +680,16 @@ impl BroadPhase {
 
                 match (&mut proxy1.data, &mut proxy2.data) {
                     (SAPProxyData::Collider(handle1), SAPProxyData::Collider(handle2)) => {
+                        let pair = ColliderPair::new(*handle1, *handle2);
+
                         if *colliding {
-                            out_events.push(BroadPhasePairEvent::AddPair(ColliderPair::new(
-                                *handle1, *handle2,
-                            )));
+                            self.num_added_pairs += 1;
+                            let _ = self.pairs.insert((*proxy_id1, *proxy_id2), pair);
+                            out_events.push(BroadPhasePairEvent::AddPair(pair));
                         } else {
-                            out_events.push(BroadPhasePairEvent::DeletePair(ColliderPair::new(
-                                *handle1, *handle2,
-                            )));
+                            self.num_removed_pairs += 1;
+                            let _ = self.pairs.remove(&(*proxy_id1, *proxy_id2));
+                            out_events.push(BroadPhasePairEvent::DeletePair(pair));
                         }
                     }
                     (SAPProxyData::Collider(_), SAPProxyData::Region(_)) => {
@@ -618,16 +722,197 @@ impl BroadPhase {
     }
 }
 
+impl BroadPhase for BroadPhaseMultiSap {
+    fn update<Colliders>(
+        &mut self,
+        prediction_distance: Real,
+        colliders: &mut Colliders,
+        modified_colliders: &[ColliderHandle],
+        removed_colliders: &[ColliderHandle],
+        events: &mut Vec<BroadPhasePairEvent>,
+    ) where
+        Colliders: ComponentSetMut<ColliderBroadPhaseData>
+            + ComponentSet<ColliderChanges>
+            + ComponentSet<ColliderPosition>
+            + ComponentSet<ColliderShape>
+            + ComponentSet<ColliderFlags>,
+    {
+        self.num_added_pairs = 0;
+        self.num_removed_pairs = 0;
+
+        // Phase 1: pre-delete the collisions that have been deleted.
+        self.handle_removed_colliders(removed_colliders);
+
+        let mut need_region_propagation = false;
+        let mut disabled_colliders = vec![];
+
+        // Phase 2: pre-delete the collisions that have been deleted.
+        for handle in modified_colliders {
+            // NOTE: we use `get` because the collider may no longer
+            //       exist if it has been removed.
+            let co_changes: Option<&ColliderChanges> = colliders.get(handle.0);
+
+            if let Some(co_changes) = co_changes {
+                let (co_bf_data, co_pos, co_shape, co_flags): (
+                    &ColliderBroadPhaseData,
+                    &ColliderPosition,
+                    &ColliderShape,
+                    &ColliderFlags,
+                ) = colliders.index_bundle(handle.0);
+
+                if !co_changes.needs_broad_phase_update() {
+                    continue;
+                }
+
+                if !co_flags.enabled.is_enabled() {
+                    // Disabled colliders are removed from the broad-phase until they are enabled again.
+                    if co_bf_data.proxy_index != crate::INVALID_U32 {
+                        self.predelete_proxy(co_bf_data.proxy_index);
+                        disabled_colliders.push(*handle);
+                        colliders.set_internal(handle.0, ColliderBroadPhaseData::default());
+                    }
+                    continue;
+                }
+
+                let mut new_proxy_id = co_bf_data.proxy_index;
+
+                if self.handle_modified_collider(
+                    prediction_distance,
+                    *handle,
+                    &mut new_proxy_id,
+                    (co_pos, co_shape, co_changes),
+                ) {
+                    need_region_propagation = true;
+                }
+
+                if co_bf_data.proxy_index != new_proxy_id {
+                    self.colliders_proxy_ids.insert(*handle, new_proxy_id);
+
+                    // Make sure we have the new proxy index in case
+                    // the collider was added for the first time.
+                    colliders.set_internal(
+                        handle.0,
+                        ColliderBroadPhaseData {
+                            proxy_index: new_proxy_id,
+                        },
+                    );
+                }
+            }
+        }
+
+        // Phase 3: bottom-up pass to propagate new regions from smaller layers to larger layers.
+        if need_region_propagation {
+            self.propagate_created_regions();
+        }
+
+        // Phase 4: top-down pass to propagate proxies from larger layers to smaller layers.
+        self.update_layers_and_find_pairs(events);
+
+        // Phase 5: bottom-up pass to remove proxies, and propagate region removed from smaller
+        // layers to possible remove regions from larger layers that would become empty that way.
+        self.complete_removals(removed_colliders);
+
+        let disabled_proxies: Vec<_> = disabled_colliders
+            .iter()
+            .filter_map(|handle| self.colliders_proxy_ids.remove(handle))
+            .collect();
+        self.remove_proxies(&disabled_proxies);
+    }
+
+    fn for_each_pair(&self, mut callback: impl FnMut(&ColliderPair) -> bool) {
+        for pair in self.pairs.values() {
+            if !callback(pair) {
+                return;
+            }
+        }
+    }
+
+    fn colliders_with_aabb_intersecting_aabb(
+        &self,
+        aabb: &AABB,
+        mut callback: impl FnMut(&ColliderHandle) -> bool,
+    ) {
+        if self.layers.is_empty() {
+            return;
+        }
+
+        let aabb = AABB::new(super::clamp_point(aabb.mins), super::clamp_point(aabb.maxs));
+        let mut curr_layer = Some(self.smallest_layer);
+
+        while let Some(curr_layer_id) = curr_layer {
+            let layer = &self.layers[curr_layer_id as usize];
+            let go_on =
+                layer.for_each_proxy_intersecting_aabb(
+                    &self.proxies,
+                    &aabb,
+                    |proxy_id| match &self.proxies[proxy_id].data {
+                        SAPProxyData::Collider(handle) => callback(handle),
+                        SAPProxyData::Region(_) => true,
+                    },
+                );
+
+            if !go_on {
+                return;
+            }
+
+            curr_layer = layer.larger_layer;
+        }
+    }
+
+    fn shift_origin(&mut self, shift: &Vector<Real>, events: &mut Vec<BroadPhasePairEvent>) {
+        let mut need_region_propagation = false;
+        self.num_added_pairs = 0;
+        self.num_removed_pairs = 0;
+
+        // Translate the collider proxies as if they all moved by `-shift`. Their regions
+        // are updated the same way as for any other motion.
+        for proxy_index in 0..self.proxies.elements.len() as SAPProxyIndex {
+            let proxy = &mut self.proxies[proxy_index];
+
+            // NOTE: the deleted proxies have an AABB set to DELETED_AABB_VALUE.
+            if proxy.data.is_region() || proxy.aabb.mins.x == super::DELETED_AABB_VALUE {
+                continue;
+            }
+
+            let prev_aabb = proxy.aabb;
+            let aabb = AABB::new(
+                super::clamp_point(prev_aabb.mins - shift),
+                super::clamp_point(prev_aabb.maxs - shift),
+            );
+            proxy.aabb = aabb;
+            let layer_id = proxy.layer_id;
+
+            if self.preupdate_proxy(layer_id, proxy_index, &prev_aabb, &aabb) {
+                need_region_propagation = true;
+            }
+        }
+
+        if need_region_propagation {
+            self.propagate_created_regions();
+        }
+
+        self.update_layers_and_find_pairs(events);
+
+        // Remove the regions that became empty.
+        self.complete_removals(&[]);
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::dynamics::{
         ImpulseJointSet, IslandManager, MultibodyJointSet, RigidBodyBuilder, RigidBodySet,
     };
-    use crate::geometry::{BroadPhase, ColliderBuilder, ColliderSet};
+    use crate::geometry::{
+        BroadPhase, BroadPhaseBvh, BroadPhaseMultiSap, BroadPhaseMultiSapParameters,
+        BroadPhasePairEvent, ColliderBuilder, ColliderHandle, ColliderSet, AABB,
+    };
+    use crate::math::{Point, Real, Vector};
+    use parry::bounding_volume::BoundingVolume;
 
     #[test]
     fn test_add_update_remove() {
-        let mut broad_phase = BroadPhase::new();
+        let mut broad_phase = BroadPhaseMultiSap::new();
         let mut bodies = RigidBodySet::new();
         let mut colliders = ColliderSet::new();
         let mut impulse_joints = ImpulseJointSet::new();
@@ -660,4 +945,182 @@ mod test {
         // Make sure the proxy handles is recycled properly.
         broad_phase.update(0.0, &mut colliders, &[coh], &[], &mut events);
     }
+
+    #[test]
+    fn test_region_layout_and_stats() {
+        const PIXELS_PER_METER: Real = 100.0;
+
+        // The same scene, in meters, and in pixels.
+        let mut broad_phases = [
+            BroadPhaseMultiSap::new(),
+            BroadPhaseMultiSap::with_parameters(BroadPhaseMultiSapParameters {
+                region_width_base: PIXELS_PER_METER,
+                ..BroadPhaseMultiSapParameters::default()
+            }),
+        ];
+        let mut all_stats = vec![];
+
+        for (broad_phase, scale) in broad_phases.iter_mut().zip([1.0, PIXELS_PER_METER]) {
+            let mut colliders = ColliderSet::new();
+            let mut handles = vec![];
+
+            for i in 0..10 {
+                let co = ColliderBuilder::ball(0.6 * scale)
+                    .translation(Vector::x() * i as Real * scale)
+                    .build();
+                handles.push(colliders.insert(co));
+            }
+
+            // A large ball under all the others, on a larger layer.
+            let mut ground_pos = Vector::zeros();
+            ground_pos.x = 4.5 * scale;
+            ground_pos.y = -12.5 * scale;
+            let ground = ColliderBuilder::ball(12.0 * scale)
+                .translation(ground_pos)
+                .build();
+            handles.push(colliders.insert(ground));
+
+            let mut events = Vec::new();
+            broad_phase.update(0.0, &mut colliders, &handles, &[], &mut events);
+
+            let stats = broad_phase.stats();
+            let num_added = events
+                .iter()
+                .filter(|e| matches!(e, BroadPhasePairEvent::AddPair(_)))
+                .count();
+            assert_eq!(stats.num_added_pairs, num_added);
+            assert_eq!(stats.num_removed_pairs, 0);
+            // 9 pairs of neighbor balls, and 10 ball-ground pairs.
+            assert_eq!(stats.num_added_pairs, 19);
+            assert_eq!(stats.num_layers(), 2);
+
+            colliders.remove(
+                handles[0],
+                &mut IslandManager::new(),
+                &mut RigidBodySet::new(),
+                true,
+            );
+            events.clear();
+            broad_phase.update(0.0, &mut colliders, &[], &[handles[0]], &mut events);
+
+            // NOTE: the SAP may report the deletion of pairs with every proxy sharing
+            //       a region with the removed one, even if their AABBs didn't intersect.
+            let stats = broad_phase.stats();
+            assert_eq!(stats.num_added_pairs, 0);
+            assert_eq!(stats.num_removed_pairs, events.len());
+            assert!(stats.num_removed_pairs >= 2);
+            all_stats.push(stats);
+        }
+
+        // Both layouts result in the same regions, scaled.
+        for (layer_m, layer_px) in all_stats[0].layers.iter().zip(&all_stats[1].layers) {
+            assert_eq!(layer_m.depth, layer_px.depth);
+            assert_eq!(
+                layer_m.region_width * PIXELS_PER_METER,
+                layer_px.region_width
+            );
+
+            let mut num_proxies_m = layer_m.num_proxies_per_region.clone();
+            let mut num_proxies_px = layer_px.num_proxies_per_region.clone();
+            num_proxies_m.sort_unstable();
+            num_proxies_px.sort_unstable();
+            assert_eq!(num_proxies_m, num_proxies_px);
+        }
+    }
+
+    fn check_pair_and_aabb_queries(mut broad_phase: impl BroadPhase) {
+        let mut colliders = ColliderSet::new();
+        let mut events = Vec::new();
+
+        // A row of balls, each touching only its direct neighbors, and a large
+        // ball, on another layer of the SAP, touching one small ball.
+        let mut handles: Vec<_> = (0..20)
+            .map(|i| {
+                let co = ColliderBuilder::ball(0.5)
+                    .translation(Vector::x() * i as Real * 0.95)
+                    .build();
+                colliders.insert(co)
+            })
+            .collect();
+        let co = ColliderBuilder::ball(5.0)
+            .translation(Vector::x() * 100.0)
+            .build();
+        handles.push(colliders.insert(co));
+        let co = ColliderBuilder::ball(0.5)
+            .translation(Vector::x() * 105.4)
+            .build();
+        handles.push(colliders.insert(co));
+
+        broad_phase.update(0.0, &mut colliders, &handles, &[], &mut events);
+
+        let mut num_pairs = 0;
+        broad_phase.for_each_pair(|pair| {
+            let aabb1 = colliders[pair.collider1].compute_aabb();
+            let aabb2 = colliders[pair.collider2].compute_aabb();
+            assert!(aabb1.intersects(&aabb2));
+            num_pairs += 1;
+            true
+        });
+        assert_eq!(num_pairs, 20);
+
+        let mut num_visited = 0;
+        broad_phase.for_each_pair(|_| {
+            num_visited += 1;
+            false
+        });
+        assert_eq!(num_visited, 1);
+
+        let mut query_aabbs = [
+            AABB::new(Point::origin(), Point::origin()),
+            AABB::new(Point::origin() + Vector::x() * 99.0, Point::origin()),
+            AABB::new(
+                Point::origin() - Vector::repeat(1000.0),
+                Vector::repeat(1000.0).into(),
+            ),
+        ];
+        query_aabbs[0].mins.x = 2.0;
+        query_aabbs[0].maxs.x = 4.0;
+        query_aabbs[1].maxs.x = 101.0;
+
+        for (query_aabb, expected) in query_aabbs.iter().zip([3, 1, 22]) {
+            let mut found: Vec<ColliderHandle> = vec![];
+            broad_phase.colliders_with_aabb_intersecting_aabb(query_aabb, |handle| {
+                found.push(*handle);
+                true
+            });
+
+            // Each collider must be reported only once.
+            assert_eq!(found.len(), expected);
+            found.sort_by_key(|handle| handle.into_raw_parts());
+            found.dedup();
+            assert_eq!(found.len(), expected);
+            assert!(found
+                .iter()
+                .all(|h| colliders[*h].compute_aabb().intersects(query_aabb)));
+        }
+
+        // Removing a ball removes its two pairs.
+        let _ = colliders.remove(
+            handles[10],
+            &mut IslandManager::new(),
+            &mut RigidBodySet::new(),
+            false,
+        );
+        let removed = colliders.take_removed();
+        broad_phase.update(0.0, &mut colliders, &[], &removed, &mut events);
+
+        let mut num_pairs = 0;
+        broad_phase.for_each_pair(|pair| {
+            assert!(pair.collider1 != handles[10] && pair.collider2 != handles[10]);
+            num_pairs += 1;
+            true
+        });
+        assert_eq!(num_pairs, 18);
+    }
+
+    #[test]
+    fn test_pair_and_aabb_queries() {
+        check_pair_and_aabb_queries(BroadPhaseMultiSap::new());
+        check_pair_and_aabb_queries(BroadPhaseBvh::new());
+    }
 }
@@ -1,4 +1,7 @@
-pub use self::broad_phase::BroadPhase;
+pub use self::broad_phase::{
+    BroadPhaseMultiSap, BroadPhaseMultiSapLayerStats, BroadPhaseMultiSapParameters,
+    BroadPhaseMultiSapStats,
+};
 pub use self::broad_phase_pair_event::{BroadPhasePairEvent, ColliderPair};
 pub use self::sap_proxy::SAPProxyIndex;
 
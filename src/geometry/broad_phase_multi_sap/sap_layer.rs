@@ -1,4 +1,4 @@
-use super::{SAPProxies, SAPProxy, SAPRegion, SAPRegionPool};
+use super::{BroadPhaseMultiSapLayerStats, SAPProxies, SAPProxy, SAPRegion, SAPRegionPool};
 use crate::geometry::broad_phase_multi_sap::DELETED_AABB_VALUE;
 use crate::geometry::{SAPProxyIndex, AABB};
 use crate::math::{Point, Real};
@@ -26,19 +26,122 @@ impl SAPLayer {
         layer_id: u8,
         smaller_layer: Option<u8>,
         larger_layer: Option<u8>,
+        region_width: Real,
     ) -> Self {
         Self {
             depth,
             smaller_layer,
             larger_layer,
             layer_id,
-            region_width: super::region_width(depth),
+            region_width,
             regions: HashMap::default(),
             regions_to_potentially_remove: vec![],
             created_regions: vec![],
         }
     }
 
+    /// Statistics about the regions of this layer.
+    pub fn stats(&self, proxies: &SAPProxies) -> BroadPhaseMultiSapLayerStats {
+        let num_proxies_per_region = self
+            .regions
+            .values()
+            .map(|region_id| {
+                proxies[*region_id]
+                    .data
+                    .as_region()
+                    .num_collider_proxies(proxies)
+            })
+            .collect();
+
+        BroadPhaseMultiSapLayerStats {
+            depth: self.depth,
+            region_width: self.region_width,
+            num_proxies_per_region,
+        }
+    }
+
+    /// Calls `callback` on each collider proxy of this layer (excluding the proxies from
+    /// other layers) with an AABB intersecting `aabb`.
+    ///
+    /// Returns `false` if `callback` returned `false`, stopping the iteration.
+    pub fn for_each_proxy_intersecting_aabb(
+        &self,
+        proxies: &SAPProxies,
+        aabb: &AABB,
+        mut callback: impl FnMut(SAPProxyIndex) -> bool,
+    ) -> bool {
+        let start = super::point_key(aabb.mins, self.region_width);
+        let end = super::point_key(aabb.maxs, self.region_width);
+        let num_keys = (end - start)
+            .iter()
+            .map(|e| *e as f64 + 1.0)
+            .product::<f64>();
+
+        let mut visit_region = |region_key: &Point<i32>, region_id: SAPProxyIndex| {
+            let region = proxies[region_id].data.as_region();
+
+            for endpoint in &region.axes[0].endpoints {
+                if !endpoint.is_start() || endpoint.is_sentinel() {
+                    continue;
+                }
+
+                let proxy = &proxies[endpoint.proxy()];
+
+                // NOTE: a proxy intersecting several regions is only reported by the region
+                //       containing the minimum of its intersection with `aabb`.
+                if proxy.data.is_region()
+                    || proxy.layer_id != self.layer_id
+                    || !proxy.aabb.intersects(aabb)
+                    || super::point_key(proxy.aabb.mins.sup(&aabb.mins), self.region_width)
+                        != *region_key
+                {
+                    continue;
+                }
+
+                if !callback(endpoint.proxy()) {
+                    return false;
+                }
+            }
+
+            true
+        };
+
+        if num_keys > self.regions.len() as f64 {
+            // The AABB covers more cells than there are regions: just check all the regions.
+            for (region_key, region_id) in &self.regions {
+                if proxies[*region_id].aabb.intersects(aabb)
+                    && !visit_region(region_key, *region_id)
+                {
+                    return false;
+                }
+            }
+        } else {
+            #[cfg(feature = "dim2")]
+            let k_range = 0..1;
+            #[cfg(feature = "dim3")]
+            let k_range = start.z..=end.z;
+
+            for i in start.x..=end.x {
+                for j in start.y..=end.y {
+                    for _k in k_range.clone() {
+                        #[cfg(feature = "dim2")]
+                        let region_key = Point::new(i, j);
+                        #[cfg(feature = "dim3")]
+                        let region_key = Point::new(i, j, _k);
+
+                        if let Some(region_id) = self.regions.get(&region_key) {
+                            if !visit_region(&region_key, *region_id) {
+                                return false;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        true
+    }
+
     /// Deletes from all the regions of this layer, all the endpoints corresponding
     /// to subregions. Clears the arrays of subregions indices from all the regions of
     /// this layer.
@@ -71,7 +174,7 @@ impl SAPLayer {
     ///
     /// This method must be called in a bottom-up loop, propagating new regions from the
     /// smallest layer, up to the largest layer. That loop is done by the Phase 3 of the
-    /// BroadPhase::update.
+    /// BroadPhaseMultiSap::update.
     pub fn propagate_created_regions(
         &mut self,
         larger_layer: &mut Self,
@@ -182,7 +285,7 @@ impl SAPLayer {
     /// If the region with the given region key does not exist yet, it is created.
     /// When a region is created, it creates a new proxy for that region, and its
     /// proxy ID is added to `self.created_region` so it can be propagated during
-    /// the Phase 3 of `BroadPhase::update`.
+    /// the Phase 3 of `BroadPhaseMultiSap::update`.
     ///
     /// This returns the proxy ID of the already existing region if it existed, or
     /// of the new region if it did not exist and has been created by this method.
@@ -81,6 +81,15 @@ impl SAPRegion {
         }
     }
 
+    /// The number of collider proxies (ignoring subregions) currently inserted in this region.
+    pub fn num_collider_proxies(&self, proxies: &SAPProxies) -> usize {
+        self.axes[0]
+            .endpoints
+            .iter()
+            .filter(|e| e.is_start() && !e.is_sentinel() && !proxies[e.proxy()].data.is_region())
+            .count()
+    }
+
     /// Does this region still contain endpoints of subproper proxies?
     pub fn contains_subproper_proxies(&self) -> bool {
         self.subproper_proxy_count > 0
@@ -1,3 +1,4 @@
+use super::BroadPhaseMultiSapParameters;
 use crate::math::{Point, Real, Vector};
 use parry::bounding_volume::AABB;
 
@@ -6,8 +7,8 @@ pub(crate) const NEXT_FREE_SENTINEL: u32 = u32::MAX;
 pub(crate) const SENTINEL_VALUE: Real = Real::MAX;
 pub(crate) const DELETED_AABB_VALUE: Real = SENTINEL_VALUE / 2.0;
 pub(crate) const MAX_AABB_EXTENT: Real = SENTINEL_VALUE / 4.0;
-pub(crate) const REGION_WIDTH_BASE: Real = 1.0;
-pub(crate) const REGION_WIDTH_POWER_BASIS: Real = 5.0;
+pub(crate) const DEFAULT_REGION_WIDTH_BASE: Real = 1.0;
+pub(crate) const DEFAULT_REGION_WIDTH_POWER_BASIS: Real = 5.0;
 
 pub(crate) fn sort2(a: u32, b: u32) -> (u32, u32) {
     assert_ne!(a, b);
@@ -36,8 +37,9 @@ pub(crate) fn region_aabb(index: Point<i32>, region_width: Real) -> AABB {
     AABB::new(mins, maxs)
 }
 
-pub(crate) fn region_width(depth: i8) -> Real {
-    (REGION_WIDTH_BASE * REGION_WIDTH_POWER_BASIS.powi(depth as i32)).min(MAX_AABB_EXTENT)
+pub(crate) fn region_width(depth: i8, params: &BroadPhaseMultiSapParameters) -> Real {
+    (params.region_width_base * params.region_width_power_basis.powi(depth as i32))
+        .min(MAX_AABB_EXTENT)
 }
 
 /// Computes the depth of the layer the given AABB should be part of.
@@ -49,13 +51,13 @@ pub(crate) fn region_width(depth: i8) -> Real {
 ///
 /// If the code bellow, we select a layer such that each region can
 /// contain at least a chain of 10 contiguous objects with that AABB.
-pub(crate) fn layer_containing_aabb(aabb: &AABB) -> i8 {
+pub(crate) fn layer_containing_aabb(aabb: &AABB, params: &BroadPhaseMultiSapParameters) -> i8 {
     // Max number of elements of this size we would like one region to be able to contain.
     const NUM_ELEMENTS_PER_DIMENSION: Real = 10.0;
 
     let width = 2.0 * aabb.half_extents().norm() * NUM_ELEMENTS_PER_DIMENSION;
-    (width / REGION_WIDTH_BASE)
-        .log(REGION_WIDTH_POWER_BASIS)
+    (width / params.region_width_base)
+        .log(params.region_width_power_basis)
         .round()
         .max(i8::MIN as Real)
         .min(i8::MAX as Real) as i8
@@ -1,6 +1,6 @@
 use crate::dynamics::{CoefficientCombineRule, MassProperties, RigidBodyHandle};
 use crate::geometry::{
-    ActiveCollisionTypes, ColliderBroadPhaseData, ColliderChanges, ColliderFlags,
+    ActiveCollisionTypes, ColliderBroadPhaseData, ColliderChanges, ColliderEnabled, ColliderFlags,
     ColliderMassProps, ColliderMaterial, ColliderParent, ColliderPosition, ColliderShape,
     ColliderType, InteractionGroups, SharedShape,
 };
@@ -27,6 +27,8 @@ pub struct Collider {
     pub(crate) co_flags: ColliderFlags,
     pub(crate) co_bf_data: ColliderBroadPhaseData,
     /// User-defined data associated to this collider.
+    ///
+    /// Use a [`UserDataMap`](crate::data::UserDataMap) to attach typed data instead.
     pub user_data: u128,
 }
 
@@ -136,6 +138,53 @@ impl Collider {
         }
     }
 
+    /// Are contact manifolds computed for the intersection pairs involving this collider, if it is a sensor?
+    pub fn is_sensor_contacts_enabled(&self) -> bool {
+        self.co_flags.sensor_contacts_enabled
+    }
+
+    /// Enables or disables the computation of contact manifolds for the intersection pairs
+    /// involving this collider, if it is a sensor.
+    ///
+    /// These manifolds can be read with [`NarrowPhase::sensor_contact_pair`](crate::geometry::NarrowPhase::sensor_contact_pair),
+    /// e.g., to know where and in which direction a collider entered the sensor. They
+    /// don't generate any force. This has no effect if this collider isn't a sensor.
+    pub fn enable_sensor_contacts(&mut self, enabled: bool) {
+        if enabled != self.co_flags.sensor_contacts_enabled {
+            self.co_changes.insert(ColliderChanges::TYPE);
+            self.co_flags.sensor_contacts_enabled = enabled;
+        }
+    }
+
+    /// Is this collider enabled?
+    ///
+    /// This returns `false` if the collider itself, or its parent rigid-body, is disabled.
+    pub fn is_enabled(&self) -> bool {
+        self.co_flags.enabled.is_enabled()
+    }
+
+    /// Enables or disables this collider.
+    ///
+    /// A disabled collider keeps its handle but no longer participates to collision detection
+    /// and scene queries. A collider attached to a disabled rigid-body remains disabled until
+    /// its parent is enabled again.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        match self.co_flags.enabled {
+            ColliderEnabled::Enabled | ColliderEnabled::DisabledByParent => {
+                if !enabled {
+                    self.co_changes.insert(ColliderChanges::ENABLED_OR_DISABLED);
+                    self.co_flags.enabled = ColliderEnabled::Disabled;
+                }
+            }
+            ColliderEnabled::Disabled => {
+                if enabled {
+                    self.co_changes.insert(ColliderChanges::ENABLED_OR_DISABLED);
+                    self.co_flags.enabled = ColliderEnabled::Enabled;
+                }
+            }
+        }
+    }
+
     /// Sets the translational part of this collider's position.
     pub fn set_translation(&mut self, translation: Vector<Real>) {
         self.co_changes.insert(ColliderChanges::POSITION);
@@ -322,6 +371,11 @@ pub struct ColliderBuilder {
     pub collision_groups: InteractionGroups,
     /// The solver groups for the collider being built.
     pub solver_groups: InteractionGroups,
+    /// Will the collider be enabled?
+    pub enabled: bool,
+    /// Whether contact manifolds are computed for the intersection pairs of the collider
+    /// to be built if it is a sensor, `false` by default.
+    pub sensor_contacts_enabled: bool,
 }
 
 impl ColliderBuilder {
@@ -343,6 +397,8 @@ impl ColliderBuilder {
             active_collision_types: ActiveCollisionTypes::default(),
             active_hooks: ActiveHooks::empty(),
             active_events: ActiveEvents::empty(),
+            enabled: true,
+            sensor_contacts_enabled: false,
         }
     }
 
@@ -625,6 +681,19 @@ impl ColliderBuilder {
         self
     }
 
+    /// Sets whether contact manifolds are computed for the intersection pairs of the
+    /// collider built by this builder, if it is a sensor.
+    pub fn sensor_contacts_enabled(mut self, enabled: bool) -> Self {
+        self.sensor_contacts_enabled = enabled;
+        self
+    }
+
+    /// Sets whether or not the collider built by this builder is enabled.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
     /// The set of physics hooks enabled for this collider.
     pub fn active_hooks(mut self, active_hooks: ActiveHooks) -> Self {
         self.active_hooks = active_hooks;
@@ -780,6 +849,12 @@ impl ColliderBuilder {
             active_collision_types: self.active_collision_types,
             active_hooks: self.active_hooks,
             active_events: self.active_events,
+            enabled: if self.enabled {
+                ColliderEnabled::Enabled
+            } else {
+                ColliderEnabled::Disabled
+            },
+            sensor_contacts_enabled: self.sensor_contacts_enabled,
         };
         let co_changes = ColliderChanges::all();
         let co_pos = ColliderPosition(self.position);
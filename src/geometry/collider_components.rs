@@ -41,6 +41,16 @@ impl IndexedData for ColliderHandle {
     }
 }
 
+impl crate::data::ArenaHandle for ColliderHandle {
+    fn from_arena_index(index: crate::data::arena::Index) -> Self {
+        Self(index)
+    }
+
+    fn arena_index(self) -> crate::data::arena::Index {
+        self.0
+    }
+}
+
 bitflags::bitflags! {
     #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
     /// Flags describing how the collider has been modified by the user.
@@ -62,6 +72,8 @@ bitflags::bitflags! {
         /// This flags is automatically set by the `PhysicsPipeline` when the `RigidBodyChanges::DOMINANCE`
         /// or `RigidBodyChanges::TYPE` of the parent rigid-body of this collider is detected.
         const PARENT_EFFECTIVE_DOMINANCE = 1 << 6; // NF update.
+        /// Flag indicating that the collider, or its parent rigid-body, has been enabled or disabled.
+        const ENABLED_OR_DISABLED = 1 << 7; // => BF & NF update.
     }
 }
 
@@ -75,7 +87,10 @@ impl ColliderChanges {
     /// Do these changes justify a broad-phase update?
     pub fn needs_broad_phase_update(self) -> bool {
         self.intersects(
-            ColliderChanges::PARENT | ColliderChanges::POSITION | ColliderChanges::SHAPE,
+            ColliderChanges::PARENT
+                | ColliderChanges::POSITION
+                | ColliderChanges::SHAPE
+                | ColliderChanges::ENABLED_OR_DISABLED,
         )
     }
 
@@ -351,6 +366,25 @@ impl Default for ActiveCollisionTypes {
     }
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+/// Indicates whether a collider participates to collision detection and scene queries.
+pub enum ColliderEnabled {
+    /// The collider is enabled.
+    Enabled,
+    /// The collider has been disabled by the user.
+    Disabled,
+    /// The collider is enabled but its parent rigid-body has been disabled.
+    DisabledByParent,
+}
+
+impl ColliderEnabled {
+    /// Is the collider enabled?
+    pub fn is_enabled(self) -> bool {
+        self == ColliderEnabled::Enabled
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 /// A set of flags for controlling collision/intersection filtering, modification, and events.
@@ -368,6 +402,12 @@ pub struct ColliderFlags {
     pub active_hooks: ActiveHooks,
     /// The events enabled for this collider.
     pub active_events: ActiveEvents,
+    /// Is this collider enabled?
+    pub enabled: ColliderEnabled,
+    /// If this collider is a sensor, are contact manifolds computed for its intersection pairs?
+    ///
+    /// These manifolds are never used by the constraints solver.
+    pub sensor_contacts_enabled: bool,
 }
 
 impl Default for ColliderFlags {
@@ -378,6 +418,8 @@ impl Default for ColliderFlags {
             solver_groups: InteractionGroups::all(),
             active_hooks: ActiveHooks::empty(),
             active_events: ActiveEvents::empty(),
+            enabled: ColliderEnabled::Enabled,
+            sensor_contacts_enabled: false,
         }
     }
 }
@@ -1,5 +1,5 @@
 use crate::data::arena::Arena;
-use crate::data::{ComponentSet, ComponentSetMut, ComponentSetOption};
+use crate::data::{ComponentSet, ComponentSetMut, ComponentSetOption, UserDataStorage};
 use crate::dynamics::{IslandManager, RigidBodyHandle, RigidBodySet};
 use crate::geometry::{
     Collider, ColliderBroadPhaseData, ColliderFlags, ColliderMassProps, ColliderMaterial,
@@ -15,6 +15,8 @@ pub struct ColliderSet {
     pub(crate) colliders: Arena<Collider>,
     pub(crate) modified_colliders: Vec<ColliderHandle>,
     pub(crate) removed_colliders: Vec<ColliderHandle>,
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    user_data: UserDataStorage<ColliderHandle>,
 }
 
 macro_rules! impl_field_component_set(
@@ -81,9 +83,23 @@ impl ColliderSet {
             colliders: Arena::new(),
             modified_colliders: Vec::new(),
             removed_colliders: Vec::new(),
+            user_data: UserDataStorage::default(),
         }
     }
 
+    /// The typed user data attached to the colliders of this set.
+    pub fn user_data(&self) -> &UserDataStorage<ColliderHandle> {
+        &self.user_data
+    }
+
+    /// The typed user data attached to the colliders of this set.
+    ///
+    /// The user data attached to an element is detached when it is removed from this set. It
+    /// isn't serialized with this set: see `UserDataStorage::serialize_map`.
+    pub fn user_data_mut(&mut self) -> &mut UserDataStorage<ColliderHandle> {
+        &mut self.user_data
+    }
+
     pub(crate) fn take_modified(&mut self) -> Vec<ColliderHandle> {
         std::mem::replace(&mut self.modified_colliders, vec![])
     }
@@ -178,6 +194,74 @@ impl ColliderSet {
         handle
     }
 
+    /// Attaches an existing collider to another rigid-body, or detaches it if `new_parent_handle`
+    /// is `None`.
+    ///
+    /// The collider keeps its handle and its current world-space position: its position
+    /// relative to the new parent is computed from the current poses of the collider and of the
+    /// new parent. The mass properties of both the previous and the new parent are updated.
+    /// A detached collider no longer moves, as if it was attached to a static rigid-body.
+    /// Both the previous and the new parent are woken up.
+    ///
+    /// Panics if the new parent doesn't exist, in which case the collider is left untouched.
+    pub fn set_parent(
+        &mut self,
+        handle: ColliderHandle,
+        new_parent_handle: Option<RigidBodyHandle>,
+        bodies: &mut RigidBodySet,
+    ) {
+        // NOTE: check the new parent first so that the collider isn't left detached.
+        if let Some(new_parent_handle) = new_parent_handle {
+            assert!(
+                bodies.contains(new_parent_handle),
+                "Parent rigid body not found."
+            );
+        }
+
+        if let Some(collider) = self.colliders.get_mut(handle.0) {
+            Self::mark_as_modified(handle, collider, &mut self.modified_colliders);
+            collider.co_changes.insert(ColliderChanges::PARENT);
+
+            /*
+             * Detach the collider from its previous parent.
+             */
+            // NOTE: we use `get_mut_internal_with_modification_tracking` instead of `get_mut_internal` so that the
+            // modification flag is updated properly.
+            if let Some(prev_parent) = collider.co_parent {
+                if let Some(parent) =
+                    bodies.get_mut_internal_with_modification_tracking(prev_parent.handle)
+                {
+                    parent.remove_collider_internal(handle, collider);
+                    parent.wake_up(true);
+                }
+            }
+
+            collider.co_parent = None;
+
+            /*
+             * Attach the collider to its new parent.
+             */
+            if let Some(new_parent_handle) = new_parent_handle {
+                let parent = bodies
+                    .get_mut_internal_with_modification_tracking(new_parent_handle)
+                    .expect("Parent rigid body not found.");
+                let co_parent = collider.co_parent.insert(ColliderParent {
+                    handle: new_parent_handle,
+                    pos_wrt_parent: parent.position().inv_mul(&collider.co_pos.0),
+                });
+
+                parent.add_collider(
+                    handle,
+                    co_parent,
+                    &mut collider.co_pos,
+                    &collider.co_shape,
+                    &collider.co_mprops,
+                );
+                parent.wake_up(true);
+            }
+        }
+    }
+
     /// Remove a collider from this set and update its parent accordingly.
     ///
     /// If `wake_up` is `true`, the rigid-body the removed collider is attached to
@@ -190,6 +274,7 @@ impl ColliderSet {
         wake_up: bool,
     ) -> Option<Collider> {
         let collider = self.colliders.remove(handle.0)?;
+        self.user_data.detach(handle);
 
         /*
          * Delete the collider from its parent body.
@@ -301,3 +386,119 @@ impl IndexMut<ColliderHandle> for ColliderSet {
         collider
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::Vector;
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn collider_set_parent() {
+        let mut world = TestWorld::new(Vector::zeros());
+
+        let body = RigidBodyBuilder::new_dynamic().build();
+        let (body, body_collider) = world.insert_body(body, ColliderBuilder::ball(0.5).build());
+
+        // The item touches the body.
+        let item = RigidBodyBuilder::new_dynamic()
+            .translation(Vector::x() * 0.9)
+            .build();
+        let (item, item_collider) = world.insert_body(item, ColliderBuilder::ball(0.5).build());
+
+        world.step();
+        assert!(
+            world
+                .narrow_phase
+                .contact_pair(body_collider, item_collider)
+                .unwrap()
+                .has_any_active_contact
+        );
+
+        let body_mass = world.bodies[body].mass();
+        let item_pos = *world.colliders[item_collider].position();
+
+        // Pick up the item: both parents are woken up.
+        world.bodies[body].sleep();
+        world.bodies[item].sleep();
+        world
+            .colliders
+            .set_parent(item_collider, Some(body), &mut world.bodies);
+        let (bodies, colliders) = (&world.bodies, &world.colliders);
+        assert!(!bodies[body].is_sleeping());
+        assert!(!bodies[item].is_sleeping());
+        assert_eq!(colliders[item_collider].parent(), Some(body));
+        assert!(bodies[body].colliders().contains(&item_collider));
+        assert!(bodies[item].colliders().is_empty());
+        assert!((bodies[body].mass() - body_mass * 2.0).abs() < 1.0e-5);
+        assert_eq!(bodies[item].mass(), 0.0);
+        assert!(
+            (colliders[item_collider].position().translation.vector - item_pos.translation.vector)
+                .norm()
+                < 1.0e-5
+        );
+
+        world.bodies[body].set_linvel(Vector::y(), true);
+        world.step_n(10);
+
+        // The item follows the body and no longer collides with it.
+        assert!(world
+            .narrow_phase
+            .contact_pair(body_collider, item_collider)
+            .is_none());
+        let (bodies, colliders) = (&world.bodies, &world.colliders);
+        let expected_pos =
+            bodies[body].position() * colliders[item_collider].position_wrt_parent().unwrap();
+        assert!(
+            (colliders[item_collider].position().translation.vector
+                - expected_pos.translation.vector)
+                .norm()
+                < 1.0e-5
+        );
+
+        // Drop the item: it collides with the body again.
+        let item_pos = *world.colliders[item_collider].position();
+        world.bodies[body].sleep();
+        world
+            .colliders
+            .set_parent(item_collider, None, &mut world.bodies);
+        assert_eq!(world.colliders[item_collider].parent(), None);
+        assert!((world.bodies[body].mass() - body_mass).abs() < 1.0e-5);
+        assert!(!world.bodies[body].is_sleeping());
+
+        world.step();
+        assert_eq!(*world.colliders[item_collider].position(), item_pos);
+        assert!(
+            world
+                .narrow_phase
+                .contact_pair(body_collider, item_collider)
+                .unwrap()
+                .has_any_active_contact
+        );
+    }
+
+    #[test]
+    fn collider_set_parent_not_found() {
+        let mut world = TestWorld::new(Vector::zeros());
+        let (body, collider) = world.insert_body(
+            RigidBodyBuilder::new_dynamic().build(),
+            ColliderBuilder::ball(0.5).build(),
+        );
+        let (removed, _) = world.insert_body(
+            RigidBodyBuilder::new_dynamic().build(),
+            ColliderBuilder::ball(0.5).build(),
+        );
+        let _ = world.remove_body(removed);
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            world
+                .colliders
+                .set_parent(collider, Some(removed), &mut world.bodies)
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(world.colliders[collider].parent(), Some(body));
+        assert_eq!(world.bodies[body].colliders(), &[collider]);
+    }
+}
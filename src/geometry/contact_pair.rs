@@ -148,6 +148,8 @@ pub struct ContactManifoldData {
     /// The relative dominance of the bodies involved in this contact manifold.
     pub relative_dominance: i16,
     /// A user-defined piece of data.
+    ///
+    /// Use a [`ContactPairUserDataMap`](crate::data::ContactPairUserDataMap) to attach typed data instead.
     pub user_data: u32,
 }
 
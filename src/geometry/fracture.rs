@@ -0,0 +1,399 @@
+use crate::dynamics::{MassProperties, RigidBody, RigidBodyBuilder};
+use crate::geometry::{Collider, ColliderMassProps, ColliderPosition, SharedShape};
+use crate::math::{Isometry, Point, Real, Vector, DIM};
+use crate::utils;
+#[cfg(feature = "dim3")]
+use crate::utils::WBasis;
+use num::Zero;
+
+/// A fragment resulting from the fracture of a collider.
+#[derive(Clone)]
+pub struct Fragment {
+    /// The rigid-body the fragment collider should be attached to.
+    ///
+    /// It is located at the original position of the fragment and moves with
+    /// the velocity of the fractured rigid-body at that position.
+    pub body: RigidBody,
+    /// The collider of this fragment, expressed in the local-space of `self.body`.
+    pub collider: Collider,
+}
+
+/// Fracture of convex colliders into convex fragments, based on a Voronoi diagram.
+///
+/// The Voronoi sites are scattered pseudo-randomly around the impact point, with a density
+/// decreasing with the distance to the impact, so that the fragments are smaller near the
+/// impact. Each fragment is the intersection of the collider shape with the Voronoi cell of
+/// one site. Only colliders with a `Cuboid`, a `ConvexPolygon` (in 2D) or a `ConvexPolyhedron`
+/// (in 3D) shape can be fractured.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct VoronoiFracture {
+    /// The number of fragments to generate.
+    pub num_fragments: usize,
+    /// The radius of the region around the impact point where the Voronoi sites are scattered.
+    ///
+    /// If `None`, the radius of the bounding sphere of the fractured shape is used.
+    pub impact_radius: Option<Real>,
+    /// The seed of the pseudo-random generator used to scatter the Voronoi sites.
+    pub seed: u64,
+}
+
+impl VoronoiFracture {
+    /// Initializes the fracture of colliders into `num_fragments` fragments.
+    pub fn new(num_fragments: usize) -> Self {
+        Self {
+            num_fragments,
+            impact_radius: None,
+            seed: 0x2545_f491_4f6c_dd1d,
+        }
+    }
+
+    /// Sets the radius of the region around the impact point where the Voronoi sites are scattered.
+    pub fn impact_radius(mut self, radius: Real) -> Self {
+        self.impact_radius = Some(radius);
+        self
+    }
+
+    /// Sets the seed of the pseudo-random generator used to scatter the Voronoi sites.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Fractures a collider hit at the given world-space `impact_point`.
+    ///
+    /// The `parent` is the rigid-body the collider is attached to, if any. The fragments
+    /// inherit its properties and its velocity at their center-of-mass. The fragments
+    /// inherit the material, collision groups, and density of the collider (the density is
+    /// deduced from its mass if the collider was given explicit mass properties).
+    ///
+    /// Returns `None` if the shape of the collider is not supported. Fewer than
+    /// `self.num_fragments` fragments are returned if not enough Voronoi sites could be
+    /// placed inside of the collider shape, for example if the impact point is too far from it.
+    pub fn fracture(
+        &self,
+        collider: &Collider,
+        parent: Option<&RigidBody>,
+        impact_point: &Point<Real>,
+    ) -> Option<Vec<Fragment>> {
+        let shape = collider.shape();
+        let polytope = Polytope::from_shape(shape)?;
+        let radius = shape.compute_local_bounding_sphere().radius();
+        let eps = radius * 1.0e-5;
+        let local_impact = collider.position().inverse_transform_point(impact_point);
+        let sites = self.scatter_sites(shape, &local_impact, radius);
+
+        let density = match &collider.co_mprops {
+            ColliderMassProps::Density(density) => *density,
+            ColliderMassProps::MassProperties(mprops) => {
+                let volume = utils::inv(shape.mass_properties(1.0).inv_mass);
+                utils::inv(mprops.inv_mass) * utils::inv(volume)
+            }
+        };
+
+        let mut result = Vec::with_capacity(sites.len());
+
+        for (i, site) in sites.iter().enumerate() {
+            let mut cell = polytope.clone();
+
+            for (j, other) in sites.iter().enumerate() {
+                if i != j {
+                    // Keep the half-space closer to `site` than to `other`.
+                    let normal = other - site;
+                    let offset = normal.dot(&na::center(site, other).coords);
+                    cell = cell.clip(&normal, offset, eps);
+                }
+            }
+
+            let vertices = cell.vertices();
+
+            if vertices.len() <= DIM {
+                continue;
+            }
+
+            let center = vertices
+                .iter()
+                .fold(Point::origin(), |acc, pt| acc + pt.coords)
+                / vertices.len() as Real;
+            let local_vertices: Vec<_> = vertices.iter().map(|pt| pt - center.coords).collect();
+
+            if let Some(fragment_shape) = SharedShape::convex_hull(&local_vertices) {
+                let fragment_pos = collider.position() * Isometry::new(center.coords, na::zero());
+                result.push(Self::make_fragment(
+                    collider,
+                    parent,
+                    fragment_shape,
+                    fragment_pos,
+                    density,
+                ));
+            }
+        }
+
+        Some(result)
+    }
+
+    fn make_fragment(
+        collider: &Collider,
+        parent: Option<&RigidBody>,
+        shape: SharedShape,
+        position: Isometry<Real>,
+        density: Real,
+    ) -> Fragment {
+        let mut fragment_collider = collider.clone();
+        fragment_collider.co_parent = None;
+        fragment_collider.co_pos = ColliderPosition::identity();
+        fragment_collider.co_mprops = ColliderMassProps::Density(density);
+        fragment_collider.set_shape(shape);
+
+        let mut body = match parent {
+            Some(parent) => {
+                let mut body = parent.clone();
+                body.rb_mprops.local_mprops = MassProperties::zero();
+                body
+            }
+            None => RigidBodyBuilder::new_dynamic().build(),
+        };
+
+        body.set_position(position, true);
+
+        if let Some(parent) = parent {
+            let world_com = position * fragment_collider.mass_properties().local_com;
+            body.set_linvel(parent.velocity_at_point(&world_com), true);
+            body.set_angvel(parent.rb_vels.angvel, true);
+        }
+
+        Fragment {
+            body,
+            collider: fragment_collider,
+        }
+    }
+
+    /// Pseudo-randomly scatters the Voronoi sites inside of the shape, denser near the impact point.
+    fn scatter_sites(
+        &self,
+        shape: &dyn parry::shape::Shape,
+        impact_point: &Point<Real>,
+        shape_radius: Real,
+    ) -> Vec<Point<Real>> {
+        const MAX_ATTEMPTS_PER_SITE: usize = 100;
+        let radius = self.impact_radius.unwrap_or(shape_radius);
+        let mut rng = XorShift(self.seed.max(1));
+        let mut sites = Vec::with_capacity(self.num_fragments);
+
+        for _ in 0..self.num_fragments * MAX_ATTEMPTS_PER_SITE {
+            if sites.len() == self.num_fragments {
+                break;
+            }
+
+            let dir = Vector::from_fn(|_, _| rng.next_real() * 2.0 - 1.0);
+
+            if dir.norm_squared() > 1.0 {
+                continue;
+            }
+
+            // Scaling the direction by its norm squashes the uniform distribution toward the
+            // impact point, so that the sites (hence the fragments) are denser near the impact.
+            let site = impact_point + dir * (dir.norm() * radius);
+
+            if shape.contains_local_point(&site) && !sites.contains(&site) {
+                sites.push(site);
+            }
+        }
+
+        sites
+    }
+}
+
+/// A xorshift pseudo-random number generator, for a deterministic placement of Voronoi sites.
+struct XorShift(u64);
+
+impl XorShift {
+    /// Generates a pseudo-random number in `[0, 1)`.
+    fn next_real(&mut self) -> Real {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 11) as Real / (1u64 << 53) as Real
+    }
+}
+
+/// A convex polygon given by its vertices in counter-clockwise order.
+#[cfg(feature = "dim2")]
+#[derive(Clone)]
+struct Polytope(Vec<Point<Real>>);
+
+/// A convex polyhedron given by its polygonal faces.
+#[cfg(feature = "dim3")]
+#[derive(Clone)]
+struct Polytope(Vec<Vec<Point<Real>>>);
+
+impl Polytope {
+    #[cfg(feature = "dim2")]
+    fn from_shape(shape: &dyn parry::shape::Shape) -> Option<Self> {
+        if let Some(cuboid) = shape.as_cuboid() {
+            Some(Polytope(cuboid.to_polyline()))
+        } else {
+            shape
+                .as_convex_polygon()
+                .map(|poly| Polytope(poly.points().to_vec()))
+        }
+    }
+
+    #[cfg(feature = "dim3")]
+    fn from_shape(shape: &dyn parry::shape::Shape) -> Option<Self> {
+        let (vertices, indices) = if let Some(cuboid) = shape.as_cuboid() {
+            cuboid.to_trimesh()
+        } else {
+            shape.as_convex_polyhedron()?.to_trimesh()
+        };
+
+        let faces = indices
+            .iter()
+            .map(|idx| idx.iter().map(|i| vertices[*i as usize]).collect())
+            .collect();
+        Some(Polytope(faces))
+    }
+
+    fn vertices(&self) -> Vec<Point<Real>> {
+        #[cfg(feature = "dim2")]
+        return self.0.clone();
+        #[cfg(feature = "dim3")]
+        return self.0.iter().flatten().copied().collect();
+    }
+
+    /// Clips this polytope, keeping only its part where `normal.dot(x) <= offset`.
+    #[cfg(feature = "dim2")]
+    fn clip(&self, normal: &Vector<Real>, offset: Real, _eps: Real) -> Self {
+        Polytope(clip_polygon(&self.0, normal, offset))
+    }
+
+    /// Clips this polytope, keeping only its part where `normal.dot(x) <= offset`.
+    #[cfg(feature = "dim3")]
+    fn clip(&self, normal: &Vector<Real>, offset: Real, eps: Real) -> Self {
+        let normal_length = normal.norm();
+        let mut faces = Vec::with_capacity(self.0.len() + 1);
+        let mut cap: Vec<Point<Real>> = vec![];
+
+        for face in &self.0 {
+            let clipped = clip_polygon(face, normal, offset);
+
+            if clipped.len() < 3 {
+                continue;
+            }
+
+            // The points lying on the clipping plane form the new cap face.
+            for pt in &clipped {
+                if (normal.dot(&pt.coords) - offset).abs() <= eps * normal_length
+                    && cap.iter().all(|c| na::distance_squared(c, pt) > eps * eps)
+                {
+                    cap.push(*pt);
+                }
+            }
+
+            faces.push(clipped);
+        }
+
+        if cap.len() >= 3 {
+            // Sort the cap vertices by angle so they form a polygon.
+            let center =
+                cap.iter().fold(Point::origin(), |acc, pt| acc + pt.coords) / cap.len() as Real;
+            let [u, v] = (normal / normal_length).orthonormal_basis();
+            let angle = |pt: &Point<Real>| {
+                let dpt = pt - center;
+                dpt.dot(&v).atan2(dpt.dot(&u))
+            };
+
+            // Skip degenerate caps, e.g., if the clipping plane has a zero normal.
+            if cap.iter().all(|pt| angle(pt).is_finite()) {
+                cap.sort_by(|a, b| angle(a).total_cmp(&angle(b)));
+                faces.push(cap);
+            }
+        }
+
+        Polytope(faces)
+    }
+}
+
+/// Clips a convex polygon, keeping only its part where `normal.dot(x) <= offset`.
+fn clip_polygon(polygon: &[Point<Real>], normal: &Vector<Real>, offset: Real) -> Vec<Point<Real>> {
+    let mut result = Vec::with_capacity(polygon.len() + 1);
+
+    for (i, a) in polygon.iter().enumerate() {
+        let b = &polygon[(i + 1) % polygon.len()];
+        let da = normal.dot(&a.coords) - offset;
+        let db = normal.dot(&b.coords) - offset;
+
+        if da <= 0.0 {
+            result.push(*a);
+        }
+
+        if (da < 0.0 && db > 0.0) || (da > 0.0 && db < 0.0) {
+            result.push(a + (b - a) * (da / (da - db)));
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Fragment, VoronoiFracture};
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Real, Vector};
+
+    #[test]
+    fn voronoi_fracture_cuboid() {
+        #[cfg(feature = "dim2")]
+        let collider = ColliderBuilder::cuboid(1.0, 0.5).density(2.0).build();
+        #[cfg(feature = "dim3")]
+        let collider = ColliderBuilder::cuboid(1.0, 0.5, 0.25).density(2.0).build();
+        let body = RigidBodyBuilder::new_dynamic().linvel(Vector::x()).build();
+        let impact = Point::from(Vector::x() * 0.9);
+
+        let fragments = VoronoiFracture::new(8)
+            .fracture(&collider, Some(&body), &impact)
+            .unwrap();
+        assert_eq!(fragments.len(), 8);
+
+        let mass = |inv_mass: Real| crate::utils::inv(inv_mass);
+        let total_mass: Real = fragments
+            .iter()
+            .map(|f| mass(f.collider.mass_properties().inv_mass))
+            .sum();
+        let expected_mass = mass(collider.mass_properties().inv_mass);
+        assert!((total_mass - expected_mass).abs() < expected_mass * 1.0e-3);
+
+        for fragment in &fragments {
+            assert_eq!(fragment.collider.density(), Some(2.0));
+            assert!((fragment.body.linvel() - Vector::x()).norm() < 1.0e-5);
+        }
+    }
+
+    #[test]
+    fn voronoi_fracture_smaller_near_impact() {
+        #[cfg(feature = "dim2")]
+        let collider = ColliderBuilder::cuboid(2.0, 2.0).build();
+        #[cfg(feature = "dim3")]
+        let collider = ColliderBuilder::cuboid(2.0, 2.0, 2.0).build();
+        let impact = Point::origin();
+
+        let fragments = VoronoiFracture::new(32)
+            .fracture(&collider, None, &impact)
+            .unwrap();
+        let (near, far): (Vec<_>, Vec<_>) = fragments.iter().partition(|f| {
+            let com = f.body.position() * f.collider.mass_properties().local_com;
+            na::distance(&com, &impact) < 1.0
+        });
+        let mean_volume = |fragments: &[&Fragment]| {
+            fragments
+                .iter()
+                .map(|f| crate::utils::inv(f.collider.mass_properties().inv_mass))
+                .sum::<Real>()
+                / fragments.len() as Real
+        };
+
+        assert!(!near.is_empty() && !far.is_empty());
+        assert!(mean_volume(&near) * 2.0 < mean_volume(&far));
+    }
+}
@@ -1,6 +1,11 @@
 //! Structures related to geometry: colliders, shapes, etc.
 
-pub use self::broad_phase_multi_sap::{BroadPhase, BroadPhasePairEvent, ColliderPair};
+pub use self::broad_phase::{BroadPhase, DefaultBroadPhase};
+pub use self::broad_phase_bvh::BroadPhaseBvh;
+pub use self::broad_phase_multi_sap::{
+    BroadPhaseMultiSap, BroadPhaseMultiSapLayerStats, BroadPhaseMultiSapParameters,
+    BroadPhaseMultiSapStats, BroadPhasePairEvent, ColliderPair,
+};
 pub use self::collider_components::*;
 pub use self::contact_pair::{ContactData, ContactManifoldData};
 pub use self::contact_pair::{ContactPair, SolverContact, SolverFlags};
@@ -14,6 +19,8 @@ pub use self::narrow_phase::NarrowPhase;
 pub use self::collider::{Collider, ColliderBuilder};
 #[cfg(feature = "default-sets")]
 pub use self::collider_set::ColliderSet;
+#[cfg(feature = "default-sets")]
+pub use self::fracture::{Fragment, VoronoiFracture};
 
 pub use parry::query::TrackedContact;
 
@@ -104,6 +111,8 @@ pub(crate) fn default_query_dispatcher() -> std::sync::Arc<dyn parry::query::Que
     std::sync::Arc::new(parry::query::DefaultQueryDispatcher)
 }
 
+mod broad_phase;
+mod broad_phase_bvh;
 mod broad_phase_multi_sap;
 mod collider_components;
 mod contact_pair;
@@ -115,3 +124,5 @@ mod narrow_phase;
 mod collider;
 #[cfg(feature = "default-sets")]
 mod collider_set;
+#[cfg(feature = "default-sets")]
+mod fracture;
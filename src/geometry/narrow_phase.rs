@@ -1,7 +1,10 @@
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
-use crate::data::{BundleSet, Coarena, ComponentSet, ComponentSetMut, ComponentSetOption};
+use crate::data::graph::Edge;
+use crate::data::{
+    BundleSet, Coarena, ComponentSet, ComponentSetMut, ComponentSetOption, UserDataStorage,
+};
 use crate::dynamics::CoefficientCombineRule;
 use crate::dynamics::{
     IslandManager, RigidBodyActivation, RigidBodyDominance, RigidBodyIds, RigidBodyType,
@@ -28,6 +31,7 @@ use std::sync::Arc;
 struct ColliderGraphIndices {
     contact_graph_index: ColliderGraphIndex,
     intersection_graph_index: ColliderGraphIndex,
+    sensor_contact_graph_index: ColliderGraphIndex,
 }
 
 impl ColliderGraphIndices {
@@ -35,6 +39,7 @@ impl ColliderGraphIndices {
         Self {
             contact_graph_index: InteractionGraph::<(), ()>::invalid_graph_index(),
             intersection_graph_index: InteractionGraph::<(), ()>::invalid_graph_index(),
+            sensor_contact_graph_index: InteractionGraph::<(), ()>::invalid_graph_index(),
         }
     }
 }
@@ -46,6 +51,23 @@ enum PairRemovalMode {
     Auto,
 }
 
+/// The result of the update of a contact or intersection pair.
+enum PairUpdate<Event> {
+    /// The pair was updated, and generated the given event, if any.
+    Updated(Option<Event>),
+    /// The pair has active hooks, and was left untouched so it can be updated sequentially.
+    Deferred,
+}
+
+impl<Event> PairUpdate<Event> {
+    fn event(self) -> Option<Event> {
+        match self {
+            PairUpdate::Updated(event) => event,
+            PairUpdate::Deferred => None,
+        }
+    }
+}
+
 /// The narrow-phase responsible for computing precise contact information between colliders.
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Clone)]
@@ -57,7 +79,12 @@ pub struct NarrowPhase {
     query_dispatcher: Arc<dyn PersistentQueryDispatcher<ContactManifoldData, ContactData>>,
     contact_graph: InteractionGraph<ColliderHandle, ContactPair>,
     intersection_graph: InteractionGraph<ColliderHandle, bool>,
+    // The contact manifolds of the intersection pairs involving sensors with
+    // `ColliderFlags::sensor_contacts_enabled` set.
+    sensor_contact_graph: InteractionGraph<ColliderHandle, ContactPair>,
     graph_indices: Coarena<ColliderGraphIndices>,
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    pair_user_data: UserDataStorage<(ColliderHandle, ColliderHandle)>,
 }
 
 pub(crate) type ContactManifoldIndex = usize;
@@ -83,10 +110,26 @@ impl NarrowPhase {
             query_dispatcher: Arc::new(d),
             contact_graph: InteractionGraph::new(),
             intersection_graph: InteractionGraph::new(),
+            sensor_contact_graph: InteractionGraph::new(),
             graph_indices: Coarena::new(),
+            pair_user_data: UserDataStorage::default(),
         }
     }
 
+    /// The typed user data attached to the contact pairs of this narrow-phase.
+    pub fn pair_user_data(&self) -> &UserDataStorage<(ColliderHandle, ColliderHandle)> {
+        &self.pair_user_data
+    }
+
+    /// The typed user data attached to the contact pairs of this narrow-phase.
+    ///
+    /// The user data attached to a contact pair is detached when this pair is removed from
+    /// the contact graph. It isn't serialized with this narrow-phase: see
+    /// `UserDataStorage::serialize_pair_map`.
+    pub fn pair_user_data_mut(&mut self) -> &mut UserDataStorage<(ColliderHandle, ColliderHandle)> {
+        &mut self.pair_user_data
+    }
+
     /// The query dispatcher used by this narrow-phase to select the right collision-detection
     /// algorithms depending of the shape types.
     pub fn query_dispatcher(
@@ -105,6 +148,12 @@ impl NarrowPhase {
         &self.intersection_graph
     }
 
+    /// The graph containing the contact manifolds of the intersection pairs involving sensors
+    /// with sensor contacts enabled.
+    pub fn sensor_contact_graph(&self) -> &InteractionGraph<ColliderHandle, ContactPair> {
+        &self.sensor_contact_graph
+    }
+
     /// All the contacts involving the given collider.
     ///
     /// It is strongly recommended to use the [`NarrowPhase::contacts_with`] method instead. This
@@ -235,6 +284,50 @@ impl NarrowPhase {
         self.contact_graph.interactions()
     }
 
+    /// The contact manifolds between a sensor with sensor contacts enabled, and another collider.
+    ///
+    /// See [`Collider::enable_sensor_contacts`](crate::geometry::Collider::enable_sensor_contacts).
+    /// If this returns `Some`, check the result [`ContactPair::has_any_active_contact`] field
+    /// to see if there is an actual contact. The contact manifolds of a pair are only computed
+    /// while its colliders intersect, and are not used by the constraints solver, so they
+    /// don't contain any solver contact.
+    pub fn sensor_contact_pair(
+        &self,
+        collider1: ColliderHandle,
+        collider2: ColliderHandle,
+    ) -> Option<&ContactPair> {
+        let id1 = self.graph_indices.get(collider1.0)?;
+        let id2 = self.graph_indices.get(collider2.0)?;
+        self.sensor_contact_graph
+            .interaction_pair(
+                id1.sensor_contact_graph_index,
+                id2.sensor_contact_graph_index,
+            )
+            .map(|c| c.2)
+    }
+
+    /// All the sensor contacts involving the given collider.
+    ///
+    /// See [`NarrowPhase::sensor_contact_pair`].
+    pub fn sensor_contacts_with(
+        &self,
+        collider: ColliderHandle,
+    ) -> impl Iterator<Item = &ContactPair> {
+        self.graph_indices
+            .get(collider.0)
+            .map(|id| id.sensor_contact_graph_index)
+            .into_iter()
+            .flat_map(move |id| self.sensor_contact_graph.interactions_with(id))
+            .map(|pair| pair.2)
+    }
+
+    /// All the sensor contact pairs maintained by this narrow-phase.
+    ///
+    /// See [`NarrowPhase::sensor_contact_pair`].
+    pub fn sensor_contact_pairs(&self) -> impl Iterator<Item = &ContactPair> {
+        self.sensor_contact_graph.interactions()
+    }
+
     /// All the intersection pairs maintained by this narrow-phase.
     pub fn intersection_pairs(
         &self,
@@ -272,6 +365,7 @@ impl NarrowPhase {
         // by the contact/intersection graphs when a node is removed.
         let mut prox_id_remap = HashMap::new();
         let mut contact_id_remap = HashMap::new();
+        let mut sensor_contact_id_remap = HashMap::new();
 
         for collider in removed_colliders {
             // NOTE: if the collider does not have any graph indices currently, there is nothing
@@ -288,15 +382,21 @@ impl NarrowPhase {
                     .get(collider)
                     .copied()
                     .unwrap_or(graph_idx.contact_graph_index);
+                let sensor_contact_graph_id = sensor_contact_id_remap
+                    .get(collider)
+                    .copied()
+                    .unwrap_or(graph_idx.sensor_contact_graph_index);
 
                 self.remove_collider(
                     intersection_graph_id,
                     contact_graph_id,
+                    sensor_contact_graph_id,
                     islands.as_deref_mut(),
                     colliders,
                     bodies,
                     &mut prox_id_remap,
                     &mut contact_id_remap,
+                    &mut sensor_contact_id_remap,
                 );
             }
         }
@@ -308,11 +408,13 @@ impl NarrowPhase {
         &mut self,
         intersection_graph_id: ColliderGraphIndex,
         contact_graph_id: ColliderGraphIndex,
+        sensor_contact_graph_id: ColliderGraphIndex,
         islands: Option<&mut IslandManager>,
         colliders: &mut Colliders,
         bodies: &mut Bodies,
         prox_id_remap: &mut HashMap<ColliderHandle, ColliderGraphIndex>,
         contact_id_remap: &mut HashMap<ColliderHandle, ColliderGraphIndex>,
+        sensor_contact_id_remap: &mut HashMap<ColliderHandle, ColliderGraphIndex>,
     ) where
         Bodies: ComponentSetMut<RigidBodyActivation>
             + ComponentSet<RigidBodyType>
@@ -332,6 +434,10 @@ impl NarrowPhase {
             }
         }
 
+        for (a, b, _) in self.contact_graph.interactions_with(contact_graph_id) {
+            self.pair_user_data.detach((a, b));
+        }
+
         // We have to manage the fact that one other collider will
         // have its graph index changed because of the node's swap-remove.
         if let Some(replacement) = self.intersection_graph.remove_node(intersection_graph_id) {
@@ -357,6 +463,18 @@ impl NarrowPhase {
                 unreachable!();
             }
         }
+
+        if let Some(replacement) = self
+            .sensor_contact_graph
+            .remove_node(sensor_contact_graph_id)
+        {
+            if let Some(replacement) = self.graph_indices.get_mut(replacement.0) {
+                replacement.sensor_contact_graph_index = sensor_contact_graph_id;
+            } else {
+                sensor_contact_id_remap.insert(replacement, sensor_contact_graph_id);
+                unreachable!();
+            }
+        }
     }
 
     pub(crate) fn handle_modified_colliders<Bodies, Colliders>(
@@ -376,6 +494,8 @@ impl NarrowPhase {
             + ComponentSetOption<ColliderParent>,
     {
         let mut pairs_to_remove = vec![];
+        let mut invalid_pairs = vec![];
+        let mut type_changed = vec![];
 
         for handle in modified_colliders {
             // NOTE: we use `get` because the collider may no longer
@@ -383,9 +503,9 @@ impl NarrowPhase {
             let co_changes: Option<&ColliderChanges> = colliders.get(handle.0);
 
             if let Some(co_changes) = co_changes {
-                if co_changes.needs_narrow_phase_update() {
+                if !co_changes.needs_narrow_phase_update() {
                     // No flag relevant to the narrow-phase is enabled for this collider.
-                    return;
+                    continue;
                 }
 
                 if let Some(gid) = self.graph_indices.get(handle.0) {
@@ -395,8 +515,11 @@ impl NarrowPhase {
                     // it could be a static or kinematic body which don't propagate the wake-up state.
 
                     let co_parent: Option<&ColliderParent> = colliders.get(handle.0);
-                    let (co_changes, co_type): (&ColliderChanges, &ColliderType) =
-                        colliders.index_bundle(handle.0);
+                    let (co_changes, co_type, co_flags): (
+                        &ColliderChanges,
+                        &ColliderType,
+                        &ColliderFlags,
+                    ) = colliders.index_bundle(handle.0);
 
                     if let Some(islands) = islands.as_deref_mut() {
                         if let Some(co_parent) = co_parent {
@@ -417,11 +540,72 @@ impl NarrowPhase {
                         }
                     }
 
+                    // Disabled colliders must not be part of any contact/intersection pair.
+                    if !co_flags.enabled.is_enabled() {
+                        for inter in self
+                            .contact_graph
+                            .interactions_with(gid.contact_graph_index)
+                        {
+                            invalid_pairs.push((
+                                ColliderPair::new(inter.0, inter.1),
+                                PairRemovalMode::FromContactGraph,
+                            ));
+                        }
+
+                        for inter in self
+                            .intersection_graph
+                            .interactions_with(gid.intersection_graph_index)
+                        {
+                            invalid_pairs.push((
+                                ColliderPair::new(inter.0, inter.1),
+                                PairRemovalMode::FromIntersectionGraph,
+                            ));
+                        }
+
+                        continue;
+                    }
+
+                    // A re-parented collider must not interact with the other colliders
+                    // attached to its new parent.
+                    if let (true, Some(co_parent)) =
+                        (co_changes.contains(ColliderChanges::PARENT), co_parent)
+                    {
+                        let same_parent = |h1: ColliderHandle, h2: ColliderHandle| {
+                            let other = if h1 == *handle { h2 } else { h1 };
+                            let other_parent: Option<&ColliderParent> = colliders.get(other.0);
+                            other_parent.map(|p| p.handle) == Some(co_parent.handle)
+                        };
+
+                        for inter in self
+                            .contact_graph
+                            .interactions_with(gid.contact_graph_index)
+                            .filter(|inter| same_parent(inter.0, inter.1))
+                        {
+                            invalid_pairs.push((
+                                ColliderPair::new(inter.0, inter.1),
+                                PairRemovalMode::FromContactGraph,
+                            ));
+                        }
+
+                        for inter in self
+                            .intersection_graph
+                            .interactions_with(gid.intersection_graph_index)
+                            .filter(|inter| same_parent(inter.0, inter.1))
+                        {
+                            invalid_pairs.push((
+                                ColliderPair::new(inter.0, inter.1),
+                                PairRemovalMode::FromIntersectionGraph,
+                            ));
+                        }
+                    }
+
                     // For each collider which had their sensor status modified, we need
                     // to transfer their contact/intersection graph edges to the intersection/contact graph.
                     // To achieve this we will remove the relevant contact/intersection pairs form the
                     // contact/intersection graphs, and then add them into the other graph.
                     if co_changes.contains(ColliderChanges::TYPE) {
+                        type_changed.push(*handle);
+
                         if co_type.is_sensor() {
                             // Find the contact pairs for this collider and
                             // push them to `pairs_to_remove`.
@@ -458,6 +642,18 @@ impl NarrowPhase {
             }
         }
 
+        // Remove the pairs involving disabled colliders, or colliders attached to the same parent.
+        for pair in invalid_pairs {
+            self.remove_pair(
+                islands.as_deref_mut(),
+                colliders,
+                bodies,
+                &pair.0,
+                events,
+                pair.1,
+            );
+        }
+
         // Remove the pair from the relevant graph.
         for pair in &pairs_to_remove {
             self.remove_pair(
@@ -474,6 +670,22 @@ impl NarrowPhase {
         for pair in pairs_to_remove {
             self.add_pair(colliders, &pair.0);
         }
+
+        // The sensor status, or the sensor contacts flag, of these colliders may have
+        // changed, so their intersection pairs may need to start or stop computing contacts.
+        for handle in type_changed {
+            if let Some(gid) = self.graph_indices.get(handle.0) {
+                let intersection_pairs: Vec<_> = self
+                    .intersection_graph
+                    .interactions_with(gid.intersection_graph_index)
+                    .map(|inter| ColliderPair::new(inter.0, inter.1))
+                    .collect();
+
+                for pair in intersection_pairs {
+                    self.update_sensor_contact_pair(colliders, &pair);
+                }
+            }
+        }
     }
 
     fn remove_pair<Bodies, Colliders>(
@@ -509,6 +721,10 @@ impl NarrowPhase {
                     let was_intersecting = self
                         .intersection_graph
                         .remove_edge(gid1.intersection_graph_index, gid2.intersection_graph_index);
+                    let _ = self.sensor_contact_graph.remove_edge(
+                        gid1.sensor_contact_graph_index,
+                        gid2.sensor_contact_graph_index,
+                    );
 
                     // Emit an intersection lost event if we had an intersection before removing the edge.
                     if Some(true) == was_intersecting {
@@ -527,6 +743,7 @@ impl NarrowPhase {
                     let contact_pair = self
                         .contact_graph
                         .remove_edge(gid1.contact_graph_index, gid2.contact_graph_index);
+                    self.pair_user_data.detach((pair.collider1, pair.collider2));
 
                     // Emit a contact stopped event if we had a contact before removing the edge.
                     // Also wake up the dynamic bodies that were in contact.
@@ -567,7 +784,9 @@ impl NarrowPhase {
 
     fn add_pair<Colliders>(&mut self, colliders: &Colliders, pair: &ColliderPair)
     where
-        Colliders: ComponentSet<ColliderType> + ComponentSetOption<ColliderParent>,
+        Colliders: ComponentSet<ColliderType>
+            + ComponentSet<ColliderFlags>
+            + ComponentSetOption<ColliderParent>,
     {
         let co_type1: Option<&ColliderType> = colliders.get(pair.collider1.0);
         let co_type2: Option<&ColliderType> = colliders.get(pair.collider2.0);
@@ -618,6 +837,8 @@ impl NarrowPhase {
                         false,
                     );
                 }
+
+                self.update_sensor_contact_pair(colliders, pair);
             } else {
                 // NOTE: same code as above, but for the contact graph.
                 // TODO: refactor both pieces of code somehow?
@@ -649,6 +870,75 @@ impl NarrowPhase {
         }
     }
 
+    /// Adds the given intersection pair to the sensor contact graph if one of its sensors has
+    /// sensor contacts enabled, or removes it from the sensor contact graph otherwise.
+    fn update_sensor_contact_pair<Colliders>(&mut self, colliders: &Colliders, pair: &ColliderPair)
+    where
+        Colliders: ComponentSet<ColliderType> + ComponentSet<ColliderFlags>,
+    {
+        let (co_type1, co_flags1): (&ColliderType, &ColliderFlags) =
+            colliders.index_bundle(pair.collider1.0);
+        let (co_type2, co_flags2): (&ColliderType, &ColliderFlags) =
+            colliders.index_bundle(pair.collider2.0);
+        let (gid1, gid2) = self.graph_indices.ensure_pair_exists(
+            pair.collider1.0,
+            pair.collider2.0,
+            ColliderGraphIndices::invalid(),
+        );
+
+        if (co_type1.is_sensor() && co_flags1.sensor_contacts_enabled)
+            || (co_type2.is_sensor() && co_flags2.sensor_contacts_enabled)
+        {
+            // NOTE: the collider won't have a graph index as long
+            // as it does not have any sensor contact pair.
+            if !InteractionGraph::<(), ()>::is_graph_index_valid(gid1.sensor_contact_graph_index) {
+                gid1.sensor_contact_graph_index =
+                    self.sensor_contact_graph.graph.add_node(pair.collider1);
+            }
+
+            if !InteractionGraph::<(), ()>::is_graph_index_valid(gid2.sensor_contact_graph_index) {
+                gid2.sensor_contact_graph_index =
+                    self.sensor_contact_graph.graph.add_node(pair.collider2);
+            }
+
+            if self
+                .sensor_contact_graph
+                .graph
+                .find_edge(
+                    gid1.sensor_contact_graph_index,
+                    gid2.sensor_contact_graph_index,
+                )
+                .is_none()
+            {
+                let interaction = ContactPair::new(pair.collider1, pair.collider2);
+                let _ = self.sensor_contact_graph.add_edge(
+                    gid1.sensor_contact_graph_index,
+                    gid2.sensor_contact_graph_index,
+                    interaction,
+                );
+            }
+        } else {
+            let _ = self.sensor_contact_graph.remove_edge(
+                gid1.sensor_contact_graph_index,
+                gid2.sensor_contact_graph_index,
+            );
+        }
+    }
+
+    /// Translates by `-shift` the world-space contact points cached by all the contact pairs.
+    ///
+    /// The contact manifolds are otherwise expressed in the local-space of the colliders so
+    /// they, and the impulses used for warm-starting, are preserved.
+    pub(crate) fn shift_origin(&mut self, shift: &Vector<Real>) {
+        for inter in self.contact_graph.graph.edges.iter_mut() {
+            for manifold in &mut inter.weight.manifolds {
+                for contact in &mut manifold.data.solver_contacts {
+                    contact.point -= shift;
+                }
+            }
+        }
+    }
+
     pub(crate) fn register_pairs<Bodies, Colliders>(
         &mut self,
         mut islands: Option<&mut IslandManager>,
@@ -709,7 +999,8 @@ impl NarrowPhase {
         let query_dispatcher = &*self.query_dispatcher;
 
         // TODO: don't iterate on all the edges.
-        par_iter_mut!(&mut self.intersection_graph.graph.edges).for_each(|edge| {
+        let edges = &mut self.intersection_graph.graph.edges;
+        let update_pair = |edge: &mut Edge<bool>, defer_hooks: bool| {
             let handle1 = nodes[edge.source().index()].weight;
             let handle2 = nodes[edge.target().index()].weight;
 
@@ -732,7 +1023,7 @@ impl NarrowPhase {
             if !co_changes1.needs_narrow_phase_update() && !co_changes2.needs_narrow_phase_update()
             {
                 // No update needed for these colliders.
-                return;
+                return PairUpdate::Updated(None);
             }
 
             // TODO: avoid lookup into bodies.
@@ -751,17 +1042,21 @@ impl NarrowPhase {
             if !co_flags1.active_collision_types.test(rb_type1, rb_type2)
                 && !co_flags2.active_collision_types.test(rb_type1, rb_type2)
             {
-                return;
+                return PairUpdate::Updated(None);
             }
 
             // Filter based on collision groups.
             if !co_flags1.collision_groups.test(co_flags2.collision_groups) {
-                return;
+                return PairUpdate::Updated(None);
             }
 
             let active_hooks = co_flags1.active_hooks | co_flags2.active_hooks;
             let active_events = co_flags1.active_events | co_flags2.active_events;
 
+            if defer_hooks && !active_hooks.is_empty() {
+                return PairUpdate::Deferred;
+            }
+
             if active_hooks.contains(ActiveHooks::FILTER_INTERSECTION_PAIR) {
                 let context = PairFilterContext {
                     bodies,
@@ -774,26 +1069,153 @@ impl NarrowPhase {
 
                 if !hooks.filter_intersection_pair(&context) {
                     // No intersection allowed.
-                    return;
+                    return PairUpdate::Updated(None);
                 }
             }
 
             let pos12 = co_pos1.inv_mul(co_pos2);
 
+            let mut event = None;
+
             if let Ok(intersection) =
                 query_dispatcher.intersection_test(&pos12, &**co_shape1, &**co_shape2)
             {
                 if active_events.contains(ActiveEvents::INTERSECTION_EVENTS)
                     && intersection != edge.weight
                 {
-                    events.handle_intersection_event(IntersectionEvent::new(
-                        handle1,
-                        handle2,
-                        intersection,
-                    ));
+                    event = Some(IntersectionEvent::new(handle1, handle2, intersection));
                 }
                 edge.weight = intersection;
             }
+
+            PairUpdate::Updated(event)
+        };
+
+        // With the `parallel` feature, the pairs with active hooks are skipped by the parallel
+        // pass and updated afterwards, sequentially and in the order of the graph edges, so the
+        // hooks are called in a deterministic order. The events are emitted in that same order.
+        let updates: Vec<_> = par_iter_mut!(edges)
+            .enumerate()
+            .filter_map(
+                |(edge_id, edge)| match update_pair(edge, cfg!(feature = "parallel")) {
+                    PairUpdate::Updated(None) => None,
+                    update => Some((edge_id, update)),
+                },
+            )
+            .collect();
+
+        for (edge_id, update) in updates {
+            let event = match update {
+                PairUpdate::Updated(event) => event,
+                PairUpdate::Deferred => update_pair(&mut edges[edge_id], false).event(),
+            };
+
+            if let Some(event) = event {
+                events.handle_intersection_event(event);
+            }
+        }
+    }
+
+    /// Computes the contact manifolds of the intersection pairs involving sensors with
+    /// sensor contacts enabled.
+    ///
+    /// This must be called after `Self::compute_intersections`. The manifolds of the pairs that
+    /// don't intersect are cleared.
+    pub(crate) fn compute_sensor_contacts<Colliders>(
+        &mut self,
+        prediction_distance: Real,
+        colliders: &Colliders,
+        modified_colliders: &[ColliderHandle],
+    ) where
+        Colliders: ComponentSet<ColliderChanges>
+            + ComponentSetOption<ColliderParent>
+            + ComponentSet<ColliderShape>
+            + ComponentSet<ColliderPosition>,
+    {
+        if modified_colliders.is_empty() {
+            return;
+        }
+
+        let query_dispatcher = &*self.query_dispatcher;
+        let intersection_graph = &self.intersection_graph;
+        let graph_indices = &self.graph_indices;
+
+        // TODO: don't iterate on all the edges.
+        let edges = &mut self.sensor_contact_graph.graph.edges;
+        par_iter_mut!(edges).for_each(|edge| {
+            let pair = &mut edge.weight;
+
+            let co_parent1: Option<&ColliderParent> = colliders.get(pair.collider1.0);
+            let (co_changes1, co_shape1, co_pos1): (
+                &ColliderChanges,
+                &ColliderShape,
+                &ColliderPosition,
+            ) = colliders.index_bundle(pair.collider1.0);
+
+            let co_parent2: Option<&ColliderParent> = colliders.get(pair.collider2.0);
+            let (co_changes2, co_shape2, co_pos2): (
+                &ColliderChanges,
+                &ColliderShape,
+                &ColliderPosition,
+            ) = colliders.index_bundle(pair.collider2.0);
+
+            if !co_changes1.needs_narrow_phase_update() && !co_changes2.needs_narrow_phase_update()
+            {
+                // No update needed for these colliders.
+                return;
+            }
+
+            let intersecting = graph_indices
+                .get(pair.collider1.0)
+                .zip(graph_indices.get(pair.collider2.0))
+                .and_then(|(gid1, gid2)| {
+                    intersection_graph.interaction_pair(
+                        gid1.intersection_graph_index,
+                        gid2.intersection_graph_index,
+                    )
+                })
+                .map(|inter| *inter.2)
+                .unwrap_or(false);
+
+            if !intersecting {
+                pair.manifolds.clear();
+                pair.has_any_active_contact = false;
+                return;
+            }
+
+            if co_changes1.contains(ColliderChanges::SHAPE)
+                || co_changes2.contains(ColliderChanges::SHAPE)
+            {
+                // The shape changed so the workspace is no longer valid.
+                pair.workspace = None;
+            }
+
+            let pos12 = co_pos1.inv_mul(co_pos2);
+            let _ = query_dispatcher.contact_manifolds(
+                &pos12,
+                &**co_shape1,
+                &**co_shape2,
+                prediction_distance,
+                &mut pair.manifolds,
+                &mut pair.workspace,
+            );
+
+            let mut has_any_active_contact = false;
+
+            for manifold in &mut pair.manifolds {
+                let world_pos1 = manifold.subshape_pos1.prepend_to(co_pos1);
+                manifold.data.solver_contacts.clear();
+                manifold.data.rigid_body1 = co_parent1.map(|p| p.handle);
+                manifold.data.rigid_body2 = co_parent2.map(|p| p.handle);
+                manifold.data.solver_flags = SolverFlags::empty();
+                manifold.data.normal = world_pos1 * manifold.local_n1;
+                has_any_active_contact |= manifold
+                    .points
+                    .iter()
+                    .any(|contact| contact.dist < prediction_distance);
+            }
+
+            pair.has_any_active_contact = has_any_active_contact;
         });
     }
 
@@ -823,7 +1245,8 @@ impl NarrowPhase {
         let query_dispatcher = &*self.query_dispatcher;
 
         // TODO: don't iterate on all the edges.
-        par_iter_mut!(&mut self.contact_graph.graph.edges).for_each(|edge| {
+        let edges = &mut self.contact_graph.graph.edges;
+        let update_pair = |edge: &mut Edge<ContactPair>, defer_hooks: bool| {
             let pair = &mut edge.weight;
 
             let co_parent1: Option<&ColliderParent> = colliders.get(pair.collider1.0);
@@ -847,7 +1270,7 @@ impl NarrowPhase {
             if !co_changes1.needs_narrow_phase_update() && !co_changes2.needs_narrow_phase_update()
             {
                 // No update needed for these colliders.
-                return;
+                return PairUpdate::Updated(None);
             }
 
             // TODO: avoid lookup into bodies.
@@ -866,17 +1289,21 @@ impl NarrowPhase {
             if !co_flags1.active_collision_types.test(rb_type1, rb_type2)
                 && !co_flags2.active_collision_types.test(rb_type1, rb_type2)
             {
-                return;
+                return PairUpdate::Updated(None);
             }
 
             // Filter based on collision groups.
             if !co_flags1.collision_groups.test(co_flags2.collision_groups) {
-                return;
+                return PairUpdate::Updated(None);
             }
 
             let active_hooks = co_flags1.active_hooks | co_flags2.active_hooks;
             let active_events = co_flags1.active_events | co_flags2.active_events;
 
+            if defer_hooks && !active_hooks.is_empty() {
+                return PairUpdate::Deferred;
+            }
+
             let mut solver_flags = if active_hooks.contains(ActiveHooks::FILTER_CONTACT_PAIRS) {
                 let context = PairFilterContext {
                     bodies,
@@ -887,11 +1314,10 @@ impl NarrowPhase {
                     collider2: pair.collider2,
                 };
 
-                if let Some(solver_flags) = hooks.filter_contact_pair(&context) {
-                    solver_flags
-                } else {
-                    // No contact allowed.
-                    return;
+                match hooks.filter_contact_pair(&context) {
+                    Some(solver_flags) => solver_flags,
+                    // No contact allowed if the filter returns `None`.
+                    None => return PairUpdate::Updated(None),
                 }
             } else {
                 SolverFlags::default()
@@ -1004,24 +1430,45 @@ impl NarrowPhase {
                 }
             }
 
+            let mut event = None;
+
             if has_any_active_contact != pair.has_any_active_contact {
                 if active_events.contains(ActiveEvents::CONTACT_EVENTS) {
                     if has_any_active_contact {
-                        events.handle_contact_event(
-                            ContactEvent::Started(pair.collider1, pair.collider2),
-                            pair,
-                        );
+                        event = Some(ContactEvent::Started(pair.collider1, pair.collider2));
                     } else {
-                        events.handle_contact_event(
-                            ContactEvent::Stopped(pair.collider1, pair.collider2),
-                            pair,
-                        );
+                        event = Some(ContactEvent::Stopped(pair.collider1, pair.collider2));
                     }
                 }
 
                 pair.has_any_active_contact = has_any_active_contact;
             }
-        });
+
+            PairUpdate::Updated(event)
+        };
+
+        // Same as in `Self::compute_intersections`: the pairs with active hooks are updated
+        // sequentially after the parallel pass, and the events are emitted in edge order.
+        let updates: Vec<_> = par_iter_mut!(edges)
+            .enumerate()
+            .filter_map(
+                |(edge_id, edge)| match update_pair(edge, cfg!(feature = "parallel")) {
+                    PairUpdate::Updated(None) => None,
+                    update => Some((edge_id, update)),
+                },
+            )
+            .collect();
+
+        for (edge_id, update) in updates {
+            let event = match update {
+                PairUpdate::Updated(event) => event,
+                PairUpdate::Deferred => update_pair(&mut edges[edge_id], false).event(),
+            };
+
+            if let Some(event) = event {
+                events.handle_contact_event(event, &edges[edge_id].weight);
+            }
+        }
     }
 
     /// Retrieve all the interactions with at least one contact point, happening between two active bodies.
@@ -1086,3 +1533,139 @@ impl NarrowPhase {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{RigidBodyBuilder, RigidBodySet};
+    use crate::geometry::{ColliderBuilder, ColliderHandle, ColliderSet, SolverFlags};
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+    use crate::pipeline::{ActiveHooks, PairFilterContext, PhysicsHooks};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingHooks {
+        pairs: Mutex<Vec<(ColliderHandle, ColliderHandle)>>,
+    }
+
+    impl PhysicsHooks<RigidBodySet, ColliderSet> for RecordingHooks {
+        fn filter_contact_pair(
+            &self,
+            context: &PairFilterContext<RigidBodySet, ColliderSet>,
+        ) -> Option<SolverFlags> {
+            let pair = (context.collider1, context.collider2);
+            self.pairs.lock().unwrap().push(pair);
+            Some(SolverFlags::COMPUTE_IMPULSES)
+        }
+    }
+
+    #[test]
+    fn hooks_called_in_edge_order() {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        #[cfg(feature = "dim3")]
+        let ground = ColliderBuilder::cuboid(50.0, 0.5, 50.0)
+            .active_hooks(ActiveHooks::FILTER_CONTACT_PAIRS)
+            .build();
+        #[cfg(feature = "dim2")]
+        let ground = ColliderBuilder::cuboid(50.0, 0.5)
+            .active_hooks(ActiveHooks::FILTER_CONTACT_PAIRS)
+            .build();
+        world.colliders.insert(ground);
+
+        for i in 0..20 {
+            let body = RigidBodyBuilder::new_dynamic()
+                .translation(Vector::x() * (i as Real * 1.5 - 15.0) + Vector::y())
+                .build();
+            world.insert_body(body, ColliderBuilder::ball(0.5).build());
+        }
+
+        for _ in 0..5 {
+            let hooks = RecordingHooks::default();
+            world.step_with_hooks(&hooks);
+
+            // Each narrow-phase update of the step called the hooks of all the pairs in
+            // the order of the contact graph edges, even with the `parallel` feature.
+            let expected: Vec<_> = world
+                .narrow_phase
+                .contact_pairs()
+                .map(|pair| (pair.collider1, pair.collider2))
+                .collect();
+            let pairs = hooks.pairs.into_inner().unwrap();
+            assert_eq!(expected.len(), 20);
+            assert!(!pairs.is_empty());
+            assert!(pairs
+                .chunks(expected.len())
+                .all(|chunk| chunk == &expected[..]));
+        }
+    }
+
+    #[test]
+    fn sensor_contacts() {
+        let mut world = TestWorld::new(Vector::zeros());
+
+        // Two overlapping sensors, only one of them computing contacts.
+        let sensor1 = ColliderBuilder::ball(1.0)
+            .sensor(true)
+            .sensor_contacts_enabled(true)
+            .build();
+        let sensor1 = world.colliders.insert(sensor1);
+        let sensor2 = ColliderBuilder::ball(1.0).sensor(true).build();
+        let sensor2 = world.colliders.insert(sensor2);
+
+        // A ball entering the sensors from their left side.
+        let rb = RigidBodyBuilder::new_dynamic()
+            .translation(-Vector::x() * 1.6)
+            .linvel(Vector::x())
+            .build();
+        let (body, ball) = world.insert_body(rb, ColliderBuilder::ball(0.5).build());
+
+        world.step_n(20);
+
+        let narrow_phase = &world.narrow_phase;
+        assert_eq!(narrow_phase.intersection_pair(sensor1, ball), Some(true));
+        assert_eq!(narrow_phase.intersection_pair(sensor2, ball), Some(true));
+        assert!(narrow_phase.sensor_contact_pair(sensor2, ball).is_none());
+        assert!(narrow_phase.contact_pair(sensor1, ball).is_none());
+        // The sensors don't apply any force.
+        assert!((world.bodies[body].linvel() - Vector::x()).norm() < 1.0e-5);
+
+        let pair = narrow_phase.sensor_contact_pair(sensor1, ball).unwrap();
+        assert!(pair.has_any_active_contact);
+        assert_eq!(narrow_phase.sensor_contacts_with(ball).count(), 1);
+
+        let (manifold, contact) = pair.find_deepest_contact().unwrap();
+        assert!(contact.dist < 0.0);
+        assert!(manifold.data.solver_contacts.is_empty());
+        // The normal points from the first collider of the pair toward the second,
+        // so the ball entered the sensor from its left side.
+        let normal = if pair.collider1 == sensor1 {
+            manifold.data.normal
+        } else {
+            -manifold.data.normal
+        };
+        assert!((normal + Vector::x()).norm() < 1.0e-5);
+
+        // Enabling or disabling sensor contacts adds or removes the pairs.
+        world.colliders[sensor1].enable_sensor_contacts(false);
+        world.colliders[sensor2].enable_sensor_contacts(true);
+        world.step();
+
+        let narrow_phase = &world.narrow_phase;
+        assert!(narrow_phase.sensor_contact_pair(sensor1, ball).is_none());
+        let pair = narrow_phase.sensor_contact_pair(sensor2, ball).unwrap();
+        assert!(pair.has_any_active_contact);
+        // The two sensors intersect each other too.
+        assert_eq!(narrow_phase.sensor_contacts_with(sensor2).count(), 2);
+
+        // Removing the ball removes its sensor contacts.
+        let _ = world
+            .colliders
+            .remove(ball, &mut world.islands, &mut world.bodies, true);
+        world.step();
+        assert!(world
+            .narrow_phase
+            .sensor_contact_pair(sensor2, ball)
+            .is_none());
+        assert_eq!(world.narrow_phase.sensor_contact_pairs().count(), 1);
+    }
+}
@@ -11,7 +11,7 @@ use crate::geometry::{
     ColliderShape, ColliderType, NarrowPhase,
 };
 use crate::math::Real;
-use crate::pipeline::{EventHandler, PhysicsHooks};
+use crate::pipeline::{EventHandler, PhysicsHooks, QueryPipeline};
 
 #[cfg(feature = "default-sets")]
 use crate::{dynamics::RigidBodySet, geometry::ColliderSet};
@@ -51,7 +51,7 @@ impl CollisionPipeline {
     fn detect_collisions<Bodies, Colliders>(
         &mut self,
         prediction_distance: Real,
-        broad_phase: &mut BroadPhase,
+        broad_phase: &mut impl BroadPhase,
         narrow_phase: &mut NarrowPhase,
         bodies: &mut Bodies,
         colliders: &mut Colliders,
@@ -86,6 +86,15 @@ impl CollisionPipeline {
             &mut self.broad_phase_events,
         );
 
+        if handle_user_changes {
+            super::user_changes::register_reparented_collider_pairs(
+                broad_phase,
+                colliders,
+                modified_colliders,
+                &mut self.broad_phase_events,
+            );
+        }
+
         // Update narrow-phase.
         if handle_user_changes {
             narrow_phase.handle_user_changes(
@@ -108,6 +117,7 @@ impl CollisionPipeline {
             events,
         );
         narrow_phase.compute_intersections(bodies, colliders, modified_colliders, hooks, events);
+        narrow_phase.compute_sensor_contacts(prediction_distance, colliders, modified_colliders);
     }
 
     fn clear_modified_colliders(
@@ -125,10 +135,38 @@ impl CollisionPipeline {
     pub fn step(
         &mut self,
         prediction_distance: Real,
-        broad_phase: &mut BroadPhase,
+        broad_phase: &mut impl BroadPhase,
+        narrow_phase: &mut NarrowPhase,
+        bodies: &mut RigidBodySet,
+        colliders: &mut ColliderSet,
+        hooks: &dyn PhysicsHooks<RigidBodySet, ColliderSet>,
+        events: &dyn EventHandler,
+    ) {
+        self.step_and_update_queries(
+            prediction_distance,
+            broad_phase,
+            narrow_phase,
+            bodies,
+            colliders,
+            None,
+            hooks,
+            events,
+        )
+    }
+
+    /// Executes one step of the collision detection and updates the given query pipeline.
+    ///
+    /// This is the same as `self.step_generic_and_update_queries`, except that it is specialized
+    /// to work with `RigidBodySet` and `ColliderSet`.
+    #[cfg(feature = "default-sets")]
+    pub fn step_and_update_queries(
+        &mut self,
+        prediction_distance: Real,
+        broad_phase: &mut impl BroadPhase,
         narrow_phase: &mut NarrowPhase,
         bodies: &mut RigidBodySet,
         colliders: &mut ColliderSet,
+        query_pipeline: Option<&mut QueryPipeline>,
         hooks: &dyn PhysicsHooks<RigidBodySet, ColliderSet>,
         events: &dyn EventHandler,
     ) {
@@ -136,7 +174,7 @@ impl CollisionPipeline {
         let mut modified_colliders = colliders.take_modified();
         let mut removed_colliders = colliders.take_removed();
 
-        self.step_generic(
+        self.step_generic_and_update_queries(
             prediction_distance,
             broad_phase,
             narrow_phase,
@@ -145,6 +183,7 @@ impl CollisionPipeline {
             &mut modified_bodies,
             &mut modified_colliders,
             &mut removed_colliders,
+            query_pipeline,
             hooks,
             events,
         );
@@ -154,7 +193,7 @@ impl CollisionPipeline {
     pub fn step_generic<Bodies, Colliders>(
         &mut self,
         prediction_distance: Real,
-        broad_phase: &mut BroadPhase,
+        broad_phase: &mut impl BroadPhase,
         narrow_phase: &mut NarrowPhase,
         bodies: &mut Bodies,
         colliders: &mut Colliders,
@@ -179,7 +218,60 @@ impl CollisionPipeline {
             + ComponentSetOption<ColliderParent>
             + ComponentSet<ColliderType>
             + ComponentSet<ColliderMaterial>
-            + ComponentSet<ColliderFlags>,
+            + ComponentSetMut<ColliderFlags>,
+    {
+        self.step_generic_and_update_queries(
+            prediction_distance,
+            broad_phase,
+            narrow_phase,
+            bodies,
+            colliders,
+            modified_bodies,
+            modified_colliders,
+            removed_colliders,
+            None,
+            hooks,
+            events,
+        )
+    }
+
+    /// Executes one step of the collision detection and updates the given query pipeline.
+    ///
+    /// If a `query_pipeline` is given, it is kept up-to-date with the colliders
+    /// modified, inserted, or removed since the last step, using
+    /// [`QueryPipeline::update_incremental`]. Note that when the number of changes exceeds
+    /// [`QueryPipeline::rebuild_threshold`], the whole acceleration structure is rebuilt
+    /// synchronously, as part of this step.
+    pub fn step_generic_and_update_queries<Bodies, Colliders>(
+        &mut self,
+        prediction_distance: Real,
+        broad_phase: &mut impl BroadPhase,
+        narrow_phase: &mut NarrowPhase,
+        bodies: &mut Bodies,
+        colliders: &mut Colliders,
+        modified_bodies: &mut Vec<RigidBodyHandle>,
+        modified_colliders: &mut Vec<ColliderHandle>,
+        removed_colliders: &mut Vec<ColliderHandle>,
+        query_pipeline: Option<&mut QueryPipeline>,
+        hooks: &dyn PhysicsHooks<Bodies, Colliders>,
+        events: &dyn EventHandler,
+    ) where
+        Bodies: ComponentSetMut<RigidBodyPosition>
+            + ComponentSetMut<RigidBodyVelocity>
+            + ComponentSetMut<RigidBodyIds>
+            + ComponentSetMut<RigidBodyActivation>
+            + ComponentSetMut<RigidBodyChanges>
+            + ComponentSet<RigidBodyColliders>
+            + ComponentSet<RigidBodyDominance>
+            + ComponentSet<RigidBodyType>,
+        Colliders: ComponentSetMut<ColliderBroadPhaseData>
+            + ComponentSetMut<ColliderChanges>
+            + ComponentSetMut<ColliderPosition>
+            + ComponentSet<ColliderShape>
+            + ComponentSetOption<ColliderParent>
+            + ComponentSet<ColliderType>
+            + ComponentSet<ColliderMaterial>
+            + ComponentSetMut<ColliderFlags>,
     {
         super::user_changes::handle_user_changes_to_colliders(
             bodies,
@@ -206,6 +298,10 @@ impl CollisionPipeline {
             true,
         );
 
+        if let Some(queries) = query_pipeline {
+            queries.update_incremental(colliders, modified_colliders, removed_colliders, true);
+        }
+
         self.clear_modified_colliders(colliders, modified_colliders);
         removed_colliders.clear();
     }
@@ -239,7 +335,7 @@ mod tests {
         let _ = collider_set.insert(collider_b);
 
         let integration_parameters = IntegrationParameters::default();
-        let mut broad_phase = BroadPhase::new();
+        let mut broad_phase = DefaultBroadPhase::new();
         let mut narrow_phase = NarrowPhase::new();
         let mut collision_pipeline = CollisionPipeline::new();
         let physics_hooks = ();
@@ -290,7 +386,7 @@ mod tests {
         let _ = collider_set.insert(collider_b);
 
         let integration_parameters = IntegrationParameters::default();
-        let mut broad_phase = BroadPhase::new();
+        let mut broad_phase = DefaultBroadPhase::new();
         let mut narrow_phase = NarrowPhase::new();
         let mut collision_pipeline = CollisionPipeline::new();
         let physics_hooks = ();
@@ -315,4 +411,73 @@ mod tests {
 
         assert!(hit, "No hit found");
     }
+
+    #[test]
+    pub fn test_all_modified_colliders_are_updated() {
+        use crate::prelude::*;
+
+        fn step(
+            collision_pipeline: &mut CollisionPipeline,
+            broad_phase: &mut DefaultBroadPhase,
+            narrow_phase: &mut NarrowPhase,
+            rigid_body_set: &mut RigidBodySet,
+            collider_set: &mut ColliderSet,
+        ) {
+            collision_pipeline.step(
+                IntegrationParameters::default().prediction_distance,
+                broad_phase,
+                narrow_phase,
+                rigid_body_set,
+                collider_set,
+                &(),
+                &(),
+            );
+        }
+
+        let mut rigid_body_set = RigidBodySet::new();
+        let mut collider_set = ColliderSet::new();
+        let mut broad_phase = DefaultBroadPhase::new();
+        let mut narrow_phase = NarrowPhase::new();
+        let mut collision_pipeline = CollisionPipeline::new();
+
+        let a = collider_set.insert(ColliderBuilder::ball(1.0).build());
+        let b = collider_set.insert(ColliderBuilder::ball(1.0).translation(Vector::x()).build());
+        let c = collider_set.insert(
+            ColliderBuilder::ball(1.0)
+                .translation(Vector::x() * 10.0)
+                .build(),
+        );
+
+        macro_rules! step {
+            () => {
+                step(
+                    &mut collision_pipeline,
+                    &mut broad_phase,
+                    &mut narrow_phase,
+                    &mut rigid_body_set,
+                    &mut collider_set,
+                )
+            };
+        }
+
+        step!();
+        assert!(narrow_phase.contact_pair(a, b).is_some());
+
+        // The first modified collider doesn't need a broad-phase update, the second one does.
+        collider_set[c].set_collision_groups(InteractionGroups::none());
+        collider_set[b].set_translation(Vector::x() * 20.0);
+        step!();
+        assert!(narrow_phase.contact_pair(a, b).is_none());
+
+        collider_set[b].set_translation(Vector::x());
+        step!();
+        assert!(narrow_phase.contact_pair(a, b).is_some());
+
+        // The first modified collider doesn't need a narrow-phase update, the second one does.
+        collider_set[c].set_friction(0.1);
+        collider_set[b].set_sensor(true);
+        step!();
+        assert!(narrow_phase.contact_pair(a, b).is_none());
+        assert!(narrow_phase.intersection_pair(a, b).is_some());
+    }
 }
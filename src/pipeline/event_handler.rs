@@ -21,6 +21,8 @@ impl Default for ActiveEvents {
 /// Trait implemented by structures responsible for handling events generated by the physics engine.
 ///
 /// Implementors of this trait will typically collect these events for future processing.
+/// Events are always emitted from the thread calling `PhysicsPipeline::step`, in a deterministic
+/// order, even if the narrow-phase runs in parallel.
 pub trait EventHandler: Send + Sync {
     /// Handle an intersection event.
     ///
@@ -4,11 +4,23 @@ pub use collision_pipeline::CollisionPipeline;
 pub use event_handler::{ActiveEvents, ChannelEventCollector, EventHandler};
 pub use physics_hooks::{ActiveHooks, ContactModificationContext, PairFilterContext, PhysicsHooks};
 pub use physics_pipeline::PhysicsPipeline;
-pub use query_pipeline::{QueryPipeline, QueryPipelineMode};
+pub use query_history::HistoricalQueryPipeline;
+pub use query_pipeline::{
+    QueryPipeline, QueryPipelineMode, ShapeContactManifold, ShapeContactPoint,
+};
+pub use query_snapshot::{QuerySnapshot, SnapshotCollider, SnapshotColliderSet};
+#[cfg(feature = "dim3")]
+pub use ray_sensors::DepthCamera;
+pub use ray_sensors::LidarSensor;
 
 mod collision_pipeline;
 mod event_handler;
 mod physics_hooks;
 mod physics_pipeline;
+mod query_history;
 mod query_pipeline;
+mod query_snapshot;
+mod ray_sensors;
+#[cfg(test)]
+pub(crate) mod test_world;
 mod user_changes;
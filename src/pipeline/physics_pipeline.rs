@@ -2,23 +2,21 @@
 
 use crate::counters::Counters;
 use crate::data::{BundleSet, ComponentSet, ComponentSetMut, ComponentSetOption};
-#[cfg(not(feature = "parallel"))]
-use crate::dynamics::IslandSolver;
 use crate::dynamics::{
-    CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager, MultibodyJointSet,
-    RigidBodyActivation, RigidBodyCcd, RigidBodyChanges, RigidBodyColliders, RigidBodyDamping,
-    RigidBodyDominance, RigidBodyForces, RigidBodyHandle, RigidBodyIds, RigidBodyMassProps,
-    RigidBodyPosition, RigidBodyType, RigidBodyVelocity,
+    CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager, IslandSolver,
+    MultibodyJointSet, RigidBodyActivation, RigidBodyCcd, RigidBodyChanges, RigidBodyColliders,
+    RigidBodyDamping, RigidBodyDominance, RigidBodyForces, RigidBodyHandle, RigidBodyIds,
+    RigidBodyMassProps, RigidBodyPosition, RigidBodyType, RigidBodyVelocity,
 };
 #[cfg(feature = "parallel")]
-use crate::dynamics::{JointGraphEdge, ParallelIslandSolver as IslandSolver};
+use crate::dynamics::{JointGraphEdge, ParallelIslandSolver};
 use crate::geometry::{
     BroadPhase, BroadPhasePairEvent, ColliderBroadPhaseData, ColliderChanges, ColliderFlags,
     ColliderHandle, ColliderMaterial, ColliderPair, ColliderParent, ColliderPosition,
     ColliderShape, ColliderType, ContactManifoldIndex, NarrowPhase,
 };
 use crate::math::{Real, Vector};
-use crate::pipeline::{EventHandler, PhysicsHooks};
+use crate::pipeline::{EventHandler, PhysicsHooks, QueryPipeline};
 
 #[cfg(feature = "default-sets")]
 use {crate::dynamics::RigidBodySet, crate::geometry::ColliderSet};
@@ -39,9 +37,12 @@ pub struct PhysicsPipeline {
     pub counters: Counters,
     manifold_indices: Vec<Vec<ContactManifoldIndex>>,
     joint_constraint_indices: Vec<Vec<ContactManifoldIndex>>,
+    coupling_constraint_indices: Vec<Vec<usize>>,
     broadphase_collider_pairs: Vec<ColliderPair>,
     broad_phase_events: Vec<BroadPhasePairEvent>,
     solvers: Vec<IslandSolver>,
+    #[cfg(feature = "parallel")]
+    parallel_solvers: Vec<ParallelIslandSolver>,
 }
 
 impl Default for PhysicsPipeline {
@@ -62,8 +63,11 @@ impl PhysicsPipeline {
         PhysicsPipeline {
             counters: Counters::new(false),
             solvers: Vec::new(),
+            #[cfg(feature = "parallel")]
+            parallel_solvers: Vec::new(),
             manifold_indices: Vec::new(),
             joint_constraint_indices: Vec::new(),
+            coupling_constraint_indices: Vec::new(),
             broadphase_collider_pairs: Vec::new(),
             broad_phase_events: Vec::new(),
         }
@@ -83,7 +87,7 @@ impl PhysicsPipeline {
         &mut self,
         integration_parameters: &IntegrationParameters,
         islands: &mut IslandManager,
-        broad_phase: &mut BroadPhase,
+        broad_phase: &mut impl BroadPhase,
         narrow_phase: &mut NarrowPhase,
         bodies: &mut Bodies,
         colliders: &mut Colliders,
@@ -120,6 +124,15 @@ impl PhysicsPipeline {
             &mut self.broad_phase_events,
         );
 
+        if handle_user_changes {
+            super::user_changes::register_reparented_collider_pairs(
+                broad_phase,
+                colliders,
+                modified_colliders,
+                &mut self.broad_phase_events,
+            );
+        }
+
         self.counters.cd.broad_phase_time.pause();
         self.counters.cd.narrow_phase_time.resume();
 
@@ -150,6 +163,11 @@ impl PhysicsPipeline {
             events,
         );
         narrow_phase.compute_intersections(bodies, colliders, modified_colliders, hooks, events);
+        narrow_phase.compute_sensor_contacts(
+            integration_parameters.prediction_distance,
+            colliders,
+            modified_colliders,
+        );
 
         self.counters.cd.narrow_phase_time.pause();
         self.counters.stages.collision_detection_time.pause();
@@ -199,6 +217,11 @@ impl PhysicsPipeline {
                 .resize(islands.num_islands(), Vec::new());
         }
 
+        if self.coupling_constraint_indices.len() < islands.num_islands() {
+            self.coupling_constraint_indices
+                .resize(islands.num_islands(), Vec::new());
+        }
+
         let mut manifolds = Vec::new();
         narrow_phase.select_active_contacts(
             islands,
@@ -210,6 +233,7 @@ impl PhysicsPipeline {
             islands,
             bodies,
             &mut self.joint_constraint_indices,
+            &mut self.coupling_constraint_indices,
         );
 
         self.counters.stages.update_time.resume();
@@ -226,9 +250,34 @@ impl PhysicsPipeline {
             bodies.map_mut_internal(handle.0, |forces: &mut RigidBodyForces| {
                 forces.add_gravity_acceleration(&gravity, &effective_inv_mass)
             });
+
+            // NOTE: the velocities of multibody links are overwritten by their multibody.
+            #[cfg(feature = "dim3")]
+            {
+                let (forces, vels, mprops): (
+                    &RigidBodyForces,
+                    &RigidBodyVelocity,
+                    &RigidBodyMassProps,
+                ) = bodies.index_bundle(handle.0);
+
+                if forces.gyroscopic_forces_enabled
+                    && multibody_joints.rigid_body_link(*handle).is_none()
+                {
+                    let new_vels = vels.integrate_gyroscopic_forces(
+                        integration_parameters.dt,
+                        &position.rotation,
+                        mprops,
+                    );
+                    bodies.set_internal(handle.0, new_vels);
+                }
+            }
         }
 
         for multibody in &mut multibody_joints.multibodies {
+            if !multibody.1.is_enabled(bodies) {
+                continue;
+            }
+
             multibody
                 .1
                 .update_dynamics(integration_parameters.dt, bodies);
@@ -242,11 +291,23 @@ impl PhysicsPipeline {
                 .resize_with(islands.num_islands(), IslandSolver::new);
         }
 
+        // NOTE: the parallel solver doesn't support the TGS-soft substeps nor the
+        //       joint couplings, so these islands are solved by the sequential solver instead.
+        #[cfg(feature = "parallel")]
+        let coupling_constraint_indices = &self.coupling_constraint_indices;
+        #[cfg(feature = "parallel")]
+        let solve_sequentially = |island_id: usize| {
+            integration_parameters.solver_mode != crate::dynamics::SolverMode::Pgs
+                || !coupling_constraint_indices[island_id].is_empty()
+        };
         #[cfg(not(feature = "parallel"))]
+        let solve_sequentially = |_island_id: usize| true;
+
         {
             enable_flush_to_zero!();
 
-            for island_id in 0..islands.num_islands() {
+            for island_id in (0..islands.num_islands()).filter(|id| solve_sequentially(*id)) {
+                let (joints, couplings) = impulse_joints.joints_and_couplings_mut();
                 self.solvers[island_id].init_and_solve(
                     island_id,
                     &mut self.counters,
@@ -255,8 +316,10 @@ impl PhysicsPipeline {
                     bodies,
                     &mut manifolds[..],
                     &self.manifold_indices[island_id],
-                    impulse_joints.joints_mut(),
+                    joints,
                     &self.joint_constraint_indices[island_id],
+                    couplings,
+                    &self.coupling_constraint_indices[island_id],
                     multibody_joints,
                 )
             }
@@ -269,7 +332,12 @@ impl PhysicsPipeline {
             use std::sync::atomic::Ordering;
 
             let num_islands = islands.num_islands();
-            let solvers = &mut self.solvers[..num_islands];
+            if self.parallel_solvers.len() < num_islands {
+                self.parallel_solvers
+                    .resize_with(num_islands, ParallelIslandSolver::new);
+            }
+
+            let solvers = &mut self.parallel_solvers[..num_islands];
             let bodies = &std::sync::atomic::AtomicPtr::new(bodies as *mut _);
             let manifolds = &std::sync::atomic::AtomicPtr::new(&mut manifolds as *mut _);
             let impulse_joints =
@@ -284,6 +352,7 @@ impl PhysicsPipeline {
                 solvers
                     .par_iter_mut()
                     .enumerate()
+                    .filter(|(island_id, _)| !solve_sequentially(*island_id))
                     .for_each(|(island_id, solver)| {
                         let bodies: &mut Bodies =
                             unsafe { std::mem::transmute(bodies.load(Ordering::Relaxed)) };
@@ -407,10 +476,9 @@ impl PhysicsPipeline {
         // there to determine if this kinematic body should wake-up dynamic
         // bodies it is touching.
         for handle in islands.active_kinematic_bodies() {
-            let (rb_type, rb_pos, rb_vel, rb_mprops): (
+            let (rb_type, rb_pos, rb_mprops): (
                 &RigidBodyType,
                 &RigidBodyPosition,
-                &RigidBodyVelocity,
                 &RigidBodyMassProps,
             ) = bodies.index_bundle(handle.0);
 
@@ -424,11 +492,8 @@ impl PhysicsPipeline {
                     bodies.set_internal(handle.0, new_vel);
                 }
                 RigidBodyType::KinematicVelocityBased => {
-                    let new_pos = rb_vel.integrate(
-                        integration_parameters.dt,
-                        &rb_pos.position,
-                        &rb_mprops.local_mprops.local_com,
-                    );
+                    // NOTE: the next position may have been clamped by the kinematic CCD.
+                    let new_pos = rb_pos.next_position;
                     bodies.set_internal(handle.0, RigidBodyPosition::from(new_pos));
                 }
                 _ => {}
@@ -436,6 +501,39 @@ impl PhysicsPipeline {
         }
     }
 
+    fn predict_kinematic_positions<Bodies>(
+        &mut self,
+        integration_parameters: &IntegrationParameters,
+        islands: &IslandManager,
+        bodies: &mut Bodies,
+    ) where
+        Bodies: ComponentSetMut<RigidBodyPosition>
+            + ComponentSet<RigidBodyVelocity>
+            + ComponentSet<RigidBodyType>
+            + ComponentSet<RigidBodyMassProps>,
+    {
+        // Compute the next positions of the velocity-based kinematic bodies from their velocities.
+        for handle in islands.active_kinematic_bodies() {
+            let (rb_type, rb_pos, rb_vel, rb_mprops): (
+                &RigidBodyType,
+                &RigidBodyPosition,
+                &RigidBodyVelocity,
+                &RigidBodyMassProps,
+            ) = bodies.index_bundle(handle.0);
+
+            if *rb_type == RigidBodyType::KinematicVelocityBased {
+                let next_position = rb_vel.integrate(
+                    integration_parameters.dt,
+                    &rb_pos.position,
+                    &rb_mprops.local_mprops.local_com,
+                );
+                bodies.map_mut_internal(handle.0, |rb_pos: &mut RigidBodyPosition| {
+                    rb_pos.next_position = next_position;
+                });
+            }
+        }
+    }
+
     /// Executes one timestep of the physics simulation.
     ///
     /// This is the same as `self.step_generic`, except that it is specialized
@@ -446,7 +544,7 @@ impl PhysicsPipeline {
         gravity: &Vector<Real>,
         integration_parameters: &IntegrationParameters,
         islands: &mut IslandManager,
-        broad_phase: &mut BroadPhase,
+        broad_phase: &mut impl BroadPhase,
         narrow_phase: &mut NarrowPhase,
         bodies: &mut RigidBodySet,
         colliders: &mut ColliderSet,
@@ -455,12 +553,50 @@ impl PhysicsPipeline {
         ccd_solver: &mut CCDSolver,
         hooks: &dyn PhysicsHooks<RigidBodySet, ColliderSet>,
         events: &dyn EventHandler,
+    ) {
+        self.step_and_update_queries(
+            gravity,
+            integration_parameters,
+            islands,
+            broad_phase,
+            narrow_phase,
+            bodies,
+            colliders,
+            impulse_joints,
+            multibody_joints,
+            ccd_solver,
+            None,
+            hooks,
+            events,
+        )
+    }
+
+    /// Executes one timestep of the physics simulation and updates the given query pipeline.
+    ///
+    /// This is the same as `self.step_generic_and_update_queries`, except that it is specialized
+    /// to work with `RigidBodySet` and `ColliderSet`.
+    #[cfg(feature = "default-sets")]
+    pub fn step_and_update_queries(
+        &mut self,
+        gravity: &Vector<Real>,
+        integration_parameters: &IntegrationParameters,
+        islands: &mut IslandManager,
+        broad_phase: &mut impl BroadPhase,
+        narrow_phase: &mut NarrowPhase,
+        bodies: &mut RigidBodySet,
+        colliders: &mut ColliderSet,
+        impulse_joints: &mut ImpulseJointSet,
+        multibody_joints: &mut MultibodyJointSet,
+        ccd_solver: &mut CCDSolver,
+        query_pipeline: Option<&mut QueryPipeline>,
+        hooks: &dyn PhysicsHooks<RigidBodySet, ColliderSet>,
+        events: &dyn EventHandler,
     ) {
         let mut modified_bodies = bodies.take_modified();
         let mut modified_colliders = colliders.take_modified();
         let mut removed_colliders = colliders.take_removed();
 
-        self.step_generic(
+        self.step_generic_and_update_queries(
             gravity,
             integration_parameters,
             islands,
@@ -474,6 +610,7 @@ impl PhysicsPipeline {
             impulse_joints,
             multibody_joints,
             ccd_solver,
+            query_pipeline,
             hooks,
             events,
         );
@@ -485,7 +622,7 @@ impl PhysicsPipeline {
         gravity: &Vector<Real>,
         integration_parameters: &IntegrationParameters,
         islands: &mut IslandManager,
-        broad_phase: &mut BroadPhase,
+        broad_phase: &mut impl BroadPhase,
         narrow_phase: &mut NarrowPhase,
         bodies: &mut Bodies,
         colliders: &mut Colliders,
@@ -517,11 +654,80 @@ impl PhysicsPipeline {
             + ComponentSetOption<ColliderParent>
             + ComponentSet<ColliderType>
             + ComponentSet<ColliderMaterial>
-            + ComponentSet<ColliderFlags>,
+            + ComponentSetMut<ColliderFlags>,
+    {
+        self.step_generic_and_update_queries(
+            gravity,
+            integration_parameters,
+            islands,
+            broad_phase,
+            narrow_phase,
+            bodies,
+            colliders,
+            modified_bodies,
+            modified_colliders,
+            removed_colliders,
+            impulse_joints,
+            multibody_joints,
+            ccd_solver,
+            None,
+            hooks,
+            events,
+        )
+    }
+
+    /// Executes one timestep of the physics simulation and updates the given query pipeline.
+    ///
+    /// If a `query_pipeline` is given, it is kept up-to-date with the colliders
+    /// modified, inserted, or removed during this timestep, using
+    /// [`QueryPipeline::update_incremental`]. Note that when the number of changes exceeds
+    /// [`QueryPipeline::rebuild_threshold`], the whole acceleration structure is rebuilt
+    /// synchronously, as part of this timestep.
+    pub fn step_generic_and_update_queries<Bodies, Colliders>(
+        &mut self,
+        gravity: &Vector<Real>,
+        integration_parameters: &IntegrationParameters,
+        islands: &mut IslandManager,
+        broad_phase: &mut impl BroadPhase,
+        narrow_phase: &mut NarrowPhase,
+        bodies: &mut Bodies,
+        colliders: &mut Colliders,
+        modified_bodies: &mut Vec<RigidBodyHandle>,
+        modified_colliders: &mut Vec<ColliderHandle>,
+        removed_colliders: &mut Vec<ColliderHandle>,
+        impulse_joints: &mut ImpulseJointSet,
+        multibody_joints: &mut MultibodyJointSet,
+        ccd_solver: &mut CCDSolver,
+        mut query_pipeline: Option<&mut QueryPipeline>,
+        hooks: &dyn PhysicsHooks<Bodies, Colliders>,
+        events: &dyn EventHandler,
+    ) where
+        Bodies: ComponentSetMut<RigidBodyPosition>
+            + ComponentSetMut<RigidBodyVelocity>
+            + ComponentSetMut<RigidBodyMassProps>
+            + ComponentSetMut<RigidBodyIds>
+            + ComponentSetMut<RigidBodyForces>
+            + ComponentSetMut<RigidBodyActivation>
+            + ComponentSetMut<RigidBodyChanges>
+            + ComponentSetMut<RigidBodyCcd>
+            + ComponentSet<RigidBodyColliders>
+            + ComponentSet<RigidBodyDamping>
+            + ComponentSet<RigidBodyDominance>
+            + ComponentSet<RigidBodyType>,
+        Colliders: ComponentSetMut<ColliderBroadPhaseData>
+            + ComponentSetMut<ColliderChanges>
+            + ComponentSetMut<ColliderPosition>
+            + ComponentSet<ColliderShape>
+            + ComponentSetOption<ColliderParent>
+            + ComponentSet<ColliderType>
+            + ComponentSet<ColliderMaterial>
+            + ComponentSetMut<ColliderFlags>,
     {
         self.counters.reset();
         self.counters.step_started();
 
+        multibody_joints.propagate_enabled_state(bodies, modified_bodies);
+
         super::user_changes::handle_user_changes_to_colliders(
             bodies,
             colliders,
@@ -538,6 +744,11 @@ impl PhysicsPipeline {
         // TODO: do this only on user-change.
         // TODO: do we want some kind of automatic inverse kinematics?
         for multibody in &mut multibody_joints.multibodies {
+            // NOTE: a disabled multibody is frozen until all its links are enabled again.
+            if !multibody.1.is_enabled(bodies) {
+                continue;
+            }
+
             multibody.1.update_root_type(bodies);
             // FIXME: what should we do here? We should not
             //        rely on the next state here.
@@ -558,6 +769,11 @@ impl PhysicsPipeline {
             true,
         );
 
+        if let Some(queries) = query_pipeline.as_deref_mut() {
+            queries.update_incremental(colliders, modified_colliders, removed_colliders, false);
+        }
+        ccd_solver.update_colliders(colliders, modified_colliders, removed_colliders);
+
         self.clear_modified_colliders(colliders, modified_colliders);
         removed_colliders.clear();
 
@@ -631,6 +847,21 @@ impl PhysicsPipeline {
 
             self.counters.ccd.num_substeps += 1;
 
+            self.predict_kinematic_positions(&integration_parameters, islands, bodies);
+
+            // If CCD is enabled, prevent the kinematic bodies with kinematic CCD
+            // from passing through the colliders they are blocked by.
+            if ccd_is_enabled {
+                self.counters.ccd.toi_computation_time.start();
+                ccd_solver.clamp_kinematic_motions(
+                    integration_parameters.dt,
+                    islands,
+                    bodies,
+                    colliders,
+                );
+                self.counters.ccd.toi_computation_time.pause();
+            }
+
             self.interpolate_kinematic_velocities(&integration_parameters, islands, bodies);
             self.build_islands_and_solve_velocity_constraints(
                 gravity,
@@ -689,11 +920,130 @@ impl PhysicsPipeline {
                 false,
             );
 
+            if let Some(queries) = query_pipeline.as_deref_mut() {
+                queries.update_incremental(
+                    colliders,
+                    modified_colliders,
+                    &[],
+                    remaining_substeps == 0,
+                );
+            }
+            ccd_solver.update_colliders(colliders, modified_colliders, &[]);
+
             self.clear_modified_colliders(colliders, modified_colliders);
         }
 
         self.counters.step_completed();
     }
+
+    /// Moves the origin of the simulation to the point `shift`.
+    ///
+    /// This is the same as `self.shift_origin_generic`, except that it is specialized
+    /// to work with `RigidBodySet` and `ColliderSet`.
+    #[cfg(feature = "default-sets")]
+    pub fn shift_origin(
+        &mut self,
+        shift: &Vector<Real>,
+        islands: &mut IslandManager,
+        broad_phase: &mut impl BroadPhase,
+        narrow_phase: &mut NarrowPhase,
+        bodies: &mut RigidBodySet,
+        colliders: &mut ColliderSet,
+        multibody_joints: &mut MultibodyJointSet,
+        query_pipeline: Option<&mut QueryPipeline>,
+        events: &dyn EventHandler,
+    ) {
+        self.shift_origin_generic(
+            shift,
+            islands,
+            broad_phase,
+            narrow_phase,
+            bodies,
+            colliders,
+            multibody_joints,
+            query_pipeline,
+            events,
+        )
+    }
+
+    /// Moves the origin of the simulation to the point `shift`.
+    ///
+    /// Everything is translated by `-shift`: the positions of all the rigid-bodies and colliders,
+    /// the broad-phase proxies, the contact points cached by the narrow-phase, and the acceleration
+    /// structure and recorded collider poses of the `query_pipeline` if one is given. This is
+    /// typically used in large worlds to keep the simulation close to the origin, where
+    /// floating-point numbers are the most accurate, e.g., by periodically moving the origin to
+    /// the position of the player.
+    ///
+    /// The relative positions of the simulated objects are preserved, so this doesn't wake
+    /// up any rigid-body, and all the contacts and their impulses used for warm-starting are
+    /// preserved.
+    pub fn shift_origin_generic<Bodies, Colliders>(
+        &mut self,
+        shift: &Vector<Real>,
+        islands: &mut IslandManager,
+        broad_phase: &mut impl BroadPhase,
+        narrow_phase: &mut NarrowPhase,
+        bodies: &mut Bodies,
+        colliders: &mut Colliders,
+        multibody_joints: &mut MultibodyJointSet,
+        query_pipeline: Option<&mut QueryPipeline>,
+        events: &dyn EventHandler,
+    ) where
+        Bodies: ComponentSetMut<RigidBodyPosition>
+            + ComponentSetMut<RigidBodyMassProps>
+            + ComponentSetMut<RigidBodyActivation>
+            + ComponentSetMut<RigidBodyIds>
+            + ComponentSet<RigidBodyType>,
+        Colliders: ComponentSetMut<ColliderPosition>
+            + ComponentSet<ColliderShape>
+            + ComponentSetOption<ColliderParent>
+            + ComponentSet<ColliderType>
+            + ComponentSet<ColliderFlags>,
+    {
+        let mut handles = vec![];
+        bodies.for_each(|handle, _: &RigidBodyPosition| handles.push(handle));
+
+        for handle in handles.drain(..) {
+            bodies.map_mut_internal(handle, |rb_pos: &mut RigidBodyPosition| {
+                rb_pos.position.translation.vector -= shift;
+                rb_pos.next_position.translation.vector -= shift;
+            });
+            bodies.map_mut_internal(handle, |rb_mprops: &mut RigidBodyMassProps| {
+                rb_mprops.world_com -= shift;
+            });
+        }
+
+        colliders.for_each(|handle, _: &ColliderPosition| handles.push(handle));
+
+        for handle in handles {
+            colliders.map_mut_internal(handle, |co_pos: &mut ColliderPosition| {
+                co_pos.translation.vector -= shift;
+            });
+        }
+
+        for multibody in multibody_joints.multibodies.iter_mut() {
+            multibody.1.shift_origin(shift);
+        }
+
+        // NOTE: the broad-phase may only report pairs starting or stopping to intersect
+        //       because of rounding errors.
+        self.broad_phase_events.clear();
+        broad_phase.shift_origin(shift, &mut self.broad_phase_events);
+        narrow_phase.register_pairs(
+            Some(islands),
+            colliders,
+            bodies,
+            &self.broad_phase_events,
+            events,
+        );
+        narrow_phase.shift_origin(shift);
+
+        if let Some(queries) = query_pipeline {
+            queries.rebuild(colliders);
+            queries.history.shift_origin(shift);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -702,9 +1052,12 @@ mod test {
         CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager, RigidBodyBuilder,
         RigidBodySet,
     };
-    use crate::geometry::{BroadPhase, ColliderBuilder, ColliderSet, NarrowPhase};
-    use crate::math::Vector;
-    use crate::pipeline::PhysicsPipeline;
+    use crate::geometry::{
+        BroadPhase, BroadPhaseBvh, ColliderBuilder, ColliderSet, DefaultBroadPhase,
+        InteractionGroups, NarrowPhase, Ray,
+    };
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::{PhysicsPipeline, QueryPipeline};
     use crate::prelude::MultibodyJointSet;
 
     #[test]
@@ -713,7 +1066,7 @@ mod test {
         let mut impulse_joints = ImpulseJointSet::new();
         let mut multibody_joints = MultibodyJointSet::new();
         let mut pipeline = PhysicsPipeline::new();
-        let mut bf = BroadPhase::new();
+        let mut bf = DefaultBroadPhase::new();
         let mut nf = NarrowPhase::new();
         let mut bodies = RigidBodySet::new();
         let mut islands = IslandManager::new();
@@ -750,7 +1103,7 @@ mod test {
         let mut impulse_joints = ImpulseJointSet::new();
         let mut multibody_joints = MultibodyJointSet::new();
         let mut pipeline = PhysicsPipeline::new();
-        let mut bf = BroadPhase::new();
+        let mut bf = DefaultBroadPhase::new();
         let mut nf = NarrowPhase::new();
         let mut islands = IslandManager::new();
 
@@ -855,7 +1208,7 @@ mod test {
         let mut pipeline = PhysicsPipeline::new();
         let gravity = Vector::y() * -9.81;
         let integration_parameters = IntegrationParameters::default();
-        let mut broad_phase = BroadPhase::new();
+        let mut broad_phase = DefaultBroadPhase::new();
         let mut narrow_phase = NarrowPhase::new();
         let mut bodies = RigidBodySet::new();
         let mut colliders = ColliderSet::new();
@@ -896,4 +1249,146 @@ mod test {
             );
         }
     }
+
+    fn shifted_stack(mut broad_phases: [impl BroadPhase; 2]) {
+        #[cfg(feature = "dim2")]
+        let shift = Vector::new(10_000.5, -2_000.25);
+        #[cfg(feature = "dim3")]
+        let shift = Vector::new(10_000.5, -2_000.25, 5_000.0);
+        let mut pipeline = PhysicsPipeline::new();
+        let mut query_pipeline = QueryPipeline::new();
+        let gravity = Vector::y() * -9.81;
+        let integration_parameters = IntegrationParameters::default();
+        let mut worlds: Vec<_> = (0..2)
+            .map(|_| {
+                let mut bodies = RigidBodySet::new();
+                let mut colliders = ColliderSet::new();
+
+                #[cfg(feature = "dim3")]
+                let ground = ColliderBuilder::cuboid(20.0, 0.5, 20.0);
+                #[cfg(feature = "dim2")]
+                let ground = ColliderBuilder::cuboid(20.0, 0.5);
+                let _ = colliders.insert(ground.translation(shift).build());
+
+                for i in 0..3 {
+                    let rb = RigidBodyBuilder::new_dynamic()
+                        .translation(shift + Vector::y() * (1.0 + i as Real))
+                        .build();
+                    let handle = bodies.insert(rb);
+                    #[cfg(feature = "dim3")]
+                    let collider = ColliderBuilder::cuboid(0.5, 0.5, 0.5).build();
+                    #[cfg(feature = "dim2")]
+                    let collider = ColliderBuilder::cuboid(0.5, 0.5).build();
+                    let _ = colliders.insert_with_parent(collider, handle, &mut bodies);
+                }
+
+                (
+                    bodies,
+                    colliders,
+                    IslandManager::new(),
+                    NarrowPhase::new(),
+                    ImpulseJointSet::new(),
+                    MultibodyJointSet::new(),
+                )
+            })
+            .collect();
+
+        let contact_impulses = |narrow_phase: &NarrowPhase| -> Vec<Real> {
+            narrow_phase
+                .contact_pairs()
+                .flat_map(|pair| pair.manifolds.iter())
+                .flat_map(|manifold| manifold.points.iter())
+                .map(|contact| contact.data.impulse)
+                .collect()
+        };
+
+        for step in 0..200 {
+            for (k, (world, broad_phase)) in
+                worlds.iter_mut().zip(broad_phases.iter_mut()).enumerate()
+            {
+                let (bodies, colliders, islands, narrow_phase, impulse_joints, multibody_joints) =
+                    world;
+                // Only the second world, which will be shifted, has a query pipeline.
+                let queries = if k == 1 {
+                    Some(&mut query_pipeline)
+                } else {
+                    None
+                };
+
+                pipeline.step_and_update_queries(
+                    &gravity,
+                    &integration_parameters,
+                    islands,
+                    broad_phase,
+                    narrow_phase,
+                    bodies,
+                    colliders,
+                    impulse_joints,
+                    multibody_joints,
+                    &mut CCDSolver::new(),
+                    queries,
+                    &(),
+                    &(),
+                );
+            }
+
+            if step == 100 {
+                // Move the origin of the second world to the center of the ground.
+                let (bodies, colliders, islands, narrow_phase, _, multibody_joints) =
+                    &mut worlds[1];
+                let sleeping: Vec<_> = bodies.iter().map(|(_, rb)| rb.is_sleeping()).collect();
+                let impulses = contact_impulses(narrow_phase);
+                assert!(!impulses.is_empty());
+
+                pipeline.shift_origin(
+                    &shift,
+                    islands,
+                    &mut broad_phases[1],
+                    narrow_phase,
+                    bodies,
+                    colliders,
+                    multibody_joints,
+                    Some(&mut query_pipeline),
+                    &(),
+                );
+
+                // Nothing is woken up, and the contacts are preserved.
+                let new_sleeping: Vec<_> = bodies.iter().map(|(_, rb)| rb.is_sleeping()).collect();
+                assert_eq!(sleeping, new_sleeping);
+                assert_eq!(impulses, contact_impulses(narrow_phase));
+
+                // The query pipeline is shifted too.
+                let ray = Ray::new(Point::origin() + Vector::y() * 10.0, -Vector::y());
+                let hit = query_pipeline.cast_ray(
+                    colliders,
+                    &ray,
+                    Real::MAX,
+                    true,
+                    InteractionGroups::all(),
+                    None,
+                );
+                let hit_parent = hit.and_then(|hit| colliders[hit.0].parent());
+                assert_eq!(hit_parent, bodies.iter().last().map(|(handle, _)| handle));
+            }
+        }
+
+        // The shifted world must behave like the original world.
+        let positions1 = worlds[0].0.iter().map(|(_, rb)| rb.translation() - shift);
+        let positions2 = worlds[1].0.iter().map(|(_, rb)| *rb.translation());
+
+        for (pos1, pos2) in positions1.zip(positions2) {
+            assert!((pos1 - pos2).norm() < 1.0e-2, "{} != {}", pos1, pos2);
+        }
+
+        assert_eq!(
+            worlds[0].3.contact_pairs().count(),
+            worlds[1].3.contact_pairs().count()
+        );
+    }
+
+    #[test]
+    fn shift_origin() {
+        shifted_stack([DefaultBroadPhase::new(), DefaultBroadPhase::new()]);
+        shifted_stack([BroadPhaseBvh::new(), BroadPhaseBvh::new()]);
+    }
 }
@@ -0,0 +1,658 @@
+//! Queries against the past poses of the colliders, e.g., for lag compensation.
+
+use crate::data::ComponentSet;
+use crate::geometry::{
+    ColliderFlags, ColliderHandle, ColliderPosition, ColliderShape, InteractionGroups, Ray,
+    RayIntersection, AABB, QBVH,
+};
+use crate::math::{Isometry, Real, Vector};
+use crate::pipeline::QueryPipeline;
+use parry::partitioning::QBVHDataGenerator;
+use parry::query::details::{
+    RayCompositeShapeToiAndNormalBestFirstVisitor, RayCompositeShapeToiBestFirstVisitor,
+};
+use parry::query::visitors::BoundingVolumeIntersectionsVisitor;
+use parry::shape::{Shape, TypedSimdCompositeShape};
+use parry::utils::hashmap::{Entry, HashMap};
+use std::collections::VecDeque;
+
+/// The poses of the colliders recorded during the last steps.
+///
+/// Only the poses that changed since the previous record are stored by each record.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ColliderPoseHistory {
+    capacity: usize,
+    // The pose of each collider at the last record, and the id of the last record
+    // it has been seen by.
+    poses: HashMap<ColliderHandle, (Isometry<Real>, u64)>,
+    records: VecDeque<PoseRecord>,
+    num_records: u64,
+}
+
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
+struct PoseRecord {
+    timestamp: Real,
+    // The poses, at the previous record, of the colliders moved, inserted (`None`),
+    // or removed since the previous record.
+    prev_poses: Vec<(ColliderHandle, Option<Isometry<Real>>)>,
+}
+
+impl ColliderPoseHistory {
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+
+        if capacity == 0 {
+            self.poses.clear();
+            self.records.clear();
+        } else {
+            while self.records.len() > capacity {
+                let _ = self.records.pop_front();
+            }
+        }
+    }
+
+    pub fn timestamps(&self) -> impl ExactSizeIterator<Item = Real> + '_ {
+        self.records.iter().map(|record| record.timestamp)
+    }
+
+    pub fn record<Colliders>(&mut self, timestamp: Real, colliders: &Colliders)
+    where
+        Colliders: ComponentSet<ColliderPosition>,
+    {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if let Some(last) = self.records.back() {
+            assert!(
+                timestamp > last.timestamp,
+                "The recorded timestamps must be increasing."
+            );
+        }
+
+        self.num_records += 1;
+        let id = self.num_records;
+        let mut prev_poses = vec![];
+        let poses = &mut self.poses;
+
+        colliders.for_each(|handle, co_pos: &ColliderPosition| {
+            let handle = ColliderHandle(handle);
+
+            match poses.entry(handle) {
+                Entry::Occupied(mut entry) => {
+                    let (pose, seen_by) = entry.get_mut();
+
+                    if *pose != co_pos.0 {
+                        prev_poses.push((handle, Some(*pose)));
+                        *pose = co_pos.0;
+                    }
+
+                    *seen_by = id;
+                }
+                Entry::Vacant(entry) => {
+                    let _ = entry.insert((co_pos.0, id));
+                    prev_poses.push((handle, None));
+                }
+            }
+        });
+
+        poses.retain(|handle, (pose, seen_by)| {
+            let removed = *seen_by != id;
+
+            if removed {
+                prev_poses.push((*handle, Some(*pose)));
+            }
+
+            !removed
+        });
+
+        if self.records.is_empty() {
+            // There is no previous record to restore.
+            prev_poses.clear();
+        }
+
+        self.records.push_back(PoseRecord {
+            timestamp,
+            prev_poses,
+        });
+
+        while self.records.len() > self.capacity {
+            let _ = self.records.pop_front();
+        }
+    }
+
+    pub fn shift_origin(&mut self, shift: &Vector<Real>) {
+        for (pose, _) in self.poses.values_mut() {
+            pose.translation.vector -= shift;
+        }
+
+        for record in &mut self.records {
+            for pose in record
+                .prev_poses
+                .iter_mut()
+                .filter_map(|(_, pose)| pose.as_mut())
+            {
+                pose.translation.vector -= shift;
+            }
+        }
+    }
+
+    /// The poses of the colliders at `timestamp` that differ from their poses at the last record.
+    ///
+    /// A `None` pose indicates that the collider didn't exist at `timestamp`.
+    fn poses_at(&self, timestamp: Real) -> Option<HashMap<ColliderHandle, Option<Isometry<Real>>>> {
+        let first = self.records.front()?;
+        let last = self.records.back()?;
+
+        if timestamp < first.timestamp || timestamp > last.timestamp {
+            return None;
+        }
+
+        // The last record at or before `timestamp`.
+        let i = self.records.partition_point(|r| r.timestamp <= timestamp) - 1;
+        let mut next_poses = HashMap::default();
+
+        // NOTE: iterate from the latest record, so the older poses overwrite the newer ones.
+        for record in self.records.iter().skip(i + 2).rev() {
+            next_poses.extend(record.prev_poses.iter().copied());
+        }
+
+        let next = match self.records.get(i + 1) {
+            Some(next) => next,
+            None => return Some(next_poses),
+        };
+
+        let mut poses = next_poses.clone();
+        poses.extend(next.prev_poses.iter().copied());
+
+        let t =
+            (timestamp - self.records[i].timestamp) / (next.timestamp - self.records[i].timestamp);
+
+        if t > 0.0 {
+            for (handle, pose) in poses.iter_mut() {
+                let next_pose = next_poses
+                    .get(handle)
+                    .copied()
+                    .unwrap_or_else(|| self.poses.get(handle).map(|p| p.0));
+
+                *pose = match (*pose, next_pose) {
+                    (Some(pose), Some(next_pose)) => Some(pose.lerp_slerp(&next_pose, t)),
+                    // The collider was inserted or removed in-between: take the closest record.
+                    (pose, next_pose) => {
+                        if t < 0.5 {
+                            pose
+                        } else {
+                            next_pose
+                        }
+                    }
+                };
+            }
+        }
+
+        Some(poses)
+    }
+}
+
+impl QueryPipeline {
+    /// The maximum number of steps for which the collider poses are recorded by
+    /// [`QueryPipeline::record_history`].
+    ///
+    /// This is zero by default, i.e., no history is recorded.
+    pub fn history_capacity(&self) -> usize {
+        self.history.capacity()
+    }
+
+    /// Sets the maximum number of steps for which the collider poses are recorded by
+    /// [`QueryPipeline::record_history`].
+    ///
+    /// The oldest records are discarded. Setting it to zero clears the history.
+    pub fn set_history_capacity(&mut self, capacity: usize) {
+        self.history.set_capacity(capacity)
+    }
+
+    /// The timestamps of the recorded collider poses, from the oldest to the latest.
+    pub fn history_timestamps(&self) -> impl ExactSizeIterator<Item = Real> + '_ {
+        self.history.timestamps()
+    }
+
+    /// Records the current poses of all the colliders, at the given `timestamp`.
+    ///
+    /// This should be called after each step of the simulation, with an increasing
+    /// timestamp (typically the simulation time). Only the poses that changed since
+    /// the last record are stored. This does nothing if the history capacity is zero.
+    pub fn record_history<Colliders>(&mut self, timestamp: Real, colliders: &Colliders)
+    where
+        Colliders: ComponentSet<ColliderPosition>,
+    {
+        self.history.record(timestamp, colliders)
+    }
+
+    /// A view of this query pipeline where the colliders are at their recorded poses at `timestamp`.
+    ///
+    /// If `timestamp` lies between two records, the poses are interpolated. Colliders inserted
+    /// after `timestamp` are ignored, and colliders removed since then can't be queried because
+    /// their shape no longer exists. Returns `None` if `timestamp` isn't in the recorded range.
+    ///
+    /// The colliders must not have moved since the last call to [`QueryPipeline::record_history`].
+    ///
+    /// This builds an acceleration structure for all the colliders that moved since `timestamp`,
+    /// which costs `O(n log n)` where `n` is the number of such colliders. Reuse the returned
+    /// view to run several queries at the same timestamp instead of calling this once per query.
+    pub fn at_timestamp<Colliders>(
+        &self,
+        colliders: &Colliders,
+        timestamp: Real,
+    ) -> Option<HistoricalQueryPipeline<'_>>
+    where
+        Colliders: ComponentSet<ColliderShape>,
+    {
+        let poses = self.history.poses_at(timestamp)?;
+        let mut qbvh = QBVH::new();
+        let generator = HistoricalDataGenerator {
+            colliders,
+            poses: &poses,
+        };
+        qbvh.clear_and_rebuild(generator, 0.0);
+
+        Some(HistoricalQueryPipeline {
+            pipeline: self,
+            poses,
+            qbvh,
+        })
+    }
+}
+
+/// A query pipeline with the colliders at their past poses, obtained with [`QueryPipeline::at_timestamp`].
+pub struct HistoricalQueryPipeline<'a> {
+    pipeline: &'a QueryPipeline,
+    // The past poses of the colliders that moved since then.
+    poses: HashMap<ColliderHandle, Option<Isometry<Real>>>,
+    // The colliders of `poses` at their past poses.
+    qbvh: QBVH<ColliderHandle>,
+}
+
+impl<'a> HistoricalQueryPipeline<'a> {
+    /// Find the closest intersection between a ray and the colliders at their past poses.
+    ///
+    /// See [`QueryPipeline::cast_ray`] for a description of the parameters.
+    pub fn cast_ray<Colliders>(
+        &self,
+        colliders: &Colliders,
+        ray: &Ray,
+        max_toi: Real,
+        solid: bool,
+        query_groups: InteractionGroups,
+        filter: Option<&dyn Fn(ColliderHandle) -> bool>,
+    ) -> Option<(ColliderHandle, Real)>
+    where
+        Colliders: ComponentSet<ColliderFlags>
+            + ComponentSet<ColliderPosition>
+            + ComponentSet<ColliderShape>,
+    {
+        let unmoved_filter = |handle| self.unmoved(handle, filter);
+        let unmoved_hit = self.pipeline.cast_ray(
+            colliders,
+            ray,
+            max_toi,
+            solid,
+            query_groups,
+            Some(&unmoved_filter),
+        );
+
+        let shape = self.as_composite_shape(colliders, query_groups, filter);
+        let mut visitor = RayCompositeShapeToiBestFirstVisitor::new(&shape, ray, max_toi, solid);
+        let moved_hit = self.qbvh.traverse_best_first(&mut visitor).map(|h| h.1);
+
+        super::query_pipeline::closest(unmoved_hit.into_iter().chain(moved_hit), |(_, toi)| *toi)
+    }
+
+    /// Find the closest intersection between a ray and the colliders at their past poses.
+    ///
+    /// See [`QueryPipeline::cast_ray_and_get_normal`] for a description of the parameters.
+    pub fn cast_ray_and_get_normal<Colliders>(
+        &self,
+        colliders: &Colliders,
+        ray: &Ray,
+        max_toi: Real,
+        solid: bool,
+        query_groups: InteractionGroups,
+        filter: Option<&dyn Fn(ColliderHandle) -> bool>,
+    ) -> Option<(ColliderHandle, RayIntersection)>
+    where
+        Colliders: ComponentSet<ColliderFlags>
+            + ComponentSet<ColliderPosition>
+            + ComponentSet<ColliderShape>,
+    {
+        let unmoved_filter = |handle| self.unmoved(handle, filter);
+        let unmoved_hit = self.pipeline.cast_ray_and_get_normal(
+            colliders,
+            ray,
+            max_toi,
+            solid,
+            query_groups,
+            Some(&unmoved_filter),
+        );
+
+        let shape = self.as_composite_shape(colliders, query_groups, filter);
+        let mut visitor =
+            RayCompositeShapeToiAndNormalBestFirstVisitor::new(&shape, ray, max_toi, solid);
+        let moved_hit = self.qbvh.traverse_best_first(&mut visitor).map(|h| h.1);
+
+        super::query_pipeline::closest(unmoved_hit.into_iter().chain(moved_hit), |(_, hit)| hit.toi)
+    }
+
+    /// Retrieve all the colliders intersecting the given shape, with the colliders at their past poses.
+    ///
+    /// See [`QueryPipeline::intersections_with_shape`] for a description of the parameters.
+    pub fn intersections_with_shape<Colliders>(
+        &self,
+        colliders: &Colliders,
+        shape_pos: &Isometry<Real>,
+        shape: &dyn Shape,
+        query_groups: InteractionGroups,
+        filter: Option<&dyn Fn(ColliderHandle) -> bool>,
+        mut callback: impl FnMut(ColliderHandle) -> bool,
+    ) where
+        Colliders: ComponentSet<ColliderFlags>
+            + ComponentSet<ColliderPosition>
+            + ComponentSet<ColliderShape>,
+    {
+        let mut exit_early = false;
+        let unmoved_filter = |handle| self.unmoved(handle, filter);
+        self.pipeline.intersections_with_shape(
+            colliders,
+            shape_pos,
+            shape,
+            query_groups,
+            Some(&unmoved_filter),
+            |handle| {
+                exit_early = !callback(handle);
+                !exit_early
+            },
+        );
+
+        if exit_early {
+            return;
+        }
+
+        let dispatcher = self.pipeline.query_dispatcher();
+        let inv_shape_pos = shape_pos.inverse();
+        let mut leaf_callback = &mut |handle: &ColliderHandle| {
+            let co_shape: Option<&ColliderShape> = colliders.get(handle.0);
+            let co_pos = self.poses.get(handle).copied().flatten();
+
+            if let (Some(co_shape), Some(co_pos)) = (co_shape, co_pos) {
+                let co_flags: &ColliderFlags = colliders.index(handle.0);
+
+                if co_flags.enabled.is_enabled()
+                    && co_flags.collision_groups.test(query_groups)
+                    && filter.map(|f| f(*handle)).unwrap_or(true)
+                {
+                    let pos12 = inv_shape_pos * co_pos;
+
+                    if dispatcher.intersection_test(&pos12, shape, &**co_shape) == Ok(true) {
+                        return callback(*handle);
+                    }
+                }
+            }
+
+            true
+        };
+
+        let shape_aabb = shape.compute_aabb(shape_pos);
+        let mut visitor = BoundingVolumeIntersectionsVisitor::new(&shape_aabb, &mut leaf_callback);
+        self.qbvh.traverse_depth_first(&mut visitor);
+    }
+
+    /// Is `handle` a collider that didn't move since the queried timestamp, and
+    /// that passes the user-defined `filter`?
+    fn unmoved(
+        &self,
+        handle: ColliderHandle,
+        filter: Option<&dyn Fn(ColliderHandle) -> bool>,
+    ) -> bool {
+        !self.poses.contains_key(&handle) && filter.map(|f| f(handle)).unwrap_or(true)
+    }
+
+    fn as_composite_shape<'b, Colliders>(
+        &'b self,
+        colliders: &'b Colliders,
+        query_groups: InteractionGroups,
+        filter: Option<&'b dyn Fn(ColliderHandle) -> bool>,
+    ) -> HistoricalCompositeShape<'b, Colliders> {
+        HistoricalCompositeShape {
+            qbvh: &self.qbvh,
+            poses: &self.poses,
+            colliders,
+            query_groups,
+            filter,
+        }
+    }
+}
+
+struct HistoricalCompositeShape<'a, Colliders> {
+    qbvh: &'a QBVH<ColliderHandle>,
+    poses: &'a HashMap<ColliderHandle, Option<Isometry<Real>>>,
+    colliders: &'a Colliders,
+    query_groups: InteractionGroups,
+    filter: Option<&'a dyn Fn(ColliderHandle) -> bool>,
+}
+
+impl<'a, Colliders> TypedSimdCompositeShape for HistoricalCompositeShape<'a, Colliders>
+where
+    Colliders: ComponentSet<ColliderFlags> + ComponentSet<ColliderShape>,
+{
+    type PartShape = dyn Shape;
+    type PartId = ColliderHandle;
+
+    fn map_typed_part_at(
+        &self,
+        shape_id: Self::PartId,
+        mut f: impl FnMut(Option<&Isometry<Real>>, &Self::PartShape),
+    ) {
+        let co_flags: Option<&ColliderFlags> = self.colliders.get(shape_id.0);
+        let co_pos = self.poses.get(&shape_id).and_then(|pose| pose.as_ref());
+
+        if let (Some(co_flags), Some(co_pos)) = (co_flags, co_pos) {
+            if co_flags.enabled.is_enabled()
+                && co_flags.collision_groups.test(self.query_groups)
+                && self.filter.map(|f| f(shape_id)).unwrap_or(true)
+            {
+                let co_shape: &ColliderShape = self.colliders.index(shape_id.0);
+                f(Some(co_pos), &**co_shape)
+            }
+        }
+    }
+
+    fn map_untyped_part_at(
+        &self,
+        shape_id: Self::PartId,
+        f: impl FnMut(Option<&Isometry<Real>>, &Self::PartShape),
+    ) {
+        self.map_typed_part_at(shape_id, f);
+    }
+
+    fn typed_qbvh(&self) -> &QBVH<ColliderHandle> {
+        self.qbvh
+    }
+}
+
+struct HistoricalDataGenerator<'a, Colliders> {
+    colliders: &'a Colliders,
+    poses: &'a HashMap<ColliderHandle, Option<Isometry<Real>>>,
+}
+
+impl<'a, Colliders> QBVHDataGenerator<ColliderHandle> for HistoricalDataGenerator<'a, Colliders>
+where
+    Colliders: ComponentSet<ColliderShape>,
+{
+    fn size_hint(&self) -> usize {
+        self.poses.len()
+    }
+
+    #[inline(always)]
+    fn for_each(&mut self, mut f: impl FnMut(ColliderHandle, AABB)) {
+        for (handle, pose) in self.poses {
+            let co_shape: Option<&ColliderShape> = self.colliders.get(handle.0);
+
+            // NOTE: the colliders removed since then no longer have a shape.
+            if let (Some(co_shape), Some(pose)) = (co_shape, pose) {
+                f(*handle, co_shape.compute_aabb(pose))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::{Ball, ColliderBuilder, InteractionGroups, Ray};
+    use crate::math::{Isometry, Point, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+    use crate::pipeline::QueryPipeline;
+
+    #[test]
+    fn lag_compensated_queries() {
+        let mut world = TestWorld::new(Vector::zeros());
+        let mut query_pipeline = QueryPipeline::new();
+        query_pipeline.set_history_capacity(10);
+        world.query_pipeline = Some(query_pipeline);
+        let dt = world.integration_parameters.dt;
+
+        let rb = RigidBodyBuilder::new_dynamic()
+            .linvel(Vector::x() * 10.0)
+            .build();
+        let (_, ball) = world.insert_body(rb, ColliderBuilder::ball(0.5).build());
+        let mut positions = vec![];
+        let mut late_ball = None;
+
+        for step in 1..=30 {
+            if step == 25 {
+                let co = ColliderBuilder::ball(0.5)
+                    .translation(Vector::x() * 100.0)
+                    .build();
+                late_ball = Some(world.colliders.insert(co));
+            }
+
+            world.step();
+            let query_pipeline = world.query_pipeline.as_mut().unwrap();
+            query_pipeline.record_history(step as Real * dt, &world.colliders);
+            positions.push(world.colliders[ball].translation().x);
+        }
+
+        let colliders = &world.colliders;
+        let query_pipeline = world.query_pipeline.as_ref().unwrap();
+        assert_eq!(query_pipeline.history_timestamps().len(), 10);
+        assert!(query_pipeline.at_timestamp(colliders, 20.0 * dt).is_none());
+        assert!(query_pipeline.at_timestamp(colliders, 31.0 * dt).is_none());
+
+        let cast_ray = |timestamp: Option<Real>, x: Real| {
+            let origin = Point::origin() + Vector::x() * x + Vector::y() * 10.0;
+            let ray = Ray::new(origin, -Vector::y());
+            let groups = InteractionGroups::all();
+
+            match timestamp {
+                Some(timestamp) => query_pipeline
+                    .at_timestamp(colliders, timestamp)
+                    .unwrap()
+                    .cast_ray(colliders, &ray, Real::MAX, true, groups, None),
+                None => query_pipeline.cast_ray(colliders, &ray, Real::MAX, true, groups, None),
+            }
+            .map(|hit| hit.0)
+        };
+
+        // The ball is hit at its past position only.
+        let x22 = positions[21];
+        assert_eq!(cast_ray(None, x22), None);
+        assert_eq!(cast_ray(Some(22.0 * dt), x22), Some(ball));
+        assert_eq!(cast_ray(Some(26.0 * dt), x22), None);
+
+        // The poses are interpolated between two records.
+        let x_mid = (positions[21] + positions[22]) / 2.0 + 0.45;
+        assert_eq!(cast_ray(Some(22.0 * dt), x_mid), None);
+        assert_eq!(cast_ray(Some(22.5 * dt), x_mid), Some(ball));
+
+        // The collider inserted later didn't exist yet.
+        assert_eq!(cast_ray(None, 100.0), late_ball);
+        assert_eq!(cast_ray(Some(24.0 * dt), 100.0), None);
+        assert_eq!(cast_ray(Some(25.0 * dt), 100.0), late_ball);
+
+        let mut found = vec![];
+        let shape_pos = Isometry::new(Vector::x() * x22, na::zero());
+        query_pipeline
+            .at_timestamp(colliders, 22.0 * dt)
+            .unwrap()
+            .intersections_with_shape(
+                colliders,
+                &shape_pos,
+                &Ball::new(0.1),
+                InteractionGroups::all(),
+                None,
+                |handle| {
+                    found.push(handle);
+                    true
+                },
+            );
+        assert_eq!(found, vec![ball]);
+    }
+
+    #[test]
+    fn lag_compensated_queries_ignore_disabled_colliders() {
+        let mut world = TestWorld::new(Vector::zeros());
+        let mut query_pipeline = QueryPipeline::new();
+        query_pipeline.set_history_capacity(10);
+        world.query_pipeline = Some(query_pipeline);
+        let dt = world.integration_parameters.dt;
+
+        let rb = RigidBodyBuilder::new_dynamic()
+            .linvel(Vector::x() * 10.0)
+            .build();
+        let (_, ball) = world.insert_body(rb, ColliderBuilder::ball(0.5).build());
+        let x0 = world.colliders[ball].translation().x;
+
+        for step in 1..=5 {
+            world.step();
+            let query_pipeline = world.query_pipeline.as_mut().unwrap();
+            query_pipeline.record_history(step as Real * dt, &world.colliders);
+        }
+
+        let query = |world: &TestWorld| {
+            let colliders = &world.colliders;
+            let past = world
+                .query_pipeline
+                .as_ref()
+                .unwrap()
+                .at_timestamp(colliders, dt)
+                .unwrap();
+            let groups = InteractionGroups::all();
+            let shape_pos = Isometry::new(Vector::x() * x0, na::zero());
+            let mut found = vec![];
+            past.intersections_with_shape(
+                colliders,
+                &shape_pos,
+                &Ball::new(0.1),
+                groups,
+                None,
+                |handle| {
+                    found.push(handle);
+                    true
+                },
+            );
+
+            let origin = Point::origin() + Vector::x() * x0 + Vector::y() * 10.0;
+            let ray = Ray::new(origin, -Vector::y());
+            let hit = past.cast_ray(colliders, &ray, Real::MAX, true, groups, None);
+            (found, hit.map(|hit| hit.0))
+        };
+
+        assert_eq!(query(&world), (vec![ball], Some(ball)));
+        world.colliders[ball].set_enabled(false);
+        assert_eq!(query(&world), (vec![], None));
+    }
+}
@@ -1,14 +1,17 @@
-use crate::data::{BundleSet, ComponentSet, ComponentSetOption};
+use crate::data::{BundleSet, Coarena, ComponentSet, ComponentSetOption};
 use crate::dynamics::{
     IslandManager, RigidBodyColliders, RigidBodyForces, RigidBodyMassProps, RigidBodyPosition,
     RigidBodyVelocity,
 };
 use crate::geometry::{
     ColliderFlags, ColliderHandle, ColliderParent, ColliderPosition, ColliderShape,
-    InteractionGroups, PointProjection, Ray, RayIntersection, AABB, QBVH,
+    ContactManifold, InteractionGroups, NarrowPhase, PointProjection, Ray, RayIntersection, AABB,
+    QBVH,
 };
-use crate::math::{Isometry, Point, Real, Vector};
-use parry::partitioning::QBVHDataGenerator;
+use crate::math::{Isometry, Point, Real, SimdBool, SimdReal, Vector, SIMD_WIDTH};
+use crate::pipeline::query_history::ColliderPoseHistory;
+use parry::bounding_volume::{BoundingVolume, SimdAABB};
+use parry::partitioning::{QBVHDataGenerator, SimdVisitStatus, SimdVisitor};
 use parry::query::details::{
     IntersectionCompositeShapeShapeBestFirstVisitor,
     NonlinearTOICompositeShapeShapeBestFirstVisitor, PointCompositeShapeProjBestFirstVisitor,
@@ -19,13 +22,25 @@ use parry::query::details::{
 use parry::query::visitors::{
     BoundingVolumeIntersectionsVisitor, PointIntersectionsVisitor, RayIntersectionsVisitor,
 };
-use parry::query::{DefaultQueryDispatcher, NonlinearRigidMotion, QueryDispatcher, TOI};
+use parry::query::{DefaultQueryDispatcher, NonlinearRigidMotion, QueryDispatcher, SimdRay, TOI};
 use parry::shape::{FeatureId, Shape, TypedSimdCompositeShape};
+use parry::utils::IsometryOpt;
+use simba::simd::{SimdBool as _, SimdValue};
+use std::cell::Cell;
 use std::sync::Arc;
 
 #[cfg(feature = "default-sets")]
 use crate::{dynamics::RigidBodySet, geometry::ColliderSet};
 
+/// The maximum number of colliders inserted with `QueryPipeline::update_incremental`
+/// before the acceleration structure is fully rebuilt.
+const MAX_NUM_INSERTED_COLLIDERS: usize = 1024;
+/// The number of rays traversing the acceleration structure together in `QueryPipeline::cast_rays`.
+const RAY_BATCH_SIZE: usize = 16;
+/// The penetration depth below which `QueryPipeline::depenetration_vector` considers that
+/// a penetration is resolved.
+const DEPENETRATION_TOLERANCE: Real = 1.0e-4;
+
 /// A pipeline for performing queries on all the colliders of a scene.
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Clone)]
@@ -38,10 +53,25 @@ pub struct QueryPipeline {
     qbvh: QBVH<ColliderHandle>,
     tree_built: bool,
     dilation_factor: Real,
+    // The colliders contained by `qbvh`, i.e., all the colliders as of its last
+    // full rebuild, minus the colliders removed since then.
+    qbvh_colliders: Coarena<()>,
+    num_qbvh_colliders: usize,
+    // The colliders inserted since the last full rebuild of `qbvh`. They are kept
+    // into a separate (smaller) tree until the next full rebuild.
+    inserted_qbvh: QBVH<ColliderHandle>,
+    inserted_colliders: Vec<ColliderHandle>,
+    // The index of each collider into `inserted_colliders`.
+    inserted_colliders_ids: Coarena<u32>,
+    inserted_qbvh_outdated: bool,
+    // The number of collider insertions, removals, and refits since the last full rebuild.
+    num_changes: usize,
+    rebuild_threshold: Real,
+    pub(super) history: ColliderPoseHistory,
 }
 
 struct QueryPipelineAsCompositeShape<'a, Colliders> {
-    query_pipeline: &'a QueryPipeline,
+    qbvh: &'a QBVH<ColliderHandle>,
     colliders: &'a Colliders,
     query_groups: InteractionGroups,
     filter: Option<&'a dyn Fn(ColliderHandle) -> bool>,
@@ -63,6 +93,31 @@ pub enum QueryPipelineMode {
     },
 }
 
+/// A contact manifold between a shape and a collider, computed by [`QueryPipeline::contacts_with_shape`].
+#[derive(Clone, Debug)]
+pub struct ShapeContactManifold {
+    /// The collider in contact with the shape.
+    pub collider: ColliderHandle,
+    /// The world-space contact normal, pointing from the shape toward the collider.
+    pub normal: Vector<Real>,
+    /// The contact points of this manifold.
+    pub points: Vec<ShapeContactPoint>,
+}
+
+/// A contact point between a shape and a collider.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ShapeContactPoint {
+    /// The world-space contact point on the shape.
+    pub point1: Point<Real>,
+    /// The world-space contact point on the collider.
+    pub point2: Point<Real>,
+    /// The distance between the two contact points along the normal.
+    ///
+    /// This is negative if the shape and the collider penetrate, in which case its
+    /// opposite is the penetration depth.
+    pub dist: Real,
+}
+
 impl<'a, Colliders> TypedSimdCompositeShape for QueryPipelineAsCompositeShape<'a, Colliders>
 where
     // TODO ECS: make everything optional but the shape?
@@ -80,7 +135,8 @@ where
         let co_flags: Option<&ColliderFlags> = self.colliders.get(shape_id.0);
 
         if let Some(co_flags) = co_flags {
-            if co_flags.collision_groups.test(self.query_groups)
+            if co_flags.enabled.is_enabled()
+                && co_flags.collision_groups.test(self.query_groups)
                 && self.filter.map(|f| f(shape_id)).unwrap_or(true)
             {
                 let (co_pos, co_shape): (&ColliderPosition, &ColliderShape) =
@@ -99,7 +155,7 @@ where
     }
 
     fn typed_qbvh(&self) -> &QBVH<ColliderHandle> {
-        &self.query_pipeline.qbvh
+        self.qbvh
     }
 }
 
@@ -117,12 +173,13 @@ impl QueryPipeline {
 
     fn as_composite_shape<'a, Colliders>(
         &'a self,
+        qbvh: &'a QBVH<ColliderHandle>,
         colliders: &'a Colliders,
         query_groups: InteractionGroups,
         filter: Option<&'a dyn Fn(ColliderHandle) -> bool>,
     ) -> QueryPipelineAsCompositeShape<'a, Colliders> {
         QueryPipelineAsCompositeShape {
-            query_pipeline: self,
+            qbvh,
             colliders,
             query_groups,
             filter,
@@ -142,9 +199,57 @@ impl QueryPipeline {
             qbvh: QBVH::new(),
             tree_built: false,
             dilation_factor: 0.01,
+            qbvh_colliders: Coarena::new(),
+            num_qbvh_colliders: 0,
+            inserted_qbvh: QBVH::new(),
+            inserted_colliders: Vec::new(),
+            inserted_colliders_ids: Coarena::new(),
+            inserted_qbvh_outdated: false,
+            num_changes: 0,
+            rebuild_threshold: 0.5,
+            history: ColliderPoseHistory::default(),
+        }
+    }
+
+    /// A copy of this query pipeline without its recorded collider poses.
+    pub(super) fn clone_without_history(&self) -> Self {
+        Self {
+            query_dispatcher: self.query_dispatcher.clone(),
+            qbvh: self.qbvh.clone(),
+            tree_built: self.tree_built,
+            dilation_factor: self.dilation_factor,
+            qbvh_colliders: self.qbvh_colliders.clone(),
+            num_qbvh_colliders: self.num_qbvh_colliders,
+            inserted_qbvh: self.inserted_qbvh.clone(),
+            inserted_colliders: self.inserted_colliders.clone(),
+            inserted_colliders_ids: self.inserted_colliders_ids.clone(),
+            inserted_qbvh_outdated: self.inserted_qbvh_outdated,
+            num_changes: self.num_changes,
+            rebuild_threshold: self.rebuild_threshold,
+            history: ColliderPoseHistory::default(),
         }
     }
 
+    /// The ratio between the number of collider insertions, removals, and motions
+    /// applied with [`QueryPipeline::update_incremental`] since the last full rebuild of the
+    /// acceleration structure, and the number of colliders, above which the acceleration
+    /// structure is fully rebuilt.
+    pub fn rebuild_threshold(&self) -> Real {
+        self.rebuild_threshold
+    }
+
+    /// Sets the ratio between the number of collider changes and the number of colliders
+    /// above which the acceleration structure is fully rebuilt by [`QueryPipeline::update_incremental`].
+    ///
+    /// Smaller values result in more frequent full rebuilds, and in faster queries.
+    pub fn set_rebuild_threshold(&mut self, threshold: Real) {
+        assert!(
+            threshold >= 0.0,
+            "The rebuild threshold must be non-negative."
+        );
+        self.rebuild_threshold = threshold;
+    }
+
     /// The query dispatcher used by this query pipeline for running scene queries.
     pub fn query_dispatcher(&self) -> &dyn QueryDispatcher {
         &*self.query_dispatcher
@@ -286,6 +391,7 @@ impl QueryPipeline {
                 mode,
             };
             self.qbvh.clear_and_rebuild(generator, self.dilation_factor);
+            self.reset_incremental_state();
 
             // FIXME: uncomment this once we handle insertion/removals properly.
             // self.tree_built = true;
@@ -358,6 +464,153 @@ impl QueryPipeline {
         }
     }
 
+    /// Incrementally updates the acceleration structure on the query pipeline.
+    ///
+    /// Instead of rebuilding the whole acceleration structure like [`QueryPipeline::update`], this
+    /// only takes into account the given modified and removed colliders. The AABBs of the modified
+    /// colliders are refitted, and the inserted colliders are added to a secondary acceleration
+    /// structure. The whole acceleration structure is rebuilt once the number of changes since its
+    /// last rebuild exceeds [`QueryPipeline::rebuild_threshold`] times the number of colliders.
+    ///
+    /// This is called automatically by the `PhysicsPipeline` and the `CollisionPipeline` if they
+    /// are given a query pipeline. Otherwise, `modified_colliders` must contain all the colliders
+    /// inserted, moved, or with a modified shape, since the last update.
+    ///
+    /// If `refit_and_rebuild` is `false`, the changes are only recorded, and applied by the next
+    /// call to this method with `refit_and_rebuild` set to `true`. Queries must not be run while
+    /// some changes have not been applied yet.
+    pub fn update_incremental<Colliders>(
+        &mut self,
+        colliders: &Colliders,
+        modified_colliders: &[ColliderHandle],
+        removed_colliders: &[ColliderHandle],
+        refit_and_rebuild: bool,
+    ) where
+        Colliders: ComponentSet<ColliderShape> + ComponentSet<ColliderPosition>,
+    {
+        // NOTE: handle removals first, in case a collider was removed and another
+        //       was inserted with the same index since the last update.
+        for handle in removed_colliders {
+            if self.qbvh_colliders.remove(handle.0, ()).is_some() {
+                // The removed collider stays in the tree until the next full rebuild,
+                // but it will no longer be reported by any query.
+                self.num_qbvh_colliders -= 1;
+                self.num_changes += 1;
+            } else if let Some(id) = self.inserted_colliders_ids.remove(handle.0, u32::MAX) {
+                let _ = self.inserted_colliders.swap_remove(id as usize);
+
+                if let Some(moved) = self.inserted_colliders.get(id as usize) {
+                    self.inserted_colliders_ids.insert(moved.0, id);
+                }
+
+                self.inserted_qbvh_outdated = true;
+            }
+        }
+
+        for handle in modified_colliders {
+            // NOTE: the collider may no longer exist if it has been removed.
+            let co_shape: Option<&ColliderShape> = colliders.get(handle.0);
+
+            if co_shape.is_none() {
+                continue;
+            }
+
+            if self.qbvh_colliders.get(handle.0).is_some() {
+                self.qbvh.pre_update(*handle);
+                self.num_changes += 1;
+            } else if self.inserted_colliders_ids.get(handle.0).is_some() {
+                if !self.inserted_qbvh_outdated {
+                    self.inserted_qbvh.pre_update(*handle);
+                }
+            } else {
+                self.inserted_colliders_ids
+                    .insert(handle.0, self.inserted_colliders.len() as u32);
+                self.inserted_colliders.push(*handle);
+                self.inserted_qbvh_outdated = true;
+                self.num_changes += 1;
+            }
+        }
+
+        if !refit_and_rebuild {
+            return;
+        }
+
+        if self.num_changes as Real > self.num_qbvh_colliders as Real * self.rebuild_threshold
+            || self.inserted_colliders.len() > MAX_NUM_INSERTED_COLLIDERS
+        {
+            self.rebuild(colliders);
+            return;
+        }
+
+        let aabb = |handle: &ColliderHandle| {
+            let co_shape: Option<&ColliderShape> = colliders.get(handle.0);
+            let co_pos: Option<&ColliderPosition> = colliders.get(handle.0);
+
+            // NOTE: the tree may still contain removed colliders.
+            match (co_shape, co_pos) {
+                (Some(co_shape), Some(co_pos)) => co_shape.compute_aabb(co_pos),
+                _ => AABB::new_invalid(),
+            }
+        };
+
+        self.qbvh.update(aabb, self.dilation_factor);
+
+        if self.inserted_qbvh_outdated {
+            let generator = CollidersDataGenerator {
+                colliders,
+                handles: Some(&self.inserted_colliders),
+            };
+            self.inserted_qbvh
+                .clear_and_rebuild(generator, self.dilation_factor);
+            self.inserted_qbvh_outdated = false;
+        } else {
+            self.inserted_qbvh.update(aabb, self.dilation_factor);
+        }
+    }
+
+    /// Fully rebuilds the acceleration structure from the current positions of all the colliders.
+    pub(crate) fn rebuild<Colliders>(&mut self, colliders: &Colliders)
+    where
+        Colliders: ComponentSet<ColliderShape> + ComponentSet<ColliderPosition>,
+    {
+        let generator = CollidersDataGenerator {
+            colliders,
+            handles: None,
+        };
+        self.qbvh.clear_and_rebuild(generator, self.dilation_factor);
+        self.reset_incremental_state();
+    }
+
+    /// Resets the incremental update state after a full rebuild of `self.qbvh`.
+    fn reset_incremental_state(&mut self) {
+        self.qbvh_colliders = Coarena::new();
+        self.num_qbvh_colliders = 0;
+
+        for (_, handle) in self.qbvh.iter_data() {
+            if *handle != ColliderHandle::invalid() {
+                self.qbvh_colliders.insert(handle.0, ());
+                self.num_qbvh_colliders += 1;
+            }
+        }
+
+        self.inserted_qbvh = QBVH::new();
+        self.inserted_colliders.clear();
+        self.inserted_colliders_ids = Coarena::new();
+        self.inserted_qbvh_outdated = false;
+        self.num_changes = 0;
+    }
+
+    /// The trees to traverse for answering a query.
+    fn qbvhs(&self) -> impl Iterator<Item = &QBVH<ColliderHandle>> {
+        let inserted_qbvh = if self.inserted_colliders.is_empty() {
+            None
+        } else {
+            Some(&self.inserted_qbvh)
+        };
+
+        std::iter::once(&self.qbvh).chain(inserted_qbvh)
+    }
+
     /// Find the closest intersection between a ray and a set of collider.
     ///
     /// # Parameters
@@ -387,11 +640,15 @@ impl QueryPipeline {
             + ComponentSet<ColliderPosition>
             + ComponentSet<ColliderShape>,
     {
-        let pipeline_shape = self.as_composite_shape(colliders, query_groups, filter);
-        let mut visitor =
-            RayCompositeShapeToiBestFirstVisitor::new(&pipeline_shape, ray, max_toi, solid);
+        let results = self.qbvhs().filter_map(|qbvh| {
+            let pipeline_shape = self.as_composite_shape(qbvh, colliders, query_groups, filter);
+            let mut visitor =
+                RayCompositeShapeToiBestFirstVisitor::new(&pipeline_shape, ray, max_toi, solid);
 
-        self.qbvh.traverse_best_first(&mut visitor).map(|h| h.1)
+            qbvh.traverse_best_first(&mut visitor).map(|h| h.1)
+        });
+
+        closest(results, |(_, toi)| *toi)
     }
 
     /// Find the closest intersection between a ray and a set of collider.
@@ -423,15 +680,142 @@ impl QueryPipeline {
             + ComponentSet<ColliderPosition>
             + ComponentSet<ColliderShape>,
     {
-        let pipeline_shape = self.as_composite_shape(colliders, query_groups, filter);
-        let mut visitor = RayCompositeShapeToiAndNormalBestFirstVisitor::new(
-            &pipeline_shape,
-            ray,
+        let results = self.qbvhs().filter_map(|qbvh| {
+            let pipeline_shape = self.as_composite_shape(qbvh, colliders, query_groups, filter);
+            let mut visitor = RayCompositeShapeToiAndNormalBestFirstVisitor::new(
+                &pipeline_shape,
+                ray,
+                max_toi,
+                solid,
+            );
+
+            qbvh.traverse_best_first(&mut visitor).map(|h| h.1)
+        });
+
+        closest(results, |(_, hit)| hit.toi)
+    }
+
+    /// Find the closest intersection between each ray of a batch and a set of colliders.
+    ///
+    /// This gives the same results as calling [`QueryPipeline::cast_ray_and_get_normal`] for each ray,
+    /// but is much faster for large batches of coherent rays, e.g., generated by a
+    /// [`LidarSensor`](crate::pipeline::LidarSensor) or a [`DepthCamera`](crate::pipeline::DepthCamera).
+    /// The rays are grouped into small batches of consecutive rays, and each batch traverses the
+    /// acceleration structure once. The subtrees that don't intersect the AABB enclosing all the
+    /// rays of a batch are culled with a single test. The other ones are tested separately
+    /// against each ray of the batch: the rays aren't spread across SIMD lanes.
+    ///
+    /// # Parameters
+    /// * `colliders` - The set of colliders taking part in this pipeline.
+    /// * `rays`: the rays to cast. Consecutive rays should have similar origins and directions for
+    ///   the best performances.
+    /// * `max_toi`: the maximum time-of-impact that can be reported for each ray.
+    /// * `solid`: if this is `true` an impact at time 0.0 (i.e. at the ray origin) is returned if
+    ///            it starts inside of a shape. If this `false` then the ray will hit the shape's boundary
+    ///            even if its starts inside of it.
+    /// * `query_groups`: the interaction groups which will be tested against the collider's `contact_group`
+    ///                   to determine if it should be taken into account by this query.
+    /// * `filter`: a more fine-grained filter. A collider is taken into account by this query if
+    ///             its `contact_group` is compatible with the `query_groups`, and if this `filter`
+    ///             is either `None` or returns `true`.
+    /// * `hits`: the closest intersection of each ray, if any. It must have the same length as `rays`.
+    pub fn cast_rays<Colliders>(
+        &self,
+        colliders: &Colliders,
+        rays: &[Ray],
+        max_toi: Real,
+        solid: bool,
+        query_groups: InteractionGroups,
+        filter: Option<&dyn Fn(ColliderHandle) -> bool>,
+        hits: &mut [Option<(ColliderHandle, RayIntersection)>],
+    ) where
+        Colliders: ComponentSet<ColliderFlags>
+            + ComponentSet<ColliderPosition>
+            + ComponentSet<ColliderShape>,
+    {
+        assert_eq!(
+            rays.len(),
+            hits.len(),
+            "There must be exactly one hit slot per ray."
+        );
+
+        for (rays, hits) in rays
+            .chunks(RAY_BATCH_SIZE)
+            .zip(hits.chunks_mut(RAY_BATCH_SIZE))
+        {
+            self.cast_ray_batch(colliders, rays, max_toi, solid, query_groups, filter, hits);
+        }
+    }
+
+    /// Find the closest intersection between each ray of a batch and a set of colliders, in parallel.
+    ///
+    /// This is the same as [`QueryPipeline::cast_rays`], except that the ray batches are distributed
+    /// among the threads of the rayon thread pool.
+    #[cfg(feature = "parallel")]
+    pub fn par_cast_rays<Colliders>(
+        &self,
+        colliders: &Colliders,
+        rays: &[Ray],
+        max_toi: Real,
+        solid: bool,
+        query_groups: InteractionGroups,
+        filter: Option<&(dyn Fn(ColliderHandle) -> bool + Sync)>,
+        hits: &mut [Option<(ColliderHandle, RayIntersection)>],
+    ) where
+        Colliders: ComponentSet<ColliderFlags>
+            + ComponentSet<ColliderPosition>
+            + ComponentSet<ColliderShape>
+            + Sync,
+    {
+        use rayon::prelude::*;
+
+        assert_eq!(
+            rays.len(),
+            hits.len(),
+            "There must be exactly one hit slot per ray."
+        );
+
+        rays.par_chunks(RAY_BATCH_SIZE)
+            .zip(hits.par_chunks_mut(RAY_BATCH_SIZE))
+            .for_each(|(rays, hits)| {
+                let filter = filter.map(|f| f as &dyn Fn(ColliderHandle) -> bool);
+                self.cast_ray_batch(colliders, rays, max_toi, solid, query_groups, filter, hits);
+            });
+    }
+
+    fn cast_ray_batch<Colliders>(
+        &self,
+        colliders: &Colliders,
+        rays: &[Ray],
+        max_toi: Real,
+        solid: bool,
+        query_groups: InteractionGroups,
+        filter: Option<&dyn Fn(ColliderHandle) -> bool>,
+        hits: &mut [Option<(ColliderHandle, RayIntersection)>],
+    ) where
+        Colliders: ComponentSet<ColliderFlags>
+            + ComponentSet<ColliderPosition>
+            + ComponentSet<ColliderShape>,
+    {
+        hits.iter_mut().for_each(|hit| *hit = None);
+
+        let mut visitor = RayBatchVisitor {
+            colliders,
+            rays,
+            simd_rays: rays.iter().map(|ray| SimdRay::splat(*ray)).collect(),
+            packet_aabb: SimdAABB::new_invalid(),
             max_toi,
             solid,
-        );
+            query_groups,
+            filter,
+            hits,
+        };
 
-        self.qbvh.traverse_best_first(&mut visitor).map(|h| h.1)
+        visitor.update_packet_aabb();
+
+        for qbvh in self.qbvhs() {
+            qbvh.traverse_depth_first(&mut visitor);
+        }
     }
 
     /// Find the all intersections between a ray and a set of collider and passes them to a callback.
@@ -466,6 +850,7 @@ impl QueryPipeline {
             + ComponentSet<ColliderPosition>
             + ComponentSet<ColliderShape>,
     {
+        let exit_early = Cell::new(false);
         let mut leaf_callback = &mut |handle: &ColliderHandle| {
             let co_shape: Option<&ColliderShape> = colliders.get(handle.0);
             if let Some(co_shape) = co_shape {
@@ -476,7 +861,9 @@ impl QueryPipeline {
                 {
                     if let Some(hit) = co_shape.cast_ray_and_get_normal(co_pos, ray, max_toi, solid)
                     {
-                        return callback(*handle, hit);
+                        let keep_going = callback(*handle, hit);
+                        exit_early.set(!keep_going);
+                        return keep_going;
                     }
                 }
             }
@@ -484,8 +871,14 @@ impl QueryPipeline {
             true
         };
 
-        let mut visitor = RayIntersectionsVisitor::new(ray, max_toi, &mut leaf_callback);
-        self.qbvh.traverse_depth_first(&mut visitor);
+        for qbvh in self.qbvhs() {
+            let mut visitor = RayIntersectionsVisitor::new(ray, max_toi, &mut leaf_callback);
+            qbvh.traverse_depth_first(&mut visitor);
+
+            if exit_early.get() {
+                break;
+            }
+        }
     }
 
     /// Gets the handle of up to one collider intersecting the given shape.
@@ -512,17 +905,17 @@ impl QueryPipeline {
             + ComponentSet<ColliderPosition>
             + ComponentSet<ColliderShape>,
     {
-        let pipeline_shape = self.as_composite_shape(colliders, query_groups, filter);
-        let mut visitor = IntersectionCompositeShapeShapeBestFirstVisitor::new(
-            &*self.query_dispatcher,
-            shape_pos,
-            &pipeline_shape,
-            shape,
-        );
+        self.qbvhs().find_map(|qbvh| {
+            let pipeline_shape = self.as_composite_shape(qbvh, colliders, query_groups, filter);
+            let mut visitor = IntersectionCompositeShapeShapeBestFirstVisitor::new(
+                &*self.query_dispatcher,
+                shape_pos,
+                &pipeline_shape,
+                shape,
+            );
 
-        self.qbvh
-            .traverse_best_first(&mut visitor)
-            .map(|h| (h.1 .0))
+            qbvh.traverse_best_first(&mut visitor).map(|h| (h.1 .0))
+        })
     }
 
     /// Find the projection of a point on the closest collider.
@@ -553,13 +946,16 @@ impl QueryPipeline {
             + ComponentSet<ColliderPosition>
             + ComponentSet<ColliderShape>,
     {
-        let pipeline_shape = self.as_composite_shape(colliders, query_groups, filter);
-        let mut visitor =
-            PointCompositeShapeProjBestFirstVisitor::new(&pipeline_shape, point, solid);
+        let results = self.qbvhs().filter_map(|qbvh| {
+            let pipeline_shape = self.as_composite_shape(qbvh, colliders, query_groups, filter);
+            let mut visitor =
+                PointCompositeShapeProjBestFirstVisitor::new(&pipeline_shape, point, solid);
 
-        self.qbvh
-            .traverse_best_first(&mut visitor)
-            .map(|h| (h.1 .1, h.1 .0))
+            qbvh.traverse_best_first(&mut visitor)
+                .map(|h| (h.1 .1, h.1 .0))
+        });
+
+        closest(results, |(_, proj)| na::distance(point, &proj.point))
     }
 
     /// Find all the colliders containing the given point.
@@ -586,6 +982,7 @@ impl QueryPipeline {
             + ComponentSet<ColliderPosition>
             + ComponentSet<ColliderShape>,
     {
+        let exit_early = Cell::new(false);
         let mut leaf_callback = &mut |handle: &ColliderHandle| {
             let co_shape: Option<&ColliderShape> = colliders.get(handle.0);
 
@@ -597,16 +994,23 @@ impl QueryPipeline {
                     && filter.map(|f| f(*handle)).unwrap_or(true)
                     && co_shape.contains_point(co_pos, point)
                 {
-                    return callback(*handle);
+                    let keep_going = callback(*handle);
+                    exit_early.set(!keep_going);
+                    return keep_going;
                 }
             }
 
             true
         };
 
-        let mut visitor = PointIntersectionsVisitor::new(point, &mut leaf_callback);
+        for qbvh in self.qbvhs() {
+            let mut visitor = PointIntersectionsVisitor::new(point, &mut leaf_callback);
+            qbvh.traverse_depth_first(&mut visitor);
 
-        self.qbvh.traverse_depth_first(&mut visitor);
+            if exit_early.get() {
+                break;
+            }
+        }
     }
 
     /// Find the projection of a point on the closest collider.
@@ -638,12 +1042,18 @@ impl QueryPipeline {
             + ComponentSet<ColliderPosition>
             + ComponentSet<ColliderShape>,
     {
-        let pipeline_shape = self.as_composite_shape(colliders, query_groups, filter);
-        let mut visitor =
-            PointCompositeShapeProjWithFeatureBestFirstVisitor::new(&pipeline_shape, point, false);
-        self.qbvh
-            .traverse_best_first(&mut visitor)
-            .map(|h| (h.1 .1 .0, h.1 .0, h.1 .1 .1))
+        let results = self.qbvhs().filter_map(|qbvh| {
+            let pipeline_shape = self.as_composite_shape(qbvh, colliders, query_groups, filter);
+            let mut visitor = PointCompositeShapeProjWithFeatureBestFirstVisitor::new(
+                &pipeline_shape,
+                point,
+                false,
+            );
+            qbvh.traverse_best_first(&mut visitor)
+                .map(|h| (h.1 .1 .0, h.1 .0, h.1 .1 .1))
+        });
+
+        closest(results, |(_, proj, _)| na::distance(point, &proj.point))
     }
 
     /// Finds all handles of all the colliders with an AABB intersecting the given AABB.
@@ -652,8 +1062,28 @@ impl QueryPipeline {
         aabb: &AABB,
         mut callback: impl FnMut(&ColliderHandle) -> bool,
     ) {
-        let mut visitor = BoundingVolumeIntersectionsVisitor::new(aabb, &mut callback);
-        self.qbvh.traverse_depth_first(&mut visitor);
+        let exit_early = Cell::new(false);
+        let mut leaf_callback = |handle: &ColliderHandle| {
+            // NOTE: the trees may still contain colliders removed since their last rebuild.
+            if self.qbvh_colliders.get(handle.0).is_none()
+                && self.inserted_colliders_ids.get(handle.0).is_none()
+            {
+                return true;
+            }
+
+            let keep_going = callback(handle);
+            exit_early.set(!keep_going);
+            keep_going
+        };
+
+        for qbvh in self.qbvhs() {
+            let mut visitor = BoundingVolumeIntersectionsVisitor::new(aabb, &mut leaf_callback);
+            qbvh.traverse_depth_first(&mut visitor);
+
+            if exit_early.get() {
+                break;
+            }
+        }
     }
 
     /// Casts a shape at a constant linear velocity and retrieve the first collider it hits.
@@ -689,16 +1119,20 @@ impl QueryPipeline {
             + ComponentSet<ColliderPosition>
             + ComponentSet<ColliderShape>,
     {
-        let pipeline_shape = self.as_composite_shape(colliders, query_groups, filter);
-        let mut visitor = TOICompositeShapeShapeBestFirstVisitor::new(
-            &*self.query_dispatcher,
-            shape_pos,
-            shape_vel,
-            &pipeline_shape,
-            shape,
-            max_toi,
-        );
-        self.qbvh.traverse_best_first(&mut visitor).map(|h| h.1)
+        let results = self.qbvhs().filter_map(|qbvh| {
+            let pipeline_shape = self.as_composite_shape(qbvh, colliders, query_groups, filter);
+            let mut visitor = TOICompositeShapeShapeBestFirstVisitor::new(
+                &*self.query_dispatcher,
+                shape_pos,
+                shape_vel,
+                &pipeline_shape,
+                shape,
+                max_toi,
+            );
+            qbvh.traverse_best_first(&mut visitor).map(|h| h.1)
+        });
+
+        closest(results, |(_, toi)| toi.toi)
     }
 
     /// Casts a shape with an arbitrary continuous motion and retrieve the first collider it hits.
@@ -740,19 +1174,23 @@ impl QueryPipeline {
             + ComponentSet<ColliderPosition>
             + ComponentSet<ColliderShape>,
     {
-        let pipeline_shape = self.as_composite_shape(colliders, query_groups, filter);
-        let pipeline_motion = NonlinearRigidMotion::identity();
-        let mut visitor = NonlinearTOICompositeShapeShapeBestFirstVisitor::new(
-            &*self.query_dispatcher,
-            &pipeline_motion,
-            &pipeline_shape,
-            shape_motion,
-            shape,
-            start_time,
-            end_time,
-            stop_at_penetration,
-        );
-        self.qbvh.traverse_best_first(&mut visitor).map(|h| h.1)
+        let results = self.qbvhs().filter_map(|qbvh| {
+            let pipeline_shape = self.as_composite_shape(qbvh, colliders, query_groups, filter);
+            let pipeline_motion = NonlinearRigidMotion::identity();
+            let mut visitor = NonlinearTOICompositeShapeShapeBestFirstVisitor::new(
+                &*self.query_dispatcher,
+                &pipeline_motion,
+                &pipeline_shape,
+                shape_motion,
+                shape,
+                start_time,
+                end_time,
+                stop_at_penetration,
+            );
+            qbvh.traverse_best_first(&mut visitor).map(|h| h.1)
+        });
+
+        closest(results, |(_, toi)| toi.toi)
     }
 
     /// Retrieve all the colliders intersecting the given shape.
@@ -784,6 +1222,7 @@ impl QueryPipeline {
         let dispatcher = &*self.query_dispatcher;
         let inv_shape_pos = shape_pos.inverse();
 
+        let exit_early = Cell::new(false);
         let mut leaf_callback = &mut |handle: &ColliderHandle| {
             let co_shape: Option<&ColliderShape> = colliders.get(handle.0);
 
@@ -797,7 +1236,9 @@ impl QueryPipeline {
                     let pos12 = inv_shape_pos * co_pos.as_ref();
 
                     if dispatcher.intersection_test(&pos12, shape, &**co_shape) == Ok(true) {
-                        return callback(*handle);
+                        let keep_going = callback(*handle);
+                        exit_early.set(!keep_going);
+                        return keep_going;
                     }
                 }
             }
@@ -806,8 +1247,567 @@ impl QueryPipeline {
         };
 
         let shape_aabb = shape.compute_aabb(shape_pos);
-        let mut visitor = BoundingVolumeIntersectionsVisitor::new(&shape_aabb, &mut leaf_callback);
+        for qbvh in self.qbvhs() {
+            let mut visitor =
+                BoundingVolumeIntersectionsVisitor::new(&shape_aabb, &mut leaf_callback);
+            qbvh.traverse_depth_first(&mut visitor);
+
+            if exit_early.get() {
+                break;
+            }
+        }
+    }
+
+    /// Computes the contact manifolds between a shape and all the colliders overlapping it.
+    ///
+    /// The contacts are computed by the query dispatcher of the `narrow_phase`, so they match
+    /// the contacts the narrow-phase would generate if the shape was a collider.
+    ///
+    /// # Parameters
+    /// * `colliders` - The set of colliders taking part in this pipeline.
+    /// * `narrow_phase` - The narrow-phase whose query dispatcher computes the contacts.
+    /// * `shape_pos` - The position of the shape to test.
+    /// * `shape` - The shape to test.
+    /// * `prediction` - Contacts with a distance up to this value are reported too. Use `0.0`
+    ///   to only report penetrating or touching colliders.
+    /// * `query_groups` - the interaction groups which will be tested against the collider's `contact_group`
+    ///                   to determine if it should be taken into account by this query.
+    /// * `filter` - a more fine-grained filter. A collider is taken into account by this query if
+    ///             its `contact_group` is compatible with the `query_groups`, and if this `filter`
+    ///             is either `None` or returns `true`.
+    /// * `callback` - A function called with each contact manifold with at least one contact point.
+    ///                If it returns `false`, this method exits early.
+    pub fn contacts_with_shape<Colliders>(
+        &self,
+        colliders: &Colliders,
+        narrow_phase: &NarrowPhase,
+        shape_pos: &Isometry<Real>,
+        shape: &dyn Shape,
+        prediction: Real,
+        query_groups: InteractionGroups,
+        filter: Option<&dyn Fn(ColliderHandle) -> bool>,
+        mut callback: impl FnMut(&ShapeContactManifold) -> bool,
+    ) where
+        Colliders: ComponentSet<ColliderFlags>
+            + ComponentSet<ColliderPosition>
+            + ComponentSet<ColliderShape>,
+    {
+        let dispatcher = narrow_phase.query_dispatcher();
+        let mut manifolds: Vec<ContactManifold> = vec![];
+
+        let exit_early = Cell::new(false);
+        let mut leaf_callback = &mut |handle: &ColliderHandle| {
+            let co_shape: Option<&ColliderShape> = colliders.get(handle.0);
+
+            if let Some(co_shape) = co_shape {
+                let (co_flags, co_pos): (&ColliderFlags, &ColliderPosition) =
+                    colliders.index_bundle(handle.0);
+
+                if co_flags.enabled.is_enabled()
+                    && co_flags.collision_groups.test(query_groups)
+                    && filter.map(|f| f(*handle)).unwrap_or(true)
+                {
+                    let pos12 = shape_pos.inv_mul(co_pos);
+                    manifolds.clear();
+                    let _ = dispatcher.contact_manifolds(
+                        &pos12,
+                        shape,
+                        &**co_shape,
+                        prediction,
+                        &mut manifolds,
+                        &mut None,
+                    );
+
+                    for manifold in &manifolds {
+                        if manifold.points.is_empty() {
+                            continue;
+                        }
+
+                        let world_pos1 = manifold.subshape_pos1.prepend_to(shape_pos);
+                        let world_pos2 = manifold.subshape_pos2.prepend_to(co_pos);
+                        let manifold = ShapeContactManifold {
+                            collider: *handle,
+                            normal: world_pos1 * manifold.local_n1,
+                            points: manifold
+                                .points
+                                .iter()
+                                .map(|contact| ShapeContactPoint {
+                                    point1: world_pos1 * contact.local_p1,
+                                    point2: world_pos2 * contact.local_p2,
+                                    dist: contact.dist,
+                                })
+                                .collect(),
+                        };
+
+                        if !callback(&manifold) {
+                            exit_early.set(true);
+                            return false;
+                        }
+                    }
+                }
+            }
+
+            true
+        };
+
+        let shape_aabb = shape.compute_aabb(shape_pos).loosened(prediction.max(0.0));
+        for qbvh in self.qbvhs() {
+            let mut visitor =
+                BoundingVolumeIntersectionsVisitor::new(&shape_aabb, &mut leaf_callback);
+            qbvh.traverse_depth_first(&mut visitor);
+
+            if exit_early.get() {
+                break;
+            }
+        }
+    }
+
+    /// Computes the translation to apply to a shape so it no longer penetrates any collider.
+    ///
+    /// The translation is computed iteratively: at each iteration, the contacts between the shape
+    /// and the colliders are computed with [`QueryPipeline::contacts_with_shape`], and the shape is
+    /// moved along the contact normals by the smallest amount resolving all the penetrations. This
+    /// can be used to spawn an object safely, or to push a character controller out of the walls.
+    ///
+    /// Returns `None` if some penetrations remain after `max_iterations` iterations, e.g.,
+    /// because the shape is squeezed between colliders.
+    pub fn depenetration_vector<Colliders>(
+        &self,
+        colliders: &Colliders,
+        narrow_phase: &NarrowPhase,
+        shape_pos: &Isometry<Real>,
+        shape: &dyn Shape,
+        max_iterations: usize,
+        query_groups: InteractionGroups,
+        filter: Option<&dyn Fn(ColliderHandle) -> bool>,
+    ) -> Option<Vector<Real>>
+    where
+        Colliders: ComponentSet<ColliderFlags>
+            + ComponentSet<ColliderPosition>
+            + ComponentSet<ColliderShape>,
+    {
+        let mut translation = Vector::zeros();
+
+        for _ in 0..max_iterations {
+            let mut correction = Vector::zeros();
+            let mut penetrating = false;
+            let pos = Isometry::from_parts(
+                (shape_pos.translation.vector + translation).into(),
+                shape_pos.rotation,
+            );
+
+            self.contacts_with_shape(
+                colliders,
+                narrow_phase,
+                &pos,
+                shape,
+                0.0,
+                query_groups,
+                filter,
+                |manifold| {
+                    for contact in &manifold.points {
+                        let depth = -contact.dist;
+
+                        if depth > DEPENETRATION_TOLERANCE {
+                            penetrating = true;
+
+                            // NOTE: only push the shape by what hasn't been already resolved by the
+                            //       correction of the other contacts along this normal.
+                            let resolved = -correction.dot(&manifold.normal);
+                            if resolved < depth {
+                                correction -= manifold.normal * (depth - resolved);
+                            }
+                        }
+                    }
+
+                    true
+                },
+            );
+
+            if !penetrating {
+                return Some(translation);
+            }
+
+            translation += correction;
+        }
+
+        None
+    }
+}
+
+/// The result with the smallest cost among the results of the queries on each tree.
+pub(super) fn closest<T>(results: impl Iterator<Item = T>, cost: impl Fn(&T) -> Real) -> Option<T> {
+    results.min_by(|a, b| {
+        cost(a)
+            .partial_cmp(&cost(b))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    })
+}
+
+/// A visitor finding the closest intersection of each ray of a batch.
+struct RayBatchVisitor<'a, 'b, Colliders> {
+    colliders: &'a Colliders,
+    rays: &'a [Ray],
+    simd_rays: Vec<SimdRay>,
+    // The AABB enclosing all the rays, up to the closest hit found so far for each ray.
+    packet_aabb: SimdAABB,
+    max_toi: Real,
+    solid: bool,
+    query_groups: InteractionGroups,
+    filter: Option<&'a dyn Fn(ColliderHandle) -> bool>,
+    hits: &'b mut [Option<(ColliderHandle, RayIntersection)>],
+}
+
+impl<'a, 'b, Colliders> SimdVisitor<ColliderHandle, SimdAABB> for RayBatchVisitor<'a, 'b, Colliders>
+where
+    Colliders:
+        ComponentSet<ColliderFlags> + ComponentSet<ColliderPosition> + ComponentSet<ColliderShape>,
+{
+    fn visit(
+        &mut self,
+        bv: &SimdAABB,
+        data: Option<[Option<&ColliderHandle>; SIMD_WIDTH]>,
+    ) -> SimdVisitStatus {
+        let packet_mask = bv.intersects(&self.packet_aabb);
+
+        if !packet_mask.any() {
+            // None of the rays can hit any of these subtrees.
+            return SimdVisitStatus::MaybeContinue(packet_mask);
+        }
+
+        let mut batch_mask = SimdBool::splat(false);
+        let mut masks = [0; RAY_BATCH_SIZE];
+
+        for (i, simd_ray) in self.simd_rays.iter().enumerate() {
+            // NOTE: the closest hit found so far bounds the subtrees to visit.
+            let mask = bv
+                .cast_local_ray(simd_ray, SimdReal::splat(self.ray_max_toi(i)))
+                .0;
+            masks[i] = mask.bitmask();
+            batch_mask = batch_mask | mask;
+        }
+
+        if let Some(data) = data {
+            for (ii, handle) in data.iter().enumerate() {
+                if let Some(handle) = handle {
+                    self.visit_leaf(**handle, &masks, 1 << ii);
+                }
+            }
+        }
+
+        SimdVisitStatus::MaybeContinue(batch_mask)
+    }
+}
+
+impl<'a, 'b, Colliders> RayBatchVisitor<'a, 'b, Colliders>
+where
+    Colliders:
+        ComponentSet<ColliderFlags> + ComponentSet<ColliderPosition> + ComponentSet<ColliderShape>,
+{
+    fn ray_max_toi(&self, i: usize) -> Real {
+        self.hits[i].map(|hit| hit.1.toi).unwrap_or(self.max_toi)
+    }
+
+    fn update_packet_aabb(&mut self) {
+        let mut aabb = AABB::new_invalid();
+
+        for (i, ray) in self.rays.iter().enumerate() {
+            aabb.take_point(ray.origin);
+            aabb.take_point(ray.point_at(self.ray_max_toi(i)));
+        }
+
+        self.packet_aabb = SimdAABB::splat(aabb);
+    }
+
+    fn visit_leaf(&mut self, handle: ColliderHandle, masks: &[u64], lane: u64) {
+        let co_shape: Option<&ColliderShape> = self.colliders.get(handle.0);
+
+        if let Some(co_shape) = co_shape {
+            let (co_flags, co_pos): (&ColliderFlags, &ColliderPosition) =
+                self.colliders.index_bundle(handle.0);
+
+            if !co_flags.enabled.is_enabled()
+                || !co_flags.collision_groups.test(self.query_groups)
+                || !self.filter.map(|f| f(handle)).unwrap_or(true)
+            {
+                return;
+            }
+
+            let mut hit_found = false;
+
+            for (i, ray) in self.rays.iter().enumerate() {
+                if masks[i] & lane == 0 {
+                    continue;
+                }
+
+                if let Some(hit) =
+                    co_shape.cast_ray_and_get_normal(co_pos, ray, self.ray_max_toi(i), self.solid)
+                {
+                    self.hits[i] = Some((handle, hit));
+                    hit_found = true;
+                }
+            }
+
+            if hit_found {
+                self.update_packet_aabb();
+            }
+        }
+    }
+}
+
+struct CollidersDataGenerator<'a, Colliders> {
+    colliders: &'a Colliders,
+    // The colliders to insert in the tree, or `None` to insert all the colliders.
+    handles: Option<&'a [ColliderHandle]>,
+}
+
+impl<'a, Colliders> QBVHDataGenerator<ColliderHandle> for CollidersDataGenerator<'a, Colliders>
+where
+    Colliders: ComponentSet<ColliderShape> + ComponentSet<ColliderPosition>,
+{
+    fn size_hint(&self) -> usize {
+        match self.handles {
+            Some(handles) => handles.len(),
+            None => ComponentSet::<ColliderShape>::size_hint(self.colliders),
+        }
+    }
+
+    #[inline(always)]
+    fn for_each(&mut self, mut f: impl FnMut(ColliderHandle, AABB)) {
+        if let Some(handles) = self.handles {
+            for handle in handles {
+                let (co_shape, co_pos): (&ColliderShape, &ColliderPosition) =
+                    self.colliders.index_bundle(handle.0);
+                f(*handle, co_shape.compute_aabb(co_pos))
+            }
+        } else {
+            self.colliders.for_each(|h, co_shape: &ColliderShape| {
+                let co_pos: &ColliderPosition = self.colliders.index(h);
+                f(ColliderHandle(h), co_shape.compute_aabb(co_pos))
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{IslandManager, RigidBodyBuilder, RigidBodySet};
+    use crate::geometry::{
+        Ball, ColliderBuilder, ColliderSet, Cuboid, InteractionGroups, NarrowPhase, Ray,
+    };
+    use crate::math::{Isometry, Point, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+    use crate::pipeline::QueryPipeline;
+
+    // Checks that the queries give the same results as a query pipeline built from scratch.
+    fn check_query_pipeline(
+        query_pipeline: &QueryPipeline,
+        islands: &IslandManager,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+    ) {
+        let mut rebuilt = QueryPipeline::new();
+        rebuilt.update(islands, bodies, colliders);
+
+        for i in 0..40 {
+            let x = i as Real * 0.5 - 9.8;
+            let origin = Point::origin() + Vector::x() * x + Vector::y() * 50.0;
+            let ray = Ray::new(origin, -Vector::y());
+            let hit = query_pipeline.cast_ray(
+                colliders,
+                &ray,
+                Real::MAX,
+                true,
+                InteractionGroups::all(),
+                None,
+            );
+            let expected = rebuilt.cast_ray(
+                colliders,
+                &ray,
+                Real::MAX,
+                true,
+                InteractionGroups::all(),
+                None,
+            );
+            assert_eq!(hit.map(|h| h.0), expected.map(|h| h.0));
+
+            let shape = Ball::new(3.0);
+            let shape_pos = Isometry::new(Vector::x() * x + Vector::y() * 2.0, na::zero());
+            let mut found = vec![];
+            let mut expected = vec![];
+            query_pipeline.intersections_with_shape(
+                colliders,
+                &shape_pos,
+                &shape,
+                InteractionGroups::all(),
+                None,
+                |handle| {
+                    found.push(handle.into_raw_parts());
+                    true
+                },
+            );
+            rebuilt.intersections_with_shape(
+                colliders,
+                &shape_pos,
+                &shape,
+                InteractionGroups::all(),
+                None,
+                |handle| {
+                    expected.push(handle.into_raw_parts());
+                    true
+                },
+            );
+            found.sort_unstable();
+            expected.sort_unstable();
+            assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    fn incremental_update() {
+        // Check both the refit and the periodic full rebuild of the query pipeline.
+        for rebuild_threshold in [0.5, 1.0e6] {
+            let mut world = TestWorld::new(Vector::y() * -9.81);
+            let mut query_pipeline = QueryPipeline::new();
+            query_pipeline.set_rebuild_threshold(rebuild_threshold);
+            world.query_pipeline = Some(query_pipeline);
+
+            for i in 0..20 {
+                #[cfg(feature = "dim3")]
+                let ground = ColliderBuilder::cuboid(0.5, 0.5, 20.0);
+                #[cfg(feature = "dim2")]
+                let ground = ColliderBuilder::cuboid(0.5, 0.5);
+                let ground = ground.translation(Vector::x() * (i as Real - 10.0)).build();
+                let _ = world.colliders.insert(ground);
+            }
+
+            let mut balls = vec![];
+
+            for step in 0..100 {
+                if step % 10 == 0 {
+                    // Remove a ball, and insert new ones, some of them reusing
+                    // the index of the removed collider.
+                    if let Some(ball) = balls.pop() {
+                        let _ = world.remove_body(ball);
+                    }
+
+                    for i in 0..3 {
+                        let rb = RigidBodyBuilder::new_dynamic()
+                            .translation(
+                                Vector::x() * (i as Real * 3.0 - 5.0 + step as Real * 0.05)
+                                    + Vector::y() * 3.0,
+                            )
+                            .build();
+                        let (handle, _) = world.insert_body(rb, ColliderBuilder::ball(0.5).build());
+                        balls.push(handle);
+                    }
+                }
+
+                world.step();
+
+                check_query_pipeline(
+                    world.query_pipeline.as_ref().unwrap(),
+                    &world.islands,
+                    &world.bodies,
+                    &world.colliders,
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn shape_contacts_and_depenetration() {
+        let mut colliders = ColliderSet::new();
+        let mut query_pipeline = QueryPipeline::new();
+        let narrow_phase = NarrowPhase::new();
+        let groups = InteractionGroups::all();
+
+        #[cfg(feature = "dim2")]
+        let (ground, wall) = (
+            ColliderBuilder::cuboid(10.0, 0.5),
+            ColliderBuilder::cuboid(0.5, 5.0),
+        );
+        #[cfg(feature = "dim3")]
+        let (ground, wall) = (
+            ColliderBuilder::cuboid(10.0, 0.5, 10.0),
+            ColliderBuilder::cuboid(0.5, 5.0, 10.0),
+        );
+        let ground = colliders.insert(ground.build());
+        let wall = colliders.insert(wall.translation(Vector::x() * 2.0).build());
+        query_pipeline.update(&IslandManager::new(), &RigidBodySet::new(), &colliders);
+
+        // A ball penetrating both the ground and the wall.
+        let ball = Ball::new(1.0);
+        let ball_pos = Isometry::new(Vector::x() * 1.2 + Vector::y() * 1.2, na::zero());
+        let mut depths = vec![];
+        query_pipeline.contacts_with_shape(
+            &colliders,
+            &narrow_phase,
+            &ball_pos,
+            &ball,
+            0.0,
+            groups,
+            None,
+            |manifold| {
+                assert_eq!(manifold.points.len(), 1);
+                depths.push((manifold.collider, -manifold.points[0].dist));
+                true
+            },
+        );
+        depths.sort_by_key(|(handle, _)| handle.into_raw_parts());
+        assert_eq!(depths.len(), 2);
+        assert_eq!((depths[0].0, depths[1].0), (ground, wall));
+        assert!((depths[0].1 - 0.3).abs() < 1.0e-5);
+        assert!((depths[1].1 - 0.7).abs() < 1.0e-5);
+
+        let translation = query_pipeline
+            .depenetration_vector(
+                &colliders,
+                &narrow_phase,
+                &ball_pos,
+                &ball,
+                10,
+                groups,
+                None,
+            )
+            .unwrap();
+        let expected = Vector::y() * 0.3 - Vector::x() * 0.7;
+        assert!((translation - expected).norm() < 1.0e-3);
+
+        // A cuboid with several contact points along the same normal is pushed only once.
+        #[cfg(feature = "dim2")]
+        let cuboid = Cuboid::new(Vector::new(0.5, 0.5));
+        #[cfg(feature = "dim3")]
+        let cuboid = Cuboid::new(Vector::new(0.5, 0.5, 0.5));
+        let cuboid_pos = Isometry::new(Vector::y() * 0.8, na::zero());
+        let translation = query_pipeline
+            .depenetration_vector(
+                &colliders,
+                &narrow_phase,
+                &cuboid_pos,
+                &cuboid,
+                10,
+                groups,
+                None,
+            )
+            .unwrap();
+        assert!((translation - Vector::y() * 0.2).norm() < 1.0e-3);
 
-        self.qbvh.traverse_depth_first(&mut visitor);
+        // The penetrations of a ball squeezed between the ground and another ball can't be resolved.
+        let ceiling = ColliderBuilder::ball(1.0).translation(Vector::y() * 2.6);
+        let _ = colliders.insert(ceiling.build());
+        query_pipeline.update(&IslandManager::new(), &RigidBodySet::new(), &colliders);
+        let squeezed_pos = Isometry::new(Vector::y() * 1.2, na::zero());
+        assert!(query_pipeline
+            .depenetration_vector(
+                &colliders,
+                &narrow_phase,
+                &squeezed_pos,
+                &ball,
+                10,
+                groups,
+                None
+            )
+            .is_none());
     }
 }
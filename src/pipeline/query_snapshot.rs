@@ -0,0 +1,285 @@
+//! Immutable copies of the query pipeline and of the colliders, queryable from other threads.
+
+use crate::data::{BundleSet, Coarena, ComponentSet, ComponentSetOption, Index};
+use crate::dynamics::RigidBodyHandle;
+use crate::geometry::{
+    ColliderFlags, ColliderHandle, ColliderParent, ColliderPosition, ColliderShape,
+    InteractionGroups,
+};
+use crate::math::{Isometry, Real};
+use crate::pipeline::QueryPipeline;
+use parry::shape::{Shape, SharedShape};
+
+/// An immutable copy of a [`QueryPipeline`] and of the colliders it contains.
+///
+/// A snapshot doesn't borrow the collider set, and is `Send + Sync`. It can be shared with
+/// worker threads (e.g. in an `Arc`) and queried while the simulation keeps being stepped.
+/// The shapes are shared with the original colliders instead of being copied.
+///
+/// The whole query API is available through the query pipeline of the snapshot, with
+/// the colliders of the snapshot, e.g.,
+/// `snapshot.query_pipeline().cast_ray(snapshot.colliders(), &ray, max_toi, true, groups, None)`.
+#[derive(Clone)]
+pub struct QuerySnapshot {
+    query_pipeline: QueryPipeline,
+    colliders: SnapshotColliderSet,
+}
+
+impl QuerySnapshot {
+    /// The copy of the query pipeline.
+    ///
+    /// Its queries must be given the colliders of this snapshot.
+    pub fn query_pipeline(&self) -> &QueryPipeline {
+        &self.query_pipeline
+    }
+
+    /// The copies of the colliders.
+    pub fn colliders(&self) -> &SnapshotColliderSet {
+        &self.colliders
+    }
+}
+
+/// The copy of a collider stored by a [`QuerySnapshot`].
+#[derive(Clone)]
+pub struct SnapshotCollider {
+    co_shape: ColliderShape,
+    co_pos: ColliderPosition,
+    co_flags: ColliderFlags,
+    co_parent: Option<ColliderParent>,
+}
+
+impl SnapshotCollider {
+    /// The rigid body this collider was attached to.
+    pub fn parent(&self) -> Option<RigidBodyHandle> {
+        self.co_parent.map(|parent| parent.handle)
+    }
+
+    /// Was this collider enabled?
+    pub fn is_enabled(&self) -> bool {
+        self.co_flags.enabled.is_enabled()
+    }
+
+    /// The world-space position of this collider.
+    pub fn position(&self) -> &Isometry<Real> {
+        &self.co_pos
+    }
+
+    /// The position of this collider relative to the rigid-body it was attached to.
+    pub fn position_wrt_parent(&self) -> Option<&Isometry<Real>> {
+        self.co_parent.as_ref().map(|p| &p.pos_wrt_parent)
+    }
+
+    /// The collision groups of this collider.
+    pub fn collision_groups(&self) -> InteractionGroups {
+        self.co_flags.collision_groups
+    }
+
+    /// The geometric shape of this collider.
+    pub fn shape(&self) -> &dyn Shape {
+        self.co_shape.as_ref()
+    }
+
+    /// The shape of this collider, shared with the original collider.
+    pub fn shared_shape(&self) -> &SharedShape {
+        &self.co_shape
+    }
+}
+
+/// The colliders copied into a [`QuerySnapshot`], indexed by the handles of the original colliders.
+#[derive(Clone, Default)]
+pub struct SnapshotColliderSet {
+    colliders: Coarena<Option<SnapshotCollider>>,
+    len: usize,
+}
+
+impl SnapshotColliderSet {
+    /// The number of colliders in this set.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Is this set empty?
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Does this set contain the copy of the collider identified by `handle`?
+    pub fn contains(&self, handle: ColliderHandle) -> bool {
+        self.get(handle).is_some()
+    }
+
+    /// The copy of the collider identified by `handle`.
+    pub fn get(&self, handle: ColliderHandle) -> Option<&SnapshotCollider> {
+        self.colliders.get(handle.0)?.as_ref()
+    }
+
+    /// Iterates through all the colliders of this set and their handles.
+    pub fn iter(&self) -> impl Iterator<Item = (ColliderHandle, &SnapshotCollider)> {
+        self.colliders
+            .iter()
+            .filter_map(|(index, co)| Some((ColliderHandle(index), co.as_ref()?)))
+    }
+}
+
+macro_rules! impl_field_component_set(
+    ($T: ty, $field: ident) => {
+        impl ComponentSetOption<$T> for SnapshotColliderSet {
+            fn get(&self, handle: Index) -> Option<&$T> {
+                self.get(ColliderHandle(handle)).map(|co| &co.$field)
+            }
+        }
+
+        impl ComponentSet<$T> for SnapshotColliderSet {
+            fn size_hint(&self) -> usize {
+                self.len()
+            }
+
+            #[inline(always)]
+            fn for_each(&self, mut f: impl FnMut(Index, &$T)) {
+                for (handle, co) in self.iter() {
+                    f(handle.0, &co.$field)
+                }
+            }
+        }
+    }
+);
+
+impl_field_component_set!(ColliderShape, co_shape);
+impl_field_component_set!(ColliderPosition, co_pos);
+impl_field_component_set!(ColliderFlags, co_flags);
+
+impl ComponentSetOption<ColliderParent> for SnapshotColliderSet {
+    #[inline(always)]
+    fn get(&self, handle: Index) -> Option<&ColliderParent> {
+        self.get(ColliderHandle(handle))
+            .and_then(|co| co.co_parent.as_ref())
+    }
+}
+
+impl QueryPipeline {
+    /// Creates an immutable, thread-safe, copy of this query pipeline and of the given colliders.
+    ///
+    /// This query pipeline must be up-to-date with the colliders. The copies of the colliders
+    /// share their shapes with the original colliders, so this is cheap even for complex shapes.
+    /// The recorded collider poses used for lag-compensated queries are not copied.
+    pub fn snapshot<Colliders>(&self, colliders: &Colliders) -> QuerySnapshot
+    where
+        Colliders: ComponentSet<ColliderShape>
+            + ComponentSet<ColliderPosition>
+            + ComponentSet<ColliderFlags>
+            + ComponentSetOption<ColliderParent>,
+    {
+        let mut snapshot_colliders = SnapshotColliderSet::default();
+
+        colliders.for_each(|handle, co_shape: &ColliderShape| {
+            let (co_pos, co_flags): (&ColliderPosition, &ColliderFlags) =
+                colliders.index_bundle(handle);
+            let co_parent: Option<&ColliderParent> = colliders.get(handle);
+            let collider = SnapshotCollider {
+                co_shape: co_shape.clone(),
+                co_pos: *co_pos,
+                co_flags: *co_flags,
+                co_parent: co_parent.copied(),
+            };
+
+            snapshot_colliders.colliders.insert(handle, Some(collider));
+            snapshot_colliders.len += 1;
+        });
+
+        QuerySnapshot {
+            query_pipeline: self.clone_without_history(),
+            colliders: snapshot_colliders,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::{ColliderBuilder, InteractionGroups, Ray};
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+    use crate::pipeline::QueryPipeline;
+    use std::sync::Arc;
+
+    #[test]
+    fn query_snapshot() {
+        let mut world = TestWorld::new(Vector::y() * -9.81);
+        world.query_pipeline = Some(QueryPipeline::new());
+
+        let handles: Vec<_> = (0..10)
+            .map(|i| {
+                let rb = RigidBodyBuilder::new_dynamic()
+                    .translation(Vector::x() * (i as Real * 2.0))
+                    .build();
+                world.insert_body(rb, ColliderBuilder::ball(0.5).build())
+            })
+            .collect();
+        let ray = |i: usize| {
+            let origin = Point::origin() + Vector::x() * (i as Real * 2.0) + Vector::y() * 10.0;
+            Ray::new(origin, -Vector::y())
+        };
+        let mut snapshot = None;
+        let mut worker = None;
+        let mut expected = vec![];
+
+        for step in 0..10 {
+            world.step();
+
+            if step == 0 {
+                let query_pipeline = world.query_pipeline.as_ref().unwrap();
+                let new_snapshot = Arc::new(query_pipeline.snapshot(&world.colliders));
+                assert_eq!(new_snapshot.colliders().len(), world.colliders.len());
+
+                expected = (0..10)
+                    .map(|i| {
+                        query_pipeline.cast_ray(
+                            &world.colliders,
+                            &ray(i),
+                            Real::MAX,
+                            true,
+                            InteractionGroups::all(),
+                            None,
+                        )
+                    })
+                    .collect();
+
+                // Query the snapshot from another thread while the simulation is stepped.
+                let worker_snapshot = new_snapshot.clone();
+                worker = Some(std::thread::spawn(move || {
+                    (0..10)
+                        .map(|i| {
+                            worker_snapshot.query_pipeline().cast_ray(
+                                worker_snapshot.colliders(),
+                                &ray(i),
+                                Real::MAX,
+                                true,
+                                InteractionGroups::all(),
+                                None,
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                }));
+                snapshot = Some(new_snapshot);
+
+                let _ = world.colliders.remove(
+                    handles[3].1,
+                    &mut world.islands,
+                    &mut world.bodies,
+                    true,
+                );
+            }
+        }
+
+        assert_eq!(worker.unwrap().join().unwrap(), expected);
+
+        // The snapshot still contains the removed collider, and its parent.
+        let (body, collider) = handles[3];
+        let snapshot = snapshot.unwrap();
+        assert!(!world.colliders.contains(collider));
+        assert_eq!(
+            snapshot.colliders().get(collider).unwrap().parent(),
+            Some(body)
+        );
+    }
+}
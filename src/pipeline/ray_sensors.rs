@@ -0,0 +1,299 @@
+//! Helpers generating the ray patterns of range sensors, to be cast with `QueryPipeline::cast_rays`.
+
+use crate::geometry::Ray;
+use crate::math::{Isometry, Real, Vector};
+use na::RealField;
+
+/// A rotating LiDAR, casting rays uniformly distributed in its field of view.
+///
+/// The sensor looks toward its local `+x` axis. The rays sweep its horizontal field of view
+/// around its local `+z` axis in 2D, or around its local `+y` axis in 3D. In 3D, each
+/// horizontal direction is sampled by `num_channels` rays spread over the vertical field of view.
+///
+/// The ray directions are normalized, so the time-of-impact of a ray is the measured range.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LidarSensor {
+    /// The number of rays cast in each horizontal sweep.
+    pub horizontal_resolution: usize,
+    /// The horizontal field of view, in radians, centered on the local `+x` axis.
+    ///
+    /// If it is `2π`, the first and last rays of a sweep don't overlap.
+    pub horizontal_fov: Real,
+    /// The number of horizontal sweeps, at different elevations.
+    #[cfg(feature = "dim3")]
+    pub num_channels: usize,
+    /// The vertical field of view, in radians, centered on the local `+x` axis.
+    #[cfg(feature = "dim3")]
+    pub vertical_fov: Real,
+    /// The maximum range of the sensor, to use as the `max_toi` of the ray casts.
+    pub max_range: Real,
+}
+
+impl LidarSensor {
+    /// A 2D LiDAR casting `resolution` rays in the given field of view.
+    #[cfg(feature = "dim2")]
+    pub fn new(resolution: usize, fov: Real, max_range: Real) -> Self {
+        Self {
+            horizontal_resolution: resolution,
+            horizontal_fov: fov,
+            max_range,
+        }
+    }
+
+    /// A 3D LiDAR casting `horizontal_resolution` rays in the horizontal field of view, for
+    /// each of its `num_channels` channels spread over the vertical field of view.
+    #[cfg(feature = "dim3")]
+    pub fn new(
+        horizontal_resolution: usize,
+        horizontal_fov: Real,
+        num_channels: usize,
+        vertical_fov: Real,
+        max_range: Real,
+    ) -> Self {
+        Self {
+            horizontal_resolution,
+            horizontal_fov,
+            num_channels,
+            vertical_fov,
+            max_range,
+        }
+    }
+
+    /// The number of rays cast by this sensor.
+    pub fn num_rays(&self) -> usize {
+        #[cfg(feature = "dim2")]
+        return self.horizontal_resolution;
+        #[cfg(feature = "dim3")]
+        return self.horizontal_resolution * self.num_channels;
+    }
+
+    /// Generates the rays of this sensor at the given world-space `pose`.
+    ///
+    /// The content of `rays` is replaced by the rays of each channel, from the lowest to the highest,
+    /// each one listing its rays in counterclockwise order.
+    pub fn rays(&self, pose: &Isometry<Real>, rays: &mut Vec<Ray>) {
+        rays.clear();
+        rays.reserve(self.num_rays());
+
+        let origin = pose.translation.vector.into();
+        let full_turn = self.horizontal_fov >= Real::two_pi() - Real::EPSILON;
+        let azimuths = angles(self.horizontal_resolution, self.horizontal_fov, full_turn);
+
+        #[cfg(feature = "dim2")]
+        for azimuth in azimuths {
+            let dir = Vector::new(azimuth.cos(), azimuth.sin());
+            rays.push(Ray::new(origin, pose.rotation * dir));
+        }
+
+        #[cfg(feature = "dim3")]
+        for elevation in angles(self.num_channels, self.vertical_fov, false) {
+            for azimuth in azimuths.clone() {
+                let dir = Vector::new(
+                    elevation.cos() * azimuth.cos(),
+                    elevation.sin(),
+                    -elevation.cos() * azimuth.sin(),
+                );
+                rays.push(Ray::new(origin, pose.rotation * dir));
+            }
+        }
+    }
+}
+
+/// A pinhole depth camera, casting one ray per pixel.
+///
+/// The camera looks toward its local `-z` axis, with its local `+y` axis pointing up in the image.
+/// The rays are generated row by row, starting with the top row, each row from left to right.
+///
+/// The ray directions are scaled so their component along the viewing direction is `1`, so
+/// the time-of-impact of a ray is the depth of its pixel.
+#[cfg(feature = "dim3")]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DepthCamera {
+    /// The width of the image, in pixels.
+    pub width: usize,
+    /// The height of the image, in pixels.
+    pub height: usize,
+    /// The vertical field of view, in radians.
+    pub vertical_fov: Real,
+    /// The maximum depth measured by the camera, to use as the `max_toi` of the ray casts.
+    pub max_depth: Real,
+}
+
+#[cfg(feature = "dim3")]
+impl DepthCamera {
+    /// A depth camera with square pixels.
+    pub fn new(width: usize, height: usize, vertical_fov: Real, max_depth: Real) -> Self {
+        Self {
+            width,
+            height,
+            vertical_fov,
+            max_depth,
+        }
+    }
+
+    /// The number of rays cast by this camera, i.e., its number of pixels.
+    pub fn num_rays(&self) -> usize {
+        self.width * self.height
+    }
+
+    /// Generates the rays of this camera at the given world-space `pose`.
+    ///
+    /// The content of `rays` is replaced by the ray of each pixel.
+    pub fn rays(&self, pose: &Isometry<Real>, rays: &mut Vec<Ray>) {
+        rays.clear();
+        rays.reserve(self.num_rays());
+
+        let origin = pose.translation.vector.into();
+        let half_height = (self.vertical_fov / 2.0).tan();
+        let half_width = half_height * self.width as Real / self.height as Real;
+
+        for j in 0..self.height {
+            let y = half_height * (1.0 - 2.0 * (j as Real + 0.5) / self.height as Real);
+
+            for i in 0..self.width {
+                let x = half_width * (2.0 * (i as Real + 0.5) / self.width as Real - 1.0);
+                rays.push(Ray::new(origin, pose.rotation * Vector::new(x, y, -1.0)));
+            }
+        }
+    }
+}
+
+/// `n` angles uniformly covering the range `[-fov / 2, fov / 2]`.
+///
+/// If `full_turn` is `true`, the angle `fov / 2` is excluded since it matches `-fov / 2`.
+fn angles(n: usize, fov: Real, full_turn: bool) -> impl Iterator<Item = Real> + Clone {
+    let num_intervals = if full_turn { n } else { n.saturating_sub(1) };
+    let step = if num_intervals == 0 {
+        0.0
+    } else {
+        fov / num_intervals as Real
+    };
+    let start = if n == 1 { 0.0 } else { -fov / 2.0 };
+
+    (0..n).map(move |i| start + step * i as Real)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{IslandManager, RigidBodySet};
+    use crate::geometry::{ColliderBuilder, ColliderHandle, ColliderSet, InteractionGroups};
+    use crate::math::{Isometry, Real, Vector};
+    #[cfg(feature = "dim3")]
+    use crate::pipeline::DepthCamera;
+    use crate::pipeline::{LidarSensor, QueryPipeline};
+    use na::RealField;
+
+    #[test]
+    fn batched_ray_casts() {
+        let mut colliders = ColliderSet::new();
+        let mut query_pipeline = QueryPipeline::new();
+        let groups = InteractionGroups::all();
+
+        for i in 0..20 {
+            let angle = i as Real * 0.3;
+            let dist = 3.0 + (i % 7) as Real;
+            // The colliders surround the LiDAR in its horizontal plane.
+            #[cfg(feature = "dim2")]
+            let translation = Vector::new(angle.cos(), angle.sin()) * dist;
+            #[cfg(feature = "dim3")]
+            let translation = Vector::new(angle.cos(), 0.0, angle.sin()) * dist;
+            let collider = if i % 2 == 0 {
+                ColliderBuilder::ball(0.5 + (i % 3) as Real * 0.2)
+            } else {
+                #[cfg(feature = "dim2")]
+                let builder = ColliderBuilder::cuboid(0.4, 0.8);
+                #[cfg(feature = "dim3")]
+                let builder = ColliderBuilder::cuboid(0.4, 0.8, 0.6);
+                builder
+            };
+            let _ = colliders.insert(collider.translation(translation).enabled(i != 5).build());
+        }
+
+        query_pipeline.update(&IslandManager::new(), &RigidBodySet::new(), &colliders);
+
+        #[cfg(feature = "dim2")]
+        let lidar = LidarSensor::new(1000, Real::two_pi(), 20.0);
+        #[cfg(feature = "dim3")]
+        let lidar = LidarSensor::new(500, Real::two_pi(), 8, 1.0, 20.0);
+        let pose = Isometry::new(Vector::y() * 0.1, na::zero());
+        let mut rays = vec![];
+        lidar.rays(&pose, &mut rays);
+        assert_eq!(rays.len(), lidar.num_rays());
+
+        let filter = |handle: ColliderHandle| handle.into_raw_parts().0 != 8;
+        let mut hits = vec![None; rays.len()];
+        query_pipeline.cast_rays(
+            &colliders,
+            &rays,
+            lidar.max_range,
+            true,
+            groups,
+            Some(&filter),
+            &mut hits,
+        );
+        assert!(hits.iter().filter(|hit| hit.is_some()).count() > rays.len() / 10);
+
+        for (ray, hit) in rays.iter().zip(hits.iter()) {
+            let expected = query_pipeline.cast_ray_and_get_normal(
+                &colliders,
+                ray,
+                lidar.max_range,
+                true,
+                groups,
+                Some(&filter),
+            );
+            assert_eq!(hit.map(|hit| hit.0), expected.map(|hit| hit.0));
+            assert_eq!(hit.map(|hit| hit.1.toi), expected.map(|hit| hit.1.toi));
+        }
+
+        #[cfg(feature = "parallel")]
+        {
+            let mut par_hits = vec![None; rays.len()];
+            query_pipeline.par_cast_rays(
+                &colliders,
+                &rays,
+                lidar.max_range,
+                true,
+                groups,
+                Some(&filter),
+                &mut par_hits,
+            );
+            let toi = |hit: &Option<(ColliderHandle, crate::geometry::RayIntersection)>| {
+                hit.map(|hit| hit.1.toi)
+            };
+            assert_eq!(
+                hits.iter().map(toi).collect::<Vec<_>>(),
+                par_hits.iter().map(toi).collect::<Vec<_>>()
+            );
+        }
+
+        // The time-of-impact of the rays of a depth camera facing a wall is the depth of the wall.
+        #[cfg(feature = "dim3")]
+        {
+            let wall = ColliderBuilder::cuboid(100.0, 100.0, 0.5)
+                .translation(Vector::new(0.0, 100.0, -30.0));
+            let _ = colliders.insert(wall.build());
+            query_pipeline.update(&IslandManager::new(), &RigidBodySet::new(), &colliders);
+
+            let camera = DepthCamera::new(32, 24, 1.0, 100.0);
+            let pose = Isometry::new(Vector::new(0.0, 100.0, 20.0), na::zero());
+            camera.rays(&pose, &mut rays);
+            let mut hits = vec![None; camera.num_rays()];
+            query_pipeline.cast_rays(
+                &colliders,
+                &rays,
+                camera.max_depth,
+                true,
+                groups,
+                None,
+                &mut hits,
+            );
+
+            for hit in hits {
+                assert!((hit.unwrap().1.toi - 49.5).abs() < 1.0e-3);
+            }
+        }
+    }
+}
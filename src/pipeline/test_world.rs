@@ -0,0 +1,110 @@
+//! A complete physics world, shared by the unit tests that need to run a simulation.
+
+use crate::dynamics::{
+    CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager, MultibodyJointSet, RigidBody,
+    RigidBodyHandle, RigidBodySet,
+};
+use crate::geometry::{
+    BroadPhase, Collider, ColliderHandle, ColliderSet, DefaultBroadPhase, NarrowPhase,
+};
+use crate::math::{Real, Vector};
+use crate::pipeline::{PhysicsHooks, PhysicsPipeline, QueryPipeline};
+
+/// All the sets and pipelines needed to step a simulation.
+pub(crate) struct TestWorld<BF = DefaultBroadPhase> {
+    pub pipeline: PhysicsPipeline,
+    pub gravity: Vector<Real>,
+    pub integration_parameters: IntegrationParameters,
+    pub islands: IslandManager,
+    pub broad_phase: BF,
+    pub narrow_phase: NarrowPhase,
+    pub bodies: RigidBodySet,
+    pub colliders: ColliderSet,
+    pub impulse_joints: ImpulseJointSet,
+    pub multibody_joints: MultibodyJointSet,
+    pub ccd_solver: CCDSolver,
+    /// The query pipeline updated at each step, if any.
+    pub query_pipeline: Option<QueryPipeline>,
+}
+
+impl TestWorld {
+    /// An empty world with the given gravity, using the default broad-phase.
+    pub fn new(gravity: Vector<Real>) -> Self {
+        Self::with_broad_phase(gravity, DefaultBroadPhase::new())
+    }
+}
+
+impl<BF: BroadPhase> TestWorld<BF> {
+    /// An empty world with the given gravity and broad-phase.
+    pub fn with_broad_phase(gravity: Vector<Real>, broad_phase: BF) -> Self {
+        Self {
+            pipeline: PhysicsPipeline::new(),
+            gravity,
+            integration_parameters: IntegrationParameters::default(),
+            islands: IslandManager::new(),
+            broad_phase,
+            narrow_phase: NarrowPhase::new(),
+            bodies: RigidBodySet::new(),
+            colliders: ColliderSet::new(),
+            impulse_joints: ImpulseJointSet::new(),
+            multibody_joints: MultibodyJointSet::new(),
+            ccd_solver: CCDSolver::new(),
+            query_pipeline: None,
+        }
+    }
+
+    /// Inserts a rigid-body with a single collider attached to it.
+    pub fn insert_body(
+        &mut self,
+        body: RigidBody,
+        collider: Collider,
+    ) -> (RigidBodyHandle, ColliderHandle) {
+        let body = self.bodies.insert(body);
+        let collider = self
+            .colliders
+            .insert_with_parent(collider, body, &mut self.bodies);
+        (body, collider)
+    }
+
+    /// Removes a rigid-body, with its colliders and joints.
+    pub fn remove_body(&mut self, handle: RigidBodyHandle) -> Option<RigidBody> {
+        self.bodies.remove(
+            handle,
+            &mut self.islands,
+            &mut self.colliders,
+            &mut self.impulse_joints,
+            &mut self.multibody_joints,
+        )
+    }
+
+    /// Runs a single timestep.
+    pub fn step(&mut self) {
+        self.step_with_hooks(&())
+    }
+
+    /// Runs a single timestep with the given physics hooks.
+    pub fn step_with_hooks(&mut self, hooks: &dyn PhysicsHooks<RigidBodySet, ColliderSet>) {
+        self.pipeline.step_and_update_queries(
+            &self.gravity,
+            &self.integration_parameters,
+            &mut self.islands,
+            &mut self.broad_phase,
+            &mut self.narrow_phase,
+            &mut self.bodies,
+            &mut self.colliders,
+            &mut self.impulse_joints,
+            &mut self.multibody_joints,
+            &mut self.ccd_solver,
+            self.query_pipeline.as_mut(),
+            hooks,
+            &(),
+        );
+    }
+
+    /// Runs `num_steps` timesteps.
+    pub fn step_n(&mut self, num_steps: usize) {
+        for _ in 0..num_steps {
+            self.step();
+        }
+    }
+}
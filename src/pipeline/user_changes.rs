@@ -3,15 +3,53 @@ use crate::dynamics::{
     IslandManager, RigidBodyActivation, RigidBodyChanges, RigidBodyColliders, RigidBodyHandle,
     RigidBodyIds, RigidBodyPosition, RigidBodyType,
 };
-use crate::geometry::{ColliderChanges, ColliderHandle, ColliderParent, ColliderPosition};
+use crate::geometry::{
+    BroadPhase, BroadPhasePairEvent, ColliderChanges, ColliderEnabled, ColliderFlags,
+    ColliderHandle, ColliderParent, ColliderPosition,
+};
+
+/// Reports an `AddPair` event for each broad-phase pair involving a re-parented collider.
+///
+/// The narrow-phase doesn't register the pairs between colliders attached to the same
+/// rigid-body, so the pairs of a collider detached from such a rigid-body must be
+/// registered again. The pairs already registered are left untouched by the narrow-phase.
+pub(crate) fn register_reparented_collider_pairs<Colliders>(
+    broad_phase: &impl BroadPhase,
+    colliders: &Colliders,
+    modified_colliders: &[ColliderHandle],
+    events: &mut Vec<BroadPhasePairEvent>,
+) where
+    Colliders: ComponentSet<ColliderChanges>,
+{
+    let reparented: Vec<_> = modified_colliders
+        .iter()
+        .filter(|handle| {
+            let co_changes: Option<&ColliderChanges> = colliders.get(handle.0);
+            co_changes.is_some_and(|changes| changes.contains(ColliderChanges::PARENT))
+        })
+        .collect();
 
-pub(crate) fn handle_user_changes_to_colliders<Colliders>(
-    bodies: &mut impl ComponentSet<RigidBodyPosition>,
+    if reparented.is_empty() {
+        return;
+    }
+
+    broad_phase.for_each_pair(|pair| {
+        if reparented.contains(&&pair.collider1) || reparented.contains(&&pair.collider2) {
+            events.push(BroadPhasePairEvent::AddPair(*pair));
+        }
+        true
+    });
+}
+
+pub(crate) fn handle_user_changes_to_colliders<Bodies, Colliders>(
+    bodies: &mut Bodies,
     colliders: &mut Colliders,
     modified_colliders: &[ColliderHandle],
 ) where
+    Bodies: ComponentSet<RigidBodyPosition> + ComponentSet<RigidBodyActivation>,
     Colliders: ComponentSetMut<ColliderChanges>
         + ComponentSetMut<ColliderPosition>
+        + ComponentSetMut<ColliderFlags>
         + ComponentSetOption<ColliderParent>,
 {
     for handle in modified_colliders {
@@ -24,7 +62,7 @@ pub(crate) fn handle_user_changes_to_colliders<Colliders>(
                 let co_parent: Option<&ColliderParent> = colliders.get(handle.0);
 
                 if let Some(co_parent) = co_parent {
-                    let parent_pos = bodies.index(co_parent.handle.0);
+                    let parent_pos: &RigidBodyPosition = bodies.index(co_parent.handle.0);
 
                     let new_pos = parent_pos.position * co_parent.pos_wrt_parent;
                     let new_changes = *co_changes | ColliderChanges::POSITION;
@@ -33,6 +71,34 @@ pub(crate) fn handle_user_changes_to_colliders<Colliders>(
                 }
             }
         }
+
+        let co_changes: Option<&ColliderChanges> = colliders.get(handle.0);
+
+        if let Some(co_changes) = co_changes {
+            if co_changes.intersects(ColliderChanges::PARENT | ColliderChanges::ENABLED_OR_DISABLED)
+            {
+                // Make sure the collider is disabled if, and only if, its parent is disabled.
+                let co_parent: Option<&ColliderParent> = colliders.get(handle.0);
+                let parent_enabled = co_parent
+                    .map(|co_parent| {
+                        let activation: &RigidBodyActivation = bodies.index(co_parent.handle.0);
+                        activation.enabled
+                    })
+                    .unwrap_or(true);
+
+                colliders.map_mut_internal(handle.0, |co_flags: &mut ColliderFlags| {
+                    co_flags.enabled = match co_flags.enabled {
+                        ColliderEnabled::Enabled if !parent_enabled => {
+                            ColliderEnabled::DisabledByParent
+                        }
+                        ColliderEnabled::DisabledByParent if parent_enabled => {
+                            ColliderEnabled::Enabled
+                        }
+                        enabled => enabled,
+                    };
+                });
+            }
+        }
     }
 }
 
@@ -51,6 +117,7 @@ pub(crate) fn handle_user_changes_to_rigid_bodies<Bodies, Colliders>(
         + ComponentSet<RigidBodyPosition>,
     Colliders: ComponentSetMut<ColliderPosition>
         + ComponentSetMut<ColliderChanges>
+        + ComponentSetMut<ColliderFlags>
         + ComponentSetOption<ColliderParent>,
 {
     enum FinalAction {
@@ -70,6 +137,14 @@ pub(crate) fn handle_user_changes_to_rigid_bodies<Bodies, Colliders>(
         let mut changes = *changes.unwrap();
         let mut ids: RigidBodyIds = *bodies.index(handle.0);
         let mut activation: RigidBodyActivation = *bodies.index(handle.0);
+
+        if !activation.enabled {
+            // Disabled rigid-bodies must not be part of any active set.
+            if let Some(islands) = islands.as_deref_mut() {
+                islands.rigid_body_removed(*handle, &ids, bodies);
+            }
+        }
+
         let (status, rb_colliders, poss): (
             &RigidBodyType,
             &RigidBodyColliders,
@@ -80,7 +155,7 @@ pub(crate) fn handle_user_changes_to_rigid_bodies<Bodies, Colliders>(
             // The body's status changed. We need to make sure
             // it is on the correct active set.
             if let Some(islands) = islands.as_deref_mut() {
-                if changes.contains(RigidBodyChanges::TYPE) {
+                if changes.contains(RigidBodyChanges::TYPE) && activation.enabled {
                     match status {
                         RigidBodyType::Dynamic => {
                             // Remove from the active kinematic set if it was there.
@@ -124,10 +199,12 @@ pub(crate) fn handle_user_changes_to_rigid_bodies<Bodies, Colliders>(
                 // Update the positions of the colliders.
                 if changes.contains(RigidBodyChanges::POSITION)
                     || changes.contains(RigidBodyChanges::COLLIDERS)
+                    || changes.contains(RigidBodyChanges::ENABLED_OR_DISABLED)
                 {
                     rb_colliders.update_positions(colliders, modified_colliders, &poss.position);
 
                     if status.is_kinematic()
+                        && activation.enabled
                         && islands.active_kinematic_set.get(ids.active_set_id) != Some(handle)
                     {
                         ids.active_set_id = islands.active_kinematic_set.len();
@@ -137,7 +214,9 @@ pub(crate) fn handle_user_changes_to_rigid_bodies<Bodies, Colliders>(
 
                 // Push the body to the active set if it is not
                 // sleeping and if it is not already inside of the active set.
-                if changes.contains(RigidBodyChanges::SLEEP)
+                if (changes.contains(RigidBodyChanges::SLEEP)
+                    || changes.contains(RigidBodyChanges::ENABLED_OR_DISABLED))
+                    && activation.enabled
                     && !activation.sleeping // May happen if the body was put to sleep manually.
                     && status.is_dynamic() // Only dynamic bodies are in the active dynamic set.
                     && islands.active_dynamic_set.get(ids.active_set_id) != Some(handle)
@@ -169,6 +248,31 @@ pub(crate) fn handle_user_changes_to_rigid_bodies<Bodies, Colliders>(
                 }
             }
 
+            if changes.contains(RigidBodyChanges::ENABLED_OR_DISABLED) {
+                // Disable or re-enable the colliders attached to this rigid-body.
+                for handle in rb_colliders.0.iter() {
+                    colliders.map_mut_internal(handle.0, |co_flags: &mut ColliderFlags| {
+                        co_flags.enabled = match co_flags.enabled {
+                            ColliderEnabled::Enabled if !activation.enabled => {
+                                ColliderEnabled::DisabledByParent
+                            }
+                            ColliderEnabled::DisabledByParent if activation.enabled => {
+                                ColliderEnabled::Enabled
+                            }
+                            enabled => enabled,
+                        };
+                    });
+                    colliders.map_mut_internal(handle.0, |co_changes: &mut ColliderChanges| {
+                        if !co_changes.contains(ColliderChanges::MODIFIED) {
+                            modified_colliders.push(*handle);
+                        }
+
+                        *co_changes |=
+                            ColliderChanges::MODIFIED | ColliderChanges::ENABLED_OR_DISABLED;
+                    });
+                }
+            }
+
             bodies.set_internal(handle.0, RigidBodyChanges::empty());
             bodies.set_internal(handle.0, ids);
             bodies.set_internal(handle.0, activation);
@@ -1,5 +1,5 @@
 use crate::{
-    physics::{PhysicsEvents, PhysicsState},
+    physics::{PhysicsBroadPhase, PhysicsEvents, PhysicsState},
     TestbedGraphics,
 };
 use plugin::HarnessPlugin;
@@ -7,7 +7,7 @@ use rapier::dynamics::{
     CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager, MultibodyJointSet,
     RigidBodySet,
 };
-use rapier::geometry::{BroadPhase, ColliderSet, NarrowPhase};
+use rapier::geometry::{ColliderSet, NarrowPhase};
 use rapier::math::{Real, Vector};
 use rapier::pipeline::{ChannelEventCollector, PhysicsHooks, PhysicsPipeline, QueryPipeline};
 
@@ -144,7 +144,7 @@ impl Harness {
         self.physics.hooks = Box::new(hooks);
 
         self.physics.islands = IslandManager::new();
-        self.physics.broad_phase = BroadPhase::new();
+        self.physics.broad_phase = PhysicsBroadPhase::default();
         self.physics.narrow_phase = NarrowPhase::new();
         self.state.timestep_id = 0;
         self.state.time = 0.0;
@@ -177,7 +177,7 @@ impl Harness {
             let physics = &mut self.physics;
             let event_handler = &self.event_handler;
             self.state.thread_pool.install(|| {
-                physics.pipeline.step(
+                physics.pipeline.step_and_update_queries(
                     &physics.gravity,
                     &physics.integration_parameters,
                     &mut physics.islands,
@@ -188,6 +188,7 @@ impl Harness {
                     &mut physics.impulse_joints,
                     &mut physics.multibody_joints,
                     &mut physics.ccd_solver,
+                    Some(&mut physics.query_pipeline),
                     &*physics.hooks,
                     event_handler,
                 );
@@ -195,7 +196,7 @@ impl Harness {
         }
 
         #[cfg(not(feature = "parallel"))]
-        self.physics.pipeline.step(
+        self.physics.pipeline.step_and_update_queries(
             &self.physics.gravity,
             &self.physics.integration_parameters,
             &mut self.physics.islands,
@@ -206,16 +207,11 @@ impl Harness {
             &mut self.physics.impulse_joints,
             &mut self.physics.multibody_joints,
             &mut self.physics.ccd_solver,
+            Some(&mut self.physics.query_pipeline),
             &*self.physics.hooks,
             &self.event_handler,
         );
 
-        self.physics.query_pipeline.update(
-            &self.physics.islands,
-            &self.physics.bodies,
-            &self.physics.colliders,
-        );
-
         for plugin in &mut self.plugins {
             plugin.step(&mut self.physics, &self.state)
         }
@@ -1,12 +1,93 @@
 use crossbeam::channel::Receiver;
+use rapier::data::{ComponentSet, ComponentSetMut};
 use rapier::dynamics::{
     CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager, MultibodyJointSet,
     RigidBodySet,
 };
-use rapier::geometry::{BroadPhase, ColliderSet, ContactEvent, IntersectionEvent, NarrowPhase};
+use rapier::geometry::{
+    BroadPhase, BroadPhaseBvh, BroadPhaseMultiSap, BroadPhasePairEvent, ColliderBroadPhaseData,
+    ColliderChanges, ColliderFlags, ColliderHandle, ColliderPair, ColliderPosition, ColliderSet,
+    ColliderShape, ContactEvent, IntersectionEvent, NarrowPhase, AABB,
+};
 use rapier::math::{Real, Vector};
 use rapier::pipeline::{PhysicsHooks, PhysicsPipeline, QueryPipeline};
 
+/// The broad-phase used by the testbed, so scenes can be run with any of them.
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum PhysicsBroadPhase {
+    MultiSap(BroadPhaseMultiSap),
+    Bvh(BroadPhaseBvh),
+}
+
+impl Default for PhysicsBroadPhase {
+    fn default() -> Self {
+        PhysicsBroadPhase::MultiSap(BroadPhaseMultiSap::new())
+    }
+}
+
+impl BroadPhase for PhysicsBroadPhase {
+    fn update<Colliders>(
+        &mut self,
+        prediction_distance: Real,
+        colliders: &mut Colliders,
+        modified_colliders: &[ColliderHandle],
+        removed_colliders: &[ColliderHandle],
+        events: &mut Vec<BroadPhasePairEvent>,
+    ) where
+        Colliders: ComponentSetMut<ColliderBroadPhaseData>
+            + ComponentSet<ColliderChanges>
+            + ComponentSet<ColliderPosition>
+            + ComponentSet<ColliderShape>
+            + ComponentSet<ColliderFlags>,
+    {
+        match self {
+            PhysicsBroadPhase::MultiSap(broad_phase) => broad_phase.update(
+                prediction_distance,
+                colliders,
+                modified_colliders,
+                removed_colliders,
+                events,
+            ),
+            PhysicsBroadPhase::Bvh(broad_phase) => broad_phase.update(
+                prediction_distance,
+                colliders,
+                modified_colliders,
+                removed_colliders,
+                events,
+            ),
+        }
+    }
+
+    fn shift_origin(&mut self, shift: &Vector<Real>, events: &mut Vec<BroadPhasePairEvent>) {
+        match self {
+            PhysicsBroadPhase::MultiSap(broad_phase) => broad_phase.shift_origin(shift, events),
+            PhysicsBroadPhase::Bvh(broad_phase) => broad_phase.shift_origin(shift, events),
+        }
+    }
+
+    fn for_each_pair(&self, callback: impl FnMut(&ColliderPair) -> bool) {
+        match self {
+            PhysicsBroadPhase::MultiSap(broad_phase) => broad_phase.for_each_pair(callback),
+            PhysicsBroadPhase::Bvh(broad_phase) => broad_phase.for_each_pair(callback),
+        }
+    }
+
+    fn colliders_with_aabb_intersecting_aabb(
+        &self,
+        aabb: &AABB,
+        callback: impl FnMut(&ColliderHandle) -> bool,
+    ) {
+        match self {
+            PhysicsBroadPhase::MultiSap(broad_phase) => {
+                broad_phase.colliders_with_aabb_intersecting_aabb(aabb, callback)
+            }
+            PhysicsBroadPhase::Bvh(broad_phase) => {
+                broad_phase.colliders_with_aabb_intersecting_aabb(aabb, callback)
+            }
+        }
+    }
+}
+
 pub struct PhysicsSnapshot {
     timestep_id: usize,
     broad_phase: Vec<u8>,
@@ -19,7 +100,7 @@ pub struct PhysicsSnapshot {
 impl PhysicsSnapshot {
     pub fn new(
         timestep_id: usize,
-        broad_phase: &BroadPhase,
+        broad_phase: &PhysicsBroadPhase,
         narrow_phase: &NarrowPhase,
         bodies: &RigidBodySet,
         colliders: &ColliderSet,
@@ -39,7 +120,7 @@ impl PhysicsSnapshot {
         &self,
     ) -> bincode::Result<(
         usize,
-        BroadPhase,
+        PhysicsBroadPhase,
         NarrowPhase,
         RigidBodySet,
         ColliderSet,
@@ -72,7 +153,7 @@ impl PhysicsSnapshot {
 
 pub struct PhysicsState {
     pub islands: IslandManager,
-    pub broad_phase: BroadPhase,
+    pub broad_phase: PhysicsBroadPhase,
     pub narrow_phase: NarrowPhase,
     pub bodies: RigidBodySet,
     pub colliders: ColliderSet,
@@ -90,7 +171,7 @@ impl PhysicsState {
     pub fn new() -> Self {
         Self {
             islands: IslandManager::new(),
-            broad_phase: BroadPhase::new(),
+            broad_phase: PhysicsBroadPhase::default(),
             narrow_phase: NarrowPhase::new(),
             bodies: RigidBodySet::new(),
             colliders: ColliderSet::new(),
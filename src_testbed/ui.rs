@@ -1,4 +1,5 @@
 use rapier::counters::Counters;
+use rapier::dynamics::SolverMode;
 use rapier::math::Real;
 
 use crate::harness::Harness;
@@ -97,6 +98,19 @@ pub fn update_ui(ui_context: &EguiContext, state: &mut TestbedState, harness: &m
             "interleave friction resolution",
         );
 
+        let mut tgs_soft = integration_parameters.solver_mode != SolverMode::Pgs;
+        ui.checkbox(&mut tgs_soft, "TGS-soft solver");
+        if tgs_soft {
+            let mut num_substeps = match integration_parameters.solver_mode {
+                SolverMode::TgsSoft { num_substeps } => num_substeps,
+                SolverMode::Pgs => 4,
+            };
+            ui.add(Slider::new(&mut num_substeps, 1..=16).text("solver substeps"));
+            integration_parameters.solver_mode = SolverMode::TgsSoft { num_substeps };
+        } else {
+            integration_parameters.solver_mode = SolverMode::Pgs;
+        }
+
         if state.selected_backend == PHYSX_BACKEND_PATCH_FRICTION
             || state.selected_backend == PHYSX_BACKEND_TWO_FRICTION_DIR
         {